@@ -0,0 +1,202 @@
+/*!
+Precomputations for a curve whose generator is multiplied over and over, e.g.
+by a signer or a verifier serving many requests:
+
+```text
+ let context = CurveContext::new(curves::secp256k1())?;   // once
+ let pub_key = context.mul_generator(&priv_key);           // many times
+```
+
+The field arithmetic (the reduction of the special primes or the Montgomery
+constants) is set up once instead of on every multiplication, and `k G` is
+read from a table of the multiples of `G` by 4 bits windows of `k`:
+
+```text
+ table[i][j - 1] = j 2^(4 i) G      for j in [1, 15]
+ k G = sum table[i][k_i - 1]        k_i the i-th window of k, when not 0
+```
+
+which replaces all the doublings by one addition per window. The table
+takes `15 * bits(n) / 4` points, about 1000 for a 256 bits order.
+*/
+
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+
+use crate::curves::CurveParams;
+use crate::elliptic_curve::{Arithmetic, CurvePoint, EllipticCurveError};
+use crate::jacobian::JacobianPoint;
+
+const WINDOW_BITS: u64 = 4;
+
+///
+/// The domain parameters of a curve with its field arithmetic and the table
+/// of the multiples of its generator. Build it once and share it, it is
+/// immutable.
+///
+#[derive(Clone, Debug)]
+pub struct CurveContext {
+    params: CurveParams,
+    f: Arithmetic,
+    a_repr: BigUint,
+    // in Jacobian coordinates, in the representation of `f`
+    table: Vec<Vec<JacobianPoint>>,
+}
+
+impl CurveContext {
+    pub fn new(params: CurveParams) -> Result<Self, EllipticCurveError> {
+        let curve = &params.curve;
+        if params.g == CurvePoint::Identity || !curve.is_on_curve(&params.g) {
+            return Err(EllipticCurveError::InvalidPoint(params.g.clone()));
+        }
+
+        let (f, a_repr) = curve.arithmetic()?;
+        let windows = params.n.bits().div_ceil(WINDOW_BITS);
+        let mut table = Vec::with_capacity(windows as usize);
+        // 2^(4 i) G
+        let mut base = JacobianPoint::from_affine(&params.g).to_repr(&f);
+        for _ in 0..windows {
+            let mut row = Vec::with_capacity((1 << WINDOW_BITS) - 1);
+            let mut multiple = base.clone();
+            for _ in 1..(1 << WINDOW_BITS) {
+                row.push(multiple.clone());
+                multiple = curve.jacobian_add_with(&f, &a_repr, &multiple, &base);
+            }
+            base = multiple;
+            table.push(row);
+        }
+
+        Ok(CurveContext { params, f, a_repr, table })
+    }
+
+    pub fn params(&self) -> &CurveParams {
+        &self.params
+    }
+
+    ///
+    /// `k G` from the table. `k` is reduced modulo `n` first, which does not
+    /// change the product.
+    ///
+    pub fn mul_generator(&self, k: &BigUint) -> CurvePoint {
+        let t = self.generator_multiple(k);
+        self.params.curve.to_affine(&t.to_value(&self.f))
+    }
+
+    ///
+    /// `u1 G + u2 B`, as needed by signature verification, with `u1 G` from
+    /// the table.
+    ///
+    pub fn double_mul_generator(&self, u1: &BigUint, u2: &BigUint, b: &CurvePoint) -> Result<CurvePoint, EllipticCurveError> {
+        let t = self.generator_multiple(u1);
+        let u = self.mul_repr(b, u2)?;
+        let sum = self.params.curve.jacobian_add_with(&self.f, &self.a_repr, &t, &u);
+        Ok(self.params.curve.to_affine(&sum.to_value(&self.f)))
+    }
+
+    ///
+    /// `d A` for any point `A` of the curve, as `EllipticCurve::scalar_mul`
+    /// but without setting up the field arithmetic again.
+    ///
+    pub fn scalar_mul(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        let t = self.mul_repr(a, d)?;
+        Ok(self.params.curve.to_affine(&t.to_value(&self.f)))
+    }
+
+    fn mul_repr(&self, a: &CurvePoint, d: &BigUint) -> Result<JacobianPoint, EllipticCurveError> {
+        let curve = &self.params.curve;
+        if !curve.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+        Ok(curve.jacobian_scalar_mul_with(&self.f, &self.a_repr, a, d).to_repr(&self.f))
+    }
+
+    fn generator_multiple(&self, k: &BigUint) -> JacobianPoint {
+        let reduced;
+        let k = if *k >= self.params.n {
+            reduced = k % &self.params.n;
+            &reduced
+        } else {
+            k
+        };
+
+        let mut t = JacobianPoint::identity();
+        for (i, row) in self.table.iter().enumerate() {
+            let window = (0..WINDOW_BITS)
+                .filter(|bit| k.bit(i as u64 * WINDOW_BITS + bit))
+                .fold(0usize, |window, bit| window | 1 << bit);
+            if window != 0 {
+                t = self.params.curve.jacobian_add_with(&self.f, &self.a_repr, &t, &row[window - 1]);
+            }
+        }
+        t
+    }
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+    use crate::elliptic_curve::EllipticCurve;
+
+    #[test]
+    fn test_mul_generator() {
+        for params in [curves::secp256k1(), curves::p384(), curves::p521()] {
+            let context = CurveContext::new(params.clone()).unwrap();
+            let curve = &params.curve;
+
+            for k in [
+                BigUint::from(0u32),
+                BigUint::from(1u32),
+                BigUint::from(0xfedcba98u32),
+                &params.n - 1u32,
+                &params.n + 5u32,
+                (BigUint::from(1u32) << 200u32) + 12345u32,
+            ] {
+                assert_eq!(context.mul_generator(&k), curve.scalar_mul(&params.g, &k).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_double_mul_generator() {
+        let params = curves::p256();
+        let context = CurveContext::new(params.clone()).unwrap();
+        let b = params.curve.scalar_mul(&params.g, &BigUint::from(7u32)).unwrap();
+        let (u1, u2) = (BigUint::from(123456789u32), &params.n - 3u32);
+
+        assert_eq!(
+            context.double_mul_generator(&u1, &u2, &b),
+            params.curve.double_scalar_mul(&u1, &params.g, &u2, &b)
+        );
+        assert_eq!(context.scalar_mul(&b, &u2), params.curve.scalar_mul(&b, &u2));
+
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(context.double_mul_generator(&u1, &u2, &off_curve).is_err());
+    }
+
+    #[test]
+    fn test_small_curve() {
+        // y^2 = x^3 + 2x + 2 mod 17, |G| = 19, Montgomery arithmetic
+        let curve = EllipticCurve { a: BigUint::from(2u32), b: BigUint::from(2u32), p: BigUint::from(17u32) };
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let params = CurveParams::new(curve.clone(), g.clone(), BigUint::from(19u32), BigUint::from(1u32));
+        let context = CurveContext::new(params).unwrap();
+
+        for k in 0..40u32 {
+            assert_eq!(context.mul_generator(&BigUint::from(k)), curve.scalar_mul(&g, &BigUint::from(k)).unwrap());
+        }
+
+        let bad = CurveParams::new(curve, CurvePoint::Identity, BigUint::from(19u32), BigUint::from(1u32));
+        assert!(CurveContext::new(bad).is_err());
+
+        // an even modulus, on which (0, 0) is a point of y^2 = x^3
+        let even = EllipticCurve { a: BigUint::from(0u32), b: BigUint::from(0u32), p: BigUint::from(4u32) };
+        let origin = CurvePoint::Coordinate(BigUint::from(0u32), BigUint::from(0u32));
+        let bad = CurveParams::new(even, origin, BigUint::from(3u32), BigUint::from(1u32));
+        assert!(CurveContext::new(bad).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+/*!
+Constant time scalar multiplication over the `ct_field` backend.
+
+The points are kept in homogeneous projective coordinates `(X : Y : Z)`
+(`x = X / Z`, `y = Y / Z`, identity `(0 : 1 : 0)`) and added with the complete
+formulas of Renes, Costello and Batina (2016, algorithm 1). Being complete
+they have no special case for doublings or the identity, so the Montgomery
+ladder
+
+```text
+ R0 = I, R1 = A
+ for i in [bits - 1, 0]
+      swap R0, R1 if bit i of d == 1
+      R1 = R0 + R1
+      R0 = R0 + R0
+      swap R0, R1 if bit i of d == 1
+```
+
+runs the same field operations whatever the scalar. The formulas need a curve
+of odd order, which is the case of every prime order curve.
+
+`scalar_mul_blinded` adds scalar and coordinates randomization on top of the
+ladder, for targets exposed to power or electromagnetic analysis.
+*/
+
+use num_bigint::BigUint;
+use rand_core::{CryptoRng, RngCore};
+use subtle::Choice;
+
+use crate::ct_field::{self, CtField, CtFieldElement};
+use crate::elliptic_curve::{CurvePoint, EllipticCurve, EllipticCurveError};
+use crate::field_element::FieldElement;
+
+///
+/// The field arithmetic used by a scalar multiplication:
+///
+/// - `VariableTime`: `BigUint` arithmetic in Jacobian coordinates, faster
+///   but leaking timing about the scalar, fine for public scalars (e.g.
+///   signature verification)
+/// - `ConstantTime`: the fixed width `CtField` arithmetic, for secret scalars
+///   (private keys, nonces)
+/// - `FixedWidth`: the `FixedField` arithmetic, limbs on the stack instead of
+///   `BigUint`s, variable time like `VariableTime`
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FieldBackend {
+    #[default]
+    VariableTime,
+    ConstantTime,
+    FixedWidth,
+}
+
+///
+/// The countermeasures applied by `scalar_mul_blinded`, all of them by
+/// default.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Blinding {
+    pub scalar: bool,
+    pub coordinates: bool,
+}
+
+impl Default for Blinding {
+    fn default() -> Self {
+        Blinding { scalar: true, coordinates: true }
+    }
+}
+
+struct ProjectivePoint {
+    x: CtFieldElement,
+    y: CtFieldElement,
+    z: CtFieldElement,
+}
+
+impl ProjectivePoint {
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        CtFieldElement::conditional_swap(&mut a.x, &mut b.x, choice);
+        CtFieldElement::conditional_swap(&mut a.y, &mut b.y, choice);
+        CtFieldElement::conditional_swap(&mut a.z, &mut b.z, choice);
+    }
+}
+
+impl EllipticCurve {
+    ///
+    /// `B = d * A` with the field arithmetic of the given backend. Every
+    /// backend gives the same result and accepts the same inputs as
+    /// `scalar_mul`.
+    ///
+    pub fn scalar_mul_with(
+        &self,
+        a: &CurvePoint,
+        d: &BigUint,
+        backend: FieldBackend,
+    ) -> Result<CurvePoint, EllipticCurveError> {
+        match backend {
+            FieldBackend::VariableTime => self.scalar_mul(a, d),
+            FieldBackend::ConstantTime => self.scalar_mul_ct(a, d),
+            FieldBackend::FixedWidth => self.scalar_mul_fixed(a, d),
+        }
+    }
+
+    ///
+    /// `B = d * A` in constant time: the ladder always runs over as many bits
+    /// as `p` has plus one (more than any scalar reduced by the group order),
+    /// or over the bits of `d` if it is larger.
+    ///
+    pub fn scalar_mul_ct(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        let bits = core::cmp::max(self.p.bits() + 1, d.bits());
+        self.ladder(a, d, bits, None)
+    }
+
+    ///
+    /// `B = d * A` in constant time with randomized intermediate values, as a
+    /// defense in depth against side channels (DPA, template attacks...):
+    ///
+    /// - scalar blinding: the ladder runs on `d + r n` for a random 64 bits
+    ///   `r`, where `n` is the order of `A`, so the bits processed change at
+    ///   every call
+    /// - coordinates blinding: `A` enters the ladder as `(x λ : y λ : λ)` for
+    ///   a random `λ != 0`, so every intermediate coordinate is randomized
+    ///
+    pub fn scalar_mul_blinded<R: CryptoRng + RngCore>(
+        &self,
+        a: &CurvePoint,
+        d: &BigUint,
+        order: &BigUint,
+        blinding: Blinding,
+        rng: &mut R,
+    ) -> Result<CurvePoint, EllipticCurveError> {
+        if *order == BigUint::from(0u32) {
+            return Err(EllipticCurveError::InvalidScalar(order.clone()));
+        }
+
+        // d + r n has at most 64 more bits than n, the same count at every call
+        let bits = core::cmp::max(self.p.bits(), order.bits()) + 65;
+        let scalar = if blinding.scalar {
+            d + BigUint::from(rng.next_u64()) * order
+        } else {
+            d.clone()
+        };
+        let bits = core::cmp::max(bits, scalar.bits());
+
+        let lambda = if blinding.coordinates {
+            Some(FieldElement::random_nonzero(rng, self.p.clone()).expect("p is greater than 3").into_value())
+        } else {
+            None
+        };
+        self.ladder(a, &scalar, bits, lambda.as_ref())
+    }
+
+    ///
+    /// Montgomery ladder over `bits` bits of `d`, the base point having
+    /// `Z = lambda` (`1` by default).
+    ///
+    fn ladder(
+        &self,
+        a: &CurvePoint,
+        d: &BigUint,
+        bits: u64,
+        lambda: Option<&BigUint>,
+    ) -> Result<CurvePoint, EllipticCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+
+        let (x, y) = match a {
+            CurvePoint::Coordinate(x, y) => (x, y),
+            CurvePoint::Identity => return Ok(CurvePoint::Identity),
+        };
+
+        let field = CtField::new(&self.p)
+            .map_err(|_| EllipticCurveError::InvalidCurve("The field modulus must be an odd prime".into()))?;
+        let element = |value: &BigUint| field.element(&(value % &self.p)).expect("reduced mod p");
+        let curve_a = element(&self.a);
+        let b3 = element(&(BigUint::from(3u32) * &self.b));
+
+        let bits = bits as usize;
+        let scalar = ct_field::to_limbs(d, bits.div_ceil(64));
+
+        let z = lambda.map(element).unwrap_or_else(|| field.one());
+        let base = ProjectivePoint { x: field.mul(&element(x), &z), y: field.mul(&element(y), &z), z };
+        let mut r0 = ProjectivePoint { x: field.zero(), y: field.one(), z: field.zero() };
+        let mut r1 = ProjectivePoint { x: base.x.clone(), y: base.y.clone(), z: base.z.clone() };
+
+        for i in (0..bits).rev() {
+            let bit = Choice::from(((scalar[i / 64] >> (i % 64)) & 1) as u8);
+            ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = complete_add(&field, &curve_a, &b3, &r0, &r1);
+            r0 = complete_add(&field, &curve_a, &b3, &r0, &r0);
+            ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        // the result being the identity is public (e.g. d multiple of the order)
+        if bool::from(field.is_zero(&r0.z)) {
+            return Ok(CurvePoint::Identity);
+        }
+        let z_inv = field.invert(&r0.z);
+        Ok(CurvePoint::Coordinate(
+            field.to_biguint(&field.mul(&r0.x, &z_inv)),
+            field.to_biguint(&field.mul(&r0.y, &z_inv)),
+        ))
+    }
+}
+
+///
+/// Complete addition for `y^2 = x^3 + a x + b`, with `b3 = 3 b`
+/// (Renes-Costello-Batina, algorithm 1).
+///
+fn complete_add(
+    field: &CtField,
+    a: &CtFieldElement,
+    b3: &CtFieldElement,
+    p: &ProjectivePoint,
+    q: &ProjectivePoint,
+) -> ProjectivePoint {
+    let t0 = field.mul(&p.x, &q.x);
+    let t1 = field.mul(&p.y, &q.y);
+    let t2 = field.mul(&p.z, &q.z);
+
+    let t3 = field.mul(&field.add(&p.x, &p.y), &field.add(&q.x, &q.y));
+    let t3 = field.sub(&t3, &field.add(&t0, &t1));
+    let t4 = field.mul(&field.add(&p.x, &p.z), &field.add(&q.x, &q.z));
+    let t4 = field.sub(&t4, &field.add(&t0, &t2));
+    let t5 = field.mul(&field.add(&p.y, &p.z), &field.add(&q.y, &q.z));
+    let t5 = field.sub(&t5, &field.add(&t1, &t2));
+
+    let z3 = field.add(&field.mul(b3, &t2), &field.mul(a, &t4));
+    let x3 = field.sub(&t1, &z3);
+    let z3 = field.add(&t1, &z3);
+    let y3 = field.mul(&x3, &z3);
+
+    let t1 = field.add(&field.add(&t0, &t0), &t0);
+    let t2 = field.mul(a, &t2);
+    let t4 = field.mul(b3, &t4);
+    let t1 = field.add(&t1, &t2);
+    let t2 = field.mul(a, &field.sub(&t0, &t2));
+    let t4 = field.add(&t4, &t2);
+
+    let y3 = field.add(&y3, &field.mul(&t1, &t4));
+    let x3 = field.sub(&field.mul(&t3, &x3), &field.mul(&t5, &t4));
+    let z3 = field.add(&field.mul(&t5, &z3), &field.mul(&t3, &t1));
+
+    ProjectivePoint { x: x3, y: y3, z: z3 }
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_scalar_mul_ct_small_curve() {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+
+        for d in 1..=40u32 {
+            let d = BigUint::from(d);
+            assert_eq!(ec.scalar_mul_ct(&g, &d), ec.scalar_mul(&g, &d));
+        }
+        assert_eq!(ec.scalar_mul_ct(&g, &BigUint::from(19u32)), Ok(CurvePoint::Identity));
+        assert_eq!(ec.scalar_mul_ct(&CurvePoint::Identity, &BigUint::from(3u32)), Ok(CurvePoint::Identity));
+
+        assert_eq!(ec.scalar_mul_ct(&g, &BigUint::from(0u32)), Ok(CurvePoint::Identity));
+        assert!(ec.scalar_mul_ct(&CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(2u32)), &BigUint::from(1u32)).is_err());
+    }
+
+    #[test]
+    fn test_scalar_mul_ct_named_curves() {
+        for params in [curves::secp256k1(), curves::p256(), curves::p521(), curves::brainpool_p384r1()] {
+            let d = (&params.n >> 1) + BigUint::from(12345u32);
+            let expected = params.curve.scalar_mul(&params.g, &d).unwrap();
+
+            assert_eq!(params.curve.scalar_mul_with(&params.g, &d, FieldBackend::ConstantTime).unwrap(), expected);
+            assert_eq!(params.curve.scalar_mul_with(&params.g, &d, FieldBackend::VariableTime).unwrap(), expected);
+            assert_eq!(params.curve.scalar_mul_with(&params.g, &d, FieldBackend::FixedWidth).unwrap(), expected);
+            assert_eq!(params.curve.scalar_mul_ct(&params.g, &params.n), Ok(CurvePoint::Identity));
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_blinded() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let params = curves::p256();
+        let d = BigUint::from(0xdeadbeefu32) << 100;
+        let expected = params.curve.scalar_mul(&params.g, &d).unwrap();
+
+        for (scalar, coordinates) in [(true, true), (true, false), (false, true), (false, false)] {
+            let blinding = Blinding { scalar, coordinates };
+            let res = params.curve.scalar_mul_blinded(&params.g, &d, &params.n, blinding, &mut rng);
+            assert_eq!(res.unwrap(), expected);
+        }
+
+        // small curve, scalars above the order
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        for d in 1..=40u32 {
+            let d = BigUint::from(d);
+            let res = ec.scalar_mul_blinded(&g, &d, &BigUint::from(19u32), Blinding::default(), &mut rng);
+            assert_eq!(res, ec.scalar_mul(&g, &d));
+        }
+
+        let zero = ec.scalar_mul_blinded(&g, &BigUint::from(0u32), &BigUint::from(19u32), Blinding::default(), &mut rng);
+        assert_eq!(zero, Ok(CurvePoint::Identity));
+        assert!(ec.scalar_mul_blinded(&g, &BigUint::from(1u32), &BigUint::from(0u32), Blinding::default(), &mut rng).is_err());
+    }
+}
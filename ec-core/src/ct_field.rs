@@ -0,0 +1,369 @@
+/*!
+A constant time backend for the arithmetic of a prime field `Fp`.
+
+The `finite_fields` functions work on `BigUint`, whose size, comparisons and
+reductions depend on the values, so they leak timing about secret inputs. Here
+an element is a fixed number of 64 bits limbs (as many as `p` needs) kept in
+Montgomery form `a R mod p` with `R = 2^(64 n)`:
+
+- additions and subtractions always compute both the result and the result
+  corrected by `p`, then pick one with a mask
+- products use the CIOS Montgomery multiplication, ending with the same masked
+  correction
+- comparisons and selections go through `subtle::Choice`
+
+The running time only depends on the number of limbs, i.e. on the public
+modulus. Only the conversions from and to `BigUint` are variable time.
+*/
+
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::finite_fields::FiniteFieldError;
+
+///
+/// The field `Fp` for an odd modulus `p`, with the Montgomery constants
+/// precomputed.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct CtField {
+    modulus: BigUint,
+    p: Vec<u64>,
+    // -p^(-1) mod 2^64
+    p_inv: u64,
+    // R mod p and R^2 mod p
+    r: Vec<u64>,
+    r2: Vec<u64>,
+}
+
+///
+/// An element of a `CtField`, in Montgomery form. It must only be used with
+/// the field that created it.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CtFieldElement {
+    limbs: Vec<u64>,
+}
+
+impl CtField {
+    pub fn new(p: &BigUint) -> Result<Self, FiniteFieldError> {
+        if *p <= BigUint::from(2u32) || !p.bit(0) {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} is not an odd modulus", p)));
+        }
+
+        let n = p.to_u64_digits().len();
+        let limbs = to_limbs(p, n);
+
+        // Newton iteration doubling the number of correct bits of p0^(-1)
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+        }
+
+        let r = (BigUint::from(1u32) << (64 * n)) % p;
+        let r2 = (&r * &r) % p;
+
+        Ok(CtField {
+            modulus: p.clone(),
+            p: limbs,
+            p_inv: inv.wrapping_neg(),
+            r: to_limbs(&r, n),
+            r2: to_limbs(&r2, n),
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Number of 64 bits limbs of an element
+    pub fn limbs(&self) -> usize {
+        self.p.len()
+    }
+
+    pub fn zero(&self) -> CtFieldElement {
+        CtFieldElement { limbs: vec![0; self.limbs()] }
+    }
+
+    pub fn one(&self) -> CtFieldElement {
+        CtFieldElement { limbs: self.r.clone() }
+    }
+
+    ///
+    /// Converts `a` in `[0, p - 1]` to Montgomery form: `a R = mont(a, R^2)`.
+    ///
+    pub fn element(&self, a: &BigUint) -> Result<CtFieldElement, FiniteFieldError> {
+        if *a >= self.modulus {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} is not lower than {}", a, self.modulus)));
+        }
+
+        Ok(CtFieldElement { limbs: self.mont_mul(&to_limbs(a, self.limbs()), &self.r2) })
+    }
+
+    ///
+    /// Converts back from Montgomery form: `a = mont(a R, 1)`.
+    ///
+    pub fn to_biguint(&self, a: &CtFieldElement) -> BigUint {
+        let mut one = vec![0u64; self.limbs()];
+        one[0] = 1;
+        from_limbs(&self.mont_mul(&a.limbs, &one))
+    }
+
+    pub fn add(&self, a: &CtFieldElement, b: &CtFieldElement) -> CtFieldElement {
+        let (sum, carry) = add_limbs(&a.limbs, &b.limbs);
+        let (reduced, borrow) = sub_limbs(&sum, &self.p);
+
+        // a + b >= p when the sum overflowed or the subtraction did not borrow
+        let use_reduced = Choice::from(carry as u8) | !Choice::from(borrow as u8);
+        CtFieldElement { limbs: select_limbs(&sum, &reduced, use_reduced) }
+    }
+
+    pub fn sub(&self, a: &CtFieldElement, b: &CtFieldElement) -> CtFieldElement {
+        let (difference, borrow) = sub_limbs(&a.limbs, &b.limbs);
+        let (corrected, _) = add_limbs(&difference, &self.p);
+
+        CtFieldElement { limbs: select_limbs(&difference, &corrected, Choice::from(borrow as u8)) }
+    }
+
+    pub fn neg(&self, a: &CtFieldElement) -> CtFieldElement {
+        self.sub(&self.zero(), a)
+    }
+
+    pub fn mul(&self, a: &CtFieldElement, b: &CtFieldElement) -> CtFieldElement {
+        CtFieldElement { limbs: self.mont_mul(&a.limbs, &b.limbs) }
+    }
+
+    pub fn square(&self, a: &CtFieldElement) -> CtFieldElement {
+        self.mul(a, a)
+    }
+
+    ///
+    /// `a^e`, where the exponent `e` is public: the sequence of operations
+    /// depends on the bits of `e` but not on `a`.
+    ///
+    pub fn pow_public(&self, a: &CtFieldElement, e: &BigUint) -> CtFieldElement {
+        let mut result = self.one();
+        for i in (0..e.bits()).rev() {
+            result = self.square(&result);
+            if e.bit(i) {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+
+    ///
+    /// `a^(-1) = a^(p - 2)` by Fermat's little theorem (`p` prime). The
+    /// inverse of `0` is `0`.
+    ///
+    pub fn invert(&self, a: &CtFieldElement) -> CtFieldElement {
+        self.pow_public(a, &(&self.modulus - BigUint::from(2u32)))
+    }
+
+    pub fn is_zero(&self, a: &CtFieldElement) -> Choice {
+        a.ct_eq(&self.zero())
+    }
+
+    ///
+    /// CIOS Montgomery multiplication `a b R^(-1) mod p`, for `a, b < p`.
+    ///
+    fn mont_mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.limbs();
+        let mut t = vec![0u64; n + 2];
+
+        for &a_i in a.iter() {
+            // t += a_i b
+            let mut carry = 0u64;
+            for j in 0..n {
+                let uv = t[j] as u128 + a_i as u128 * b[j] as u128 + carry as u128;
+                t[j] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[n] as u128 + carry as u128;
+            t[n] = uv as u64;
+            t[n + 1] = (uv >> 64) as u64;
+
+            // t = (t + m p) / 2^64 where m makes the low limb vanish
+            let m = t[0].wrapping_mul(self.p_inv);
+            let uv = t[0] as u128 + m as u128 * self.p[0] as u128;
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..n {
+                let uv = t[j] as u128 + m as u128 * self.p[j] as u128 + carry as u128;
+                t[j - 1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[n] as u128 + carry as u128;
+            t[n - 1] = uv as u64;
+            t[n] = t[n + 1] + (uv >> 64) as u64;
+        }
+
+        // t < 2p, subtract p unless it borrows past the top limb
+        let (reduced, borrow) = sub_limbs(&t[..n], &self.p);
+        let use_reduced = !Choice::from(borrow as u8) | !t[n].ct_eq(&0);
+        select_limbs(&t[..n], &reduced, use_reduced)
+    }
+}
+
+impl ConstantTimeEq for CtFieldElement {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs
+            .iter()
+            .zip(other.limbs.iter())
+            .fold(Choice::from(1u8), |equal, (a, b)| equal & a.ct_eq(b))
+    }
+}
+
+impl CtFieldElement {
+    ///
+    /// Returns `b` if `choice` is set, `a` otherwise.
+    ///
+    pub fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CtFieldElement { limbs: select_limbs(&a.limbs, &b.limbs, choice) }
+    }
+
+    ///
+    /// Swaps `a` and `b` if `choice` is set.
+    ///
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        for (a_i, b_i) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            u64::conditional_swap(a_i, b_i, choice);
+        }
+    }
+}
+
+///
+/// The `n` little endian limbs of `a < 2^(64 n)`.
+///
+pub(crate) fn to_limbs(a: &BigUint, n: usize) -> Vec<u64> {
+    let mut limbs = a.to_u64_digits();
+    limbs.resize(n, 0);
+    limbs
+}
+
+fn from_limbs(limbs: &[u64]) -> BigUint {
+    BigUint::from_slice(
+        &limbs.iter().flat_map(|limb| [*limb as u32, (*limb >> 32) as u32]).collect::<Vec<_>>(),
+    )
+}
+
+fn add_limbs(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
+    let mut carry = 0u64;
+    let sum = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a_i, b_i)| {
+            let uv = *a_i as u128 + *b_i as u128 + carry as u128;
+            carry = (uv >> 64) as u64;
+            uv as u64
+        })
+        .collect();
+    (sum, carry)
+}
+
+fn sub_limbs(a: &[u64], b: &[u64]) -> (Vec<u64>, u64) {
+    let mut borrow = 0u64;
+    let difference = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a_i, b_i)| {
+            let uv = (*a_i as u128).wrapping_sub(*b_i as u128 + borrow as u128);
+            borrow = (uv >> 127) as u64;
+            uv as u64
+        })
+        .collect();
+    (difference, borrow)
+}
+
+fn select_limbs(a: &[u64], b: &[u64], choice: Choice) -> Vec<u64> {
+    a.iter().zip(b.iter()).map(|(a_i, b_i)| u64::conditional_select(a_i, b_i, choice)).collect()
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+    use crate::finite_fields;
+
+    #[test]
+    fn test_new() {
+        assert!(CtField::new(&BigUint::from(2u32)).is_err());
+        assert!(CtField::new(&BigUint::from(16u32)).is_err());
+
+        let field = CtField::new(&curves::p521().curve.p).unwrap();
+        assert_eq!(field.limbs(), 9);
+        assert_eq!(field.to_biguint(&field.one()), BigUint::from(1u32));
+        assert!(bool::from(field.is_zero(&field.zero())));
+    }
+
+    #[test]
+    fn test_matches_finite_fields() {
+        // single limb, a full limb and multi limbs moduli
+        let moduli = [
+            BigUint::from(17u32),
+            BigUint::from(0xffff_ffff_ffff_ffc5u64),
+            curves::secp256k1().curve.p,
+            curves::p384().curve.p,
+            curves::brainpool_p512r1().curve.p,
+        ];
+
+        for p in moduli {
+            let field = CtField::new(&p).unwrap();
+            let values = [
+                BigUint::from(0u32),
+                BigUint::from(1u32),
+                BigUint::from(2u32),
+                &p >> 1,
+                &p - BigUint::from(2u32),
+                &p - BigUint::from(1u32),
+            ];
+
+            for a in values.iter() {
+                let a_ct = field.element(a).unwrap();
+                assert_eq!(field.to_biguint(&a_ct), *a);
+                assert_eq!(field.to_biguint(&field.neg(&a_ct)), finite_fields::inverse_add(a, &p).unwrap());
+                assert_eq!(field.to_biguint(&field.invert(&a_ct)), finite_fields::inverse_multiplicate_prime(a, &p).unwrap());
+
+                for b in values.iter() {
+                    let b_ct = field.element(b).unwrap();
+                    assert_eq!(field.to_biguint(&field.add(&a_ct, &b_ct)), finite_fields::add(a, b, &p).unwrap());
+                    assert_eq!(field.to_biguint(&field.sub(&a_ct, &b_ct)), finite_fields::subtract(a, b, &p).unwrap());
+                    assert_eq!(field.to_biguint(&field.mul(&a_ct, &b_ct)), finite_fields::multiplicate(a, b, &p).unwrap());
+                    assert_eq!(bool::from(a_ct.ct_eq(&b_ct)), a == b);
+                }
+            }
+
+            assert!(field.element(&p).is_err());
+        }
+    }
+
+    #[test]
+    fn test_conditional_select_swap() {
+        let field = CtField::new(&BigUint::from(17u32)).unwrap();
+        let mut a = field.element(&BigUint::from(3u32)).unwrap();
+        let mut b = field.element(&BigUint::from(5u32)).unwrap();
+
+        assert_eq!(CtFieldElement::conditional_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(CtFieldElement::conditional_select(&a, &b, Choice::from(1)), b);
+
+        CtFieldElement::conditional_swap(&mut a, &mut b, Choice::from(0));
+        assert_eq!(field.to_biguint(&a), BigUint::from(3u32));
+        CtFieldElement::conditional_swap(&mut a, &mut b, Choice::from(1));
+        assert_eq!(field.to_biguint(&a), BigUint::from(5u32));
+        assert_eq!(field.to_biguint(&b), BigUint::from(3u32));
+    }
+}
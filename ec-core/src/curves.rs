@@ -0,0 +1,704 @@
+/*!
+Named curves with their domain parameters as specified in the
+[SEC 2](https://www.secg.org/sec2-v2.pdf) standard and in
+[RFC 5639](https://www.rfc-editor.org/rfc/rfc5639) (Brainpool), so the hex
+constants do not have to be copied around (and mistyped) by every user of this
+library.
+*/
+
+use alloc::boxed::Box;
+use core::fmt::Debug;
+use once_cell::race::OnceBox;
+
+use num_bigint::BigUint;
+use crate::edwards::{EdwardsCurve, EdwardsParams, EdwardsPoint};
+use crate::elliptic_curve::{CurvePoint, EllipticCurve, EllipticCurveError};
+use crate::finite_fields::Reducer;
+use crate::montgomery::MontgomeryCurve;
+
+///
+/// Domain parameters of an elliptic curve group:
+///
+/// - `curve`: the curve `y^2 = x^3 + ax + b mod p`
+/// - `g`: the generator of the group
+/// - `n`: the order of the generator, `n * G = I`
+/// - `h`: the cofactor, `#E(Fp) = h * n`
+///
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurveParams {
+    pub curve: EllipticCurve,
+    pub g: CurvePoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub n: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub h: BigUint,
+}
+
+impl CurveParams {
+    pub fn new(curve: EllipticCurve, g: CurvePoint, n: BigUint, h: BigUint) -> Self {
+        CurveParams { curve, g, n, h }
+    }
+
+    /// The curve `y^2 = x^3 + ax + b mod p` the group lives in.
+    pub fn curve(&self) -> &EllipticCurve {
+        &self.curve
+    }
+
+    /// The generator `G` of the group.
+    pub fn generator(&self) -> &CurvePoint {
+        &self.g
+    }
+
+    /// The order `n` of the generator.
+    pub fn order(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// The cofactor `h = #E(Fp) / n`.
+    pub fn cofactor(&self) -> &BigUint {
+        &self.h
+    }
+
+    ///
+    /// Whether `a` is a point of the curve in the subgroup of order `n`
+    /// generated by `G`, the identity included. With `h = 1` that is every
+    /// point of the curve; otherwise `n A = I` is checked, rejecting the
+    /// points of small order a peer could send to learn bits of a secret
+    /// key (small subgroup attacks).
+    ///
+    pub fn is_in_prime_subgroup(&self, a: &CurvePoint) -> bool {
+        if !self.curve.is_on_curve(a) {
+            return false;
+        }
+        if self.h == BigUint::from(1u32) {
+            return true;
+        }
+        self.curve.scalar_mul(a, &self.n) == Ok(CurvePoint::Identity)
+    }
+
+    ///
+    /// `B = (d mod n) * A`, so scalars of any size cost no more than the bits
+    /// of `n`. It equals `d * A` for the points of the subgroup generated by
+    /// `G`, which are all the points of the curve when `h = 1`; on a curve
+    /// with a cofactor `A` has to be checked to be in that subgroup first.
+    ///
+    pub fn scalar_mul_mod_order(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        self.curve.scalar_mul(a, &(d % &self.n))
+    }
+}
+
+
+///
+/// secp256k1: the Koblitz curve used by Bitcoin and Ethereum
+///
+/// `y^2 = x^3 + 7 mod p`
+///
+pub fn secp256k1() -> CurveParams {
+    CurveParams {
+        curve: EllipticCurve {
+            a: BigUint::from(0u32),
+            b: BigUint::from(7u32),
+            p: hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"),
+        },
+        g: CurvePoint::Coordinate(
+            hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        ),
+        n: hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// NIST P-256 (secp256r1)
+///
+/// `y^2 = x^3 - 3x + b mod p`
+///
+pub fn p256() -> CurveParams {
+    let p = hex("FFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF");
+    CurveParams {
+        curve: EllipticCurve {
+            a: &p - BigUint::from(3u32),
+            b: hex("5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B"),
+            p,
+        },
+        g: CurvePoint::Coordinate(
+            hex("6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296"),
+            hex("4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5"),
+        ),
+        n: hex("FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// NIST P-384 (secp384r1)
+///
+/// `y^2 = x^3 - 3x + b mod p`
+///
+pub fn p384() -> CurveParams {
+    let p = hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFF");
+    CurveParams {
+        curve: EllipticCurve {
+            a: &p - BigUint::from(3u32),
+            b: hex("B3312FA7E23EE7E4988E056BE3F82D19181D9C6EFE8141120314088F5013875AC656398D8A2ED19D2A85C8EDD3EC2AEF"),
+            p,
+        },
+        g: CurvePoint::Coordinate(
+            hex("AA87CA22BE8B05378EB1C71EF320AD746E1D3B628BA79B9859F741E082542A385502F25DBF55296C3A545E3872760AB7"),
+            hex("3617DE4A96262C6F5D9E98BF9292DC29F8F41DBD289A147CE9DA3113B5F0B8C00A60B1CE1D7E819D7A431D7C90EA0E5F"),
+        ),
+        n: hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC7634D81F4372DDF581A0DB248B0A77AECEC196ACCC52973"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// NIST P-521 (secp521r1)
+///
+/// `y^2 = x^3 - 3x + b mod p` with `p = 2^521 - 1`
+///
+pub fn p521() -> CurveParams {
+    let p = (BigUint::from(1u32) << 521) - BigUint::from(1u32);
+    CurveParams {
+        curve: EllipticCurve {
+            a: &p - BigUint::from(3u32),
+            b: hex("0051953EB9618E1C9A1F929A21A0B68540EEA2DA725B99B315F3B8B489918EF109E156193951EC7E937B1652C0BD3BB1BF073573DF883D2C34F1EF451FD46B503F00"),
+            p,
+        },
+        g: CurvePoint::Coordinate(
+            hex("00C6858E06B70404E9CD9E3ECB662395B4429C648139053FB521F828AF606B4D3DBAA14B5E77EFE75928FE1DC127A2FFA8DE3348B3C1856A429BF97E7E31C2E5BD66"),
+            hex("011839296A789A3BC0045C8A5FB42C7D1BD998F54449579B446817AFBD17273E662C97EE72995EF42640C550B9013FAD0761353C7086A272C24088BE94769FD16650"),
+        ),
+        n: hex("01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFA51868783BF2F966B7FCC0148F709A5D03BB5C9B8899C47AEBB6FB71E91386409"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// brainpoolP256r1 as specified in RFC 5639
+///
+/// `y^2 = x^3 + ax + b mod p`
+///
+pub fn brainpool_p256r1() -> CurveParams {
+    CurveParams {
+        curve: EllipticCurve {
+            a: hex("7D5A0975FC2C3057EEF67530417AFFE7FB8055C126DC5C6CE94A4B44F330B5D9"),
+            b: hex("26DC5C6CE94A4B44F330B5D9BBD77CBF958416295CF7E1CE6BCCDC18FF8C07B6"),
+            p: hex("A9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5377"),
+        },
+        g: CurvePoint::Coordinate(
+            hex("8BD2AEB9CB7E57CB2C4B482FFC81B7AFB9DE27E1E3BD23C23A4453BD9ACE3262"),
+            hex("547EF835C3DAC4FD97F8461A14611DC9C27745132DED8E545C1D54C72F046997"),
+        ),
+        n: hex("A9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// brainpoolP384r1 as specified in RFC 5639
+///
+/// `y^2 = x^3 + ax + b mod p`
+///
+pub fn brainpool_p384r1() -> CurveParams {
+    CurveParams {
+        curve: EllipticCurve {
+            a: hex("7BC382C63D8C150C3C72080ACE05AFA0C2BEA28E4FB22787139165EFBA91F90F8AA5814A503AD4EB04A8C7DD22CE2826"),
+            b: hex("04A8C7DD22CE28268B39B55416F0447C2FB77DE107DCD2A62E880EA53EEB62D57CB4390295DBC9943AB78696FA504C11"),
+            p: hex("8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B412B1DA197FB71123ACD3A729901D1A71874700133107EC53"),
+        },
+        g: CurvePoint::Coordinate(
+            hex("1D1C64F068CF45FFA2A63A81B7C13F6B8847A3E77EF14FE3DB7FCAFE0CBD10E8E826E03436D646AAEF87B2E247D4AF1E"),
+            hex("8ABE1D7520F9C2A45CB1EB8E95CFD55262B70B29FEEC5864E19C054FF99129280E4646217791811142820341263C5315"),
+        ),
+        n: hex("8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B31F166E6CAC0425A7CF3AB6AF6B7FC3103B883202E9046565"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// brainpoolP512r1 as specified in RFC 5639
+///
+/// `y^2 = x^3 + ax + b mod p`
+///
+pub fn brainpool_p512r1() -> CurveParams {
+    CurveParams {
+        curve: EllipticCurve {
+            a: hex("7830A3318B603B89E2327145AC234CC594CBDD8D3DF91610A83441CAEA9863BC2DED5D5AA8253AA10A2EF1C98B9AC8B57F1117A72BF2C7B9E7C1AC4D77FC94CA"),
+            b: hex("3DF91610A83441CAEA9863BC2DED5D5AA8253AA10A2EF1C98B9AC8B57F1117A72BF2C7B9E7C1AC4D77FC94CADC083E67984050B75EBAE5DD2809BD638016F723"),
+            p: hex("AADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA703308717D4D9B009BC66842AECDA12AE6A380E62881FF2F2D82C68528AA6056583A48F3"),
+        },
+        g: CurvePoint::Coordinate(
+            hex("81AEE4BDD82ED9645A21322E9C4C6A9385ED9F70B5D916C1B43B62EEF4D0098EFF3B1F78E2D0D48D50D1687B93B97D5F7C6D5047406A5E688B352209BCB9F822"),
+            hex("7DDE385D566332ECC0EABFA9CF7822FDF209F70024A57B1AA000C55B881F8111B2DCDE494A5F485E5BCA4BD88A2763AED1CA2B2FA8F0540678CD1E0F3AD80892"),
+        ),
+        n: hex("AADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA70330870553E5C414CA92619418661197FAC10471DB1D381085DDADDB58796829CA90069"),
+        h: BigUint::from(1u32),
+    }
+}
+
+
+///
+/// edwards25519, the twisted Edwards curve used by Ed25519 (RFC 8032)
+///
+/// `-x^2 + y^2 = 1 + d x^2 y^2 mod p` with `p = 2^255 - 19`
+///
+pub fn ed25519() -> EdwardsParams {
+    let p = (BigUint::from(1u32) << 255) - BigUint::from(19u32);
+    EdwardsParams {
+        curve: EdwardsCurve {
+            a: &p - BigUint::from(1u32),
+            d: hex("52036CEE2B6FFE738CC740797779E89800700A4D4141D8AB75EB4DCA135978A3"),
+            p,
+        },
+        g: EdwardsPoint::new(
+            hex("216936D3CD6E53FEC0A4E231FDD6DC5C692CC7609525A7B2C9562D608F25D51A"),
+            hex("6666666666666666666666666666666666666666666666666666666666666658"),
+        ),
+        n: (BigUint::from(1u32) << 252) + hex("14DEF9DEA2F79CD65812631A5CF5D3ED"),
+        h: BigUint::from(8u32),
+    }
+}
+
+
+///
+/// curve25519, the Montgomery curve used by X25519 (RFC 7748), whose base
+/// point is `u = 9`
+///
+/// `y^2 = x^3 + 486662 x^2 + x mod p` with `p = 2^255 - 19`
+///
+pub fn curve25519() -> MontgomeryCurve {
+    MontgomeryCurve {
+        a: BigUint::from(486662u32),
+        b: BigUint::from(1u32),
+        p: (BigUint::from(1u32) << 255) - BigUint::from(19u32),
+    }
+}
+
+
+///
+/// A curve of the registry: its canonical name, the other names it is known
+/// by and its ASN.1 object identifier.
+///
+struct NamedCurve {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    oid: &'static [u64],
+    params: fn() -> CurveParams,
+    cache: OnceBox<CurveParams>,
+    reducer: OnceBox<Reducer>,
+}
+
+impl NamedCurve {
+    const fn new(
+        name: &'static str,
+        aliases: &'static [&'static str],
+        oid: &'static [u64],
+        params: fn() -> CurveParams,
+    ) -> Self {
+        NamedCurve { name, aliases, oid, params, cache: OnceBox::new(), reducer: OnceBox::new() }
+    }
+
+    fn params(&'static self) -> &'static CurveParams {
+        self.cache.get_or_init(|| Box::new((self.params)()))
+    }
+
+    fn reducer(&'static self) -> &'static Reducer {
+        self.reducer
+            .get_or_init(|| Box::new(Reducer::new(&self.params().curve.p).expect("p is greater than 1")))
+    }
+
+    fn has_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+
+static NAMED_CURVES: [NamedCurve; 7] = [
+    NamedCurve::new("secp256k1", &[], &[1, 3, 132, 0, 10], secp256k1),
+    NamedCurve::new("P-256", &["secp256r1", "prime256v1"], &[1, 2, 840, 10045, 3, 1, 7], p256),
+    NamedCurve::new("P-384", &["secp384r1"], &[1, 3, 132, 0, 34], p384),
+    NamedCurve::new("P-521", &["secp521r1"], &[1, 3, 132, 0, 35], p521),
+    NamedCurve::new("brainpoolP256r1", &[], &[1, 3, 36, 3, 3, 2, 8, 1, 1, 7], brainpool_p256r1),
+    NamedCurve::new("brainpoolP384r1", &[], &[1, 3, 36, 3, 3, 2, 8, 1, 1, 11], brainpool_p384r1),
+    NamedCurve::new("brainpoolP512r1", &[], &[1, 3, 36, 3, 3, 2, 8, 1, 1, 13], brainpool_p512r1),
+];
+
+
+///
+/// Looks up a named curve by its name (case insensitive), e.g. `secp256k1`,
+/// `P-256`, `prime256v1` or `brainpoolP384r1`. The parameters are built once
+/// and shared by every caller.
+///
+pub fn by_name(name: &str) -> Option<&'static CurveParams> {
+    NAMED_CURVES.iter().find(|curve| curve.has_name(name)).map(NamedCurve::params)
+}
+
+
+///
+/// Looks up a named curve by the arcs of its object identifier, e.g.
+/// `[1, 3, 132, 0, 10]` for secp256k1, as found in certificates and PKCS#8
+/// structures.
+///
+pub fn by_oid(oid: &[u64]) -> Option<&'static CurveParams> {
+    NAMED_CURVES.iter().find(|curve| curve.oid == oid).map(NamedCurve::params)
+}
+
+
+
+///
+/// The arcs of the object identifier of a named curve, the reverse of
+/// `by_oid`. Returns `None` for curves which are not in the registry.
+///
+pub fn oid_of(params: &CurveParams) -> Option<&'static [u64]> {
+    NAMED_CURVES.iter().find(|curve| curve.params() == params).map(|curve| curve.oid)
+}
+
+///
+/// The canonical names of the curves of the registry, in the order of the
+/// registry.
+///
+pub fn names() -> impl Iterator<Item = &'static str> {
+    NAMED_CURVES.iter().map(|curve| curve.name)
+}
+
+///
+/// A curve of the registry selected at the type level, so keys and signatures
+/// can be tagged with their curve (`PublicKey<P256>`) and the compiler rejects
+/// mixing them. The domain parameters are the ones of `by_name(Self::NAME)`,
+/// built once.
+///
+pub trait Curve: Copy + Clone + Debug + Default + PartialEq + Eq + Send + Sync + 'static {
+    /// Canonical name of the curve in the registry.
+    const NAME: &'static str;
+
+    fn params() -> &'static CurveParams {
+        by_name(Self::NAME).expect("a Curve is a named curve of the registry")
+    }
+
+    /// `a` of `y^2 = x^3 + ax + b mod p`
+    fn a() -> &'static BigUint {
+        &Self::params().curve.a
+    }
+
+    /// `b` of `y^2 = x^3 + ax + b mod p`
+    fn b() -> &'static BigUint {
+        &Self::params().curve.b
+    }
+
+    /// The field prime `p`.
+    fn p() -> &'static BigUint {
+        &Self::params().curve.p
+    }
+
+    /// The generator `G` of the group.
+    fn generator() -> &'static CurvePoint {
+        &Self::params().g
+    }
+
+    /// The order `n` of the generator.
+    fn order() -> &'static BigUint {
+        &Self::params().n
+    }
+
+    /// Byte size of a scalar, i.e. of an integer modulo `n`.
+    fn scalar_size() -> usize {
+        Self::order().bits().div_ceil(8) as usize
+    }
+
+    /// The Barrett reducer of `p`, built once.
+    fn reducer() -> &'static Reducer {
+        NAMED_CURVES
+            .iter()
+            .find(|curve| curve.has_name(Self::NAME))
+            .map(NamedCurve::reducer)
+            .expect("a Curve is a named curve of the registry")
+    }
+}
+
+macro_rules! named_curve {
+    ($(#[$doc:meta])* $marker:ident, $name:literal) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $marker;
+
+        impl Curve for $marker {
+            const NAME: &'static str = $name;
+        }
+    };
+}
+
+named_curve!(
+    /// secp256k1, see `secp256k1()`
+    Secp256k1, "secp256k1"
+);
+named_curve!(
+    /// NIST P-256, see `p256()`
+    P256, "P-256"
+);
+named_curve!(
+    /// NIST P-384, see `p384()`
+    P384, "P-384"
+);
+named_curve!(
+    /// NIST P-521, see `p521()`
+    P521, "P-521"
+);
+named_curve!(
+    /// brainpoolP256r1, see `brainpool_p256r1()`
+    BrainpoolP256r1, "brainpoolP256r1"
+);
+named_curve!(
+    /// brainpoolP384r1, see `brainpool_p384r1()`
+    BrainpoolP384r1, "brainpoolP384r1"
+);
+named_curve!(
+    /// brainpoolP512r1, see `brainpool_p512r1()`
+    BrainpoolP512r1, "brainpoolP512r1"
+);
+
+fn hex(value: &str) -> BigUint {
+    BigUint::parse_bytes(value.as_bytes(), 16).expect("invalid curve constant")
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::finite_fields::FieldArithmetic;
+
+    #[test]
+    fn test_curve_markers() {
+        assert_eq!(Secp256k1::params(), &secp256k1());
+        assert_eq!(P256::params(), &p256());
+        assert_eq!(P521::params(), &p521());
+        assert_eq!(BrainpoolP512r1::params(), &brainpool_p512r1());
+        assert!(core::ptr::eq(P384::params(), by_name("secp384r1").unwrap()));
+
+        assert_eq!(Secp256k1::b(), &BigUint::from(7u32));
+        assert_eq!(P256::a(), &(P256::p() - BigUint::from(3u32)));
+        assert_eq!(P256::generator(), &p256().g);
+        assert_eq!(P256::order(), &p256().n);
+        assert_eq!((P256::scalar_size(), P384::scalar_size(), P521::scalar_size()), (32, 48, 66));
+        assert_eq!(P384::reducer().modulus(), P384::p());
+        assert!(core::ptr::eq(P384::reducer(), P384::reducer()));
+    }
+
+    #[test]
+    fn test_secp256k1() {
+        let params = secp256k1();
+
+        assert!(params.curve.is_on_curve(&params.g));
+        assert_eq!(params.curve.scalar_mul(&params.g, &params.n), Ok(CurvePoint::Identity));
+        assert_eq!(params.h, BigUint::from(1u32));
+
+        // 3 * G, from the well known secp256k1 multiples
+        let g3 = params.curve.scalar_mul(&params.g, &BigUint::from(3u32)).unwrap();
+        assert_eq!(
+            g3,
+            CurvePoint::Coordinate(
+                hex("F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+                hex("388F7B0F632DE8140FE337E62A37F3566500A99934C2231B6CB9FD7584B8E672"),
+            )
+        );
+    }
+
+    // Known answers for `k * G` from <http://point-at-infinity.org/ecc/nisttv>
+    // and the public keys of the first FIPS 186-4 `SigGen.txt` vector.
+    fn check_known_answers(params: &CurveParams, mul: (&str, &str, &str), key: (&str, &str, &str)) {
+        assert!(params.curve.is_on_curve(&params.g));
+        assert_eq!(params.curve.scalar_mul(&params.g, &params.n), Ok(CurvePoint::Identity));
+
+        for (k, x, y) in [mul, key] {
+            let res = params.curve.scalar_mul(&params.g, &hex(k));
+            assert_eq!(res, Ok(CurvePoint::Coordinate(hex(x), hex(y))));
+        }
+    }
+
+    #[test]
+    fn test_p256() {
+        check_known_answers(
+            &p256(),
+            (
+                "018EBBB95EED0E13",
+                "339150844EC15234807FE862A86BE77977DBFB3AE3D96F4C22795513AEAAB82F",
+                "B1C14DDFDC8EC1B2583F51E85A5EB3A155840F2034730E9B5ADA38B674336A21",
+            ),
+            (
+                "519b423d715f8b581f4fa8ee59f4771a5b44c8130b4e3eacca54a56dda72b464",
+                "1ccbe91c075fc7f4f033bfa248db8fccd3565de94bbfb12f3c59ff46c271bf83",
+                "ce4014c68811f9a21a1fdb2c0e6113e06db7ca93b7404e78dc7ccd5ca89a4ca9",
+            ),
+        );
+    }
+
+    #[test]
+    fn test_p384() {
+        check_known_answers(
+            &p384(),
+            (
+                "018EBBB95EED0E13",
+                "A499EFE48839BC3ABCD1C5CEDBDD51904F9514DB44F4686DB918983B0C9DC3AEE05A88B72433E9515F91A329F5F4FA60",
+                "3B7CA28EF31F809C2F1BA24AAED847D0F8B406A4B8968542DE139DB5828CA410E615D1182E25B91B1131E230B727D36A",
+            ),
+            (
+                "201b432d8df14324182d6261db3e4b3f46a8284482d52e370da41e6cbdf45ec2952f5db7ccbce3bc29449f4fb080ac97",
+                "c2b47944fb5de342d03285880177ca5f7d0f2fcad7678cce4229d6e1932fcac11bfc3c3e97d942a3c56bf34123013dbf",
+                "37257906a8223866eda0743c519616a76a758ae58aee81c5fd35fbf3a855b7754a36d4a0672df95d6c44a81cf7620c2d",
+            ),
+        );
+    }
+
+    #[test]
+    fn test_p521() {
+        check_known_answers(
+            &p521(),
+            (
+                "018EBBB95EED0E13",
+                "01650048FBD63E8C30B305BF36BD7643B91448EF2206E8A0CA84A140789A99B0423A0A2533EA079CA7E049843E69E5FA2C25A163819110CEC1A30ACBBB3A422A40D8",
+                "010C9C64A0E0DB6052DBC5646687D06DECE5E9E0703153EFE9CB816FE025E85354D3C5F869D6DB3F4C0C01B5F97919A5E72CEEBE03042E5AA99112691CFFC2724828",
+            ),
+            (
+                "00f749d32704bc533ca82cef0acf103d8f4fba67f08d2678e515ed7db886267ffaf02fab0080dca2359b72f574ccc29a0f218c8655c0cccf9fee6c5e567aa14cb926",
+                "0061387fd6b95914e885f912edfbb5fb274655027f216c4091ca83e19336740fd81aedfe047f51b42bdf68161121013e0d55b117a14e4303f926c8debb77a7fdaad1",
+                "00e7d0c75c38626e895ca21526b9f9fdf84dcecb93f2b233390550d2b1463b7ee3f58df7346435ff0434199583c97c665a97f12f706f2357da4b40288def888e59e6",
+            ),
+        );
+    }
+
+    fn check_generator(params: &CurveParams) {
+        assert!(params.curve.is_on_curve(&params.g));
+        assert_eq!(params.curve.scalar_mul(&params.g, &params.n), Ok(CurvePoint::Identity));
+        assert_eq!(params.curve.p.bits(), params.n.bits());
+    }
+
+    #[test]
+    fn test_brainpool_p256r1() {
+        check_generator(&brainpool_p256r1());
+    }
+
+    #[test]
+    fn test_brainpool_p384r1() {
+        check_generator(&brainpool_p384r1());
+    }
+
+    #[test]
+    fn test_brainpool_p512r1() {
+        check_generator(&brainpool_p512r1());
+    }
+
+    #[test]
+    fn test_by_name() {
+        assert_eq!(by_name("secp256k1"), Some(&secp256k1()));
+        assert_eq!(by_name("P-256"), Some(&p256()));
+        assert_eq!(by_name("prime256v1"), Some(&p256()));
+        assert_eq!(by_name("SECP384R1"), Some(&p384()));
+        assert_eq!(by_name("brainpoolP512r1"), Some(&brainpool_p512r1()));
+        assert_eq!(by_name("curve25519"), None);
+        assert_eq!(names().count(), NAMED_CURVES.len());
+        assert!(names().all(|name| by_name(name).is_some()));
+
+        // the parameters are built once and shared
+        assert!(std::ptr::eq(by_name("P-521").unwrap(), by_name("secp521r1").unwrap()));
+    }
+
+    #[test]
+    fn test_by_oid() {
+        assert_eq!(by_oid(&[1, 3, 132, 0, 10]), Some(&secp256k1()));
+        assert_eq!(by_oid(&[1, 2, 840, 10045, 3, 1, 7]), Some(&p256()));
+        assert_eq!(by_oid(&[1, 3, 132, 0, 35]), Some(&p521()));
+        assert_eq!(by_oid(&[1, 3, 36, 3, 3, 2, 8, 1, 1, 7]), Some(&brainpool_p256r1()));
+        assert_eq!(by_oid(&[1, 3, 132, 0]), None);
+        assert_eq!(by_oid(&[]), None);
+    }
+
+    #[test]
+    fn test_oid_of() {
+        assert_eq!(oid_of(&secp256k1()), Some(&[1u64, 3, 132, 0, 10][..]));
+        assert_eq!(oid_of(&p384()), Some(&[1u64, 3, 132, 0, 34][..]));
+        for curve in NAMED_CURVES.iter() {
+            assert_eq!(by_oid(oid_of(curve.params()).unwrap()), Some(curve.params()));
+        }
+
+        let mut params = p256();
+        params.h = BigUint::from(2u32);
+        assert_eq!(oid_of(&params), None);
+    }
+
+    #[test]
+    fn test_curve_params_accessors() {
+        let params = CurveParams::new(
+            EllipticCurve {
+                a: BigUint::from(2u32),
+                b: BigUint::from(2u32),
+                p: BigUint::from(17u32),
+            },
+            CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)),
+            BigUint::from(19u32),
+            BigUint::from(1u32),
+        );
+
+        assert_eq!(params.curve().p, BigUint::from(17u32));
+        assert_eq!(params.generator(), &CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)));
+        assert_eq!(params.order(), &BigUint::from(19u32));
+        assert_eq!(params.cofactor(), &BigUint::from(1u32));
+    }
+
+    #[test]
+    fn test_is_in_prime_subgroup() {
+        // y^2 = x^3 + x + 5 mod 23 has 22 points: n = 11, h = 2
+        let params = CurveParams::new(
+            EllipticCurve::new(BigUint::from(1u32), BigUint::from(5u32), BigUint::from(23u32)).unwrap(),
+            CurvePoint::Coordinate(BigUint::from(11u32), BigUint::from(6u32)),
+            BigUint::from(11u32),
+            BigUint::from(2u32),
+        );
+        let point = |x: u32, y: u32| CurvePoint::Coordinate(BigUint::from(x), BigUint::from(y));
+
+        assert!(params.is_in_prime_subgroup(&params.g));
+        assert!(params.is_in_prime_subgroup(&params.curve.scalar_mul(&params.g, &BigUint::from(3u32)).unwrap()));
+        assert!(params.is_in_prime_subgroup(&CurvePoint::Identity));
+        // of order 2 and 22
+        assert!(!params.is_in_prime_subgroup(&point(16, 0)));
+        assert!(!params.is_in_prime_subgroup(&point(3, 9)));
+        assert!(!params.is_in_prime_subgroup(&point(1, 1)));
+
+        let params = secp256k1();
+        assert!(params.is_in_prime_subgroup(&params.g));
+        assert!(!params.is_in_prime_subgroup(&p256().g));
+    }
+
+    #[test]
+    fn test_scalar_mul_mod_order() {
+        let params = secp256k1();
+        let d = BigUint::from(0xDEADBEEFu32);
+        let expected = params.curve.scalar_mul(&params.g, &d).unwrap();
+
+        assert_eq!(params.scalar_mul_mod_order(&params.g, &d).unwrap(), expected);
+        assert_eq!(params.scalar_mul_mod_order(&params.g, &(&d + &params.n * 5u32)).unwrap(), expected);
+        assert_eq!(params.scalar_mul_mod_order(&params.g, &params.n).unwrap(), CurvePoint::Identity);
+
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(params.scalar_mul_mod_order(&off_curve, &d).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+/*!
+Twisted Edwards curves, used by EdDSA signatures (Ed25519).
+
+A twisted Edwards curve is defined algebraically by;
+
+`a x^2 + y^2 = 1 + d x^2 y^2 mod p`
+
+The addition law is unified (the same formula doubles a point) and complete
+when `a` is a square and `d` is not a square mod `p`, so there are no special
+cases to handle. The identity element is the point `(0, 1)`.
+*/
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+
+use crate::finite_fields;
+
+///
+/// A point `(x, y)` in a twisted Edwards curve. The identity element is
+/// `(0, 1)`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdwardsPoint {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub x: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub y: BigUint,
+}
+
+impl EdwardsPoint {
+    pub fn new(x: BigUint, y: BigUint) -> Self {
+        EdwardsPoint { x, y }
+    }
+
+    pub fn identity() -> Self {
+        EdwardsPoint::new(BigUint::from(0u32), BigUint::from(1u32))
+    }
+}
+
+
+#[derive(PartialEq, Debug)]
+pub enum EdwardsCurveError {
+    InvalidPoint(EdwardsPoint),
+    InvalidEncoding(String),
+}
+
+
+///
+/// This represents a twisted Edwards curve of the form
+/// a x^2 + y^2 = 1 + d x^2 y^2 mod p
+///
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdwardsCurve {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub a: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub d: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub p: BigUint,
+}
+
+
+///
+/// Domain parameters of a twisted Edwards curve group:
+///
+/// - `curve`: the curve `a x^2 + y^2 = 1 + d x^2 y^2 mod p`
+/// - `g`: the generator of the prime order subgroup
+/// - `n`: the order of the generator, `n * G = I`
+/// - `h`: the cofactor, `#E(Fp) = h * n`
+///
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdwardsParams {
+    pub curve: EdwardsCurve,
+    pub g: EdwardsPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub n: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub h: BigUint,
+}
+
+
+// Extended coordinates `(X, Y, Z, T)` with `x = X / Z`, `y = Y / Z` and
+// `x y = T / Z`, which make the addition inversion free.
+#[derive(Clone)]
+struct ExtendedPoint {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+    t: BigUint,
+}
+
+
+impl EdwardsCurve {
+    ///
+    /// Checks if a point A = (x,y) belongs to the curve:
+    ///
+    /// if `a x^2 + y^2 == 1 + d x^2 y^2 mod p` then returns `true`, if not,
+    /// returns `false`.
+    ///
+    pub fn is_on_curve(&self, a: &EdwardsPoint) -> bool {
+        let p = &self.p;
+        if a.x >= *p || a.y >= *p {
+            return false;
+        }
+
+        let x2 = fmul(&a.x, &a.x, p);
+        let y2 = fmul(&a.y, &a.y, p);
+        let lhs = fadd(&fmul(&self.a, &x2, p), &y2, p);
+        let rhs = fadd(&BigUint::from(1u32), &fmul(&self.d, &fmul(&x2, &y2, p), p), p);
+
+        lhs == rhs
+    }
+
+    ///
+    /// Perform a point addition with the unified addition law:
+    ///
+    /// - `x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2)`
+    /// - `y3 = (y1 y2 - a x1 x2) / (1 - d x1 x2 y1 y2)`
+    ///
+    pub fn add(&self, a: &EdwardsPoint, b: &EdwardsPoint) -> Result<EdwardsPoint, EdwardsCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EdwardsCurveError::InvalidPoint(a.clone()));
+        }
+        if !self.is_on_curve(b) {
+            return Err(EdwardsCurveError::InvalidPoint(b.clone()));
+        }
+
+        let sum = self.extended_add(&ExtendedPoint::from_affine(a), &ExtendedPoint::from_affine(b));
+        Ok(self.to_affine(&sum))
+    }
+
+    ///
+    /// Perform a point doubling: `B = A + A = 2 * A`.
+    ///
+    pub fn double(&self, a: &EdwardsPoint) -> Result<EdwardsPoint, EdwardsCurveError> {
+        self.add(a, a)
+    }
+
+    ///
+    /// The opposite of a point: `-(x, y) = (-x, y)`.
+    ///
+    pub fn neg(&self, a: &EdwardsPoint) -> Result<EdwardsPoint, EdwardsCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EdwardsCurveError::InvalidPoint(a.clone()));
+        }
+
+        Ok(EdwardsPoint::new(fsub(&BigUint::from(0u32), &a.x, &self.p), a.y.clone()))
+    }
+
+    ///
+    /// Perform a scalar multiplication of a point: `B = d * A` with the
+    /// double-and-add algorithm in extended coordinates. As the identity is a
+    /// regular point of the curve, `0 * A = (0, 1)`.
+    ///
+    pub fn scalar_mul(&self, a: &EdwardsPoint, d: &BigUint) -> Result<EdwardsPoint, EdwardsCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EdwardsCurveError::InvalidPoint(a.clone()));
+        }
+
+        let base = ExtendedPoint::from_affine(a);
+        let mut t = ExtendedPoint::from_affine(&EdwardsPoint::identity());
+        for i in (0..d.bits()).rev() {
+            t = self.extended_add(&t, &t);
+            if d.bit(i) {
+                t = self.extended_add(&t, &base);
+            }
+        }
+        Ok(self.to_affine(&t))
+    }
+
+    ///
+    /// Number of bytes of an encoded point: `p` plus the sign bit of `x`.
+    ///
+    pub fn encoding_size(&self) -> usize {
+        (self.p.bits() + 1).div_ceil(8) as usize
+    }
+
+    ///
+    /// Encodes a point as specified in RFC 8032: `y` as a little endian
+    /// integer with the most significant bit of the last byte holding the
+    /// parity of `x`.
+    ///
+    pub fn encode(&self, a: &EdwardsPoint) -> Result<Vec<u8>, EdwardsCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EdwardsCurveError::InvalidPoint(a.clone()));
+        }
+
+        let mut bytes = a.y.to_bytes_le();
+        bytes.resize(self.encoding_size(), 0);
+        if a.x.bit(0) {
+            let last = bytes.len() - 1;
+            bytes[last] |= 0x80;
+        }
+        Ok(bytes)
+    }
+
+    ///
+    /// Decodes a point encoded as specified in RFC 8032, recovering `x` from:
+    ///
+    /// `x^2 = (1 - y^2) / (a - d y^2) mod p`
+    ///
+    pub fn decode(&self, bytes: &[u8]) -> Result<EdwardsPoint, EdwardsCurveError> {
+        let size = self.encoding_size();
+        if bytes.len() != size {
+            return Err(EdwardsCurveError::InvalidEncoding(format!(
+                "encoded point has to be {} bytes long, got {}", size, bytes.len()
+            )));
+        }
+
+        let mut y_bytes = bytes.to_vec();
+        let odd = y_bytes[size - 1] & 0x80 != 0;
+        y_bytes[size - 1] &= 0x7F;
+
+        let p = &self.p;
+        let y = BigUint::from_bytes_le(&y_bytes);
+        if y >= *p {
+            return Err(EdwardsCurveError::InvalidEncoding("y is not smaller than p".into()));
+        }
+
+        let one = BigUint::from(1u32);
+        let y2 = fmul(&y, &y, p);
+        let numerator = fsub(&one, &y2, p);
+        let denominator = fsub(&self.a, &fmul(&self.d, &y2, p), p);
+        let x2 = finite_fields::divide(&numerator, &denominator, p)
+            .map_err(|_| EdwardsCurveError::InvalidEncoding("could not compute x^2".into()))?;
+        let (x, minus_x) = finite_fields::sqrt_mod_p(&x2, p)
+            .map_err(|_| EdwardsCurveError::InvalidEncoding("y is not the coordinate of a point in the curve".into()))?;
+
+        if x == BigUint::from(0u32) && odd {
+            return Err(EdwardsCurveError::InvalidEncoding("x = 0 can not be odd".into()));
+        }
+        let x = if x.bit(0) == odd { x } else { minus_x };
+
+        Ok(EdwardsPoint::new(x, y))
+    }
+
+
+    ///
+    /// Unified addition in extended coordinates (`add-2008-hwcd`):
+    ///
+    /// ```text
+    ///  A = X1 X2, B = Y1 Y2, C = d T1 T2, D = Z1 Z2
+    ///  E = (X1 + Y1)(X2 + Y2) - A - B, F = D - C, G = D + C, H = B - a A
+    ///  X3 = E F, Y3 = G H, T3 = E H, Z3 = F G
+    /// ```
+    ///
+    fn extended_add(&self, a: &ExtendedPoint, b: &ExtendedPoint) -> ExtendedPoint {
+        let p = &self.p;
+        let aa = fmul(&a.x, &b.x, p);
+        let bb = fmul(&a.y, &b.y, p);
+        let cc = fmul(&self.d, &fmul(&a.t, &b.t, p), p);
+        let dd = fmul(&a.z, &b.z, p);
+
+        let e = fmul(&fadd(&a.x, &a.y, p), &fadd(&b.x, &b.y, p), p);
+        let e = fsub(&fsub(&e, &aa, p), &bb, p);
+        let f = fsub(&dd, &cc, p);
+        let g = fadd(&dd, &cc, p);
+        let h = fsub(&bb, &fmul(&self.a, &aa, p), p);
+
+        ExtendedPoint {
+            x: fmul(&e, &f, p),
+            y: fmul(&g, &h, p),
+            z: fmul(&f, &g, p),
+            t: fmul(&e, &h, p),
+        }
+    }
+
+    fn to_affine(&self, a: &ExtendedPoint) -> EdwardsPoint {
+        let p = &self.p;
+        let z_inv = finite_fields::inverse_multiplicate_prime(&a.z, p).expect("z is reduced mod p");
+
+        EdwardsPoint::new(fmul(&a.x, &z_inv, p), fmul(&a.y, &z_inv, p))
+    }
+}
+
+
+impl ExtendedPoint {
+    fn from_affine(a: &EdwardsPoint) -> Self {
+        ExtendedPoint {
+            x: a.x.clone(),
+            y: a.y.clone(),
+            z: BigUint::from(1u32),
+            t: &a.x * &a.y,
+        }
+    }
+}
+
+
+fn fadd(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a + b) % p
+}
+
+fn fsub(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a + p - b % p) % p
+}
+
+fn fmul(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a * b) % p
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+
+    #[test]
+    fn test_ed25519_group_law() {
+        let params = curves::ed25519();
+        let ec = &params.curve;
+        let g = &params.g;
+
+        assert!(ec.is_on_curve(g));
+        assert!(ec.is_on_curve(&EdwardsPoint::identity()));
+        assert_eq!(ec.scalar_mul(g, &params.n), Ok(EdwardsPoint::identity()));
+        assert_eq!(ec.scalar_mul(g, &BigUint::from(0u32)), Ok(EdwardsPoint::identity()));
+
+        // G + I = G, G + (-G) = I, 2 G + G = 3 G
+        assert_eq!(ec.add(g, &EdwardsPoint::identity()), Ok(g.clone()));
+        assert_eq!(ec.add(g, &ec.neg(g).unwrap()), Ok(EdwardsPoint::identity()));
+        let g2 = ec.double(g).unwrap();
+        assert_eq!(ec.add(&g2, g), ec.scalar_mul(g, &BigUint::from(3u32)));
+
+        let bad = EdwardsPoint::new(BigUint::from(1u32), BigUint::from(1u32));
+        assert_eq!(ec.add(g, &bad), Err(EdwardsCurveError::InvalidPoint(bad)));
+    }
+
+    #[test]
+    fn test_ed25519_encoding() {
+        let params = curves::ed25519();
+        let ec = &params.curve;
+
+        // the base point encodes as 0x58 followed by 0x66 bytes
+        let mut expected = vec![0x66u8; 32];
+        expected[0] = 0x58;
+        assert_eq!(ec.encode(&params.g), Ok(expected.clone()));
+        assert_eq!(ec.decode(&expected), Ok(params.g.clone()));
+
+        for k in 1u32..10 {
+            let point = ec.scalar_mul(&params.g, &BigUint::from(k)).unwrap();
+            let encoded = ec.encode(&point).unwrap();
+            assert_eq!(ec.decode(&encoded), Ok(point));
+        }
+
+        assert!(ec.decode(&[0u8; 31]).is_err());
+        // y = p is out of range
+        let mut p_bytes = params.curve.p.to_bytes_le();
+        p_bytes.resize(32, 0);
+        assert!(ec.decode(&p_bytes).is_err());
+    }
+}
@@ -33,19 +33,26 @@ where P, Q, R are points on a elliptic curve and, d is a scalar quantity.
 
 from studying elliptic curves, you would understand that elliptic curve can be defined algebraically by;
 
->> `y^2 = x^3 + ax + b mod p`
+`y^2 = x^3 + ax + b mod p`
 
 putting into consideration the constraint of this equation;
 
-    1. `p` should be a prime number that is greater than `3`
-    2. `4 a^3 + 27 b^2 != 0`
+1. `p` should be a prime number that is greater than `3`
+2. `4 a^3 + 27 b^2 != 0`
 
 */
 
 
 
-use num_bigint::BigUint;
-use crate::finite_fields;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use num_bigint::{BigInt, BigUint, Sign};
+use crate::finite_fields::{self, FieldArithmetic, FiniteFieldError, MontgomeryContext, SpecialReducer};
+use crate::jacobian::JacobianPoint;
+use subtle::{Choice, ConstantTimeEq};
 
 ///
 /// This represents a point in the elliptic curve. The identity element is such
@@ -56,16 +63,59 @@ use crate::finite_fields;
 ///  - `I + I = 2 * I = I`
 ///
 #[derive(Clone, PartialEq, Debug, )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CurvePoint {
-    Coordinate(BigUint, BigUint),
+    Coordinate(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))] BigUint,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))] BigUint,
+    ),
     Identity
 }
 
 
+///
+/// Errors of the curve operations. Failures of the underlying field
+/// arithmetic are kept as the `source` of the error.
+///
 #[derive(PartialEq, Debug)]
 pub enum EllipticCurveError {
+    /// A point which is not on the curve, or not valid for the operation.
     InvalidPoint(CurvePoint),
+    /// A scalar outside of the range accepted by the operation.
     InvalidScalar(BigUint),
+    /// A malformed serialized point.
+    InvalidEncoding(String),
+    /// Parameters which do not define a usable curve.
+    InvalidCurve(String),
+    /// A field operation failed.
+    Field(FiniteFieldError),
+}
+
+impl fmt::Display for EllipticCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EllipticCurveError::InvalidPoint(point) => write!(f, "invalid point: {}", point),
+            EllipticCurveError::InvalidScalar(d) => write!(f, "invalid scalar: {}", d),
+            EllipticCurveError::InvalidEncoding(message) => write!(f, "invalid encoding: {}", message),
+            EllipticCurveError::InvalidCurve(message) => write!(f, "invalid curve: {}", message),
+            EllipticCurveError::Field(error) => write!(f, "field operation failed: {}", error),
+        }
+    }
+}
+
+impl core::error::Error for EllipticCurveError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            EllipticCurveError::Field(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FiniteFieldError> for EllipticCurveError {
+    fn from(error: FiniteFieldError) -> Self {
+        EllipticCurveError::Field(error)
+    }
 }
 
 
@@ -75,22 +125,55 @@ pub enum EllipticCurveError {
 /// y^2 = x^3 + ax + b mod p
 ///
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EllipticCurve {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
     pub a: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
     pub b: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
     pub p: BigUint,
 }
 
 
 
 impl EllipticCurve {
+    ///
+    /// Builds the curve `y^2 = x^3 + ax + b mod p` checking that:
+    ///
+    /// - `p` is a prime number greater than `3`
+    /// - `a` and `b` are elements of the field, i.e. smaller than `p`
+    /// - `4 a^3 + 27 b^2 != 0 mod p`, so the curve has no singular point
+    ///
+    pub fn new(a: BigUint, b: BigUint, p: BigUint) -> Result<Self, EllipticCurveError> {
+        if p <= BigUint::from(3u32) {
+            return Err(EllipticCurveError::InvalidCurve(format!("p has to be greater than 3, got {}", p)));
+        }
+        if !finite_fields::is_probable_prime(&p) {
+            return Err(EllipticCurveError::InvalidCurve(format!("p is not a prime number: {}", p)));
+        }
+        if a >= p || b >= p {
+            return Err(EllipticCurveError::InvalidCurve("a and b have to be smaller than p".into()));
+        }
+
+        // 4 a^3 + 27 b^2 mod p
+        let a3 = a.modpow(&BigUint::from(3u32), &p);
+        let b2 = b.modpow(&BigUint::from(2u32), &p);
+        let discriminant = (BigUint::from(4u32) * a3 + BigUint::from(27u32) * b2) % &p;
+        if discriminant == BigUint::from(0u32) {
+            return Err(EllipticCurveError::InvalidCurve("the curve is singular: 4 a^3 + 27 b^2 = 0 mod p".into()));
+        }
+
+        Ok(EllipticCurve { a, b, p })
+    }
+
+
     ///
     /// Perform a point addition: `C = A + B` where `A` and `B` are points which
     /// belong to the curve. Geometrically speaking, the point `C` is the
     /// x-reflection of the intersection of the lines that passes through `A`
     /// and `B` and intersects the curve.
     ///
-
     pub fn add(&self, a: &CurvePoint, b: &CurvePoint) -> Result<CurvePoint, EllipticCurveError> {
         // first thing is to make sure these points to be add are in the elliptic curve
         if !self.is_on_curve(a) {
@@ -109,7 +192,7 @@ impl EllipticCurve {
             (CurvePoint::Identity, _) => Ok(b.clone()),
             (_, CurvePoint::Identity) => Ok(a.clone()),
             (CurvePoint::Coordinate(x1, y1), CurvePoint::Coordinate(x2, y2)) => {
-                let y1_plus_y2 = finite_fields::add(y1, y2, &self.p).unwrap();
+                let y1_plus_y2 = finite_fields::add(y1, y2, &self.p)?;
 
 
                 // checking if the sum is a point at infinity
@@ -119,10 +202,10 @@ impl EllipticCurve {
 
 
                 // s = (y2 - y1) / (x2 - x1) mod p
-                let numerator = finite_fields::subtract(y2, y1, &self.p).unwrap();
-                let denominator = finite_fields::subtract(x2, x1, &self.p).unwrap();
-                let s = finite_fields::divide(&numerator, &denominator, &self.p).unwrap();
-                let (x3, y3) = self.compute_x3_y3(x1, y1, x2, &s);
+                let numerator = finite_fields::subtract(y2, y1, &self.p)?;
+                let denominator = finite_fields::subtract(x2, x1, &self.p)?;
+                let s = finite_fields::divide(&numerator, &denominator, &self.p)?;
+                let (x3, y3) = self.compute_x3_y3(x1, y1, x2, &s)?;
 
 
                 Ok(CurvePoint::Coordinate(x3, y3))
@@ -150,12 +233,11 @@ impl EllipticCurve {
 
                 // s = (3 * x1^2 + a) / (2 * y1) mod p
                 let numerator = x1.modpow(&BigUint::from(2u32), &self.p);
-                let numerator =
-                    finite_fields::multiplicate(&BigUint::from(3u32), &numerator, &self.p).unwrap();
-                let numerator = finite_fields::add(&self.a, &numerator, &self.p).unwrap();
-                let denominator = finite_fields::multiplicate(&BigUint::from(2u32), y1, &self.p).unwrap();
-                let s = finite_fields::divide(&numerator, &denominator, &self.p).unwrap();
-                let (x3, y3) = self.compute_x3_y3(x1, y1, x1, &s);
+                let numerator = finite_fields::multiplicate_reduce(&BigUint::from(3u32), &numerator, &self.p)?;
+                let numerator = finite_fields::add_reduce(&self.a, &numerator, &self.p)?;
+                let denominator = finite_fields::multiplicate_reduce(&BigUint::from(2u32), y1, &self.p)?;
+                let s = finite_fields::divide(&numerator, &denominator, &self.p)?;
+                let (x3, y3) = self.compute_x3_y3(x1, y1, x1, &s)?;
 
                 Ok(CurvePoint::Coordinate(x3, y3))
             }
@@ -163,6 +245,33 @@ impl EllipticCurve {
     }
 
 
+    ///
+    /// The opposite of a point: `-(x, y) = (x, p - y)` and `-I = I`, so that
+    /// `A + (-A) = I`.
+    ///
+    pub fn neg(&self, a: &CurvePoint) -> Result<CurvePoint, EllipticCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+
+        match a {
+            CurvePoint::Identity => Ok(CurvePoint::Identity),
+            CurvePoint::Coordinate(x, y) => {
+                let minus_y = finite_fields::inverse_add(y, &self.p)
+                    .map_err(|_| EllipticCurveError::InvalidPoint(a.clone()))?;
+                Ok(CurvePoint::Coordinate(x.clone(), minus_y))
+            }
+        }
+    }
+
+
+    ///
+    /// Point subtraction: `C = A - B = A + (-B)`.
+    ///
+    pub fn sub(&self, a: &CurvePoint, b: &CurvePoint) -> Result<CurvePoint, EllipticCurveError> {
+        self.add(a, &self.neg(b)?)
+    }
+
 
     ///
     /// computes the resulting point of the addition:
@@ -185,22 +294,23 @@ impl EllipticCurve {
         y1: &BigUint,
         x2: &BigUint,
         s: &BigUint,
-    ) -> (BigUint, BigUint) {
+    ) -> Result<(BigUint, BigUint), FiniteFieldError> {
         let s2 = s.modpow(&BigUint::from(2u32), &self.p);
-        let x3 = finite_fields::subtract(&s2, x1, &self.p).unwrap();
-        let x3 = finite_fields::subtract(&x3, x2, &self.p).unwrap();
+        let x3 = finite_fields::subtract(&s2, x1, &self.p)?;
+        let x3 = finite_fields::subtract(&x3, x2, &self.p)?;
 
-        let y3 = finite_fields::subtract(x1, &x3, &self.p).unwrap();
-        let y3 = finite_fields::multiplicate(s, &y3, &self.p).unwrap();
-        let y3 = finite_fields::subtract(&y3, y1, &self.p).unwrap();
+        let y3 = finite_fields::subtract(x1, &x3, &self.p)?;
+        let y3 = finite_fields::multiplicate(s, &y3, &self.p)?;
+        let y3 = finite_fields::subtract(&y3, y1, &self.p)?;
 
-        (x3, y3)
+        Ok((x3, y3))
     }
 
 
     ///
     /// Perform a scalar multiplication of a point: `B = d * A` where `A` is a
-    /// point in the curve and `d > 0` is a positive scalar of any value.
+    /// point in the curve and `d` is a scalar of any value, zero included
+    /// (`scalar_mul_strict` rejects it).
     ///
     /// It uses the addition/doubling algorithm
     ///
@@ -212,51 +322,558 @@ impl EllipticCurve {
     ///           T = T + A
     /// ```
     ///
+    /// The intermediate points are kept in Jacobian coordinates, so only the
+    /// final conversion back to affine coordinates needs a modular inversion.
+    ///
+    /// As in any group, `0 * A = I` and `d * I = I`.
+    ///
     pub fn scalar_mul(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+
+        let (f, a_repr) = self.arithmetic()?;
+        let t = self.jacobian_scalar_mul_with(&f, &a_repr, a, d);
+        Ok(self.to_affine(&t))
+    }
+
+    ///
+    /// `scalar_mul` rejecting `d = 0` with `InvalidScalar`, for the callers
+    /// which multiply by a secret that must not be zero (private keys,
+    /// nonces).
+    ///
+    pub fn scalar_mul_strict(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
         if *d == BigUint::from(0u32) {
             return Err(EllipticCurveError::InvalidScalar(d.clone()));
         }
+        self.scalar_mul(a, d)
+    }
 
-        let mut t = a.clone();
+    ///
+    /// `B = d * A` for a signed scalar `d`, as produced by GLV
+    /// decompositions or NAF recodings: a negative scalar is applied to the
+    /// opposite point, `(-d) * A = d * (-A)`. Same errors as `scalar_mul`.
+    ///
+    pub fn scalar_mul_signed(&self, a: &CurvePoint, d: &BigInt) -> Result<CurvePoint, EllipticCurveError> {
+        match d.sign() {
+            Sign::Minus => self.scalar_mul(&self.neg(a)?, d.magnitude()),
+            _ => self.scalar_mul(a, d.magnitude()),
+        }
+    }
+
+    // the double-and-add loop of `scalar_mul` in the representation of `f`,
+    // for a checked point
+    pub(crate) fn jacobian_scalar_mul_with<F: FieldArithmetic>(&self, f: &F, a_repr: &BigUint, a: &CurvePoint, d: &BigUint) -> JacobianPoint {
+        if *d == BigUint::from(0u32) {
+            return JacobianPoint::identity();
+        }
+
+        let base = JacobianPoint::from_affine(a).to_repr(f);
+        let mut t = base.clone();
         for i in (0..(d.bits() - 1)).rev() {
-            t = self.double(&t)?;
+            t = self.jacobian_double_with(f, a_repr, &t);
             if d.bit(i) {
-                t = self.add(&t, a)?;
+                t = self.jacobian_add_with(f, a_repr, &t, &base);
+            }
+        }
+        t.to_value(f)
+    }
+
+
+    ///
+    /// Computes `C = u1 * A + u2 * B` interleaving both multiplications
+    /// (Shamir's trick), so the doublings are shared:
+    ///
+    /// ```text
+    ///  A + B precomputed
+    ///  T = I
+    ///  for i in [max(bits of u1, bits of u2) - 1, 0]
+    ///       T = 2 * T
+    ///       T = T + (A, B or A + B depending on bit i of u1 and u2)
+    /// ```
+    ///
+    /// Zero scalars are accepted: `0 * A = I`.
+    ///
+    pub fn double_scalar_mul(
+        &self,
+        u1: &BigUint,
+        a: &CurvePoint,
+        u2: &BigUint,
+        b: &CurvePoint,
+    ) -> Result<CurvePoint, EllipticCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+        if !self.is_on_curve(b) {
+            return Err(EllipticCurveError::InvalidPoint(b.clone()));
+        }
+
+        let (f, a_repr) = self.arithmetic()?;
+        let a = JacobianPoint::from_affine(a).to_repr(&f);
+        let b = JacobianPoint::from_affine(b).to_repr(&f);
+        let a_plus_b = self.jacobian_add_with(&f, &a_repr, &a, &b);
+
+        let mut t = JacobianPoint::identity();
+        for i in (0..u1.bits().max(u2.bits())).rev() {
+            t = self.jacobian_double_with(&f, &a_repr, &t);
+            match (u1.bit(i), u2.bit(i)) {
+                (true, true) => t = self.jacobian_add_with(&f, &a_repr, &t, &a_plus_b),
+                (true, false) => t = self.jacobian_add_with(&f, &a_repr, &t, &a),
+                (false, true) => t = self.jacobian_add_with(&f, &a_repr, &t, &b),
+                (false, false) => {}
+            }
+        }
+        Ok(self.to_affine(&t.to_value(&f)))
+    }
+
+    ///
+    /// Computes the multi-scalar multiplication `C = d1 * A1 + ... + dn * An`
+    /// sharing the doublings between all the terms (Straus):
+    ///
+    /// ```text
+    ///  T = I
+    ///  for i in [max(bits of dj) - 1, 0]
+    ///       T = 2 * T
+    ///       for every j with bit i of dj == 1
+    ///           T = T + Aj
+    /// ```
+    ///
+    /// Zero scalars are accepted and an empty sum is the identity.
+    ///
+    /// With the `parallel` feature the terms are split in one chunk per
+    /// thread, whose sums are computed in parallel and then added.
+    ///
+    pub fn multi_scalar_mul(&self, terms: &[(BigUint, CurvePoint)]) -> Result<CurvePoint, EllipticCurveError> {
+        let (f, a_repr) = self.arithmetic()?;
+        let mut points = Vec::with_capacity(terms.len());
+        for (d, point) in terms {
+            if !self.is_on_curve(point) {
+                return Err(EllipticCurveError::InvalidPoint(point.clone()));
+            }
+            points.push((d, JacobianPoint::from_affine(point).to_repr(&f)));
+        }
+
+        #[cfg(feature = "parallel")]
+        let t = {
+            use rayon::prelude::*;
+
+            let chunk_size = points.len().div_ceil(rayon::current_num_threads()).max(1);
+            points
+                .par_chunks(chunk_size)
+                .map(|chunk| self.straus_with(&f, &a_repr, chunk))
+                .reduce(JacobianPoint::identity, |t, u| self.jacobian_add_with(&f, &a_repr, &t, &u))
+        };
+        #[cfg(not(feature = "parallel"))]
+        let t = self.straus_with(&f, &a_repr, &points);
+
+        Ok(self.to_affine(&t.to_value(&f)))
+    }
+
+    // The Straus loop of `multi_scalar_mul`, on points in the representation
+    // of `f`
+    fn straus_with<F: FieldArithmetic>(&self, f: &F, a_repr: &BigUint, terms: &[(&BigUint, JacobianPoint)]) -> JacobianPoint {
+        let bits = terms.iter().map(|(d, _)| d.bits()).max().unwrap_or(0);
+        let mut t = JacobianPoint::identity();
+        for i in (0..bits).rev() {
+            t = self.jacobian_double_with(f, a_repr, &t);
+            for (d, point) in terms {
+                if d.bit(i) {
+                    t = self.jacobian_add_with(f, a_repr, &t, point);
+                }
             }
         }
-        Ok(t)
+        t
     }
 
+    ///
+    /// Computes every `Bj = dj * Aj` on its own, as many `scalar_mul`, but
+    /// converts all the results back to affine coordinates together with
+    /// `to_affine_batch`: a single modular inversion for the whole batch.
+    /// With the `parallel` feature the products are computed in parallel.
+    ///
+    pub fn batch_scalar_mul(&self, terms: &[(BigUint, CurvePoint)]) -> Result<Vec<CurvePoint>, EllipticCurveError> {
+        if let Some((_, point)) = terms.iter().find(|(_, point)| !self.is_on_curve(point)) {
+            return Err(EllipticCurveError::InvalidPoint(point.clone()));
+        }
+
+        let (f, a_repr) = self.arithmetic()?;
+        #[cfg(feature = "parallel")]
+        let results: Vec<JacobianPoint> = {
+            use rayon::prelude::*;
+
+            terms
+                .par_iter()
+                .map(|(d, point)| self.jacobian_scalar_mul_with(&f, &a_repr, point, d))
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<JacobianPoint> = terms
+            .iter()
+            .map(|(d, point)| self.jacobian_scalar_mul_with(&f, &a_repr, point, d))
+            .collect();
+
+        Ok(self.to_affine_batch(&results))
+    }
+
+    ///
+    /// The arithmetic of the repeated point operations of the scalar
+    /// multiplications, with `a` in its representation: the reduction
+    /// identity of `p` for the special primes of the named curves, Montgomery
+    /// multiplication otherwise, which fails on an even `p` (the fields are
+    /// public, so the curve may not come from `EllipticCurve::new`).
+    ///
+    pub(crate) fn arithmetic(&self) -> Result<(Arithmetic, BigUint), EllipticCurveError> {
+        let f = match SpecialReducer::new(&self.p) {
+            Some(reducer) => Arithmetic::Special(reducer),
+            None => Arithmetic::Montgomery(
+                MontgomeryContext::new(&self.p)
+                    .map_err(|_| EllipticCurveError::InvalidCurve("The field modulus must be an odd prime".into()))?,
+            ),
+        };
+        let a_repr = f.to_repr(&self.a);
+        Ok((f, a_repr))
+    }
 
     ///
     /// Checks if a point A = (x,y) belongs to the elliptic curve:
     ///
     /// if `y^2 == x^3 + a * x + b mod p` then returns `true`, if not, returns
-    /// `false`.
+    /// `false`. Coordinates which are not reduced modulo `p` are never on the
+    /// curve.
     ///
     pub fn is_on_curve(&self, a: &CurvePoint) -> bool {
         match a {
             CurvePoint::Coordinate(x, y) => {
-                let y2 = y.modpow(&BigUint::from(2u32), &self.p);
-                let x3 = x.modpow(&BigUint::from(3u32), &self.p);
-                let a_x = finite_fields::multiplicate(&self.a, x, &self.p).unwrap();
-                let x3_plus_ax = finite_fields::add(&x3, &a_x, &self.p).unwrap();
+                if *x >= self.p || *y >= self.p {
+                    return false;
+                }
 
-                y2 == finite_fields::add(&x3_plus_ax, &self.b, &self.p).unwrap()
+                let y2 = y.modpow(&BigUint::from(2u32), &self.p);
+                self.y_squared(x).is_ok_and(|rhs| rhs == y2)
             }
             CurvePoint::Identity => true, // the identity element is always on the curve, there no need try another
         }
     }
+
+
+    ///
+    /// Number of bytes needed to encode a coordinate of a point in the curve,
+    /// that is the byte length of `p`.
+    ///
+    pub fn coordinate_size(&self) -> usize {
+        self.p.bits().div_ceil(8) as usize
+    }
+
+
+    ///
+    /// Computes the right hand side of the curve equation for a given `x`:
+    ///
+    /// `y^2 = x^3 + a * x + b mod p`
+    ///
+    fn y_squared(&self, x: &BigUint) -> Result<BigUint, FiniteFieldError> {
+        let x3 = x.modpow(&BigUint::from(3u32), &self.p);
+        let a_x = finite_fields::multiplicate_reduce(&self.a, x, &self.p)?;
+        let x3_plus_ax = finite_fields::add(&x3, &a_x, &self.p)?;
+
+        finite_fields::add_reduce(&x3_plus_ax, &self.b, &self.p)
+    }
+}
+
+
+
+impl CurvePoint {
+    ///
+    /// Encodes the point in the SEC1 compressed form:
+    ///
+    /// `02 || x` if `y` is even, `03 || x` if `y` is odd
+    ///
+    /// where `x` is left-padded to the byte length of `p`. The identity has no
+    /// compressed encoding.
+    ///
+    pub fn to_sec1_compressed(&self, curve: &EllipticCurve) -> Result<Vec<u8>, EllipticCurveError> {
+        match self {
+            CurvePoint::Identity => Err(EllipticCurveError::InvalidPoint(self.clone())),
+            CurvePoint::Coordinate(x, y) => {
+                if *x >= curve.p || *y >= curve.p || !curve.is_on_curve(self) {
+                    return Err(EllipticCurveError::InvalidPoint(self.clone()));
+                }
+
+                let tag = if y.bit(0) { 0x03 } else { 0x02 };
+                let x_bytes = finite_fields::to_fixed_bytes(x, curve.coordinate_size())
+                    .map_err(|_| EllipticCurveError::InvalidPoint(self.clone()))?;
+
+                let mut bytes = Vec::with_capacity(1 + x_bytes.len());
+                bytes.push(tag);
+                bytes.extend_from_slice(&x_bytes);
+                Ok(bytes)
+            }
+        }
+    }
+
+
+    ///
+    /// Decodes a point from the SEC1 compressed form `02/03 || x`, recovering
+    /// `y` as the square root of `x^3 + a * x + b mod p` with the parity given
+    /// by the tag byte.
+    ///
+    pub fn from_sec1_compressed(bytes: &[u8], curve: &EllipticCurve) -> Result<CurvePoint, EllipticCurveError> {
+        let size = curve.coordinate_size();
+        if bytes.len() != 1 + size {
+            return Err(EllipticCurveError::InvalidEncoding(format!(
+                "compressed point has to be {} bytes long, got {}", 1 + size, bytes.len()
+            )));
+        }
+
+        let odd = match bytes[0] {
+            0x02 => false,
+            0x03 => true,
+            tag => return Err(EllipticCurveError::InvalidEncoding(format!("invalid compressed point tag: {:#04x}", tag))),
+        };
+
+        CurvePoint::lift_x(&BigUint::from_bytes_be(&bytes[1..]), odd, curve)
+    }
+
+
+    ///
+    /// The point of the curve with the coordinate `x` whose `y` has the given
+    /// parity, as in compressed points or BIP-340 public keys:
+    ///
+    /// `y = ±sqrt(x^3 + a * x + b) mod p`
+    ///
+    /// Fails if `x >= p`, if `x^3 + a * x + b` is not a square, or if `y = 0`
+    /// and an odd `y` is requested.
+    ///
+    pub fn lift_x(x: &BigUint, odd: bool, curve: &EllipticCurve) -> Result<CurvePoint, EllipticCurveError> {
+        if *x >= curve.p {
+            return Err(EllipticCurveError::InvalidEncoding("x is not smaller than p".into()));
+        }
+
+        let y2 = curve
+            .y_squared(x)
+            .map_err(|_| EllipticCurveError::InvalidEncoding("could not compute x^3 + a * x + b".into()))?;
+        if finite_fields::legendre(&y2, &curve.p) == Ok(-1) {
+            return Err(EllipticCurveError::InvalidEncoding("x is not the coordinate of a point in the curve".into()));
+        }
+        let (y, minus_y) = finite_fields::sqrt_mod_p(&y2, &curve.p)
+            .map_err(|_| EllipticCurveError::InvalidEncoding("could not compute the square root of y^2".into()))?;
+        let y = if y.bit(0) == odd { y } else { minus_y };
+
+        // y = 0 has no odd counterpart
+        if y.bit(0) != odd {
+            return Err(EllipticCurveError::InvalidEncoding("no point with the requested parity".into()));
+        }
+
+        Ok(CurvePoint::Coordinate(x.clone(), y))
+    }
+
+
+    ///
+    /// Encodes the point in the SEC1 uncompressed form `04 || x || y` where
+    /// both coordinates are left-padded to the byte length of `p`. The
+    /// identity has no uncompressed encoding.
+    ///
+    pub fn to_sec1_uncompressed(&self, curve: &EllipticCurve) -> Result<Vec<u8>, EllipticCurveError> {
+        match self {
+            CurvePoint::Identity => Err(EllipticCurveError::InvalidPoint(self.clone())),
+            CurvePoint::Coordinate(x, y) => {
+                if *x >= curve.p || *y >= curve.p || !curve.is_on_curve(self) {
+                    return Err(EllipticCurveError::InvalidPoint(self.clone()));
+                }
+
+                let size = curve.coordinate_size();
+                let x_bytes = finite_fields::to_fixed_bytes(x, size)
+                    .map_err(|_| EllipticCurveError::InvalidPoint(self.clone()))?;
+                let y_bytes = finite_fields::to_fixed_bytes(y, size)
+                    .map_err(|_| EllipticCurveError::InvalidPoint(self.clone()))?;
+
+                let mut bytes = Vec::with_capacity(1 + 2 * size);
+                bytes.push(0x04);
+                bytes.extend_from_slice(&x_bytes);
+                bytes.extend_from_slice(&y_bytes);
+                Ok(bytes)
+            }
+        }
+    }
+
+
+    ///
+    /// Decodes a point from the SEC1 uncompressed form `04 || x || y`. The
+    /// point at infinity (`00`), coordinates not smaller than `p` and points
+    /// which do not belong to the curve are rejected.
+    ///
+    pub fn from_sec1_uncompressed(bytes: &[u8], curve: &EllipticCurve) -> Result<CurvePoint, EllipticCurveError> {
+        if bytes == [0x00] {
+            return Err(EllipticCurveError::InvalidEncoding("the identity can not be decoded as a point".into()));
+        }
+
+        let size = curve.coordinate_size();
+        if bytes.len() != 1 + 2 * size {
+            return Err(EllipticCurveError::InvalidEncoding(format!(
+                "uncompressed point has to be {} bytes long, got {}", 1 + 2 * size, bytes.len()
+            )));
+        }
+
+        if bytes[0] != 0x04 {
+            return Err(EllipticCurveError::InvalidEncoding(format!("invalid uncompressed point tag: {:#04x}", bytes[0])));
+        }
+
+        let x = BigUint::from_bytes_be(&bytes[1..1 + size]);
+        let y = BigUint::from_bytes_be(&bytes[1 + size..]);
+        if x >= curve.p || y >= curve.p {
+            return Err(EllipticCurveError::InvalidEncoding("coordinates have to be smaller than p".into()));
+        }
+
+        let point = CurvePoint::Coordinate(x, y);
+        if !curve.is_on_curve(&point) {
+            return Err(EllipticCurveError::InvalidPoint(point));
+        }
+
+        Ok(point)
+    }
+
+
+    ///
+    /// Encodes the point as the hex string of its SEC1 compressed or
+    /// uncompressed form.
+    ///
+    pub fn to_hex(&self, curve: &EllipticCurve, compressed: bool) -> Result<String, EllipticCurveError> {
+        let bytes = if compressed {
+            self.to_sec1_compressed(curve)?
+        } else {
+            self.to_sec1_uncompressed(curve)?
+        };
+        Ok(finite_fields::encode_hex(&bytes))
+    }
+
+
+    ///
+    /// Decodes a point from the hex string of its SEC1 encoding, compressed
+    /// (`02/03` tag) or uncompressed (`04` tag).
+    ///
+    pub fn from_hex(hex: &str, curve: &EllipticCurve) -> Result<CurvePoint, EllipticCurveError> {
+        let bytes = finite_fields::decode_hex(hex)
+            .map_err(|_| EllipticCurveError::InvalidEncoding(format!("{} is not a hex string", hex)))?;
+        match bytes.first() {
+            Some(0x04) => CurvePoint::from_sec1_uncompressed(&bytes, curve),
+            _ => CurvePoint::from_sec1_compressed(&bytes, curve),
+        }
+    }
 }
 
 
+///
+/// Constant time comparison, see `biguint_ct_eq`: the coordinates are
+/// compared even if only one of the points is the identity.
+///
+impl ConstantTimeEq for CurvePoint {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        fn parts(point: &CurvePoint) -> (Choice, BigUint, BigUint) {
+            match point {
+                CurvePoint::Identity => (Choice::from(1u8), BigUint::from(0u32), BigUint::from(0u32)),
+                CurvePoint::Coordinate(x, y) => (Choice::from(0u8), x.clone(), y.clone()),
+            }
+        }
+
+        let ((a_identity, a_x, a_y), (b_identity, b_x, b_y)) = (parts(self), parts(other));
+        a_identity.ct_eq(&b_identity) & finite_fields::biguint_ct_eq(&a_x, &b_x) & finite_fields::biguint_ct_eq(&a_y, &b_y)
+    }
+}
+
 
+///
+/// Writes `Identity`, or the hex of `04 || x || y` with both coordinates
+/// left-padded to the same byte length. A point does not know its curve, so
+/// it can neither be compressed nor padded to the size of `p` here: use
+/// `to_hex` for the SEC1 encodings of a given curve.
+///
+impl fmt::Display for CurvePoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CurvePoint::Identity => f.write_str("Identity"),
+            CurvePoint::Coordinate(x, y) => {
+                let size = x.bits().max(y.bits()).div_ceil(8).max(1) as usize;
+                let x_bytes = finite_fields::to_fixed_bytes(x, size).map_err(|_| fmt::Error)?;
+                let y_bytes = finite_fields::to_fixed_bytes(y, size).map_err(|_| fmt::Error)?;
+                write!(f, "04{}{}", finite_fields::encode_hex(&x_bytes), finite_fields::encode_hex(&y_bytes))
+            }
+        }
+    }
+}
 
 
+///
+/// Parses the `Display` form of a point. Whether the point is on a curve is
+/// not checked, and compressed points are rejected as they can only be
+/// decoded against a curve, see `from_hex`.
+///
+impl FromStr for CurvePoint {
+    type Err = EllipticCurveError;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "Identity" {
+            return Ok(CurvePoint::Identity);
+        }
 
+        let bytes = finite_fields::decode_hex(s)
+            .map_err(|_| EllipticCurveError::InvalidEncoding(format!("{} is not a hex string", s)))?;
+        match bytes.split_first() {
+            Some((0x04, coordinates)) if !coordinates.is_empty() && coordinates.len().is_multiple_of(2) => {
+                let (x, y) = coordinates.split_at(coordinates.len() / 2);
+                Ok(CurvePoint::Coordinate(BigUint::from_bytes_be(x), BigUint::from_bytes_be(y)))
+            }
+            Some((0x02 | 0x03, _)) => Err(EllipticCurveError::InvalidEncoding(
+                "compressed points need a curve to be decoded".into(),
+            )),
+            _ => Err(EllipticCurveError::InvalidEncoding(format!("{} is not an uncompressed point", s))),
+        }
+    }
+}
 
 
+
+
+
+
+
+
+
+// the field arithmetic picked by `EllipticCurve::arithmetic`
+#[derive(Clone, Debug)]
+pub(crate) enum Arithmetic {
+    Special(SpecialReducer),
+    Montgomery(MontgomeryContext),
+}
+
+impl FieldArithmetic for Arithmetic {
+    fn modulus(&self) -> &BigUint {
+        match self {
+            Arithmetic::Special(f) => f.modulus(),
+            Arithmetic::Montgomery(f) => f.modulus(),
+        }
+    }
+
+    fn to_repr(&self, a: &BigUint) -> BigUint {
+        match self {
+            Arithmetic::Special(f) => f.to_repr(a),
+            Arithmetic::Montgomery(f) => f.to_repr(a),
+        }
+    }
+
+    fn to_value(&self, a: &BigUint) -> BigUint {
+        match self {
+            Arithmetic::Special(f) => f.to_value(a),
+            Arithmetic::Montgomery(f) => f.to_value(a),
+        }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        match self {
+            Arithmetic::Special(f) => f.mul(a, b),
+            Arithmetic::Montgomery(f) => f.mul(a, b),
+        }
+    }
+}
+
 // ===================================
 // TEST-------------------------------
 // ===================================
@@ -361,6 +978,35 @@ mod test {
         assert_eq!(res, Ok(CurvePoint::Identity));
     }
 
+    #[test]
+    fn test_neg_and_sub() {
+        // y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+        let p1 = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let p2 = CurvePoint::Coordinate(BigUint::from(6u32), BigUint::from(3u32));
+
+        // -(5,1) = (5,16)
+        let minus_p1 = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(16u32));
+        assert_eq!(ec.neg(&p1), Ok(minus_p1.clone()));
+        assert_eq!(ec.neg(&minus_p1), Ok(p1.clone()));
+        assert_eq!(ec.neg(&CurvePoint::Identity), Ok(CurvePoint::Identity));
+        assert_eq!(ec.add(&p1, &minus_p1), Ok(CurvePoint::Identity));
+
+        // 2 (5,1) - (5,1) = (5,1)
+        assert_eq!(ec.sub(&p2, &p1), Ok(p1.clone()));
+        assert_eq!(ec.sub(&p1, &p1), Ok(CurvePoint::Identity));
+        assert_eq!(ec.sub(&p1, &CurvePoint::Identity), Ok(p1.clone()));
+        assert_eq!(ec.sub(&CurvePoint::Identity, &p1), Ok(minus_p1));
+
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert_eq!(ec.neg(&off_curve), Err(EllipticCurveError::InvalidPoint(off_curve.clone())));
+        assert!(ec.sub(&p1, &off_curve).is_err());
+    }
+
     #[test]
     fn test_point_doubling() {
         // y^2 = x^3 + 2x + 2 mod 17
@@ -428,6 +1074,15 @@ mod test {
         let res = ec.scalar_mul(&a, &BigUint::from(19u32));
         assert_eq!(res, pr);
 
+        // 0 * (5, 1) = I and d * I = I, only the strict variant rejects 0
+        assert_eq!(ec.scalar_mul(&a, &BigUint::from(0u32)), Ok(CurvePoint::Identity));
+        assert_eq!(ec.scalar_mul(&CurvePoint::Identity, &BigUint::from(7u32)), Ok(CurvePoint::Identity));
+        assert_eq!(
+            ec.scalar_mul_strict(&a, &BigUint::from(0u32)),
+            Err(EllipticCurveError::InvalidScalar(BigUint::from(0u32)))
+        );
+        assert_eq!(ec.scalar_mul_strict(&a, &BigUint::from(19u32)), Ok(CurvePoint::Identity));
+
         // 2 * (10, 6) = (16,13)
         let p1 = CurvePoint::Coordinate(BigUint::from(10u32), BigUint::from(6u32));
         let pr = Ok(CurvePoint::Coordinate(BigUint::from(16u32), BigUint::from(13u32)));
@@ -493,4 +1148,466 @@ mod test {
         let res = ec.scalar_mul(&p, &n); // n * p
         assert_eq!(res, Ok(CurvePoint::Identity));
     }
+
+    #[test]
+    fn test_sec1_compressed() {
+        // y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p1 = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        assert_eq!(p1.to_sec1_compressed(&ec), Ok(vec![0x03, 0x05]));
+
+        let p2 = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(16u32));
+        assert_eq!(p2.to_sec1_compressed(&ec), Ok(vec![0x02, 0x05]));
+
+        assert_eq!(
+            CurvePoint::Identity.to_sec1_compressed(&ec),
+            Err(EllipticCurveError::InvalidPoint(CurvePoint::Identity))
+        );
+
+        let p3 = CurvePoint::Coordinate(BigUint::from(4u32), BigUint::from(1u32));
+        assert_eq!(
+            p3.to_sec1_compressed(&ec),
+            Err(EllipticCurveError::InvalidPoint(p3.clone()))
+        );
+    }
+
+    #[test]
+    fn test_sec1_compressed_secp256k1() {
+        let ec = EllipticCurve {
+            a: BigUint::from(0u32),
+            b: BigUint::from(7u32),
+            p: BigUint::parse_bytes(
+                b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+                16,
+            )
+                .expect("could not convert p"),
+        };
+
+        let g = CurvePoint::Coordinate(
+            BigUint::parse_bytes(
+                b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+                16,
+            )
+                .expect("could not convert gx"),
+            BigUint::parse_bytes(
+                b"483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+                16,
+            )
+                .expect("could not convert gy"),
+        );
+
+        let encoded = g.to_sec1_compressed(&ec).unwrap();
+        assert_eq!(encoded.len(), 33);
+        assert_eq!(
+            encoded,
+            hex_to_bytes("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798")
+        );
+
+        // 2 * G = (C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5, even y)
+        let g2 = ec.double(&g).unwrap();
+        assert_eq!(
+            g2.to_sec1_compressed(&ec).unwrap(),
+            hex_to_bytes("02C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5")
+        );
+    }
+
+    #[test]
+    fn test_lift_x() {
+        // y^2 = x^3 + 2x + 2 mod 17: x = 5 gives y = 1 or 16
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+        let point = |x: u32, y: u32| CurvePoint::Coordinate(BigUint::from(x), BigUint::from(y));
+
+        assert_eq!(CurvePoint::lift_x(&BigUint::from(5u32), true, &ec), Ok(point(5, 1)));
+        assert_eq!(CurvePoint::lift_x(&BigUint::from(5u32), false, &ec), Ok(point(5, 16)));
+
+        // x = 2 gives y^2 = 14, not a square mod 17
+        assert!(CurvePoint::lift_x(&BigUint::from(2u32), false, &ec).is_err());
+        assert!(CurvePoint::lift_x(&BigUint::from(22u32), false, &ec).is_err());
+
+        // BIP-340 keys are the even y lift of x
+        let params = crate::curves::secp256k1();
+        let gx = BigUint::parse_bytes(b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798", 16).unwrap();
+        assert_eq!(CurvePoint::lift_x(&gx, false, &params.curve), Ok(params.g.clone()));
+        assert_eq!(CurvePoint::lift_x(&gx, true, &params.curve), params.curve.neg(&params.g));
+    }
+
+    #[test]
+    fn test_sec1_compressed_roundtrip() {
+        // y^2 = x^3 + 2x + 2 mod 17, p = 1 mod 4 so Tonelli-Shanks is used
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let a = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        for d in 1u32..19 {
+            let point = ec.scalar_mul(&a, &BigUint::from(d)).unwrap();
+            let encoded = point.to_sec1_compressed(&ec).unwrap();
+            assert_eq!(CurvePoint::from_sec1_compressed(&encoded, &ec), Ok(point));
+        }
+
+        // 4^3 + 2 * 4 + 2 = 74 = 6 mod 17 is not a square
+        assert!(CurvePoint::from_sec1_compressed(&[0x02, 0x04], &ec).is_err());
+        // bad tag, bad length and x >= p
+        assert!(CurvePoint::from_sec1_compressed(&[0x04, 0x05], &ec).is_err());
+        assert!(CurvePoint::from_sec1_compressed(&[0x02, 0x00, 0x05], &ec).is_err());
+        assert!(CurvePoint::from_sec1_compressed(&[0x02, 0x16], &ec).is_err());
+    }
+
+    #[test]
+    fn test_sec1_compressed_roundtrip_secp256k1() {
+        let ec = EllipticCurve {
+            a: BigUint::from(0u32),
+            b: BigUint::from(7u32),
+            p: BigUint::parse_bytes(
+                b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+                16,
+            )
+                .expect("could not convert p"),
+        };
+
+        let encoded = hex_to_bytes("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let g = CurvePoint::from_sec1_compressed(&encoded, &ec).unwrap();
+        assert_eq!(
+            g,
+            CurvePoint::Coordinate(
+                BigUint::parse_bytes(
+                    b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+                    16,
+                )
+                    .unwrap(),
+                BigUint::parse_bytes(
+                    b"483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+                    16,
+                )
+                    .unwrap(),
+            )
+        );
+
+        let g_neg = CurvePoint::from_sec1_compressed(
+            &hex_to_bytes("0379BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            &ec,
+        )
+            .unwrap();
+        assert_eq!(ec.add(&g, &g_neg), Ok(CurvePoint::Identity));
+    }
+
+    #[test]
+    fn test_sec1_uncompressed() {
+        // y^2 = x^3 + 2x + 2 mod 17
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let p1 = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let encoded = p1.to_sec1_uncompressed(&ec).unwrap();
+        assert_eq!(encoded, vec![0x04, 0x05, 0x01]);
+        assert_eq!(CurvePoint::from_sec1_uncompressed(&encoded, &ec), Ok(p1));
+
+        assert!(CurvePoint::Identity.to_sec1_uncompressed(&ec).is_err());
+
+        // identity, bad tag, bad length, out of range and off curve points
+        assert!(CurvePoint::from_sec1_uncompressed(&[0x00], &ec).is_err());
+        assert!(CurvePoint::from_sec1_uncompressed(&[0x02, 0x05, 0x01], &ec).is_err());
+        assert!(CurvePoint::from_sec1_uncompressed(&[0x04, 0x05], &ec).is_err());
+        assert!(CurvePoint::from_sec1_uncompressed(&[0x04, 0x05, 0x12], &ec).is_err());
+        assert_eq!(
+            CurvePoint::from_sec1_uncompressed(&[0x04, 0x04, 0x01], &ec),
+            Err(EllipticCurveError::InvalidPoint(CurvePoint::Coordinate(BigUint::from(4u32), BigUint::from(1u32))))
+        );
+    }
+
+    #[test]
+    fn test_sec1_uncompressed_secp256k1() {
+        let ec = EllipticCurve {
+            a: BigUint::from(0u32),
+            b: BigUint::from(7u32),
+            p: BigUint::parse_bytes(
+                b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+                16,
+            )
+                .expect("could not convert p"),
+        };
+
+        let encoded = hex_to_bytes(
+            "0479BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798\
+             483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+        );
+        assert_eq!(encoded.len(), 65);
+
+        let g = CurvePoint::from_sec1_uncompressed(&encoded, &ec).unwrap();
+        assert_eq!(g.to_sec1_uncompressed(&ec).unwrap(), encoded);
+
+        let compressed = g.to_sec1_compressed(&ec).unwrap();
+        assert_eq!(CurvePoint::from_sec1_compressed(&compressed, &ec), Ok(g));
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        BigUint::parse_bytes(hex.as_bytes(), 16).unwrap().to_bytes_be()
+    }
+
+    #[test]
+    fn test_hex_roundtrip_secp256k1() {
+        let params = crate::curves::secp256k1();
+        let compressed = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                            483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+        assert_eq!(params.g.to_hex(&params.curve, true).unwrap(), compressed);
+        assert_eq!(params.g.to_hex(&params.curve, false).unwrap(), uncompressed);
+        assert_eq!(CurvePoint::from_hex(compressed, &params.curve), Ok(params.g.clone()));
+        assert_eq!(CurvePoint::from_hex(&uncompressed.to_uppercase(), &params.curve), Ok(params.g.clone()));
+
+        assert!(CurvePoint::Identity.to_hex(&params.curve, true).is_err());
+        assert!(CurvePoint::from_hex("02zz", &params.curve).is_err());
+        assert!(CurvePoint::from_hex(&compressed[..64], &params.curve).is_err());
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        let params = crate::curves::secp256k1();
+        let text = params.g.to_string();
+        assert_eq!(text, params.g.to_hex(&params.curve, false).unwrap());
+        assert_eq!(text.parse::<CurvePoint>(), Ok(params.g.clone()));
+
+        assert_eq!(CurvePoint::Identity.to_string(), "Identity");
+        assert_eq!("Identity".parse::<CurvePoint>(), Ok(CurvePoint::Identity));
+
+        // both coordinates on the width of the largest one
+        let point = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(0x0101u32));
+        assert_eq!(point.to_string(), "0400050101");
+        assert_eq!("0400050101".parse::<CurvePoint>(), Ok(point));
+
+        for text in ["identity", "04", "040005010", "02ab", "0500050101", "", "zz"] {
+            assert!(text.parse::<CurvePoint>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let g = crate::curves::p256().g;
+        let zero = CurvePoint::Coordinate(BigUint::from(0u32), BigUint::from(0u32));
+        assert!(bool::from(g.ct_eq(&g.clone())));
+        assert!(bool::from(CurvePoint::Identity.ct_eq(&CurvePoint::Identity)));
+        assert!(!bool::from(g.ct_eq(&CurvePoint::Identity)));
+        assert!(!bool::from(zero.ct_eq(&CurvePoint::Identity)));
+        assert!(!bool::from(g.ct_eq(&zero)));
+    }
+
+    #[test]
+    fn test_untrusted_coordinates_do_not_panic() {
+        let ec = EllipticCurve { a: BigUint::from(2u32), b: BigUint::from(2u32), p: BigUint::from(17u32) };
+        let a = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+
+        // (5, 1) with coordinates shifted by p
+        let unreduced = CurvePoint::Coordinate(BigUint::from(22u32), BigUint::from(1u32));
+        assert!(!ec.is_on_curve(&unreduced));
+        assert_eq!(ec.add(&a, &unreduced), Err(EllipticCurveError::InvalidPoint(unreduced.clone())));
+        assert_eq!(ec.double(&unreduced), Err(EllipticCurveError::InvalidPoint(unreduced.clone())));
+        assert!(ec.scalar_mul(&unreduced, &BigUint::from(3u32)).is_err());
+
+        // curve parameters which are not reduced define the same curve
+        let ec_unreduced = EllipticCurve { a: BigUint::from(19u32), b: BigUint::from(36u32), p: BigUint::from(17u32) };
+        assert!(ec_unreduced.is_on_curve(&a));
+        assert_eq!(ec_unreduced.double(&a), ec.double(&a));
+        assert_eq!(ec_unreduced.scalar_mul(&a, &BigUint::from(5u32)), ec.scalar_mul(&a, &BigUint::from(5u32)));
+
+        // degenerate modulus
+        let ec_zero = EllipticCurve { a: BigUint::from(0u32), b: BigUint::from(0u32), p: BigUint::from(0u32) };
+        assert!(!ec_zero.is_on_curve(&a));
+        assert!(ec_zero.add(&a, &a).is_err());
+    }
+
+    #[test]
+    fn test_error_display_and_source() {
+        use std::error::Error;
+
+        let error = EllipticCurveError::InvalidPoint(CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(2u32)));
+        assert_eq!(error.to_string(), "invalid point: 040102");
+        assert!(error.source().is_none());
+
+        let error = EllipticCurveError::from(FiniteFieldError::NonResidue(BigUint::from(3u32)));
+        assert_eq!(error.to_string(), "field operation failed: 3 is not a quadratic residue");
+        assert_eq!(error.source().unwrap().to_string(), "3 is not a quadratic residue");
+    }
+
+    #[test]
+    fn test_even_modulus() {
+        // built without `EllipticCurve::new`: (0, 0) is on y^2 = x^3 mod 4,
+        // and there is no Montgomery arithmetic modulo 4
+        let ec = EllipticCurve { a: BigUint::from(0u32), b: BigUint::from(0u32), p: BigUint::from(4u32) };
+        let point = CurvePoint::Coordinate(BigUint::from(0u32), BigUint::from(0u32));
+        let three = BigUint::from(3u32);
+        assert!(ec.is_on_curve(&point));
+
+        assert!(matches!(ec.scalar_mul(&point, &three), Err(EllipticCurveError::InvalidCurve(_))));
+        assert!(matches!(
+            ec.double_scalar_mul(&three, &point, &three, &point),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+        let terms = [(three.clone(), point.clone()), (three, point)];
+        assert!(matches!(ec.multi_scalar_mul(&terms), Err(EllipticCurveError::InvalidCurve(_))));
+        assert!(matches!(ec.batch_scalar_mul(&terms), Err(EllipticCurveError::InvalidCurve(_))));
+    }
+
+    #[test]
+    fn test_new_curve() {
+        let ec = EllipticCurve::new(BigUint::from(2u32), BigUint::from(2u32), BigUint::from(17u32));
+        assert_eq!(
+            ec,
+            Ok(EllipticCurve {
+                a: BigUint::from(2u32),
+                b: BigUint::from(2u32),
+                p: BigUint::from(17u32),
+            })
+        );
+
+        // p <= 3 and p not prime
+        assert!(matches!(
+            EllipticCurve::new(BigUint::from(1u32), BigUint::from(1u32), BigUint::from(3u32)),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+        assert!(matches!(
+            EllipticCurve::new(BigUint::from(2u32), BigUint::from(2u32), BigUint::from(15u32)),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+
+        // a, b >= p
+        assert!(matches!(
+            EllipticCurve::new(BigUint::from(17u32), BigUint::from(2u32), BigUint::from(17u32)),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+
+        // 4 * 0^3 + 27 * 0^2 = 0 and 4 * (-3)^3 + 27 * 2^2 = 0
+        assert!(matches!(
+            EllipticCurve::new(BigUint::from(0u32), BigUint::from(0u32), BigUint::from(17u32)),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+        assert!(matches!(
+            EllipticCurve::new(BigUint::from(14u32), BigUint::from(2u32), BigUint::from(17u32)),
+            Err(EllipticCurveError::InvalidCurve(_))
+        ));
+
+        // secp256k1
+        let p = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F", 16).unwrap();
+        assert!(EllipticCurve::new(BigUint::from(0u32), BigUint::from(7u32), p).is_ok());
+    }
+
+    #[test]
+    fn test_double_scalar_mul() {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let q = ec.scalar_mul(&g, &BigUint::from(7u32)).unwrap();
+
+        for u1 in 0u32..25 {
+            for u2 in 0u32..25 {
+                // u1 * G + u2 * (7 * G) = (u1 + 7 * u2) * G
+                let expected = match (u1 + 7 * u2) % 19 {
+                    0 => CurvePoint::Identity,
+                    d => ec.scalar_mul(&g, &BigUint::from(d)).unwrap(),
+                };
+                let res = ec.double_scalar_mul(&BigUint::from(u1), &g, &BigUint::from(u2), &q);
+                assert_eq!(res, Ok(expected), "{} * G + {} * Q", u1, u2);
+            }
+        }
+
+        let bad = CurvePoint::Coordinate(BigUint::from(4u32), BigUint::from(1u32));
+        assert_eq!(
+            ec.double_scalar_mul(&BigUint::from(1u32), &g, &BigUint::from(1u32), &bad),
+            Err(EllipticCurveError::InvalidPoint(bad.clone()))
+        );
+    }
+
+    #[test]
+    fn test_multi_scalar_mul() {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let g3 = ec.scalar_mul(&g, &BigUint::from(3u32)).unwrap();
+        let g11 = ec.scalar_mul(&g, &BigUint::from(11u32)).unwrap();
+
+        // 4 * G + 5 * 3G + 0 * 11G + 2 * 11G = 41 G = 3 G
+        let terms = vec![
+            (BigUint::from(4u32), g.clone()),
+            (BigUint::from(5u32), g3.clone()),
+            (BigUint::from(0u32), g11.clone()),
+            (BigUint::from(2u32), g11.clone()),
+        ];
+        assert_eq!(ec.multi_scalar_mul(&terms), Ok(g3.clone()));
+
+        // 1 * 3G + 6 * 11G = 69 G = 12 G
+        let terms = vec![(BigUint::from(1u32), g3), (BigUint::from(6u32), g11)];
+        assert_eq!(ec.multi_scalar_mul(&terms), ec.scalar_mul(&g, &BigUint::from(12u32)));
+
+        assert_eq!(ec.multi_scalar_mul(&[]), Ok(CurvePoint::Identity));
+        assert_eq!(ec.multi_scalar_mul(&[(BigUint::from(19u32), g.clone())]), Ok(CurvePoint::Identity));
+
+        // more terms than threads with the `parallel` feature:
+        // sum of i * iG for i in [1, 64] = 89440 G = 7 G
+        let terms: Vec<_> = (1..=64u32)
+            .map(|i| (BigUint::from(i), ec.scalar_mul(&g, &BigUint::from(i)).unwrap()))
+            .collect();
+        assert_eq!(ec.multi_scalar_mul(&terms), ec.scalar_mul(&g, &BigUint::from(7u32)));
+    }
+
+    #[test]
+    fn test_scalar_mul_signed() {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+
+        // -3 G = 16 G
+        assert_eq!(ec.scalar_mul_signed(&g, &BigInt::from(-3)), ec.scalar_mul(&g, &BigUint::from(16u32)));
+        assert_eq!(ec.scalar_mul_signed(&g, &BigInt::from(3)), ec.scalar_mul(&g, &BigUint::from(3u32)));
+        assert_eq!(ec.scalar_mul_signed(&g, &BigInt::from(-19)), Ok(CurvePoint::Identity));
+        assert_eq!(ec.scalar_mul_signed(&CurvePoint::Identity, &BigInt::from(-2)), Ok(CurvePoint::Identity));
+        assert_eq!(ec.scalar_mul_signed(&g, &BigInt::from(0)), Ok(CurvePoint::Identity));
+
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(ec.scalar_mul_signed(&off_curve, &BigInt::from(-2)).is_err());
+    }
+
+    #[test]
+    fn test_batch_scalar_mul() {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        let ec = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        let terms: Vec<(BigUint, CurvePoint)> = (1u32..=19).map(|d| (BigUint::from(d), g.clone())).collect();
+        let expected: Vec<CurvePoint> = terms.iter().map(|(d, a)| ec.scalar_mul(a, d).unwrap()).collect();
+        assert_eq!(ec.batch_scalar_mul(&terms), Ok(expected));
+        assert_eq!(ec.batch_scalar_mul(&[]), Ok(vec![]));
+        assert_eq!(ec.batch_scalar_mul(&[(BigUint::from(0u32), g)]), Ok(vec![CurvePoint::Identity]));
+    }
 }
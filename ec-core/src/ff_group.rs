@@ -0,0 +1,937 @@
+/*!
+Implementations of the `ff` and `group` traits for the named curves, so they
+plug into generic protocol crates (bulletproofs, zk tooling...) written
+against those traits:
+
+- `Fp<M>` is an element of the prime field of modulus `M` and implements
+  `ff::PrimeField`; `Base<C>` and `Scalar<C>` are the fields of the
+  coordinates and of the scalars of the curve `C`
+- `GroupPoint<C>` is a point of `C` and implements `group::Group` and
+  `group::GroupEncoding` (SEC1 compressed, all zeros for the identity)
+
+```text
+ use group::Group;
+
+ let g = GroupPoint::<P256>::generator();
+ let p = g * Scalar::<P256>::from(3u64);
+```
+
+Both types are `Copy`, so they are stored as fixed size limbs and converted
+to `BigUint` for the arithmetic. The arithmetic is variable time.
+
+The constants of every field were computed once for `ff`: `S` and `t` with
+`modulus - 1 = 2^S t`, `MULTIPLICATIVE_GENERATOR` the smallest non square
+`g` with `g^((modulus - 1) / f) != 1` for every known prime factor `f` of
+`modulus - 1`, `ROOT_OF_UNITY = g^t` and `DELTA = g^(2^S)`.
+*/
+
+use core::fmt::Debug;
+use core::iter::{Product, Sum};
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use alloc::vec;
+use alloc::vec::Vec;
+use ff::{Field, PrimeField};
+use group::{Group, GroupEncoding};
+use num_bigint::BigUint;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::curves::{BrainpoolP256r1, BrainpoolP384r1, BrainpoolP512r1, Curve, P256, P384, P521, Secp256k1};
+use crate::elliptic_curve::CurvePoint;
+use crate::finite_fields;
+
+/// Number of 64 bits limbs of an element, enough for the 521 bits of P-521.
+pub const LIMBS: usize = 9;
+
+/// Little endian 64 bits limbs.
+pub type Limbs = [u64; LIMBS];
+
+///
+/// A prime modulus with the constants `ff::PrimeField` needs, all as little
+/// endian limbs.
+///
+pub trait Modulus: Copy + Clone + Debug + Default + PartialEq + Eq + Send + Sync + 'static {
+    /// `0x` followed by the big endian hex digits of the modulus.
+    const MODULUS: &'static str;
+    const NUM_BITS: u32;
+    const S: u32;
+    const Q: Limbs;
+    const TWO_INV: Limbs;
+    const MULTIPLICATIVE_GENERATOR: Limbs;
+    const ROOT_OF_UNITY: Limbs;
+    const ROOT_OF_UNITY_INV: Limbs;
+    const DELTA: Limbs;
+
+    /// Byte size of an element.
+    fn byte_size() -> usize {
+        Self::NUM_BITS.div_ceil(8) as usize
+    }
+}
+
+///
+/// A named curve with the moduli of its coordinates (`p`) and scalars (`n`).
+///
+pub trait GroupCurve: Curve {
+    type Base: Modulus;
+    type Scalar: Modulus;
+}
+
+/// An element of the field of the coordinates of `C`.
+pub type Base<C> = Fp<<C as GroupCurve>::Base>;
+
+/// A scalar of `C`, i.e. an integer modulo the order of the group.
+pub type Scalar<C> = Fp<<C as GroupCurve>::Scalar>;
+
+macro_rules! modulus {
+    (
+        $(#[$doc:meta])* $marker:ident, $hex:literal, $bits:literal, $s:literal,
+        $q:expr, $two_inv:expr, $generator:expr, $root:expr, $root_inv:expr, $delta:expr
+    ) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $marker;
+
+        impl Modulus for $marker {
+            const MODULUS: &'static str = $hex;
+            const NUM_BITS: u32 = $bits;
+            const S: u32 = $s;
+            const Q: Limbs = $q;
+            const TWO_INV: Limbs = $two_inv;
+            const MULTIPLICATIVE_GENERATOR: Limbs = $generator;
+            const ROOT_OF_UNITY: Limbs = $root;
+            const ROOT_OF_UNITY_INV: Limbs = $root_inv;
+            const DELTA: Limbs = $delta;
+        }
+    };
+}
+
+modulus!(
+    /// The field of secp256k1, `p`
+    Secp256k1Base,
+    "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    256,
+    1,
+    [0xfffffffefffffc2f, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xffffffff7ffffe18, 0xffffffffffffffff, 0xffffffffffffffff, 0x7fffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000003, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfffffffefffffc2e, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfffffffefffffc2e, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000009, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of secp256k1, `n`
+    Secp256k1Scalar,
+    "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+    256,
+    6,
+    [0xbfd25e8cd0364141, 0xbaaedce6af48a03b, 0xfffffffffffffffe, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xdfe92f46681b20a1, 0x5d576e7357a4501d, 0xffffffffffffffff, 0x7fffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000007, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x992f4b5402b052f2, 0x98bdeab680756045, 0xdf9879a3fbc483a8, 0x0c1dc060e7a91986, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xb6fb30a0884f0d1c, 0x77a275910aa413c3, 0xefc7b0c75b8cbb72, 0xfd3ae181f12d7096, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x199417c8c0bb7601, 0xd63b78e780e1341e, 0x000cbc21fe4561c8, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of P-256, `p`
+    P256Base,
+    "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    256,
+    1,
+    [0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000000, 0x0000000080000000, 0x8000000000000000, 0x7fffffff80000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000006, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfffffffffffffffe, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfffffffffffffffe, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000024, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of P-256, `n`
+    P256Scalar,
+    "0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    256,
+    4,
+    [0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x79dce5617e3192a9, 0xde737d56d38bcf42, 0x7fffffffffffffff, 0x7fffffff80000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000007, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0592d7fbb41e6602, 0x1546cad004378daf, 0xba807ace842a3dfc, 0xffc97f062a770992, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x379c7f0657c73764, 0xe3ac117c794c4137, 0xc645fa0458131cae, 0xa0a66a5562d46f2a, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x00001e39a5057d81, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of P-384, `p`
+    P384Base,
+    "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+    384,
+    1,
+    [0x00000000ffffffff, 0xffffffff00000000, 0xfffffffffffffffe, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000080000000, 0x7fffffff80000000, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x7fffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000013, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x00000000fffffffe, 0xffffffff00000000, 0xfffffffffffffffe, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x00000000fffffffe, 0xffffffff00000000, 0xfffffffffffffffe, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000169, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of P-384, `n`
+    P384Scalar,
+    "0xffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+    384,
+    1,
+    [0xecec196accc52973, 0x581a0db248b0a77a, 0xc7634d81f4372ddf, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x76760cb5666294ba, 0xac0d06d9245853bd, 0xe3b1a6c0fa1b96ef, 0xffffffffffffffff, 0xffffffffffffffff, 0x7fffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000002, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xecec196accc52972, 0x581a0db248b0a77a, 0xc7634d81f4372ddf, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xecec196accc52972, 0x581a0db248b0a77a, 0xc7634d81f4372ddf, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000004, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of P-521, `p`
+    P521Base,
+    "0x1ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    521,
+    1,
+    [0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000001ff],
+    [0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000100],
+    [0x0000000000000003, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfffffffffffffffe, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000001ff],
+    [0xfffffffffffffffe, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000001ff],
+    [0x0000000000000009, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of P-521, `n`
+    P521Scalar,
+    "0x1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e91386409",
+    521,
+    3,
+    [0xbb6fb71e91386409, 0x3bb5c9b8899c47ae, 0x7fcc0148f709a5d0, 0x51868783bf2f966b, 0xfffffffffffffffa, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000001ff],
+    [0x5db7db8f489c3205, 0x1ddae4dc44ce23d7, 0xbfe600a47b84d2e8, 0x28c343c1df97cb35, 0xfffffffffffffffd, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff, 0x00000000000000ff],
+    [0x0000000000000003, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xa7f9c7b7b63e2205, 0x2472b3da861ac810, 0xd998b7836647d3a7, 0x535f79ff391dcdbc, 0xfcc5673a3055276d, 0xe6000d7c12dafa92, 0xd708ad2fa8c4fbc7, 0x0a650d44b28c17f3, 0x000000000000009a],
+    [0x2300c0b1a6fdba3d, 0x36565f876e427d91, 0xdc0924dbdb4eeddc, 0x09eec4daac5aafec, 0xc66f26e7596ce9b0, 0xfdde671628ddb041, 0x4fcf1abd8a70366c, 0x7f97418458545ac0, 0x00000000000000e3],
+    [0x00000000000019a1, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of brainpoolP256r1, `p`
+    BrainpoolP256r1Base,
+    "0xa9fb57dba1eea9bc3e660a909d838d726e3bf623d52620282013481d1f6e5377",
+    256,
+    1,
+    [0x2013481d1f6e5377, 0x6e3bf623d5262028, 0x3e660a909d838d72, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x1009a40e8fb729bc, 0x371dfb11ea931014, 0x1f3305484ec1c6b9, 0x54fdabedd0f754de, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x000000000000000b, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x2013481d1f6e5376, 0x6e3bf623d5262028, 0x3e660a909d838d72, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x2013481d1f6e5376, 0x6e3bf623d5262028, 0x3e660a909d838d72, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000079, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of brainpoolP256r1, `n`
+    BrainpoolP256r1Scalar,
+    "0xa9fb57dba1eea9bc3e660a909d838d718c397aa3b561a6f7901e0e82974856a7",
+    256,
+    1,
+    [0x901e0e82974856a7, 0x8c397aa3b561a6f7, 0x3e660a909d838d71, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xc80f07414ba42b54, 0xc61cbd51dab0d37b, 0x1f3305484ec1c6b8, 0x54fdabedd0f754de, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000003, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x901e0e82974856a6, 0x8c397aa3b561a6f7, 0x3e660a909d838d71, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x901e0e82974856a6, 0x8c397aa3b561a6f7, 0x3e660a909d838d71, 0xa9fb57dba1eea9bc, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000009, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of brainpoolP384r1, `p`
+    BrainpoolP384r1Base,
+    "0x8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b412b1da197fb71123acd3a729901d1a71874700133107ec53",
+    384,
+    1,
+    [0x874700133107ec53, 0xacd3a729901d1a71, 0x12b1da197fb71123, 0x152f7109ed5456b4, 0x0f5d6f7e50e641df, 0x8cb91e82a3386d28, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xc3a380099883f62a, 0xd669d394c80e8d38, 0x0958ed0cbfdb8891, 0x8a97b884f6aa2b5a, 0x07aeb7bf287320ef, 0x465c8f41519c3694, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000003, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x874700133107ec52, 0xacd3a729901d1a71, 0x12b1da197fb71123, 0x152f7109ed5456b4, 0x0f5d6f7e50e641df, 0x8cb91e82a3386d28, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x874700133107ec52, 0xacd3a729901d1a71, 0x12b1da197fb71123, 0x152f7109ed5456b4, 0x0f5d6f7e50e641df, 0x8cb91e82a3386d28, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000009, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of brainpoolP384r1, `n`
+    BrainpoolP384r1Scalar,
+    "0x8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b31f166e6cac0425a7cf3ab6af6b7fc3103b883202e9046565",
+    384,
+    2,
+    [0x3b883202e9046565, 0xcf3ab6af6b7fc310, 0x1f166e6cac0425a7, 0x152f7109ed5456b3, 0x0f5d6f7e50e641df, 0x8cb91e82a3386d28, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x1dc41901748232b3, 0xe79d5b57b5bfe188, 0x8f8b3736560212d3, 0x8a97b884f6aa2b59, 0x07aeb7bf287320ef, 0x465c8f41519c3694, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000002, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xfcf3b95f8d4258ff, 0x8602aeecf53a1982, 0xe476be128dc50cfa, 0x30bb074c85684b3e, 0x55a851fce47cc5f8, 0x76cdc6369fb54dde, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x3e9478a35bc20c66, 0x493807c27645a98d, 0x3a9fb05a1e3f18ad, 0xe47469bd67ec0b74, 0xb9b51d816c697be6, 0x15eb584c03831f49, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x0000000000000010, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The field of brainpoolP512r1, `p`
+    BrainpoolP512r1Base,
+    "0xaadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca703308717d4d9b009bc66842aecda12ae6a380e62881ff2f2d82c68528aa6056583a48f3",
+    512,
+    1,
+    [0x28aa6056583a48f3, 0x2881ff2f2d82c685, 0xaecda12ae6a380e6, 0x7d4d9b009bc66842, 0xd6639cca70330871, 0xcb308db3b3c9d20e, 0x3fd4e6ae33c9fc07, 0xaadd9db8dbe9c48b, 0x0000000000000000],
+    [0x9455302b2c1d247a, 0x1440ff9796c16342, 0x5766d0957351c073, 0xbea6cd804de33421, 0x6b31ce6538198438, 0xe59846d9d9e4e907, 0x9fea735719e4fe03, 0x556ecedc6df4e245, 0x0000000000000000],
+    [0x0000000000000002, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x28aa6056583a48f2, 0x2881ff2f2d82c685, 0xaecda12ae6a380e6, 0x7d4d9b009bc66842, 0xd6639cca70330871, 0xcb308db3b3c9d20e, 0x3fd4e6ae33c9fc07, 0xaadd9db8dbe9c48b, 0x0000000000000000],
+    [0x28aa6056583a48f2, 0x2881ff2f2d82c685, 0xaecda12ae6a380e6, 0x7d4d9b009bc66842, 0xd6639cca70330871, 0xcb308db3b3c9d20e, 0x3fd4e6ae33c9fc07, 0xaadd9db8dbe9c48b, 0x0000000000000000],
+    [0x0000000000000004, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+modulus!(
+    /// The scalars of brainpoolP512r1, `n`
+    BrainpoolP512r1Scalar,
+    "0xaadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca70330870553e5c414ca92619418661197fac10471db1d381085ddaddb58796829ca90069",
+    512,
+    3,
+    [0xb58796829ca90069, 0x1db1d381085ddadd, 0x418661197fac1047, 0x553e5c414ca92619, 0xd6639cca70330870, 0xcb308db3b3c9d20e, 0x3fd4e6ae33c9fc07, 0xaadd9db8dbe9c48b, 0x0000000000000000],
+    [0xdac3cb414e548035, 0x8ed8e9c0842eed6e, 0xa0c3308cbfd60823, 0x2a9f2e20a654930c, 0x6b31ce6538198438, 0xe59846d9d9e4e907, 0x9fea735719e4fe03, 0x556ecedc6df4e245, 0x0000000000000000],
+    [0x0000000000000007, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0x9be4c815611ab592, 0x7a97f496cab90507, 0x8f3d67a1794f1b7c, 0xa70a67e4093ee583, 0x8590e1866f006103, 0x713b6e3c45ccfe62, 0x4783bead7df20bb1, 0x73f4a3dac6cabf59, 0x0000000000000000],
+    [0x97905f90dac838d7, 0x18136733604b32c2, 0x68ab08310f423a80, 0xdba47fa1eb7ad6dd, 0xab91ada271564b8a, 0xc9ea5ad8176ec730, 0xa4dfc2867a37b116, 0x915fe20ed15be823, 0x0000000000000000],
+    [0x000000000057f6c1, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000]
+);
+
+macro_rules! group_curve {
+    ($curve:ident, $base:ident, $scalar:ident) => {
+        impl GroupCurve for $curve {
+            type Base = $base;
+            type Scalar = $scalar;
+        }
+    };
+}
+
+group_curve!(Secp256k1, Secp256k1Base, Secp256k1Scalar);
+group_curve!(P256, P256Base, P256Scalar);
+group_curve!(P384, P384Base, P384Scalar);
+group_curve!(P521, P521Base, P521Scalar);
+group_curve!(BrainpoolP256r1, BrainpoolP256r1Base, BrainpoolP256r1Scalar);
+group_curve!(BrainpoolP384r1, BrainpoolP384r1Base, BrainpoolP384r1Scalar);
+group_curve!(BrainpoolP512r1, BrainpoolP512r1Base, BrainpoolP512r1Scalar);
+
+fn to_biguint(limbs: &Limbs) -> BigUint {
+    let digits: Vec<u32> = limbs.iter().flat_map(|limb| [*limb as u32, (*limb >> 32) as u32]).collect();
+    BigUint::new(digits)
+}
+
+fn to_limbs(value: &BigUint) -> Limbs {
+    let mut limbs = [0u64; LIMBS];
+    for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+const fn small(value: u64) -> Limbs {
+    let mut limbs = [0u64; LIMBS];
+    limbs[0] = value;
+    limbs
+}
+
+///
+/// `value mod M`, always reduced.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fp<M: Modulus> {
+    limbs: Limbs,
+    modulus: PhantomData<M>,
+}
+
+impl<M: Modulus> Fp<M> {
+    const fn from_limbs(limbs: Limbs) -> Self {
+        Fp { limbs, modulus: PhantomData }
+    }
+
+    pub fn modulus() -> BigUint {
+        to_biguint(&M::Q)
+    }
+
+    /// `value mod M`
+    pub fn from_biguint(value: &BigUint) -> Self {
+        Fp::from_limbs(to_limbs(&(value % Self::modulus())))
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        to_biguint(&self.limbs)
+    }
+
+    fn reduce(value: BigUint) -> Self {
+        Fp::from_biguint(&value)
+    }
+}
+
+impl<M: Modulus> From<u64> for Fp<M> {
+    fn from(value: u64) -> Self {
+        Fp::from_biguint(&BigUint::from(value))
+    }
+}
+
+impl<M: Modulus> ConditionallySelectable for Fp<M> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut limbs = [0u64; LIMBS];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.limbs[i], &b.limbs[i], choice);
+        }
+        Fp::from_limbs(limbs)
+    }
+}
+
+impl<M: Modulus> ConstantTimeEq for Fp<M> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.limbs[..].ct_eq(&other.limbs[..])
+    }
+}
+
+impl<M: Modulus> Add<&Fp<M>> for Fp<M> {
+    type Output = Fp<M>;
+
+    fn add(self, other: &Fp<M>) -> Fp<M> {
+        Fp::reduce(self.to_biguint() + other.to_biguint())
+    }
+}
+
+impl<M: Modulus> Sub<&Fp<M>> for Fp<M> {
+    type Output = Fp<M>;
+
+    fn sub(self, other: &Fp<M>) -> Fp<M> {
+        Fp::reduce(self.to_biguint() + Self::modulus() - other.to_biguint())
+    }
+}
+
+impl<M: Modulus> Mul<&Fp<M>> for Fp<M> {
+    type Output = Fp<M>;
+
+    fn mul(self, other: &Fp<M>) -> Fp<M> {
+        Fp::reduce(self.to_biguint() * other.to_biguint())
+    }
+}
+
+impl<M: Modulus> Neg for Fp<M> {
+    type Output = Fp<M>;
+
+    fn neg(self) -> Fp<M> {
+        Fp::ZERO - self
+    }
+}
+
+impl<M: Modulus> Sum for Fp<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a, M: Modulus> Sum<&'a Fp<M>> for Fp<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Fp::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<M: Modulus> Product for Fp<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a, M: Modulus> Product<&'a Fp<M>> for Fp<M> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Fp::ONE, |acc, x| acc * x)
+    }
+}
+
+// `a op b` and `a op= b` for owned and borrowed operands, from `a op &b`
+macro_rules! forward_ops {
+    ($param:ident: $bound:ident, $type:ty, $rhs:ty, $($imp:ident $method:ident $assign_imp:ident $assign_method:ident),*) => {
+        $(
+            impl<$param: $bound> $imp<$rhs> for $type {
+                type Output = $type;
+
+                fn $method(self, other: $rhs) -> $type {
+                    self.$method(&other)
+                }
+            }
+
+            impl<$param: $bound> $assign_imp<$rhs> for $type {
+                fn $assign_method(&mut self, other: $rhs) {
+                    *self = self.$method(&other);
+                }
+            }
+
+            impl<$param: $bound> $assign_imp<&$rhs> for $type {
+                fn $assign_method(&mut self, other: &$rhs) {
+                    *self = self.$method(other);
+                }
+            }
+        )*
+    };
+}
+
+forward_ops!(M: Modulus, Fp<M>, Fp<M>, Add add AddAssign add_assign, Sub sub SubAssign sub_assign, Mul mul MulAssign mul_assign);
+
+impl<M: Modulus> Field for Fp<M> {
+    const ZERO: Self = Fp::from_limbs([0u64; LIMBS]);
+    const ONE: Self = Fp::from_limbs(small(1));
+
+    ///
+    /// Reduces `size + 8` random bytes, so the bias is negligible.
+    ///
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut bytes = vec![0u8; M::byte_size() + 8];
+        rng.fill_bytes(&mut bytes);
+        Fp::reduce(BigUint::from_bytes_be(&bytes))
+    }
+
+    fn square(&self) -> Self {
+        *self * self
+    }
+
+    fn double(&self) -> Self {
+        *self + self
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        let inverse = finite_fields::inverse_multiplicate_prime(&self.to_biguint(), &Self::modulus())
+            .expect("an element is smaller than the modulus");
+        CtOption::new(Fp::reduce(inverse), !self.is_zero())
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        match finite_fields::sqrt_mod_p(&self.to_biguint(), &Self::modulus()) {
+            Ok((root, _)) => CtOption::new(Fp::reduce(root), Choice::from(1)),
+            Err(_) => CtOption::new(Fp::ZERO, Choice::from(0)),
+        }
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        ff::helpers::sqrt_ratio_generic(num, div)
+    }
+}
+
+///
+/// Big endian encoding of an element of `Fp<M>`, on `M::byte_size()` bytes.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct FieldBytes<M: Modulus> {
+    bytes: [u8; 8 * LIMBS],
+    modulus: PhantomData<M>,
+}
+
+impl<M: Modulus> Default for FieldBytes<M> {
+    fn default() -> Self {
+        FieldBytes { bytes: [0u8; 8 * LIMBS], modulus: PhantomData }
+    }
+}
+
+impl<M: Modulus> AsRef<[u8]> for FieldBytes<M> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..M::byte_size()]
+    }
+}
+
+impl<M: Modulus> AsMut<[u8]> for FieldBytes<M> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[..M::byte_size()]
+    }
+}
+
+impl<M: Modulus> PrimeField for Fp<M> {
+    type Repr = FieldBytes<M>;
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let value = BigUint::from_bytes_be(repr.as_ref());
+        let is_reduced = Choice::from(u8::from(value < Self::modulus()));
+        CtOption::new(Fp::reduce(value), is_reduced)
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        let mut repr = FieldBytes::default();
+        let bytes = finite_fields::to_fixed_bytes(&self.to_biguint(), M::byte_size())
+            .expect("an element fits the byte size of the modulus");
+        repr.as_mut().copy_from_slice(&bytes);
+        repr
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from((self.limbs[0] & 1) as u8)
+    }
+
+    const MODULUS: &'static str = M::MODULUS;
+    const NUM_BITS: u32 = M::NUM_BITS;
+    const CAPACITY: u32 = M::NUM_BITS - 1;
+    const TWO_INV: Self = Fp::from_limbs(M::TWO_INV);
+    const MULTIPLICATIVE_GENERATOR: Self = Fp::from_limbs(M::MULTIPLICATIVE_GENERATOR);
+    const S: u32 = M::S;
+    const ROOT_OF_UNITY: Self = Fp::from_limbs(M::ROOT_OF_UNITY);
+    const ROOT_OF_UNITY_INV: Self = Fp::from_limbs(M::ROOT_OF_UNITY_INV);
+    const DELTA: Self = Fp::from_limbs(M::DELTA);
+}
+
+///
+/// A point of the curve `C` in affine coordinates, identity included.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupPoint<C: GroupCurve> {
+    x: Base<C>,
+    y: Base<C>,
+    // the identity has zero coordinates, so the derived `PartialEq` is right
+    is_identity: bool,
+    curve: PhantomData<C>,
+}
+
+impl<C: GroupCurve> GroupPoint<C> {
+    ///
+    /// Converts a point, failing if it is not on the curve.
+    ///
+    pub fn from_curve_point(point: &CurvePoint) -> Option<Self> {
+        if !C::params().curve.is_on_curve(point) {
+            return None;
+        }
+        Some(match point {
+            CurvePoint::Identity => GroupPoint::identity(),
+            CurvePoint::Coordinate(x, y) => GroupPoint {
+                x: Fp::from_biguint(x),
+                y: Fp::from_biguint(y),
+                is_identity: false,
+                curve: PhantomData,
+            },
+        })
+    }
+
+    pub fn to_curve_point(&self) -> CurvePoint {
+        if self.is_identity {
+            return CurvePoint::Identity;
+        }
+        CurvePoint::Coordinate(self.x.to_biguint(), self.y.to_biguint())
+    }
+
+    // operations on points of the curve can not fail
+    fn from_result(point: Result<CurvePoint, crate::EllipticCurveError>) -> Self {
+        GroupPoint::from_curve_point(&point.expect("the points are on the curve")).expect("the result is on the curve")
+    }
+}
+
+impl<C: GroupCurve> ConditionallySelectable for GroupPoint<C> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        GroupPoint {
+            x: Fp::conditional_select(&a.x, &b.x, choice),
+            y: Fp::conditional_select(&a.y, &b.y, choice),
+            is_identity: bool::from(Choice::conditional_select(
+                &Choice::from(u8::from(a.is_identity)),
+                &Choice::from(u8::from(b.is_identity)),
+                choice,
+            )),
+            curve: PhantomData,
+        }
+    }
+}
+
+impl<C: GroupCurve> Add<&GroupPoint<C>> for GroupPoint<C> {
+    type Output = GroupPoint<C>;
+
+    fn add(self, other: &GroupPoint<C>) -> GroupPoint<C> {
+        GroupPoint::from_result(C::params().curve.add(&self.to_curve_point(), &other.to_curve_point()))
+    }
+}
+
+impl<C: GroupCurve> Neg for GroupPoint<C> {
+    type Output = GroupPoint<C>;
+
+    fn neg(self) -> GroupPoint<C> {
+        GroupPoint { y: -self.y, ..self }
+    }
+}
+
+impl<C: GroupCurve> Sub<&GroupPoint<C>> for GroupPoint<C> {
+    type Output = GroupPoint<C>;
+
+    fn sub(self, other: &GroupPoint<C>) -> GroupPoint<C> {
+        self + (-*other)
+    }
+}
+
+impl<C: GroupCurve> Mul<&Scalar<C>> for GroupPoint<C> {
+    type Output = GroupPoint<C>;
+
+    fn mul(self, d: &Scalar<C>) -> GroupPoint<C> {
+        if bool::from(d.is_zero()) {
+            return GroupPoint::identity();
+        }
+        GroupPoint::from_result(C::params().curve.scalar_mul(&self.to_curve_point(), &d.to_biguint()))
+    }
+}
+
+forward_ops!(C: GroupCurve, GroupPoint<C>, GroupPoint<C>, Add add AddAssign add_assign, Sub sub SubAssign sub_assign);
+forward_ops!(C: GroupCurve, GroupPoint<C>, Scalar<C>, Mul mul MulAssign mul_assign);
+
+impl<C: GroupCurve> Sum for GroupPoint<C> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(GroupPoint::identity(), |acc, x| acc + x)
+    }
+}
+
+impl<'a, C: GroupCurve> Sum<&'a GroupPoint<C>> for GroupPoint<C> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(GroupPoint::identity(), |acc, x| acc + x)
+    }
+}
+
+impl<C: GroupCurve> Group for GroupPoint<C> {
+    type Scalar = Scalar<C>;
+
+    fn random(rng: impl RngCore) -> Self {
+        Self::generator() * Scalar::<C>::random(rng)
+    }
+
+    fn identity() -> Self {
+        GroupPoint { x: Fp::ZERO, y: Fp::ZERO, is_identity: true, curve: PhantomData }
+    }
+
+    fn generator() -> Self {
+        GroupPoint::from_curve_point(C::generator()).expect("the generator is on the curve")
+    }
+
+    fn is_identity(&self) -> Choice {
+        Choice::from(u8::from(self.is_identity))
+    }
+
+    fn double(&self) -> Self {
+        GroupPoint::from_result(C::params().curve.double(&self.to_curve_point()))
+    }
+}
+
+///
+/// SEC1 compressed encoding of a `GroupPoint<C>`, `0x02 | 0x03 || x`. The
+/// identity is encoded as zeros.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct PointBytes<C: GroupCurve> {
+    bytes: [u8; 8 * LIMBS + 1],
+    curve: PhantomData<C>,
+}
+
+impl<C: GroupCurve> PointBytes<C> {
+    fn size() -> usize {
+        C::Base::byte_size() + 1
+    }
+}
+
+impl<C: GroupCurve> Default for PointBytes<C> {
+    fn default() -> Self {
+        PointBytes { bytes: [0u8; 8 * LIMBS + 1], curve: PhantomData }
+    }
+}
+
+impl<C: GroupCurve> AsRef<[u8]> for PointBytes<C> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..Self::size()]
+    }
+}
+
+impl<C: GroupCurve> AsMut<[u8]> for PointBytes<C> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[..Self::size()]
+    }
+}
+
+impl<C: GroupCurve> GroupEncoding for GroupPoint<C> {
+    type Repr = PointBytes<C>;
+
+    fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
+        if bytes.as_ref().iter().all(|byte| *byte == 0) {
+            return CtOption::new(GroupPoint::identity(), Choice::from(1));
+        }
+        match CurvePoint::from_sec1_compressed(bytes.as_ref(), &C::params().curve) {
+            Ok(point) => CtOption::new(
+                GroupPoint::from_curve_point(&point).expect("a decoded point is on the curve"),
+                Choice::from(1),
+            ),
+            Err(_) => CtOption::new(GroupPoint::identity(), Choice::from(0)),
+        }
+    }
+
+    fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
+        Self::from_bytes(bytes)
+    }
+
+    fn to_bytes(&self) -> Self::Repr {
+        let mut repr = PointBytes::default();
+        if !self.is_identity {
+            let encoded = self
+                .to_curve_point()
+                .to_sec1_compressed(&C::params().curve)
+                .expect("the point is not the identity");
+            repr.as_mut().copy_from_slice(&encoded);
+        }
+        repr
+    }
+}
+
+///
+/// The coordinates as `elliptic_curve` reads them, e.g. to compute `r` of a
+/// signature from `R = k G`. The identity reads as `(0, 0)`.
+///
+#[cfg(feature = "rustcrypto")]
+impl<C: GroupCurve> elliptic_curve::point::AffineCoordinates for GroupPoint<C> {
+    type FieldRepr = FieldBytes<C::Base>;
+
+    fn x(&self) -> Self::FieldRepr {
+        self.x.to_repr()
+    }
+
+    fn y_is_odd(&self) -> Choice {
+        self.y.is_odd()
+    }
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use group::ff::Field;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn check_modulus<M: Modulus>() {
+        let q = to_biguint(&M::Q);
+        let one = BigUint::from(1u32);
+        let t = (&q - &one) >> M::S;
+        assert!(t.bit(0));
+        assert_eq!(BigUint::parse_bytes(&M::MODULUS.as_bytes()[2..], 16).unwrap(), q);
+        assert_eq!(q.bits() as u32, M::NUM_BITS);
+
+        let generator = to_biguint(&M::MULTIPLICATIVE_GENERATOR);
+        let root = to_biguint(&M::ROOT_OF_UNITY);
+        assert_eq!(generator.modpow(&((&q - &one) >> 1), &q), &q - &one);
+        assert_eq!(generator.modpow(&t, &q), root);
+        assert_eq!(root.modpow(&(BigUint::from(1u32) << M::S), &q), one);
+        assert_eq!(root * to_biguint(&M::ROOT_OF_UNITY_INV) % &q, one);
+        assert_eq!(generator.modpow(&(BigUint::from(1u32) << M::S), &q), to_biguint(&M::DELTA));
+        assert_eq!(to_biguint(&M::TWO_INV) * 2u32 % &q, one);
+    }
+
+    #[test]
+    fn test_constants() {
+        check_modulus::<Secp256k1Base>();
+        check_modulus::<Secp256k1Scalar>();
+        check_modulus::<P256Base>();
+        check_modulus::<P256Scalar>();
+        check_modulus::<P384Base>();
+        check_modulus::<P384Scalar>();
+        check_modulus::<P521Base>();
+        check_modulus::<P521Scalar>();
+        check_modulus::<BrainpoolP256r1Base>();
+        check_modulus::<BrainpoolP256r1Scalar>();
+        check_modulus::<BrainpoolP384r1Base>();
+        check_modulus::<BrainpoolP384r1Scalar>();
+        check_modulus::<BrainpoolP512r1Base>();
+        check_modulus::<BrainpoolP512r1Scalar>();
+
+        assert_eq!(Scalar::<P256>::modulus(), P256::params().n);
+        assert_eq!(Base::<P521>::modulus(), P521::params().curve.p);
+    }
+
+    // only goes through the `ff` traits, as a generic crate would
+    fn check_field<F: PrimeField>(rng: &mut StdRng) {
+        for _ in 0..10 {
+            let (a, b, c) = (F::random(&mut *rng), F::random(&mut *rng), F::random(&mut *rng));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!(a - b + b, a);
+            assert_eq!(a.double(), a + a);
+            assert_eq!(a.square(), a * a);
+            assert_eq!(-a + a, F::ZERO);
+            if !bool::from(a.is_zero()) {
+                assert_eq!(a * a.invert().unwrap(), F::ONE);
+            }
+            assert_eq!(a.square().sqrt().unwrap().square(), a.square());
+            assert_eq!(F::from_repr(a.to_repr()).unwrap(), a);
+            assert_eq!([a, b, c].iter().sum::<F>(), a + b + c);
+            assert_eq!([a, b, c].iter().product::<F>(), a * b * c);
+        }
+
+        assert!(bool::from(F::ZERO.invert().is_none()));
+        assert_eq!(F::TWO_INV.double(), F::ONE);
+        assert_eq!(F::ROOT_OF_UNITY * F::ROOT_OF_UNITY_INV, F::ONE);
+        // the generator is not a square
+        assert!(bool::from(F::MULTIPLICATIVE_GENERATOR.sqrt().is_none()));
+        let (is_square, root) = F::sqrt_ratio(&F::from(9), &F::from(4));
+        assert!(bool::from(is_square));
+        assert_eq!(root.square() * F::from(4), F::from(9));
+        assert!(bool::from(F::ONE.is_odd()));
+    }
+
+    #[test]
+    fn test_prime_field() {
+        let mut rng = StdRng::seed_from_u64(1);
+        check_field::<Base<Secp256k1>>(&mut rng);
+        check_field::<Scalar<Secp256k1>>(&mut rng);
+        check_field::<Scalar<P256>>(&mut rng);
+        check_field::<Base<P384>>(&mut rng);
+        check_field::<Scalar<P521>>(&mut rng);
+        check_field::<Base<BrainpoolP512r1>>(&mut rng);
+    }
+
+    #[test]
+    fn test_field_repr() {
+        let repr = Scalar::<P256>::from(0x0102).to_repr();
+        assert_eq!(repr.as_ref().len(), 32);
+        assert_eq!(&repr.as_ref()[30..], &[0x01, 0x02]);
+        assert_eq!(Base::<P521>::ONE.to_repr().as_ref().len(), 66);
+
+        let mut repr = FieldBytes::<P256Scalar>::default();
+        repr.as_mut().copy_from_slice(&P256::params().n.to_bytes_be());
+        assert!(bool::from(Scalar::<P256>::from_repr(repr).is_none()));
+    }
+
+    fn check_group<G: Group + GroupEncoding>(rng: &mut StdRng) {
+        let g = G::generator();
+        assert_eq!(g * G::Scalar::from(3), g + g + g);
+        assert_eq!(g.double(), g + g);
+        assert!(bool::from((g.double() - g - g).is_identity()));
+        assert!(bool::from((g * G::Scalar::ZERO).is_identity()));
+        assert_eq!(-g + g, G::identity());
+
+        let p = G::random(&mut *rng);
+        let (a, b) = (G::Scalar::random(&mut *rng), G::Scalar::random(&mut *rng));
+        assert_eq!(p * a + p * b, p * (a + b));
+        assert_eq!([p, g].iter().sum::<G>(), p + g);
+
+        for point in [p, G::identity()] {
+            assert_eq!(G::from_bytes(&point.to_bytes()).unwrap(), point);
+        }
+    }
+
+    #[test]
+    fn test_group() {
+        let mut rng = StdRng::seed_from_u64(2);
+        check_group::<GroupPoint<Secp256k1>>(&mut rng);
+        check_group::<GroupPoint<P256>>(&mut rng);
+        check_group::<GroupPoint<P384>>(&mut rng);
+        check_group::<GroupPoint<BrainpoolP256r1>>(&mut rng);
+    }
+
+    #[test]
+    fn test_group_encoding() {
+        let g = GroupPoint::<P256>::generator();
+        let encoded = g.to_bytes();
+        assert_eq!(encoded.as_ref(), &P256::generator().to_sec1_compressed(&P256::params().curve).unwrap()[..]);
+        assert_eq!(GroupPoint::<P256>::identity().to_bytes().as_ref(), &[0u8; 33]);
+
+        let mut bad = encoded;
+        bad.as_mut()[0] = 0x05;
+        assert!(bool::from(GroupPoint::<P256>::from_bytes(&bad).is_none()));
+        assert_eq!(g.to_curve_point(), *P256::generator());
+        assert!(GroupPoint::<P256>::from_curve_point(Secp256k1::generator()).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "rustcrypto")]
+    fn test_affine_coordinates() {
+        use elliptic_curve::point::AffineCoordinates;
+
+        let g = GroupPoint::<Secp256k1>::generator();
+        let encoded = g.to_bytes();
+        assert_eq!(g.x().as_ref(), &encoded.as_ref()[1..]);
+        assert_eq!(bool::from(g.y_is_odd()), encoded.as_ref()[0] == 0x03);
+        assert_eq!(bool::from((-g).y_is_odd()), !bool::from(g.y_is_odd()));
+    }
+}
@@ -0,0 +1,325 @@
+/*!
+An element of the prime field `F_p` carrying its modulus, so field arithmetic
+can be written with operators instead of threading `(a, b, p)` through the
+functions of `finite_fields`:
+
+```text
+ let x = FieldElement::new(x, p.clone())?;
+ let y2 = x.pow(&3u32.into()) + &a * &x + b;
+```
+
+The value is reduced once, when the element is built, so the operations do
+not need to check their arguments. Mixing elements of different fields is a
+programming error and panics, as does dividing by zero.
+*/
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use alloc::format;
+use alloc::vec;
+use num_bigint::BigUint;
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::finite_fields::{self, FiniteFieldError};
+
+///
+/// `value mod modulus`, with `value` always in `[0, modulus - 1]`.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldElement {
+    value: BigUint,
+    modulus: BigUint,
+}
+
+impl FieldElement {
+    ///
+    /// Builds `value mod modulus`. The modulus has to be greater than `1`; it is
+    /// expected to be prime for `inverse` and `/` to be correct.
+    ///
+    pub fn new(value: BigUint, modulus: BigUint) -> Result<Self, FiniteFieldError> {
+        if modulus <= BigUint::from(1u32) {
+            return Err(FiniteFieldError::InvalidArgument(format!("modulus has to be greater than 1: {}", modulus)));
+        }
+        Ok(FieldElement { value: value % &modulus, modulus })
+    }
+
+    ///
+    /// A uniformly random element of `F_p` by rejection sampling: draws
+    /// `bits(p)` random bits until the value is smaller than `p`, which takes
+    /// less than two draws on average and has no modulo bias.
+    ///
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R, modulus: BigUint) -> Result<Self, FiniteFieldError> {
+        if modulus <= BigUint::from(1u32) {
+            return Err(FiniteFieldError::InvalidArgument(format!("modulus has to be greater than 1: {}", modulus)));
+        }
+
+        let bits = modulus.bits();
+        let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+        let top_mask = 0xffu8 >> (8 * bytes.len() as u64 - bits);
+        loop {
+            rng.fill_bytes(&mut bytes);
+            bytes[0] &= top_mask;
+            let value = BigUint::from_bytes_be(&bytes);
+            if value < modulus {
+                return Ok(FieldElement { value, modulus });
+            }
+        }
+    }
+
+    ///
+    /// A uniformly random element of `F_p` other than zero, as needed for
+    /// private keys, nonces and blinding factors.
+    ///
+    pub fn random_nonzero<R: CryptoRng + RngCore>(rng: &mut R, modulus: BigUint) -> Result<Self, FiniteFieldError> {
+        loop {
+            let element = FieldElement::random(rng, modulus.clone())?;
+            if !element.is_zero() {
+                return Ok(element);
+            }
+        }
+    }
+
+    pub fn zero(modulus: BigUint) -> Result<Self, FiniteFieldError> {
+        FieldElement::new(BigUint::from(0u32), modulus)
+    }
+
+    pub fn one(modulus: BigUint) -> Result<Self, FiniteFieldError> {
+        FieldElement::new(BigUint::from(1u32), modulus)
+    }
+
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    pub fn into_value(self) -> BigUint {
+        self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == BigUint::from(0u32)
+    }
+
+    ///
+    /// `self^exponent mod p`
+    ///
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        self.with_value(self.value.modpow(exponent, &self.modulus))
+    }
+
+    ///
+    /// The multiplicative inverse `self^(p-2) mod p`, failing for zero.
+    ///
+    pub fn inverse(&self) -> Result<Self, FiniteFieldError> {
+        if self.is_zero() {
+            return Err(FiniteFieldError::InvalidArgument("0 has no multiplicative inverse".into()));
+        }
+        Ok(self.with_value(finite_fields::inverse_multiplicate_prime(&self.value, &self.modulus)?))
+    }
+
+    fn with_value(&self, value: BigUint) -> Self {
+        FieldElement { value, modulus: self.modulus.clone() }
+    }
+
+    fn same_field(&self, other: &FieldElement) {
+        assert!(self.modulus == other.modulus, "elements of different fields");
+    }
+}
+
+///
+/// Constant time comparison of the values and of the moduli, see
+/// `finite_fields::biguint_ct_eq`.
+///
+impl ConstantTimeEq for FieldElement {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        finite_fields::biguint_ct_eq(&self.value, &other.value) & finite_fields::biguint_ct_eq(&self.modulus, &other.modulus)
+    }
+}
+
+impl Add<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    fn add(self, other: &FieldElement) -> FieldElement {
+        self.same_field(other);
+        self.with_value((&self.value + &other.value) % &self.modulus)
+    }
+}
+
+impl Sub<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    fn sub(self, other: &FieldElement) -> FieldElement {
+        self.same_field(other);
+        self.with_value((&self.value + &self.modulus - &other.value) % &self.modulus)
+    }
+}
+
+impl Mul<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    fn mul(self, other: &FieldElement) -> FieldElement {
+        self.same_field(other);
+        self.with_value((&self.value * &other.value) % &self.modulus)
+    }
+}
+
+impl Div<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    fn div(self, other: &FieldElement) -> FieldElement {
+        self.same_field(other);
+        let inverse = other.inverse().expect("division by zero");
+        self.with_value((&self.value * &inverse.value) % &self.modulus)
+    }
+}
+
+impl Neg for &FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        self.with_value((&self.modulus - &self.value) % &self.modulus)
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        -&self
+    }
+}
+
+// the owned and mixed forms all borrow into the `&a op &b` implementations
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident) => {
+        impl $imp for FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, other: FieldElement) -> FieldElement {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&FieldElement> for FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, other: &FieldElement) -> FieldElement {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<FieldElement> for &FieldElement {
+            type Output = FieldElement;
+
+            fn $method(self, other: FieldElement) -> FieldElement {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+forward_binop!(Div, div);
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f17(value: u32) -> FieldElement {
+        FieldElement::new(BigUint::from(value), BigUint::from(17u32)).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(f17(20).value(), &BigUint::from(3u32));
+        assert_eq!(f17(3).modulus(), &BigUint::from(17u32));
+        assert!(FieldElement::new(BigUint::from(0u32), BigUint::from(1u32)).is_err());
+        assert!(FieldElement::zero(BigUint::from(17u32)).unwrap().is_zero());
+        assert_eq!(FieldElement::one(BigUint::from(17u32)).unwrap(), f17(18));
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(f17(10) + f17(9), f17(2));
+        assert_eq!(&f17(3) - &f17(5), f17(15));
+        assert_eq!(f17(5) * &f17(7), f17(1));
+        assert_eq!(&f17(1) / f17(5), f17(7));
+        assert_eq!(f17(6) / f17(3), f17(2));
+        assert_eq!(-f17(4), f17(13));
+        assert_eq!(-f17(0), f17(0));
+    }
+
+    #[test]
+    fn test_matches_finite_fields() {
+        let p = BigUint::from(17u32);
+        for a in 0..17u32 {
+            for b in 1..17u32 {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!((f17(a) + f17(b)).into_value(), finite_fields::add(&x, &y, &p).unwrap());
+                assert_eq!((f17(a) - f17(b)).into_value(), finite_fields::subtract(&x, &y, &p).unwrap());
+                assert_eq!((f17(a) * f17(b)).into_value(), finite_fields::multiplicate(&x, &y, &p).unwrap());
+                assert_eq!((f17(a) / f17(b)).into_value(), finite_fields::divide(&x, &y, &p).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow_inverse() {
+        assert_eq!(f17(3).pow(&BigUint::from(4u32)), f17(13));
+        assert_eq!(f17(3).pow(&BigUint::from(0u32)), f17(1));
+        assert_eq!(f17(3).inverse().unwrap(), f17(6));
+        assert_eq!(&f17(3).inverse().unwrap() * &f17(3), f17(1));
+        assert!(f17(0).inverse().is_err());
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(bool::from(f17(3).ct_eq(&f17(20))));
+        assert!(!bool::from(f17(3).ct_eq(&f17(4))));
+        let other_field = FieldElement::new(BigUint::from(3u32), BigUint::from(19u32)).unwrap();
+        assert!(!bool::from(f17(3).ct_eq(&other_field)));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn test_divide_by_zero() {
+        let _ = f17(1) / f17(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "elements of different fields")]
+    fn test_different_fields() {
+        let _ = f17(1) + FieldElement::new(BigUint::from(1u32), BigUint::from(19u32)).unwrap();
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(79);
+        let mut counts = [0u32; 17];
+        for _ in 0..1700 {
+            let element = FieldElement::random(&mut rng, BigUint::from(17u32)).unwrap();
+            assert_eq!(element.modulus(), &BigUint::from(17u32));
+            counts[element.value().to_u32_digits().first().copied().unwrap_or(0) as usize] += 1;
+        }
+        // every value shows up, none much more often than the others
+        assert!(counts.iter().all(|count| (50..150).contains(count)));
+
+        for _ in 0..100 {
+            assert_eq!(FieldElement::random_nonzero(&mut rng, BigUint::from(2u32)).unwrap().value(), &BigUint::from(1u32));
+        }
+        assert!(FieldElement::random(&mut rng, BigUint::from(1u32)).is_err());
+    }
+}
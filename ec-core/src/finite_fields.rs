@@ -2,28 +2,54 @@
 /// A library which implements the bottom layer finite field group operations needed to
 /// operate with the coordinates of the elliptic curve group.
 ///
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq};
 
 
 
 
+///
+/// Errors of the field operations, carrying the operands which made them
+/// fail.
+///
 #[derive(Debug, PartialEq)]
 pub enum FiniteFieldError {
+    /// An operand or modulus outside of the domain of the operation.
     InvalidArgument(String),
+    /// The operation has no result for these operands.
     InvalidResult(String),
+    /// `sqrt_mod_p` of an element which is not a square.
+    NonResidue(BigUint),
+}
+
+impl fmt::Display for FiniteFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FiniteFieldError::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            FiniteFieldError::InvalidResult(message) => write!(f, "invalid result: {}", message),
+            FiniteFieldError::NonResidue(a) => write!(f, "{} is not a quadratic residue", a),
+        }
+    }
 }
 
+impl core::error::Error for FiniteFieldError {}
+
 
 ///
 /// Adds to elements in the set (this would also return a point in the curve)
 ///
 /// `a + b = a mod p`
 ///
-
 pub fn add(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
     params_to_mod_check(a,b,p)?;
 
-    Ok((a + b).modpow(&BigUint::from(1u32), p))
+    Ok(p.add(a, b))
 }
 
 
@@ -36,7 +62,7 @@ pub fn add(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteField
 pub fn multiplicate(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
     params_to_mod_check(a,b,p)?;
 
-    Ok((a * b).modpow(&BigUint::from(1u32), p))
+    Ok(p.mul(a, b))
 }
 
 
@@ -96,27 +122,835 @@ pub fn divide(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFi
 }
 
 
+///
+/// The `*_reduce` variants of the operations above reduce their operands mod
+/// p instead of rejecting the ones greater than or equal to `p`:
+///
+/// `add_reduce(a, b, p) = add(a mod p, b mod p, p)`
+///
+/// They only fail if `p = 0`, or for the inversions if the operand is a
+/// multiple of `p`.
+///
+pub fn add_reduce(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    add(&reduce(a, p)?, &reduce(b, p)?, p)
+}
+
+pub fn subtract_reduce(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    subtract(&reduce(a, p)?, &reduce(b, p)?, p)
+}
+
+pub fn multiplicate_reduce(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    multiplicate(&reduce(a, p)?, &reduce(b, p)?, p)
+}
+
+pub fn inverse_add_reduce(a: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    inverse_add(&reduce(a, p)?, p)
+}
 
+pub fn inverse_multiplicate_prime_reduce(a: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    let a = reduce(a, p)?;
+    if a == BigUint::from(0u32) {
+        return Err(FiniteFieldError::InvalidArgument("0 has no multiplicative inverse".into()));
+    }
+    inverse_multiplicate_prime(&a, p)
+}
 
+pub fn divide_reduce(a: &BigUint, b: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    let b_inverse = inverse_multiplicate_prime_reduce(b, p)?;
+    multiplicate(&reduce(a, p)?, &b_inverse, p)
+}
 
+fn reduce(a: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    if *p == BigUint::from(0u32) {
+        return Err(FiniteFieldError::InvalidArgument("p has to be greater than 0".into()));
+    }
+    Ok(a % p)
+}
 
 
+///
+/// Inverts every element of `a` with a single exponentiation (Montgomery's
+/// trick), `p` being prime:
+///
+/// ```text
+///  c_i = a_0 a_1 ... a_i                      prefix products
+///  u = c_(n-1)^(-1)
+///  a_i^(-1) = u c_(i-1),  u = u a_i           from the last element down
+/// ```
+///
+/// That is one inversion and `3 (n - 1)` multiplications instead of `n`
+/// inversions. Fails if an element is zero or not reduced mod p.
+///
+pub fn batch_inverse(a: &[BigUint], p: &BigUint) -> Result<Vec<BigUint>, FiniteFieldError> {
+    if a.is_empty() {
+        return Ok(Vec::new());
+    }
 
+    let mut prefix = Vec::with_capacity(a.len());
+    let mut product = BigUint::from(1u32);
+    for element in a {
+        params_to_mod_check_single_point(element, p)?;
+        if *element == BigUint::from(0u32) {
+            return Err(FiniteFieldError::InvalidArgument("0 has no multiplicative inverse".into()));
+        }
+        product = p.mul(&product, element);
+        prefix.push(product.clone());
+    }
 
+    let mut u = inverse_multiplicate_prime(&product, p)?;
+    let mut inverses = vec![BigUint::from(0u32); a.len()];
+    for i in (1..a.len()).rev() {
+        inverses[i] = p.mul(&u, &prefix[i - 1]);
+        u = p.mul(&u, &a[i]);
+    }
+    inverses[0] = u;
 
+    Ok(inverses)
+}
 
 
 ///
-/// This function check if `a  < b`; if a is b, function would return true
+/// The Legendre symbol `(a / p)` for an odd prime `p`:
 ///
-pub fn check_is_less_than(a: &BigUint, b: &BigUint)  -> bool {
-    if a < b {
-        true
+/// - `1` if `a` is a non zero square mod p
+/// - `-1` if `a` is not a square mod p
+/// - `0` if `a = 0`
+///
+/// It is computed as the Jacobi symbol with the reciprocity law, which only
+/// needs shifts and reductions instead of the exponentiation of Euler's
+/// criterion `a^((p-1)/2) mod p`.
+///
+pub fn legendre(a: &BigUint, p: &BigUint) -> Result<i8, FiniteFieldError> {
+    params_to_mod_check_single_point(a, p)?;
+    if *p <= BigUint::from(2u32) || !p.bit(0) {
+        return Err(FiniteFieldError::InvalidArgument(format!("p has to be an odd prime: {}", p)));
+    }
+
+    let zero = BigUint::from(0u32);
+    let mut a = a.clone();
+    let mut n = p.clone();
+    let mut symbol = 1i8;
+    while a != zero {
+        // (2 / n) = -1 for n = 3, 5 mod 8
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        let n_mod_8 = n.iter_u32_digits().next().unwrap_or(0) & 7;
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            symbol = -symbol;
+        }
+
+        // (a / n) = -(n / a) if a = n = 3 mod 4
+        core::mem::swap(&mut a, &mut n);
+        if a.bit(1) && n.bit(1) {
+            symbol = -symbol;
+        }
+        a %= &n;
+    }
+
+    if n == BigUint::from(1u32) {
+        Ok(symbol)
     } else {
-        false
+        Ok(0)
     }
 }
 
+
+///
+/// Whether `a` has a square root mod the odd prime `p`, `0` included:
+/// `legendre(a, p) != -1`.
+///
+pub fn is_quadratic_residue(a: &BigUint, p: &BigUint) -> Result<bool, FiniteFieldError> {
+    Ok(legendre(a, p)? != -1)
+}
+
+
+///
+/// Finds both square roots of an element in the set if p is an odd prime
+/// number:
+///
+/// `r * r = (p - r) * (p - r) = a mod p`
+///
+/// - if `p = 3 mod 4` then `r = a^((p+1)/4) mod p`
+/// - otherwise the Tonelli-Shanks algorithm is used
+///
+/// The roots are returned as `(r, p - r)` with `r < p - r`, so with `p` odd
+/// exactly one of them is even unless `a = 0`, whose only root is `(0, 0)`.
+/// Fails with `NonResidue` if `a` has no square root.
+///
+pub fn sqrt_mod_p(a: &BigUint, p: &BigUint) -> Result<(BigUint, BigUint), FiniteFieldError> {
+    params_to_mod_check_single_point(a, p)?;
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    if *p <= two || !p.bit(0) {
+        return Err(FiniteFieldError::InvalidArgument(format!("p has to be an odd prime: {}", p)));
+    }
+    if *a == zero {
+        return Ok((zero.clone(), zero));
+    }
+
+    if !is_quadratic_residue(a, p)? {
+        return Err(FiniteFieldError::NonResidue(a.clone()));
+    }
+
+    let r = if p % BigUint::from(4u32) == BigUint::from(3u32) {
+        a.modpow(&((p + &one) >> 2), p)
+    } else {
+        tonelli_shanks(a, p)?
+    };
+
+    let minus_r = p - &r;
+    if r < minus_r {
+        Ok((r, minus_r))
+    } else {
+        Ok((minus_r, r))
+    }
+}
+
+
+///
+/// Tonelli-Shanks square root of a quadratic residue `a` modulo an odd prime `p`:
+///
+/// ```text
+///  p - 1 = q * 2^s with q odd
+///  z = any non residue
+///  m = s, c = z^q, t = a^q, r = a^((q+1)/2)
+///  while t != 1
+///       i = least 0 < i < m such that t^(2^i) = 1
+///       b = c^(2^(m-i-1))
+///       m = i, c = b^2, t = t * b^2, r = r * b
+/// ```
+///
+fn tonelli_shanks(a: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    let p_minus_one = p - &one;
+
+    let mut q = p_minus_one.clone();
+    let mut s = 0u64;
+    while !q.bit(0) {
+        q >>= 1;
+        s += 1;
+    }
+
+    let mut z = two.clone();
+    while legendre(&z, p)? != -1 {
+        z += &one;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + &one) >> 1), p);
+
+    while t != one {
+        let mut i = 0u64;
+        let mut t2i = t.clone();
+        while t2i != one {
+            t2i = multiplicate(&t2i, &t2i, p)?;
+            i += 1;
+            if i == m {
+                return Err(FiniteFieldError::InvalidResult(format!("{} is not a quadratic residue mod {}", a, p)));
+            }
+        }
+
+        let b = c.modpow(&(&one << (m - i - 1)), p);
+        m = i;
+        c = multiplicate(&b, &b, p)?;
+        t = multiplicate(&t, &c, p)?;
+        r = multiplicate(&r, &b, p)?;
+    }
+
+    Ok(r)
+}
+
+
+
+
+
+
+
+
+
+
+
+
+///
+/// Multiplication modulo `p` in some representation of the elements of the
+/// set, so long chains of operations (the point formulas of a scalar
+/// multiplication) can pick their reduction strategy. Every representation
+/// is linear, so additions and subtractions are the usual ones.
+///
+/// A `BigUint` is the modulus itself and reduces by division.
+///
+pub trait FieldArithmetic {
+    fn modulus(&self) -> &BigUint;
+
+    /// Converts `a < p` to the representation.
+    fn to_repr(&self, a: &BigUint) -> BigUint;
+
+    /// Converts back from the representation.
+    fn to_value(&self, a: &BigUint) -> BigUint;
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint;
+
+    fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let sum = a + b;
+        if sum >= *self.modulus() {
+            sum - self.modulus()
+        } else {
+            sum
+        }
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if a >= b {
+            a - b
+        } else {
+            a + self.modulus() - b
+        }
+    }
+}
+
+impl FieldArithmetic for BigUint {
+    fn modulus(&self) -> &BigUint {
+        self
+    }
+
+    fn to_repr(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn to_value(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % self
+    }
+}
+
+
+///
+/// Montgomery arithmetic modulo an odd `p`. With `R = 2^(64 n)` the first
+/// power of the limb size above `p`, an element `a` is kept as `a R mod p`
+/// and products are reduced with masks and shifts instead of a division
+/// (REDC):
+///
+/// ```text
+///  p' = -p^(-1) mod R, R^2 mod p     precomputed
+///  t = a R * b R
+///  m = (t mod R) p' mod R
+///  u = (t + m p) / R                  = a b R mod p, minus p if u >= p
+/// ```
+///
+/// Converting in and out costs a product each, so it pays off for repeated
+/// multiplications only.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct MontgomeryContext {
+    p: BigUint,
+    // R = 2^shift
+    shift: u64,
+    mask: BigUint,
+    p_prime: BigUint,
+    r2: BigUint,
+}
+
+impl MontgomeryContext {
+    pub fn new(p: &BigUint) -> Result<Self, FiniteFieldError> {
+        if *p <= BigUint::from(2u32) || !p.bit(0) {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} is not an odd modulus", p)));
+        }
+
+        let shift = 64 * p.bits().div_ceil(64);
+        let r = BigUint::from(1u32) << shift;
+        let mask = &r - BigUint::from(1u32);
+
+        // Newton iteration doubling the number of correct bits of p^(-1) mod R
+        let mut inverse = BigUint::from(1u32);
+        let mut correct_bits = 1;
+        while correct_bits < shift {
+            let error = (p * &inverse) & &mask;
+            inverse = (&inverse * ((&r + BigUint::from(2u32) - error) & &mask)) & &mask;
+            correct_bits *= 2;
+        }
+
+        let r2 = (&r * &r) % p;
+        Ok(MontgomeryContext { p: p.clone(), shift, p_prime: (&r - inverse) & &mask, mask, r2 })
+    }
+
+    ///
+    /// `t R^(-1) mod p` for `t < p R`.
+    ///
+    pub fn reduce(&self, t: &BigUint) -> BigUint {
+        let m = ((t & &self.mask) * &self.p_prime) & &self.mask;
+        let u = (t + m * &self.p) >> self.shift;
+        if u >= self.p {
+            u - &self.p
+        } else {
+            u
+        }
+    }
+
+    ///
+    /// `a^e` of an element in Montgomery form, square and multiply.
+    ///
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        let mut result = self.to_repr(&BigUint::from(1u32));
+        for i in (0..e.bits()).rev() {
+            result = self.mul(&result, &result);
+            if e.bit(i) {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+}
+
+impl FieldArithmetic for MontgomeryContext {
+    fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    /// `a R mod p = REDC(a R^2)`
+    fn to_repr(&self, a: &BigUint) -> BigUint {
+        self.reduce(&(a * &self.r2))
+    }
+
+    /// `a = REDC(a R)`
+    fn to_value(&self, a: &BigUint) -> BigUint {
+        self.reduce(a)
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&(a * b))
+    }
+}
+
+///
+/// Barrett reduction modulo a fixed `p`, for reducing many values by the
+/// same modulus. With `k` the bit size of `p` and `mu = floor(4^k / p)`
+/// precomputed, the quotient is estimated with two products and shifts:
+///
+/// ```text
+///  q = ((t >> (k - 1)) mu) >> (k + 1)      q <= t / p < q + 3
+///  r = t - q p, minus p at most twice
+/// ```
+///
+/// for any `t < 4^k`, e.g. the product of two elements of the set.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct Reducer {
+    p: BigUint,
+    k: u64,
+    mu: BigUint,
+}
+
+impl Reducer {
+    pub fn new(p: &BigUint) -> Result<Self, FiniteFieldError> {
+        if *p <= BigUint::from(1u32) {
+            return Err(FiniteFieldError::InvalidArgument(format!("modulus has to be greater than 1: {}", p)));
+        }
+
+        let k = p.bits();
+        Ok(Reducer { p: p.clone(), k, mu: (BigUint::from(1u32) << (2 * k)) / p })
+    }
+
+    ///
+    /// `t mod p` for `t < 4^k`.
+    ///
+    pub fn reduce(&self, t: &BigUint) -> BigUint {
+        debug_assert!(t.bits() <= 2 * self.k, "the value is too large for the reducer");
+        let q = ((t >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        let mut r = t - q * &self.p;
+        while r >= self.p {
+            r -= &self.p;
+        }
+        r
+    }
+}
+
+impl FieldArithmetic for Reducer {
+    fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    fn to_repr(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn to_value(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&(a * b))
+    }
+}
+
+
+///
+/// The primes of the named curves with a fast reduction identity:
+///
+/// - secp256k1, `2^256 - 2^32 - 977`, and P-521, `2^521 - 1`, are
+///   pseudo-Mersenne primes `2^k - c` with a small `c`, so `2^k = c mod p`
+///   folds the high part of a value onto its low part
+/// - the 32 bits words of a product modulo P-256 and P-384 are recombined
+///   with the additions and subtractions of FIPS 186-4, D.2
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpecialPrime {
+    Secp256k1,
+    P256,
+    P384,
+    P521,
+}
+
+impl SpecialPrime {
+    pub fn modulus(&self) -> BigUint {
+        let one = BigUint::from(1u32);
+        match self {
+            SpecialPrime::Secp256k1 => (&one << 256) - (&one << 32) - BigUint::from(977u32),
+            SpecialPrime::P256 => (&one << 256) - (&one << 224) + (&one << 192) + (&one << 96) - one,
+            SpecialPrime::P384 => (&one << 384) - (&one << 128) - (&one << 96) + (&one << 32) - one,
+            SpecialPrime::P521 => (&one << 521) - one,
+        }
+    }
+}
+
+// Words of FIPS 186-4 D.2, least significant first, with `Z` a zero word:
+// the reduction of `c = (c_(2n-1), ..., c_0)` is `sum coefficient * term mod p`
+const Z256: usize = 16;
+const P256_TERMS: [(i64, [usize; 8]); 9] = [
+    (1, [0, 1, 2, 3, 4, 5, 6, 7]),
+    (2, [Z256, Z256, Z256, 11, 12, 13, 14, 15]),
+    (2, [Z256, Z256, Z256, 12, 13, 14, 15, Z256]),
+    (1, [8, 9, 10, Z256, Z256, Z256, 14, 15]),
+    (1, [9, 10, 11, 13, 14, 15, 13, 8]),
+    (-1, [11, 12, 13, Z256, Z256, Z256, 8, 10]),
+    (-1, [12, 13, 14, 15, Z256, Z256, 9, 11]),
+    (-1, [13, 14, 15, 8, 9, 10, Z256, 12]),
+    (-1, [14, 15, Z256, 9, 10, 11, Z256, 13]),
+];
+
+const Z384: usize = 24;
+const P384_TERMS: [(i64, [usize; 12]); 10] = [
+    (1, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]),
+    (2, [Z384, Z384, Z384, Z384, 21, 22, 23, Z384, Z384, Z384, Z384, Z384]),
+    (1, [12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]),
+    (1, [21, 22, 23, 12, 13, 14, 15, 16, 17, 18, 19, 20]),
+    (1, [Z384, 23, Z384, 20, 12, 13, 14, 15, 16, 17, 18, 19]),
+    (1, [Z384, Z384, Z384, Z384, 20, 21, 22, 23, Z384, Z384, Z384, Z384]),
+    (1, [20, Z384, Z384, 21, 22, 23, Z384, Z384, Z384, Z384, Z384, Z384]),
+    (-1, [23, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]),
+    (-1, [Z384, 20, 21, 22, 23, Z384, Z384, Z384, Z384, Z384, Z384, Z384]),
+    (-1, [Z384, Z384, Z384, 23, 23, Z384, Z384, Z384, Z384, Z384, Z384, Z384]),
+];
+
+///
+/// Reduction modulo one of the `SpecialPrime`s, without any big integer
+/// division.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpecialReducer {
+    p: BigUint,
+    prime: SpecialPrime,
+    // 2^k - 1 and c of the pseudo-Mersenne primes 2^k - c
+    mask: BigUint,
+    c: BigUint,
+    // the 32 bits words of p, for the FIPS 186-4 ones
+    words: Vec<u32>,
+}
+
+impl SpecialReducer {
+    ///
+    /// The reducer of `p` if it is one of the `SpecialPrime`s.
+    ///
+    pub fn new(p: &BigUint) -> Option<Self> {
+        [SpecialPrime::Secp256k1, SpecialPrime::P256, SpecialPrime::P384, SpecialPrime::P521]
+            .into_iter()
+            .find(|prime| prime.modulus() == *p)
+            .map(|prime| {
+                let k = p.bits();
+                let mask = (BigUint::from(1u32) << k) - BigUint::from(1u32);
+                SpecialReducer { p: p.clone(), prime, c: &mask + BigUint::from(1u32) - p, mask, words: p.to_u32_digits() }
+            })
+    }
+
+    pub fn prime(&self) -> SpecialPrime {
+        self.prime
+    }
+
+    ///
+    /// `t mod p` for `t < p^2`.
+    ///
+    pub fn reduce(&self, t: &BigUint) -> BigUint {
+        match self.prime {
+            SpecialPrime::Secp256k1 => self.fold(t, 256),
+            SpecialPrime::P256 => self.recombine(t, &P256_TERMS),
+            SpecialPrime::P384 => self.recombine(t, &P384_TERMS),
+            SpecialPrime::P521 => self.fold(t, 521),
+        }
+    }
+
+    // t = hi 2^k + lo = hi c + lo mod p, until t fits in k bits
+    fn fold(&self, t: &BigUint, k: u64) -> BigUint {
+        let mut t = (t >> k) * &self.c + (t & &self.mask);
+        while t.bits() > k {
+            t = (&t >> k) * &self.c + (&t & &self.mask);
+        }
+        while t >= self.p {
+            t -= &self.p;
+        }
+        t
+    }
+
+    fn recombine<const N: usize>(&self, t: &BigUint, terms: &[(i64, [usize; N])]) -> BigUint {
+        let mut c = t.to_u32_digits();
+        c.resize(2 * N + 1, 0);
+        let p = &self.words;
+
+        // the sum of the terms is in ]-4p, 7p[ for P-256 and ]-3p, 8p[ for
+        // P-384: adding 4p keeps every result positive
+        let mut words = Vec::with_capacity(N + 1);
+        let mut carry = 0i64;
+        for i in 0..N {
+            let sum = terms.iter().fold(carry + 4 * p[i] as i64, |sum, (coefficient, term)| {
+                sum + coefficient * c[term[i]] as i64
+            });
+            words.push(sum as u32);
+            carry = sum >> 32;
+        }
+        words.push(carry as u32);
+
+        let mut r = BigUint::new(words);
+        while r >= self.p {
+            r -= &self.p;
+        }
+        r
+    }
+}
+
+impl FieldArithmetic for SpecialReducer {
+    fn modulus(&self) -> &BigUint {
+        &self.p
+    }
+
+    fn to_repr(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn to_value(&self, a: &BigUint) -> BigUint {
+        a.clone()
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&(a * b))
+    }
+}
+
+
+///
+/// The number of Miller-Rabin rounds used to validate the moduli and orders
+/// of user supplied curves: a composite passes with probability below
+/// `4^(-32)`.
+///
+pub const MILLER_RABIN_ROUNDS: usize = 32;
+
+
+///
+/// Miller-Rabin probabilistic primality test of `n` with `rounds` bases:
+///
+/// ```text
+///  n - 1 = d * 2^s with d odd
+///  for every base b
+///       x = b^d mod n
+///       n is composite unless x = 1 or x^(2^i) = n - 1 for some i < s
+/// ```
+///
+/// The bases are the first prime numbers (up to 37, which alone is exact for
+/// `n < 3.3 * 10^24`) and then values derived from SHA-256 of `n`, so the
+/// result is reproducible. A composite passes with probability at most
+/// `4^(-rounds)`, a prime always passes.
+///
+pub fn is_prime(n: &BigUint, rounds: usize) -> bool {
+    const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+    if *n <= one {
+        return false;
+    }
+    for prime in SMALL_PRIMES {
+        let prime = BigUint::from(prime);
+        if *n == prime {
+            return true;
+        }
+        if (n % &prime) == BigUint::from(0u32) {
+            return false;
+        }
+    }
+
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    // n > 37 here, so every base is in [2, n - 2]
+    let n_bytes = n.to_bytes_be();
+    let bases = SMALL_PRIMES.iter().map(|b| BigUint::from(*b)).chain((0u32..).map(|i| {
+        let mut hasher = Sha256::new();
+        hasher.update(&n_bytes);
+        hasher.update(i.to_be_bytes());
+        BigUint::from_bytes_be(&hasher.finalize()) % (n - 3u32) + &two
+    }));
+
+    'bases: for base in bases.take(rounds) {
+        let mut x = base.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = n.mul(&x, &x);
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+
+///
+/// `is_prime` with `MILLER_RABIN_ROUNDS` rounds, the check run on the moduli
+/// of user supplied curves.
+///
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    is_prime(n, MILLER_RABIN_ROUNDS)
+}
+
+///
+/// Evaluates the polynomial `c_0 + c_1 x + ... + c_k x^k` of the set at `x`
+/// with Horner's rule:
+///
+/// `(...((c_k x + c_(k-1)) x + c_(k-2)) ...) x + c_0 mod p`
+///
+pub fn evaluate_polynomial(coefficients: &[BigUint], x: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    params_to_mod_check_single_point(x, p)?;
+
+    coefficients.iter().rev().try_fold(BigUint::from(0u32), |acc, coefficient| {
+        params_to_mod_check_single_point(coefficient, p)?;
+        add(&multiplicate(&acc, x, p)?, coefficient, p)
+    })
+}
+
+
+///
+/// Computes the Lagrange basis polynomial of `xs[i]` over the points `xs`,
+/// evaluated at `x` (p has to be prime):
+///
+/// `l_i(x) = prod_(j != i) (x - x_j) / (x_i - x_j) mod p`
+///
+pub fn lagrange_coefficient(i: usize, xs: &[BigUint], x: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    if i >= xs.len() {
+        return Err(FiniteFieldError::InvalidArgument(format!("no point at index {}", i)));
+    }
+
+    let mut numerator = BigUint::from(1u32);
+    let mut denominator = BigUint::from(1u32);
+    for (j, x_j) in xs.iter().enumerate().filter(|(j, _)| *j != i) {
+        let difference = subtract(&xs[i], x_j, p)?;
+        if difference == BigUint::from(0u32) {
+            return Err(FiniteFieldError::InvalidArgument(format!("points {} and {} are the same", i, j)));
+        }
+        numerator = multiplicate(&numerator, &subtract(x, x_j, p)?, p)?;
+        denominator = multiplicate(&denominator, &difference, p)?;
+    }
+
+    divide(&numerator, &denominator, p)
+}
+
+
+///
+/// Evaluates at `x` the unique polynomial of degree `len - 1` going through
+/// the `(x_i, y_i)` points (p has to be prime):
+///
+/// `f(x) = sum y_i l_i(x) mod p`
+///
+pub fn lagrange_interpolate(points: &[(BigUint, BigUint)], x: &BigUint, p: &BigUint) -> Result<BigUint, FiniteFieldError> {
+    let xs: Vec<BigUint> = points.iter().map(|(x_i, _)| x_i.clone()).collect();
+
+    points.iter().enumerate().try_fold(BigUint::from(0u32), |acc, (i, (_, y_i))| {
+        let l_i = lagrange_coefficient(i, &xs, x, p)?;
+        add(&acc, &multiplicate(y_i, &l_i, p)?, p)
+    })
+}
+
+///
+/// Encodes an element of the set as a fixed width big endian byte string,
+/// left-padding it with zeros:
+///
+/// `5, len = 4 => [0, 0, 0, 5]`
+///
+pub fn to_fixed_bytes(a: &BigUint, len: usize) -> Result<Vec<u8>, FiniteFieldError> {
+    let bytes = a.to_bytes_be();
+    if bytes.len() > len {
+        return Err(FiniteFieldError::InvalidArgument(format!("{} does not fit in {} bytes", a, len)));
+    }
+
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend_from_slice(&bytes);
+    Ok(out)
+}
+
+///
+/// Compares two integers in constant time for integers of a given size:
+/// every 64 bits digit of both is compared, whatever the digit at which they
+/// differ, so only their sizes leak. Use it instead of `==` on secrets.
+///
+pub fn biguint_ct_eq(a: &BigUint, b: &BigUint) -> Choice {
+    let (a, b) = (a.to_u64_digits(), b.to_u64_digits());
+    (0..a.len().max(b.len())).fold(Choice::from(1u8), |equal, i| {
+        let digit = |digits: &[u64]| digits.get(i).copied().unwrap_or(0);
+        equal & digit(&a).ct_eq(&digit(&b))
+    })
+}
+
+///
+/// Encodes a byte string as lowercase hex, without `0x` prefix:
+///
+/// `[0x02, 0xab] => "02ab"`
+///
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+///
+/// Decodes a hex string, in lower or upper case and with an optional `0x`
+/// prefix, into a byte string.
+///
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, FiniteFieldError> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if !digits.len().is_multiple_of(2) || !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(FiniteFieldError::InvalidArgument(format!("{} is not a hex string", hex)));
+    }
+
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("two hex digits"))
+        .collect())
+}
+
+
+///
+/// This function check if `a  < b`; if a is b, function would return true
+///
+pub fn check_is_less_than(a: &BigUint, b: &BigUint)  -> bool {
+    a < b
+}
+
 ///
 /// When adding elliptic curves point, it is important that the points to be added id not
 /// greater than the operating mob p of the group.
@@ -276,4 +1110,304 @@ mod test {
 
         assert_eq!(divide(&a, &a, &p), Ok(BigUint::from(1u32)));
     }
+
+    #[test]
+    fn test_reduce_variants() {
+        let p = BigUint::from(11u32);
+        let a = BigUint::from(26u32);
+        let b = BigUint::from(9u32);
+
+        // 26 = 4 mod 11
+        assert!(add(&a, &b, &p).is_err());
+        assert_eq!(add_reduce(&a, &b, &p), Ok(BigUint::from(2u32)));
+        assert_eq!(subtract_reduce(&b, &a, &p), Ok(BigUint::from(5u32)));
+        assert_eq!(multiplicate_reduce(&a, &b, &p), Ok(BigUint::from(3u32)));
+        assert_eq!(inverse_add_reduce(&a, &p), Ok(BigUint::from(7u32)));
+        assert_eq!(inverse_multiplicate_prime_reduce(&a, &p), Ok(BigUint::from(3u32)));
+        assert_eq!(divide_reduce(&a, &a, &p), Ok(BigUint::from(1u32)));
+
+        // reduced operands give the same result as the checked functions
+        assert_eq!(multiplicate_reduce(&BigUint::from(4u32), &b, &p), multiplicate(&BigUint::from(4u32), &b, &p));
+
+        assert!(inverse_multiplicate_prime_reduce(&BigUint::from(22u32), &p).is_err());
+        assert!(divide_reduce(&a, &p, &p).is_err());
+        assert!(add_reduce(&a, &b, &BigUint::from(0u32)).is_err());
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        let p = BigUint::from(101u32);
+        let a: Vec<BigUint> = (1u32..=100).map(BigUint::from).collect();
+
+        let inverses = batch_inverse(&a, &p).unwrap();
+        for (element, inverse) in a.iter().zip(inverses.iter()) {
+            assert_eq!(*inverse, inverse_multiplicate_prime(element, &p).unwrap());
+        }
+
+        assert_eq!(batch_inverse(&[BigUint::from(4u32)], &BigUint::from(11u32)), Ok(vec![BigUint::from(3u32)]));
+        assert_eq!(batch_inverse(&[], &p), Ok(vec![]));
+        assert!(batch_inverse(&[BigUint::from(3u32), BigUint::from(0u32)], &p).is_err());
+        assert!(batch_inverse(&[BigUint::from(101u32)], &p).is_err());
+    }
+
+    #[test]
+    fn test_to_fixed_bytes() {
+        assert_eq!(to_fixed_bytes(&BigUint::from(5u32), 4), Ok(vec![0, 0, 0, 5]));
+        assert_eq!(to_fixed_bytes(&BigUint::from(0x0102u32), 2), Ok(vec![1, 2]));
+        assert_eq!(to_fixed_bytes(&BigUint::from(0u32), 2), Ok(vec![0, 0]));
+        assert!(to_fixed_bytes(&BigUint::from(0x010203u32), 2).is_err());
+    }
+
+    #[test]
+    fn test_biguint_ct_eq() {
+        let big = BigUint::from(1u32) << 200u32;
+        assert!(bool::from(biguint_ct_eq(&big, &big.clone())));
+        assert!(!bool::from(biguint_ct_eq(&big, &(&big + 1u32))));
+        assert!(!bool::from(biguint_ct_eq(&big, &BigUint::from(0u32))));
+        assert!(bool::from(biguint_ct_eq(&BigUint::from(0u32), &BigUint::from(0u32))));
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            inverse_multiplicate_prime_reduce(&BigUint::from(7u32), &BigUint::from(7u32)).unwrap_err().to_string(),
+            "invalid argument: 0 has no multiplicative inverse"
+        );
+        assert_eq!(
+            sqrt_mod_p(&BigUint::from(3u32), &BigUint::from(7u32)).unwrap_err().to_string(),
+            "3 is not a quadratic residue"
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(encode_hex(&[0x02, 0xab, 0x00]), "02ab00");
+        assert_eq!(encode_hex(&[]), "");
+        assert_eq!(decode_hex("02ab00"), Ok(vec![0x02, 0xab, 0x00]));
+        assert_eq!(decode_hex("0x02AB"), Ok(vec![0x02, 0xab]));
+        assert_eq!(decode_hex(""), Ok(vec![]));
+
+        for hex in ["0", "0g", "+1", "é0", "0x0x00"] {
+            assert!(decode_hex(hex).is_err(), "{}", hex);
+        }
+    }
+
+    #[test]
+    fn test_legendre() {
+        // the squares mod 11 are 1, 3, 4, 5, 9
+        let p = BigUint::from(11u32);
+        let symbols: Vec<i8> = (0u32..11).map(|a| legendre(&BigUint::from(a), &p).unwrap()).collect();
+        assert_eq!(symbols, vec![0, 1, -1, 1, 1, 1, -1, -1, -1, 1, -1]);
+
+        // against Euler's criterion
+        let p = BigUint::from(1_000_003u32);
+        let p_minus_one = &p - BigUint::from(1u32);
+        for a in (1u32..2000).map(|a| BigUint::from(a * 499)) {
+            let euler = a.modpow(&(&p_minus_one >> 1), &p);
+            assert_eq!(legendre(&a, &p).unwrap() == 1, euler == BigUint::from(1u32));
+            assert_eq!(is_quadratic_residue(&a, &p).unwrap(), euler == BigUint::from(1u32));
+        }
+
+        assert_eq!(is_quadratic_residue(&BigUint::from(0u32), &p), Ok(true));
+        assert!(legendre(&BigUint::from(3u32), &BigUint::from(16u32)).is_err());
+        assert!(legendre(&BigUint::from(11u32), &BigUint::from(11u32)).is_err());
+    }
+
+    #[test]
+    fn test_sqrt() {
+        // 4^2 = 16 = 5 mod 11
+        let a = BigUint::from(5u32);
+        let p = BigUint::from(11u32);
+
+        let (r, minus_r) = sqrt_mod_p(&a, &p).unwrap();
+        assert_eq!((r.clone(), minus_r.clone()), (BigUint::from(4u32), BigUint::from(7u32)));
+        assert_eq!(multiplicate(&r, &r, &p), Ok(a.clone()));
+        assert_eq!(multiplicate(&minus_r, &minus_r, &p), Ok(a));
+
+        // 2 is not a square mod 11
+        let a = BigUint::from(2u32);
+        assert_eq!(sqrt_mod_p(&a, &p), Err(FiniteFieldError::NonResidue(a)));
+
+        assert_eq!(sqrt_mod_p(&BigUint::from(0u32), &p), Ok((BigUint::from(0u32), BigUint::from(0u32))));
+    }
+
+    #[test]
+    fn test_sqrt_tonelli_shanks() {
+        // 17 = 1 mod 16, 41 = 1 mod 8, 13 = 5 mod 8
+        for p in [17u32, 41, 13, 97, 113] {
+            let p = BigUint::from(p);
+
+            let mut residues = 0;
+            for a in 1u32..p.to_u32_digits()[0] {
+                let a = BigUint::from(a);
+                match sqrt_mod_p(&a, &p) {
+                    Ok((r, minus_r)) => {
+                        assert_eq!(multiplicate(&r, &r, &p), Ok(a.clone()));
+                        assert_eq!(&r + &minus_r, p);
+                        assert_ne!(r.bit(0), minus_r.bit(0));
+                        residues += 1;
+                    }
+                    Err(e) => assert_eq!(e, FiniteFieldError::NonResidue(a)),
+                }
+            }
+
+            // exactly half of the non zero elements are squares
+            assert_eq!(BigUint::from(2 * residues as u32 + 1), p);
+        }
+
+        // p has to be an odd prime
+        assert!(sqrt_mod_p(&BigUint::from(1u32), &BigUint::from(16u32)).is_err());
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        for p in [2u32, 3, 5, 17, 19, 41, 97, 7919, 65537] {
+            assert!(is_probable_prime(&BigUint::from(p)), "{} is prime", p);
+        }
+        for n in [0u32, 1, 4, 9, 15, 91, 7917, 65535] {
+            assert!(!is_probable_prime(&BigUint::from(n)), "{} is not prime", n);
+        }
+
+        // secp256k1 field prime
+        let p = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F", 16).unwrap();
+        assert!(is_probable_prime(&p));
+        assert!(!is_probable_prime(&(p + BigUint::from(2u32))));
+    }
+
+    #[test]
+    fn test_is_prime() {
+        // Carmichael numbers pass the Fermat test for every coprime base
+        for n in [561u32, 1105, 1729, 41041, 825265] {
+            assert!(!is_prime(&BigUint::from(n), 1), "{} is not prime", n);
+        }
+
+        // strong pseudoprime to the bases 2, 3, 5, ..., 37 (3.18 * 10^23)
+        let n = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+        assert!(is_prime(&n, 12));
+        assert!(!is_prime(&n, 13));
+
+        // 2^521 - 1 and its neighbour
+        let m521 = (BigUint::from(1u32) << 521) - BigUint::from(1u32);
+        assert!(is_prime(&m521, MILLER_RABIN_ROUNDS));
+        assert!(!is_prime(&(&m521 - BigUint::from(2u32)), MILLER_RABIN_ROUNDS));
+        assert!(is_prime(&BigUint::from(2u32), 0));
+        assert!(!is_prime(&BigUint::from(1u32), MILLER_RABIN_ROUNDS));
+    }
+
+    #[test]
+    fn test_evaluate_polynomial() {
+        let p = BigUint::from(17u32);
+        // 3 + 2x + 5x^2
+        let coefficients = [BigUint::from(3u32), BigUint::from(2u32), BigUint::from(5u32)];
+
+        assert_eq!(evaluate_polynomial(&coefficients, &BigUint::from(0u32), &p).unwrap(), BigUint::from(3u32));
+        assert_eq!(evaluate_polynomial(&coefficients, &BigUint::from(2u32), &p).unwrap(), BigUint::from(10u32));
+        assert_eq!(evaluate_polynomial(&[], &BigUint::from(2u32), &p).unwrap(), BigUint::from(0u32));
+        assert!(evaluate_polynomial(&coefficients, &BigUint::from(17u32), &p).is_err());
+    }
+
+    #[test]
+    fn test_lagrange_interpolate() {
+        let p = BigUint::from(17u32);
+        let coefficients = [BigUint::from(3u32), BigUint::from(2u32), BigUint::from(5u32)];
+        let points: Vec<(BigUint, BigUint)> = [1u32, 4, 9]
+            .iter()
+            .map(|x| {
+                let x = BigUint::from(*x);
+                let y = evaluate_polynomial(&coefficients, &x, &p).unwrap();
+                (x, y)
+            })
+            .collect();
+
+        for x in 0u32..17 {
+            let x = BigUint::from(x);
+            assert_eq!(
+                lagrange_interpolate(&points, &x, &p).unwrap(),
+                evaluate_polynomial(&coefficients, &x, &p).unwrap()
+            );
+        }
+
+        // the same x twice
+        let duplicated = [points[0].clone(), points[0].clone()];
+        assert!(lagrange_interpolate(&duplicated, &BigUint::from(0u32), &p).is_err());
+        assert!(lagrange_coefficient(3, &[BigUint::from(1u32)], &BigUint::from(0u32), &p).is_err());
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let p = BigUint::from(17u32);
+        let f = MontgomeryContext::new(&p).unwrap();
+        for a in 0u32..17 {
+            let a = BigUint::from(a);
+            assert_eq!(f.to_value(&f.to_repr(&a)), a);
+            for b in 0u32..17 {
+                let b = BigUint::from(b);
+                let product = f.to_value(&f.mul(&f.to_repr(&a), &f.to_repr(&b)));
+                assert_eq!(product, multiplicate(&a, &b, &p).unwrap());
+                assert_eq!(f.sub(&a, &b), subtract(&a, &b, &p).unwrap());
+                assert_eq!(p.add(&a, &b), add(&a, &b, &p).unwrap());
+            }
+        }
+
+        // several limbs: secp256k1's p
+        let p = BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F", 16).unwrap();
+        let f = MontgomeryContext::new(&p).unwrap();
+        let a = BigUint::parse_bytes(b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798", 16).unwrap();
+        let e = BigUint::from(0xABCDEFu32);
+        assert_eq!(f.to_value(&f.mul(&f.to_repr(&a), &f.to_repr(&a))), (&a * &a) % &p);
+        assert_eq!(f.to_value(&f.pow(&f.to_repr(&a), &e)), a.modpow(&e, &p));
+
+        assert!(MontgomeryContext::new(&BigUint::from(16u32)).is_err());
+        assert!(MontgomeryContext::new(&BigUint::from(1u32)).is_err());
+    }
+
+    #[test]
+    fn test_barrett() {
+        let p = BigUint::from(17u32);
+        let reducer = Reducer::new(&p).unwrap();
+        for t in 0u32..289 {
+            assert_eq!(reducer.reduce(&BigUint::from(t)), BigUint::from(t % 17));
+        }
+
+        // P-521: 2^521 - 1
+        let p = (BigUint::from(1u32) << 521) - BigUint::from(1u32);
+        let reducer = Reducer::new(&p).unwrap();
+        let a = &p - BigUint::from(12345u32);
+        let b = (BigUint::from(3u32) << 400) + BigUint::from(7u32);
+        assert_eq!(reducer.mul(&a, &b), (&a * &b) % &p);
+        assert_eq!(reducer.mul(&a, &a), (&a * &a) % &p);
+        assert_eq!(reducer.reduce(&(&p * &p - BigUint::from(1u32))), &p - BigUint::from(1u32));
+
+        assert!(Reducer::new(&BigUint::from(1u32)).is_err());
+    }
+
+    #[test]
+    fn test_special_reducers() {
+        let primes = [SpecialPrime::Secp256k1, SpecialPrime::P256, SpecialPrime::P384, SpecialPrime::P521];
+        for prime in primes {
+            let p = prime.modulus();
+            let reducer = SpecialReducer::new(&p).unwrap();
+            assert_eq!(reducer.prime(), prime);
+
+            let one = BigUint::from(1u32);
+            let minus_one = &p - &one;
+            let values = [
+                BigUint::from(0u32),
+                one.clone(),
+                minus_one.clone(),
+                &p >> 1,
+                BigUint::from(0xDEADBEEFu32) << (p.bits() / 2),
+                (&one << (p.bits() - 1)) + BigUint::from(977u32),
+                BigUint::parse_bytes(b"79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798", 16).unwrap(),
+            ];
+            for a in &values {
+                for b in &values {
+                    assert_eq!(reducer.mul(a, b), (a * b) % &p, "{:?}: {} * {}", prime, a, b);
+                }
+            }
+            assert_eq!(reducer.reduce(&(&p * &p - &one)), minus_one);
+        }
+
+        assert!(SpecialReducer::new(&BigUint::from(17u32)).is_none());
+    }
 }
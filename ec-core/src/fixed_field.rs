@@ -0,0 +1,452 @@
+/*!
+A fixed width backend for the arithmetic of a prime field `Fp`: an element is
+an array of `N` 64 bits limbs on the stack, so the field operations of a
+scalar multiplication do not allocate, unlike their `BigUint` counterparts.
+
+```text
+ Fe256 = FixedFieldElement<4>     secp256k1, P-256, brainpoolP256r1
+ Fe384 = FixedFieldElement<6>     P-384, brainpoolP384r1
+```
+
+Elements are kept in Montgomery form `a R mod p` with `R = 2^(64 N)`, as in
+`ct_field`, but the operations branch on their values: this backend is
+variable time and meant for public scalars, `ct_field` being the constant
+time one.
+*/
+
+use alloc::format;
+use num_bigint::BigUint;
+
+use crate::elliptic_curve::{CurvePoint, EllipticCurve, EllipticCurveError};
+use crate::finite_fields::FiniteFieldError;
+
+/// An element of a 256 bits field.
+pub type Fe256 = FixedFieldElement<4>;
+
+/// An element of a 384 bits field.
+pub type Fe384 = FixedFieldElement<6>;
+
+///
+/// The field `Fp` for an odd modulus `p < 2^(64 N)`, with the Montgomery
+/// constants precomputed.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct FixedField<const N: usize> {
+    modulus: BigUint,
+    p: [u64; N],
+    // -p^(-1) mod 2^64
+    p_inv: u64,
+    // R mod p and R^2 mod p
+    r: [u64; N],
+    r2: [u64; N],
+}
+
+///
+/// An element of a `FixedField<N>`, in Montgomery form. It must only be used
+/// with the field that created it.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedFieldElement<const N: usize> {
+    limbs: [u64; N],
+}
+
+impl<const N: usize> FixedField<N> {
+    pub fn new(p: &BigUint) -> Result<Self, FiniteFieldError> {
+        if *p <= BigUint::from(2u32) || !p.bit(0) {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} is not an odd modulus", p)));
+        }
+        if p.bits() > 64 * N as u64 {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} does not fit in {} limbs", p, N)));
+        }
+
+        let limbs = to_limbs::<N>(p);
+
+        // Newton iteration doubling the number of correct bits of p0^(-1)
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+        }
+
+        let r = (BigUint::from(1u32) << (64 * N)) % p;
+        let r2 = (&r * &r) % p;
+
+        Ok(FixedField {
+            modulus: p.clone(),
+            p: limbs,
+            p_inv: inv.wrapping_neg(),
+            r: to_limbs(&r),
+            r2: to_limbs(&r2),
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    pub fn zero(&self) -> FixedFieldElement<N> {
+        FixedFieldElement { limbs: [0; N] }
+    }
+
+    pub fn one(&self) -> FixedFieldElement<N> {
+        FixedFieldElement { limbs: self.r }
+    }
+
+    ///
+    /// Converts `a` in `[0, p - 1]` to Montgomery form: `a R = mont(a, R^2)`.
+    ///
+    pub fn element(&self, a: &BigUint) -> Result<FixedFieldElement<N>, FiniteFieldError> {
+        if *a >= self.modulus {
+            return Err(FiniteFieldError::InvalidArgument(format!("{} is not lower than {}", a, self.modulus)));
+        }
+
+        Ok(FixedFieldElement { limbs: self.mont_mul(&to_limbs(a), &self.r2) })
+    }
+
+    ///
+    /// Converts back from Montgomery form: `a = mont(a R, 1)`.
+    ///
+    pub fn to_biguint(&self, a: &FixedFieldElement<N>) -> BigUint {
+        let mut one = [0u64; N];
+        one[0] = 1;
+        from_limbs(&self.mont_mul(&a.limbs, &one))
+    }
+
+    pub fn add(&self, a: &FixedFieldElement<N>, b: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        let (sum, carry) = add_limbs(&a.limbs, &b.limbs);
+        if carry != 0 || !less_than(&sum, &self.p) {
+            return FixedFieldElement { limbs: sub_limbs(&sum, &self.p).0 };
+        }
+        FixedFieldElement { limbs: sum }
+    }
+
+    pub fn sub(&self, a: &FixedFieldElement<N>, b: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        let (difference, borrow) = sub_limbs(&a.limbs, &b.limbs);
+        if borrow != 0 {
+            return FixedFieldElement { limbs: add_limbs(&difference, &self.p).0 };
+        }
+        FixedFieldElement { limbs: difference }
+    }
+
+    pub fn neg(&self, a: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        self.sub(&self.zero(), a)
+    }
+
+    pub fn mul(&self, a: &FixedFieldElement<N>, b: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        FixedFieldElement { limbs: self.mont_mul(&a.limbs, &b.limbs) }
+    }
+
+    pub fn square(&self, a: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        self.mul(a, a)
+    }
+
+    ///
+    /// `a^(-1) = a^(p - 2)` by Fermat's little theorem (`p` prime). The
+    /// inverse of `0` is `0`.
+    ///
+    pub fn invert(&self, a: &FixedFieldElement<N>) -> FixedFieldElement<N> {
+        let e = &self.modulus - BigUint::from(2u32);
+        let mut result = self.one();
+        for i in (0..e.bits()).rev() {
+            result = self.square(&result);
+            if e.bit(i) {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+
+    ///
+    /// CIOS Montgomery multiplication `a b R^(-1) mod p`, for `a, b < p`.
+    ///
+    fn mont_mul(&self, a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+        // t has N + 2 limbs, the two top ones kept apart
+        let mut t = [0u64; N];
+        let (mut t_n, mut t_n1);
+
+        t_n = 0;
+        for &a_i in a.iter() {
+            // t += a_i b
+            let mut carry = 0u64;
+            for j in 0..N {
+                let uv = t[j] as u128 + a_i as u128 * b[j] as u128 + carry as u128;
+                t[j] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t_n as u128 + carry as u128;
+            t_n = uv as u64;
+            t_n1 = (uv >> 64) as u64;
+
+            // t = (t + m p) / 2^64 where m makes the low limb vanish
+            let m = t[0].wrapping_mul(self.p_inv);
+            let uv = t[0] as u128 + m as u128 * self.p[0] as u128;
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..N {
+                let uv = t[j] as u128 + m as u128 * self.p[j] as u128 + carry as u128;
+                t[j - 1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t_n as u128 + carry as u128;
+            t[N - 1] = uv as u64;
+            t_n = t_n1 + (uv >> 64) as u64;
+        }
+
+        // t < 2p
+        if t_n != 0 || !less_than(&t, &self.p) {
+            return sub_limbs(&t, &self.p).0;
+        }
+        t
+    }
+}
+
+impl<const N: usize> FixedFieldElement<N> {
+    pub fn is_zero(&self) -> bool {
+        self.limbs == [0; N]
+    }
+}
+
+// (X, Y, Z) in Jacobian coordinates, the identity has Z = 0
+#[derive(Clone, Copy)]
+struct FixedJacobian<const N: usize> {
+    x: FixedFieldElement<N>,
+    y: FixedFieldElement<N>,
+    z: FixedFieldElement<N>,
+}
+
+impl EllipticCurve {
+    ///
+    /// `B = d * A` with the fixed width backend, for fields of 4 limbs (256
+    /// bits curves), 6 (384 bits), 8 (512 bits) or 9 (P-521). Other sizes
+    /// go through `scalar_mul`. Same inputs and result as `scalar_mul`.
+    ///
+    pub fn scalar_mul_fixed(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        match self.p.bits().div_ceil(64) {
+            4 => self.scalar_mul_limbs::<4>(a, d),
+            6 => self.scalar_mul_limbs::<6>(a, d),
+            8 => self.scalar_mul_limbs::<8>(a, d),
+            9 => self.scalar_mul_limbs::<9>(a, d),
+            _ => self.scalar_mul(a, d),
+        }
+    }
+
+    fn scalar_mul_limbs<const N: usize>(&self, a: &CurvePoint, d: &BigUint) -> Result<CurvePoint, EllipticCurveError> {
+        if !self.is_on_curve(a) {
+            return Err(EllipticCurveError::InvalidPoint(a.clone()));
+        }
+        if *d == BigUint::from(0u32) {
+            return Ok(CurvePoint::Identity);
+        }
+
+        let (x, y) = match a {
+            CurvePoint::Identity => return Ok(CurvePoint::Identity),
+            CurvePoint::Coordinate(x, y) => (x, y),
+        };
+
+        let field = FixedField::<N>::new(&self.p).expect("p is an odd prime of N limbs");
+        let element = |value: &BigUint| field.element(value).expect("the coordinates are reduced mod p");
+        let a_coefficient = element(&self.a);
+        let base = FixedJacobian { x: element(x), y: element(y), z: field.one() };
+
+        let mut t = base;
+        for i in (0..(d.bits() - 1)).rev() {
+            t = fixed_double(&field, &a_coefficient, &t);
+            if d.bit(i) {
+                t = fixed_add(&field, &a_coefficient, &t, &base);
+            }
+        }
+
+        if t.z.is_zero() {
+            return Ok(CurvePoint::Identity);
+        }
+        let z_inv = field.invert(&t.z);
+        let z_inv2 = field.square(&z_inv);
+        let z_inv3 = field.mul(&z_inv2, &z_inv);
+        Ok(CurvePoint::Coordinate(
+            field.to_biguint(&field.mul(&t.x, &z_inv2)),
+            field.to_biguint(&field.mul(&t.y, &z_inv3)),
+        ))
+    }
+}
+
+// The same formulas as `jacobian_double` and `jacobian_add`.
+
+fn fixed_double<const N: usize>(f: &FixedField<N>, a: &FixedFieldElement<N>, p: &FixedJacobian<N>) -> FixedJacobian<N> {
+    if p.z.is_zero() || p.y.is_zero() {
+        return FixedJacobian { x: f.one(), y: f.one(), z: f.zero() };
+    }
+
+    let xx = f.square(&p.x);
+    let yy = f.square(&p.y);
+    let yyyy = f.square(&yy);
+    let zz = f.square(&p.z);
+
+    let xyy = f.mul(&p.x, &yy);
+    let s = f.add(&xyy, &xyy);
+    let s = f.add(&s, &s);
+    let m = f.add(&f.add(&xx, &xx), &xx);
+    let m = f.add(&m, &f.mul(a, &f.square(&zz)));
+
+    let yyyy8 = f.add(&yyyy, &yyyy);
+    let yyyy8 = f.add(&yyyy8, &yyyy8);
+    let yyyy8 = f.add(&yyyy8, &yyyy8);
+    let yz = f.mul(&p.y, &p.z);
+
+    let x3 = f.sub(&f.square(&m), &f.add(&s, &s));
+    let y3 = f.sub(&f.mul(&m, &f.sub(&s, &x3)), &yyyy8);
+    FixedJacobian { x: x3, y: y3, z: f.add(&yz, &yz) }
+}
+
+fn fixed_add<const N: usize>(
+    f: &FixedField<N>,
+    a: &FixedFieldElement<N>,
+    p: &FixedJacobian<N>,
+    q: &FixedJacobian<N>,
+) -> FixedJacobian<N> {
+    if p.z.is_zero() {
+        return *q;
+    }
+    if q.z.is_zero() {
+        return *p;
+    }
+
+    let z1z1 = f.square(&p.z);
+    let z2z2 = f.square(&q.z);
+    let u1 = f.mul(&p.x, &z2z2);
+    let u2 = f.mul(&q.x, &z1z1);
+    let s1 = f.mul(&p.y, &f.mul(&q.z, &z2z2));
+    let s2 = f.mul(&q.y, &f.mul(&p.z, &z1z1));
+
+    let h = f.sub(&u2, &u1);
+    let r = f.sub(&s2, &s1);
+    if h.is_zero() {
+        if r.is_zero() {
+            return fixed_double(f, a, p);
+        }
+        return FixedJacobian { x: f.one(), y: f.one(), z: f.zero() };
+    }
+
+    let hh = f.square(&h);
+    let hhh = f.mul(&h, &hh);
+    let v = f.mul(&u1, &hh);
+
+    let x3 = f.sub(&f.sub(&f.square(&r), &hhh), &f.add(&v, &v));
+    let y3 = f.sub(&f.mul(&r, &f.sub(&v, &x3)), &f.mul(&s1, &hhh));
+    let z3 = f.mul(&f.mul(&p.z, &q.z), &h);
+    FixedJacobian { x: x3, y: y3, z: z3 }
+}
+
+fn to_limbs<const N: usize>(a: &BigUint) -> [u64; N] {
+    let mut limbs = [0u64; N];
+    for (limb, digit) in limbs.iter_mut().zip(a.iter_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+fn from_limbs<const N: usize>(limbs: &[u64; N]) -> BigUint {
+    let mut digits = [0u32; 18];
+    for (i, limb) in limbs.iter().enumerate() {
+        digits[2 * i] = *limb as u32;
+        digits[2 * i + 1] = (*limb >> 32) as u32;
+    }
+    BigUint::from_slice(&digits[..2 * N])
+}
+
+fn add_limbs<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut sum = [0u64; N];
+    let mut carry = 0u64;
+    for i in 0..N {
+        let uv = a[i] as u128 + b[i] as u128 + carry as u128;
+        sum[i] = uv as u64;
+        carry = (uv >> 64) as u64;
+    }
+    (sum, carry)
+}
+
+fn sub_limbs<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut difference = [0u64; N];
+    let mut borrow = 0u64;
+    for i in 0..N {
+        let uv = (a[i] as u128).wrapping_sub(b[i] as u128 + borrow as u128);
+        difference[i] = uv as u64;
+        borrow = (uv >> 127) as u64;
+    }
+    (difference, borrow)
+}
+
+fn less_than<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()) == core::cmp::Ordering::Less
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+    use crate::finite_fields;
+
+    #[test]
+    fn test_new() {
+        assert!(FixedField::<4>::new(&BigUint::from(16u32)).is_err());
+        assert!(FixedField::<4>::new(&curves::p384().curve.p).is_err());
+
+        let field = FixedField::<6>::new(&curves::p384().curve.p).unwrap();
+        assert_eq!(field.to_biguint(&field.one()), BigUint::from(1u32));
+        assert!(field.zero().is_zero());
+        assert!(field.element(field.modulus()).is_err());
+    }
+
+    fn check_field<const N: usize>(p: &BigUint) {
+        let field = FixedField::<N>::new(p).unwrap();
+        let values = [
+            BigUint::from(0u32),
+            BigUint::from(1u32),
+            BigUint::from(2u32),
+            p >> 1,
+            p - BigUint::from(2u32),
+            p - BigUint::from(1u32),
+        ];
+
+        for a in values.iter() {
+            let a_fixed = field.element(a).unwrap();
+            assert_eq!(field.to_biguint(&a_fixed), *a);
+            assert_eq!(field.to_biguint(&field.neg(&a_fixed)), finite_fields::inverse_add(a, p).unwrap());
+            assert_eq!(field.to_biguint(&field.invert(&a_fixed)), finite_fields::inverse_multiplicate_prime(a, p).unwrap());
+
+            for b in values.iter() {
+                let b_fixed = field.element(b).unwrap();
+                assert_eq!(field.to_biguint(&field.add(&a_fixed, &b_fixed)), finite_fields::add(a, b, p).unwrap());
+                assert_eq!(field.to_biguint(&field.sub(&a_fixed, &b_fixed)), finite_fields::subtract(a, b, p).unwrap());
+                assert_eq!(field.to_biguint(&field.mul(&a_fixed, &b_fixed)), finite_fields::multiplicate(a, b, p).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_finite_fields() {
+        check_field::<1>(&BigUint::from(0xffff_ffff_ffff_ffc5u64));
+        check_field::<4>(&curves::secp256k1().curve.p);
+        check_field::<4>(&curves::p256().curve.p);
+        check_field::<6>(&curves::p384().curve.p);
+        check_field::<9>(&curves::p521().curve.p);
+    }
+
+    #[test]
+    fn test_scalar_mul_fixed() {
+        for params in [curves::secp256k1(), curves::p256(), curves::p384(), curves::p521(), curves::brainpool_p512r1()] {
+            let ec = &params.curve;
+            for d in [BigUint::from(1u32), BigUint::from(2u32), BigUint::from(0xDEADBEEFu32), &params.n - BigUint::from(1u32)] {
+                assert_eq!(ec.scalar_mul_fixed(&params.g, &d).unwrap(), ec.scalar_mul(&params.g, &d).unwrap());
+            }
+            assert_eq!(ec.scalar_mul_fixed(&params.g, &params.n).unwrap(), CurvePoint::Identity);
+            assert_eq!(ec.scalar_mul_fixed(&params.g, &BigUint::from(0u32)).unwrap(), CurvePoint::Identity);
+        }
+
+        // not a size of the backend
+        let ec = EllipticCurve::new(BigUint::from(2u32), BigUint::from(2u32), BigUint::from(17u32)).unwrap();
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+        assert_eq!(ec.scalar_mul_fixed(&g, &BigUint::from(16u32)).unwrap(), ec.scalar_mul(&g, &BigUint::from(16u32)).unwrap());
+    }
+}
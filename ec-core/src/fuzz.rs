@@ -0,0 +1,135 @@
+/*!
+`arbitrary::Arbitrary` implementations for fuzz targets, so that they can
+take structured inputs instead of parsing raw bytes themselves:
+
+```text
+ fuzz_target!(|point: CurvePoint| { ... validation, encodings ... });
+ fuzz_target!(|input: CurveInput| { ... group law on input.point ... });
+```
+
+`CurvePoint` and `FieldElement` are built from arbitrary integers, so most
+points are on no curve at all and exercise the validation paths. A
+`CurveInput` is a point of the group of a named curve, for the group law;
+`arbitrary_point` draws more points of the same curve.
+*/
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use num_bigint::BigUint;
+
+use crate::curves::{self, CurveParams};
+use crate::elliptic_curve::CurvePoint;
+use crate::field_element::FieldElement;
+
+/// Byte size of the largest integers generated, the coordinates of P-521.
+pub const MAX_INT_BYTES: usize = 66;
+
+///
+/// A big endian integer of at most `max_bytes` bytes.
+///
+pub fn arbitrary_biguint(u: &mut Unstructured, max_bytes: usize) -> Result<BigUint> {
+    let len = u.int_in_range(0..=max_bytes)?;
+    Ok(BigUint::from_bytes_be(u.bytes(len)?))
+}
+
+///
+/// One of the curves of `curves::by_name`.
+///
+pub fn arbitrary_curve(u: &mut Unstructured) -> Result<&'static CurveParams> {
+    let names: alloc::vec::Vec<&str> = curves::names().collect();
+    let name = u.choose(&names)?;
+    Ok(curves::by_name(name).expect("a name of the registry"))
+}
+
+///
+/// `k G` for an arbitrary `k` in `[0, n - 1]`, so the identity can come up.
+///
+pub fn arbitrary_point(u: &mut Unstructured, params: &CurveParams) -> Result<CurvePoint> {
+    let scalar = arbitrary_biguint(u, params.n.bits().div_ceil(8) as usize)? % &params.n;
+    params
+        .curve
+        .scalar_mul(&params.g, &scalar)
+        .map_err(|_| arbitrary::Error::IncorrectFormat)
+}
+
+impl<'a> Arbitrary<'a> for CurvePoint {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.ratio(1u8, 16u8)? {
+            return Ok(CurvePoint::Identity);
+        }
+        Ok(CurvePoint::Coordinate(
+            arbitrary_biguint(u, MAX_INT_BYTES)?,
+            arbitrary_biguint(u, MAX_INT_BYTES)?,
+        ))
+    }
+}
+
+///
+/// An element of the field of a named curve.
+///
+impl<'a> Arbitrary<'a> for FieldElement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let params = arbitrary_curve(u)?;
+        FieldElement::new(arbitrary_biguint(u, MAX_INT_BYTES)?, params.curve.p.clone())
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+///
+/// A named curve and a point of its group.
+///
+#[derive(Clone, Debug)]
+pub struct CurveInput {
+    pub params: &'static CurveParams,
+    pub point: CurvePoint,
+}
+
+impl<'a> Arbitrary<'a> for CurveInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let params = arbitrary_curve(u)?;
+        Ok(CurveInput { params, point: arbitrary_point(u, params)? })
+    }
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // deterministic "fuzzer" input
+    fn data(seed: u8) -> alloc::vec::Vec<u8> {
+        (0..512u32).map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed)).collect()
+    }
+
+    #[test]
+    fn test_curve_input_is_on_curve() {
+        for seed in 0..16 {
+            let data = data(seed);
+            let mut u = Unstructured::new(&data);
+            let input = CurveInput::arbitrary(&mut u).unwrap();
+            assert!(input.params.curve.is_on_curve(&input.point));
+
+            let other = arbitrary_point(&mut u, input.params).unwrap();
+            assert!(input.params.curve.add(&input.point, &other).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_field_element_and_point() {
+        for seed in 0..16 {
+            let data = data(seed);
+            let mut u = Unstructured::new(&data);
+            let element = FieldElement::arbitrary(&mut u).unwrap();
+            assert!(element.value() < element.modulus());
+
+            if let CurvePoint::Coordinate(x, y) = CurvePoint::arbitrary(&mut u).unwrap() {
+                assert!(x.bits() <= 8 * MAX_INT_BYTES as u64 && y.bits() <= 8 * MAX_INT_BYTES as u64);
+            }
+        }
+
+        // an empty input still gives a value
+        assert!(CurvePoint::arbitrary(&mut Unstructured::new(&[])).is_ok());
+    }
+}
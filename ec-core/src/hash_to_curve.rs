@@ -0,0 +1,314 @@
+/*!
+Hashing arbitrary byte strings to points of a curve as specified in
+[RFC 9380](https://www.rfc-editor.org/rfc/rfc9380):
+
+- `expand_message_xmd` stretches the message into uniform bytes with a hash
+  function
+- `hash_to_field` turns those bytes into field elements
+- the simplified Shallue-van de Woestijne-Ulas (SSWU) map sends each field
+  element to a point of the curve
+
+The simplified SWU map needs `a != 0` and `b != 0`, so it is available for the
+NIST curves (secp256k1 would need an extra isogeny map).
+*/
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::curves::{self, CurveParams};
+use crate::elliptic_curve::CurvePoint;
+use crate::finite_fields;
+
+
+#[derive(Debug, PartialEq)]
+pub enum HashToCurveError {
+    InvalidArgument(String),
+    InvalidResult(String),
+}
+
+
+/// `expand_message(msg, dst, len_in_bytes)`
+pub type ExpandMessage = fn(&[u8], &[u8], usize) -> Result<Vec<u8>, HashToCurveError>;
+
+
+///
+/// A hash-to-curve suite: the curve, the non square `z` used by the SSWU map,
+/// the number of bytes `l` hashed per field element and the message expander.
+///
+pub struct HashToCurve {
+    pub params: CurveParams,
+    pub z: BigUint,
+    pub l: usize,
+    expand: ExpandMessage,
+}
+
+
+impl HashToCurve {
+    /// `P256_XMD:SHA-256_SSWU_RO_`
+    pub fn p256() -> Self {
+        HashToCurve::new(curves::p256(), 10, 48, expand_message_xmd::<Sha256>)
+    }
+
+    /// `P384_XMD:SHA-384_SSWU_RO_`
+    pub fn p384() -> Self {
+        HashToCurve::new(curves::p384(), 12, 72, expand_message_xmd::<Sha384>)
+    }
+
+    /// `P521_XMD:SHA-512_SSWU_RO_`
+    pub fn p521() -> Self {
+        HashToCurve::new(curves::p521(), 4, 98, expand_message_xmd::<Sha512>)
+    }
+
+    // z is given as its opposite, as all the suites use a small negative z
+    fn new(params: CurveParams, minus_z: u32, l: usize, expand: ExpandMessage) -> Self {
+        let z = &params.curve.p - BigUint::from(minus_z);
+        HashToCurve { params, z, l, expand }
+    }
+
+    ///
+    /// Hashes a message to a point with a uniform distribution (the `_RO_`
+    /// variant): `Q = map_to_curve(u0) + map_to_curve(u1)`.
+    ///
+    pub fn hash_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<CurvePoint, HashToCurveError> {
+        let u = self.hash_to_field(msg, dst, 2)?;
+        let q0 = self.map_to_curve(&u[0])?;
+        let q1 = self.map_to_curve(&u[1])?;
+
+        // the cofactor of the supported curves is 1
+        self.params
+            .curve
+            .add(&q0, &q1)
+            .map_err(|_| HashToCurveError::InvalidResult("could not add the mapped points".into()))
+    }
+
+    ///
+    /// Hashes a message to a point with a non uniform distribution (the
+    /// `_NU_` variant): `Q = map_to_curve(u0)`.
+    ///
+    pub fn encode_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<CurvePoint, HashToCurveError> {
+        let u = self.hash_to_field(msg, dst, 1)?;
+        self.map_to_curve(&u[0])
+    }
+
+    ///
+    /// Hashes a message to `count` elements of the field:
+    ///
+    /// `u_i = OS2IP(uniform_bytes[l * i..l * (i + 1)]) mod p`
+    ///
+    pub fn hash_to_field(&self, msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<BigUint>, HashToCurveError> {
+        let uniform_bytes = (self.expand)(msg, dst, count * self.l)?;
+
+        Ok(uniform_bytes
+            .chunks(self.l)
+            .map(|chunk| BigUint::from_bytes_be(chunk) % &self.params.curve.p)
+            .collect())
+    }
+
+    ///
+    /// The simplified SWU map:
+    ///
+    /// ```text
+    ///  tv1 = 1 / (z^2 u^4 + z u^2)       (0 if not invertible)
+    ///  x1 = (-b / a) (1 + tv1)           (b / (z a) if tv1 = 0)
+    ///  if x1^3 + a x1 + b is a square then x = x1
+    ///  else x = z u^2 x1
+    ///  y = sqrt(x^3 + a x + b) with the same sign as u
+    /// ```
+    ///
+    pub fn map_to_curve(&self, u: &BigUint) -> Result<CurvePoint, HashToCurveError> {
+        let ec = &self.params.curve;
+        let p = &ec.p;
+        let zero = BigUint::from(0u32);
+        let one = BigUint::from(1u32);
+        let map_err = |_| HashToCurveError::InvalidResult("field operation failed".into());
+
+        let u = u % p;
+        let u2 = finite_fields::multiplicate(&u, &u, p).map_err(map_err)?;
+        let z_u2 = finite_fields::multiplicate(&self.z, &u2, p).map_err(map_err)?;
+        let tv1 = finite_fields::multiplicate(&z_u2, &z_u2, p)
+            .and_then(|z2_u4| finite_fields::add(&z2_u4, &z_u2, p))
+            .map_err(map_err)?;
+
+        let x1 = if tv1 == zero {
+            let z_a = finite_fields::multiplicate(&self.z, &ec.a, p).map_err(map_err)?;
+            finite_fields::divide(&ec.b, &z_a, p).map_err(map_err)?
+        } else {
+            let tv1 = finite_fields::inverse_multiplicate_prime(&tv1, p).map_err(map_err)?;
+            let minus_b = finite_fields::inverse_add(&ec.b, p).map_err(map_err)?;
+            finite_fields::divide(&minus_b, &ec.a, p)
+                .and_then(|minus_b_over_a| {
+                    finite_fields::add(&one, &tv1, p)
+                        .and_then(|one_plus_tv1| finite_fields::multiplicate(&minus_b_over_a, &one_plus_tv1, p))
+                })
+                .map_err(map_err)?
+        };
+
+        // the Legendre symbol picks the candidate before taking a single root
+        let gx1 = self.g(&x1)?;
+        let (x, y) = if finite_fields::is_quadratic_residue(&gx1, p).map_err(map_err)? {
+            (x1, finite_fields::sqrt_mod_p(&gx1, p).map_err(map_err)?.0)
+        } else {
+            let x2 = finite_fields::multiplicate(&z_u2, &x1, p).map_err(map_err)?;
+            let y = finite_fields::sqrt_mod_p(&self.g(&x2)?, p)
+                .map_err(|_| HashToCurveError::InvalidResult("neither g(x1) nor g(x2) is a square".into()))?
+                .0;
+            (x2, y)
+        };
+
+        let y = if u.bit(0) != y.bit(0) {
+            finite_fields::inverse_add(&y, p).map_err(map_err)?
+        } else {
+            y
+        };
+
+        Ok(CurvePoint::Coordinate(x, y))
+    }
+
+    // g(x) = x^3 + a x + b
+    fn g(&self, x: &BigUint) -> Result<BigUint, HashToCurveError> {
+        let ec = &self.params.curve;
+        let x3 = x.modpow(&BigUint::from(3u32), &ec.p);
+
+        finite_fields::multiplicate(&ec.a, x, &ec.p)
+            .and_then(|a_x| finite_fields::add(&x3, &a_x, &ec.p))
+            .and_then(|x3_plus_ax| finite_fields::add(&x3_plus_ax, &ec.b, &ec.p))
+            .map_err(|_| HashToCurveError::InvalidResult("could not compute x^3 + a x + b".into()))
+    }
+}
+
+
+///
+/// `expand_message_xmd` of RFC 9380: expands `msg` into `len_in_bytes`
+/// uniform bytes with the hash function `H` and the domain separation tag
+/// `dst`:
+///
+/// ```text
+///  dst_prime = dst || len(dst)
+///  b_0 = H(zeros(block size) || msg || len_in_bytes || 0 || dst_prime)
+///  b_1 = H(b_0 || 1 || dst_prime)
+///  b_i = H((b_0 xor b_(i-1)) || i || dst_prime)
+///  uniform_bytes = b_1 || ... || b_ell
+/// ```
+///
+pub fn expand_message_xmd<H>(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>, HashToCurveError>
+where
+    H: Digest + BlockSizeUser,
+{
+    let b_in_bytes = <H as Digest>::output_size();
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    if ell > 255 || len_in_bytes > 65535 {
+        return Err(HashToCurveError::InvalidArgument(format!("can not expand to {} bytes", len_in_bytes)));
+    }
+    if dst.len() > 255 {
+        return Err(HashToCurveError::InvalidArgument("dst can not be longer than 255 bytes".into()));
+    }
+
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+
+    let b_0 = H::new()
+        .chain_update(vec![0u8; H::block_size()])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut b_i = H::new()
+        .chain_update(&b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = H::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hex(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn test_expand_message_xmd_sha256() {
+        // RFC 9380, appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+        let res = expand_message_xmd::<Sha256>(b"", dst, 0x20).unwrap();
+        assert_eq!(
+            BigUint::from_bytes_be(&res),
+            hex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+        );
+
+        let res = expand_message_xmd::<Sha256>(b"abc", dst, 0x20).unwrap();
+        assert_eq!(
+            BigUint::from_bytes_be(&res),
+            hex("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+        );
+
+        assert_eq!(expand_message_xmd::<Sha256>(b"abc", dst, 0x80).unwrap().len(), 0x80);
+        assert!(expand_message_xmd::<Sha256>(b"abc", dst, 256 * 32).is_err());
+        assert!(expand_message_xmd::<Sha256>(b"abc", &[0u8; 256], 32).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_curve_p256() {
+        // RFC 9380, appendix J.1.1
+        let suite = HashToCurve::p256();
+        let dst = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+
+        let point = suite.hash_to_curve(b"", dst).unwrap();
+        assert_eq!(
+            point,
+            CurvePoint::Coordinate(
+                hex("2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4"),
+                hex("8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415"),
+            )
+        );
+    }
+
+    #[test]
+    fn test_hash_to_curve_points_are_on_curve() {
+        for suite in [HashToCurve::p256(), HashToCurve::p384(), HashToCurve::p521()] {
+            for msg in [&b""[..], b"abc", b"a longer message to hash to the curve"] {
+                let point = suite.hash_to_curve(msg, b"TEST-DST").unwrap();
+                assert!(suite.params.curve.is_on_curve(&point));
+
+                let encoded = suite.encode_to_curve(msg, b"TEST-DST").unwrap();
+                assert!(suite.params.curve.is_on_curve(&encoded));
+                assert_ne!(point, encoded);
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+Conversions between `CurvePoint` and the point types of the `k256` and `p256`
+crates, to migrate code one call at a time or to check results against
+those crates:
+
+```text
+ let point = CurvePoint::from(k256_public_key);
+ let k256_public_key = k256::PublicKey::try_from(&point)?;
+```
+
+A `CurvePoint` does not know its curve, so converting to a `k256` or `p256`
+type checks that the point is on that curve. `PublicKey` rejects the
+identity, `AffinePoint` accepts it.
+*/
+
+use num_bigint::BigUint;
+
+use crate::elliptic_curve::{CurvePoint, EllipticCurveError};
+use crate::finite_fields::to_fixed_bytes;
+
+macro_rules! interop {
+    ($krate:ident) => {
+        impl From<&$krate::AffinePoint> for CurvePoint {
+            fn from(point: &$krate::AffinePoint) -> Self {
+                use $krate::elliptic_curve::sec1::ToEncodedPoint;
+
+                let encoded = point.to_encoded_point(false);
+                match (encoded.x(), encoded.y()) {
+                    (Some(x), Some(y)) => CurvePoint::Coordinate(BigUint::from_bytes_be(x), BigUint::from_bytes_be(y)),
+                    _ => CurvePoint::Identity,
+                }
+            }
+        }
+
+        impl From<$krate::AffinePoint> for CurvePoint {
+            fn from(point: $krate::AffinePoint) -> Self {
+                CurvePoint::from(&point)
+            }
+        }
+
+        impl From<&$krate::PublicKey> for CurvePoint {
+            fn from(key: &$krate::PublicKey) -> Self {
+                CurvePoint::from(key.as_affine())
+            }
+        }
+
+        impl From<$krate::PublicKey> for CurvePoint {
+            fn from(key: $krate::PublicKey) -> Self {
+                CurvePoint::from(key.as_affine())
+            }
+        }
+
+        impl TryFrom<&CurvePoint> for $krate::AffinePoint {
+            type Error = EllipticCurveError;
+
+            fn try_from(point: &CurvePoint) -> Result<Self, EllipticCurveError> {
+                use $krate::elliptic_curve::sec1::FromEncodedPoint;
+                use $krate::{EncodedPoint, FieldBytes};
+
+                let encoded = match point {
+                    CurvePoint::Identity => EncodedPoint::identity(),
+                    CurvePoint::Coordinate(x, y) => {
+                        let size = FieldBytes::default().len();
+                        let (x, y) = match (to_fixed_bytes(x, size), to_fixed_bytes(y, size)) {
+                            (Ok(x), Ok(y)) => (x, y),
+                            _ => return Err(EllipticCurveError::InvalidPoint(point.clone())),
+                        };
+                        EncodedPoint::from_affine_coordinates(
+                            FieldBytes::from_slice(&x),
+                            FieldBytes::from_slice(&y),
+                            false,
+                        )
+                    }
+                };
+
+                Option::from($krate::AffinePoint::from_encoded_point(&encoded))
+                    .ok_or_else(|| EllipticCurveError::InvalidPoint(point.clone()))
+            }
+        }
+
+        impl TryFrom<CurvePoint> for $krate::AffinePoint {
+            type Error = EllipticCurveError;
+
+            fn try_from(point: CurvePoint) -> Result<Self, EllipticCurveError> {
+                $krate::AffinePoint::try_from(&point)
+            }
+        }
+
+        impl TryFrom<&CurvePoint> for $krate::PublicKey {
+            type Error = EllipticCurveError;
+
+            fn try_from(point: &CurvePoint) -> Result<Self, EllipticCurveError> {
+                let affine = $krate::AffinePoint::try_from(point)?;
+                $krate::PublicKey::from_affine(affine).map_err(|_| EllipticCurveError::InvalidPoint(point.clone()))
+            }
+        }
+
+        impl TryFrom<CurvePoint> for $krate::PublicKey {
+            type Error = EllipticCurveError;
+
+            fn try_from(point: CurvePoint) -> Result<Self, EllipticCurveError> {
+                $krate::PublicKey::try_from(&point)
+            }
+        }
+    };
+}
+
+interop!(k256);
+interop!(p256);
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves::{Curve, P256, Secp256k1};
+
+    #[test]
+    fn test_generators() {
+        assert_eq!(CurvePoint::from(k256::AffinePoint::GENERATOR), *Secp256k1::generator());
+        assert_eq!(CurvePoint::from(p256::AffinePoint::GENERATOR), *P256::generator());
+        assert_eq!(k256::AffinePoint::try_from(Secp256k1::generator()).unwrap(), k256::AffinePoint::GENERATOR);
+        assert_eq!(p256::AffinePoint::try_from(P256::generator()).unwrap(), p256::AffinePoint::GENERATOR);
+    }
+
+    #[test]
+    fn test_scalar_mul_matches() {
+        let d = BigUint::from(0xDEADBEEFu32);
+        let ours = Secp256k1::params().curve.scalar_mul(Secp256k1::generator(), &d).unwrap();
+        let theirs = k256::ProjectivePoint::GENERATOR * k256::Scalar::from(0xDEADBEEFu64);
+
+        let key = k256::PublicKey::from_affine(theirs.to_affine()).unwrap();
+        assert_eq!(CurvePoint::from(&key), ours);
+        assert_eq!(k256::PublicKey::try_from(&ours).unwrap(), key);
+    }
+
+    #[test]
+    fn test_identity_and_invalid_points() {
+        assert_eq!(CurvePoint::from(p256::AffinePoint::IDENTITY), CurvePoint::Identity);
+        assert_eq!(p256::AffinePoint::try_from(CurvePoint::Identity).unwrap(), p256::AffinePoint::IDENTITY);
+        assert!(p256::PublicKey::try_from(CurvePoint::Identity).is_err());
+
+        // a point of secp256k1 is not a point of P-256
+        assert_eq!(
+            p256::PublicKey::try_from(Secp256k1::generator()),
+            Err(EllipticCurveError::InvalidPoint(Secp256k1::generator().clone()))
+        );
+        let too_large = CurvePoint::Coordinate(BigUint::from(1u32) << 256, BigUint::from(1u32));
+        assert!(k256::AffinePoint::try_from(&too_large).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+/*!
+Jacobian projective coordinates for the short Weierstrass curves.
+
+A point `(X, Y, Z)` represents the affine point `(X / Z^2, Y / Z^3)` and the
+identity is any point with `Z = 0`. Adding and doubling in this representation
+does not need any modular inversion, so a scalar multiplication only pays for a
+single inversion when converting the result back to affine coordinates.
+*/
+
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+
+use crate::elliptic_curve::{CurvePoint, EllipticCurve};
+use crate::finite_fields::{self, FieldArithmetic};
+
+///
+/// A point `(X, Y, Z)` in Jacobian coordinates, where `x = X / Z^2` and
+/// `y = Y / Z^3`. The identity has `Z = 0`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JacobianPoint {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub x: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub y: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub z: BigUint,
+}
+
+impl JacobianPoint {
+    pub fn identity() -> Self {
+        JacobianPoint {
+            x: BigUint::from(1u32),
+            y: BigUint::from(1u32),
+            z: BigUint::from(0u32),
+        }
+    }
+
+    ///
+    /// `Z = 0`, on reduced coordinates: the curve functions reduce a point
+    /// first, so that a `Z` which is a multiple of `p` is an identity too.
+    ///
+    pub fn is_identity(&self) -> bool {
+        self.z == BigUint::from(0u32)
+    }
+
+    ///
+    /// Lifts an affine point to Jacobian coordinates: `(x, y) => (x, y, 1)`.
+    ///
+    pub fn from_affine(point: &CurvePoint) -> Self {
+        match point {
+            CurvePoint::Identity => JacobianPoint::identity(),
+            CurvePoint::Coordinate(x, y) => JacobianPoint {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::from(1u32),
+            },
+        }
+    }
+}
+
+impl EllipticCurve {
+    ///
+    /// Doubles a point in Jacobian coordinates (`dbl-2007-bl` without the
+    /// `a = -3` shortcut):
+    ///
+    /// ```text
+    ///  S = 4 X Y^2
+    ///  M = 3 X^2 + a Z^4
+    ///  X3 = M^2 - 2 S
+    ///  Y3 = M (S - X3) - 8 Y^4
+    ///  Z3 = 2 Y Z
+    /// ```
+    ///
+    pub fn jacobian_double(&self, a: &JacobianPoint) -> JacobianPoint {
+        self.jacobian_double_with(&self.p, &(&self.a % &self.p), &a.reduce(&self.p))
+    }
+
+    ///
+    /// Adds two points in Jacobian coordinates (`add-2007-bl`):
+    ///
+    /// ```text
+    ///  U1 = X1 Z2^2, U2 = X2 Z1^2
+    ///  S1 = Y1 Z2^3, S2 = Y2 Z1^3
+    ///  H = U2 - U1, R = S2 - S1
+    ///  X3 = R^2 - H^3 - 2 U1 H^2
+    ///  Y3 = R (U1 H^2 - X3) - S1 H^3
+    ///  Z3 = Z1 Z2 H
+    /// ```
+    ///
+    /// `H = 0` means both points share the same `x`, so the result is either a
+    /// doubling or the identity.
+    ///
+    pub fn jacobian_add(&self, a: &JacobianPoint, b: &JacobianPoint) -> JacobianPoint {
+        self.jacobian_add_with(&self.p, &(&self.a % &self.p), &a.reduce(&self.p), &b.reduce(&self.p))
+    }
+
+    ///
+    /// Converts a point back to affine coordinates with a single inversion:
+    ///
+    /// `(X, Y, Z) => (X / Z^2, Y / Z^3)`
+    ///
+    pub fn to_affine(&self, a: &JacobianPoint) -> CurvePoint {
+        let p = &self.p;
+        let a = a.reduce(p);
+        if a.is_identity() {
+            return CurvePoint::Identity;
+        }
+
+        let z_inv = finite_fields::inverse_multiplicate_prime(&a.z, p)
+            .expect("z is reduced mod p and not zero");
+        let z_inv2 = p.mul(&z_inv, &z_inv);
+        let z_inv3 = p.mul(&z_inv2, &z_inv);
+
+        CurvePoint::Coordinate(p.mul(&a.x, &z_inv2), p.mul(&a.y, &z_inv3))
+    }
+
+    ///
+    /// Converts many points back to affine coordinates with a single
+    /// inversion for all of them, the `Z` being inverted together by
+    /// `finite_fields::batch_inverse`. The identities are left out of the
+    /// inversion and stay identities.
+    ///
+    pub fn to_affine_batch(&self, points: &[JacobianPoint]) -> Vec<CurvePoint> {
+        let p = &self.p;
+        let points: Vec<JacobianPoint> = points.iter().map(|a| a.reduce(p)).collect();
+        let zs: Vec<BigUint> = points.iter().filter(|a| !a.is_identity()).map(|a| a.z.clone()).collect();
+        let mut z_invs = finite_fields::batch_inverse(&zs, p)
+            .expect("the z are reduced and not zero")
+            .into_iter();
+
+        points
+            .iter()
+            .map(|a| {
+                if a.is_identity() {
+                    return CurvePoint::Identity;
+                }
+                let z_inv = z_invs.next().expect("one inverse per point");
+                let z_inv2 = p.mul(&z_inv, &z_inv);
+                let z_inv3 = p.mul(&z_inv2, &z_inv);
+                CurvePoint::Coordinate(p.mul(&a.x, &z_inv2), p.mul(&a.y, &z_inv3))
+            })
+            .collect()
+    }
+
+    // The formulas run on the coordinates and `a` in the representation of
+    // `f`, which are always reduced mod p, so the range checks of the
+    // `finite_fields` functions are skipped. Small constants are additions,
+    // as a product by a constant would need it in that representation too.
+
+    pub(crate) fn jacobian_double_with<F: FieldArithmetic>(&self, f: &F, a_repr: &BigUint, a: &JacobianPoint) -> JacobianPoint {
+        if a.is_identity() || a.y == BigUint::from(0u32) {
+            return JacobianPoint::identity();
+        }
+
+        let xx = f.mul(&a.x, &a.x);
+        let yy = f.mul(&a.y, &a.y);
+        let yyyy = f.mul(&yy, &yy);
+        let zz = f.mul(&a.z, &a.z);
+
+        let xyy = f.mul(&a.x, &yy);
+        let s = f.add(&f.add(&xyy, &xyy), &f.add(&xyy, &xyy));
+        let m = f.add(&f.add(&xx, &xx), &xx);
+        let m = f.add(&m, &f.mul(a_repr, &f.mul(&zz, &zz)));
+
+        let yyyy8 = f.add(&yyyy, &yyyy);
+        let yyyy8 = f.add(&yyyy8, &yyyy8);
+        let yyyy8 = f.add(&yyyy8, &yyyy8);
+        let yz = f.mul(&a.y, &a.z);
+
+        let x3 = f.sub(&f.mul(&m, &m), &f.add(&s, &s));
+        let y3 = f.sub(&f.mul(&m, &f.sub(&s, &x3)), &yyyy8);
+        let z3 = f.add(&yz, &yz);
+
+        JacobianPoint { x: x3, y: y3, z: z3 }
+    }
+
+    pub(crate) fn jacobian_add_with<F: FieldArithmetic>(
+        &self,
+        f: &F,
+        a_repr: &BigUint,
+        a: &JacobianPoint,
+        b: &JacobianPoint,
+    ) -> JacobianPoint {
+        if a.is_identity() {
+            return b.clone();
+        }
+        if b.is_identity() {
+            return a.clone();
+        }
+
+        let z1z1 = f.mul(&a.z, &a.z);
+        let z2z2 = f.mul(&b.z, &b.z);
+        let u1 = f.mul(&a.x, &z2z2);
+        let u2 = f.mul(&b.x, &z1z1);
+        let s1 = f.mul(&a.y, &f.mul(&b.z, &z2z2));
+        let s2 = f.mul(&b.y, &f.mul(&a.z, &z1z1));
+
+        let h = f.sub(&u2, &u1);
+        let r = f.sub(&s2, &s1);
+        if h == BigUint::from(0u32) {
+            if r == BigUint::from(0u32) {
+                return self.jacobian_double_with(f, a_repr, a);
+            }
+            return JacobianPoint::identity();
+        }
+
+        let hh = f.mul(&h, &h);
+        let hhh = f.mul(&h, &hh);
+        let v = f.mul(&u1, &hh);
+
+        let x3 = f.sub(&f.sub(&f.mul(&r, &r), &hhh), &f.add(&v, &v));
+        let y3 = f.sub(&f.mul(&r, &f.sub(&v, &x3)), &f.mul(&s1, &hhh));
+        let z3 = f.mul(&f.mul(&a.z, &b.z), &h);
+
+        JacobianPoint { x: x3, y: y3, z: z3 }
+    }
+}
+
+impl JacobianPoint {
+    // the point with its coordinates reduced mod p, the public fields may
+    // hold any value
+    pub(crate) fn reduce(&self, p: &BigUint) -> Self {
+        JacobianPoint { x: &self.x % p, y: &self.y % p, z: &self.z % p }
+    }
+
+    // converts every coordinate, the identity stays an identity as Z = 0 in
+    // every representation
+    pub(crate) fn to_repr<F: FieldArithmetic>(&self, f: &F) -> Self {
+        JacobianPoint { x: f.to_repr(&self.x), y: f.to_repr(&self.y), z: f.to_repr(&self.z) }
+    }
+
+    pub(crate) fn to_value<F: FieldArithmetic>(&self, f: &F) -> Self {
+        JacobianPoint { x: f.to_value(&self.x), y: f.to_value(&self.y), z: f.to_value(&self.z) }
+    }
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn curve() -> EllipticCurve {
+        // y^2 = x^3 + 2x + 2 mod 17   |G| = 19
+        EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        }
+    }
+
+    #[test]
+    fn test_jacobian_double() {
+        let ec = curve();
+
+        // 2 * (5, 1) = (6, 3)
+        let a = JacobianPoint::from_affine(&CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)));
+        let res = ec.jacobian_double(&a);
+        assert_eq!(ec.to_affine(&res), CurvePoint::Coordinate(BigUint::from(6u32), BigUint::from(3u32)));
+
+        assert!(ec.jacobian_double(&JacobianPoint::identity()).is_identity());
+    }
+
+    #[test]
+    fn test_jacobian_add_matches_affine() {
+        let ec = curve();
+        let g = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+
+        // walk the whole group comparing against the affine formulas
+        let mut affine = CurvePoint::Identity;
+        let mut jacobian = JacobianPoint::identity();
+        for _ in 0..20 {
+            affine = ec.add(&affine, &g).unwrap();
+            jacobian = ec.jacobian_add(&jacobian, &JacobianPoint::from_affine(&g));
+            assert_eq!(ec.to_affine(&jacobian), affine);
+        }
+
+        // P + (-P) = I and P + P = 2 * P with non trivial Z
+        let p1 = ec.jacobian_double(&JacobianPoint::from_affine(&g));
+        let minus_p1 = JacobianPoint::from_affine(&CurvePoint::Coordinate(BigUint::from(6u32), BigUint::from(14u32)));
+        assert!(ec.jacobian_add(&p1, &minus_p1).is_identity());
+        assert_eq!(ec.to_affine(&ec.jacobian_add(&p1, &p1)), ec.to_affine(&ec.jacobian_double(&p1)));
+    }
+
+    #[test]
+    fn test_to_affine_batch() {
+        let ec = curve();
+        let g = JacobianPoint::from_affine(&CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)));
+
+        let mut points = vec![JacobianPoint::identity()];
+        for _ in 0..20 {
+            let next = ec.jacobian_add(points.last().unwrap(), &g);
+            points.push(next);
+        }
+        let expected: Vec<CurvePoint> = points.iter().map(|a| ec.to_affine(a)).collect();
+        assert_eq!(ec.to_affine_batch(&points), expected);
+        assert!(ec.to_affine_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_unreduced_coordinates() {
+        let ec = curve();
+        let p = &ec.p;
+        let g = JacobianPoint::from_affine(&CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)));
+        // the same points with coordinates above p
+        let g_unreduced = JacobianPoint { x: &g.x + p * 3u32, y: &g.y + p, z: &g.z + p * 2u32 };
+
+        // Z a non zero multiple of p is the identity
+        for z in [p.clone(), p * 5u32] {
+            let identity = JacobianPoint { x: BigUint::from(3u32), y: BigUint::from(4u32), z };
+            assert_eq!(ec.to_affine(&identity), CurvePoint::Identity);
+            assert_eq!(ec.to_affine_batch(&[identity.clone(), g.clone()]), vec![CurvePoint::Identity, ec.to_affine(&g)]);
+            assert_eq!(ec.to_affine(&ec.jacobian_add(&identity, &g)), ec.to_affine(&g));
+        }
+
+        assert_eq!(ec.to_affine(&g_unreduced), ec.to_affine(&g));
+        assert_eq!(ec.to_affine(&ec.jacobian_double(&g_unreduced)), ec.to_affine(&ec.jacobian_double(&g)));
+        let g2 = ec.jacobian_double(&g);
+        assert_eq!(ec.to_affine(&ec.jacobian_add(&g_unreduced, &g2)), ec.to_affine(&ec.jacobian_add(&g, &g2)));
+        assert_eq!(ec.to_affine(&ec.jacobian_add(&g2, &g_unreduced)), ec.to_affine(&ec.jacobian_add(&g2, &g)));
+    }
+}
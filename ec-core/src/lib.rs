@@ -1,10 +1,48 @@
-use num_bigint::BigUint;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+pub mod context;
+pub mod ct_curve;
+pub mod ct_field;
+pub mod curves;
+pub mod edwards;
 pub mod elliptic_curve;
+#[cfg(feature = "group")]
+pub mod ff_group;
+pub mod field_element;
 pub mod finite_fields;
+pub mod fixed_field;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod hash_to_curve;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod jacobian;
+pub mod montgomery;
+pub mod point;
+#[cfg(feature = "proptest")]
+pub mod proptest_support;
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
+pub mod scalar;
+#[cfg(feature = "serde")]
+pub mod serde_support;
 
 
 
 
+pub use context::CurveContext;
+pub use ct_curve::{Blinding, FieldBackend};
+pub use ct_field::{CtField, CtFieldElement};
+pub use curves::{Curve, CurveParams};
+pub use edwards::{EdwardsCurve, EdwardsCurveError, EdwardsParams, EdwardsPoint};
 pub use elliptic_curve::{EllipticCurve, EllipticCurveError, CurvePoint};
+pub use field_element::FieldElement;
 pub use finite_fields::*;
+pub use fixed_field::{Fe256, Fe384, FixedField, FixedFieldElement};
+pub use hash_to_curve::{HashToCurve, HashToCurveError};
+pub use jacobian::JacobianPoint;
+pub use montgomery::{x25519, MontgomeryCurve};
+pub use point::Point;
+pub use scalar::Scalar;
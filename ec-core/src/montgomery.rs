@@ -0,0 +1,203 @@
+/*!
+Montgomery curves, used for Diffie-Hellman key agreement (X25519).
+
+A Montgomery curve is defined algebraically by;
+
+`b y^2 = x^3 + a x^2 + x mod p`
+
+Only the `x` (or `u`) coordinate is needed to compute a scalar multiplication
+with the Montgomery ladder, which makes it both simple and regular: every bit
+of the scalar costs one differential addition and one doubling.
+*/
+
+use num_bigint::BigUint;
+
+use crate::finite_fields;
+
+///
+/// This represents a Montgomery curve of the form
+/// b y^2 = x^3 + a x^2 + x mod p
+///
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MontgomeryCurve {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub a: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub b: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::biguint"))]
+    pub p: BigUint,
+}
+
+
+impl MontgomeryCurve {
+    ///
+    /// Computes the `u` coordinate of `k * P` from the `u` coordinate of `P`
+    /// with the Montgomery ladder as described in RFC 7748:
+    ///
+    /// ```text
+    ///  (x2, z2) = (1, 0), (x3, z3) = (u, 1)
+    ///  for i in [(bits of k) - 1, 0]
+    ///       swap the points if bit i of k differs from the previous one
+    ///       A = x2 + z2, B = x2 - z2, C = x3 + z3, D = x3 - z3
+    ///       E = A^2 - B^2, DA = D A, CB = C B
+    ///       x3 = (DA + CB)^2, z3 = u (DA - CB)^2
+    ///       x2 = A^2 B^2, z2 = E (A^2 + a24 E)
+    ///  result = x2 / z2
+    /// ```
+    ///
+    /// where `a24 = (a - 2) / 4`. The point at infinity, `z2 = 0`, is mapped
+    /// to `0`.
+    ///
+    pub fn ladder(&self, k: &BigUint, u: &BigUint) -> BigUint {
+        let p = &self.p;
+        let x1 = u % p;
+        let a24 = fmul(
+            &fsub(&self.a, &BigUint::from(2u32), p),
+            &finite_fields::inverse_multiplicate_prime(&BigUint::from(4u32), p).expect("p > 4"),
+            p,
+        );
+
+        let mut x2 = BigUint::from(1u32);
+        let mut z2 = BigUint::from(0u32);
+        let mut x3 = x1.clone();
+        let mut z3 = BigUint::from(1u32);
+        let mut swap = false;
+
+        for i in (0..k.bits()).rev() {
+            let bit = k.bit(i);
+            if swap != bit {
+                core::mem::swap(&mut x2, &mut x3);
+                core::mem::swap(&mut z2, &mut z3);
+            }
+            swap = bit;
+
+            let a = fadd(&x2, &z2, p);
+            let aa = fmul(&a, &a, p);
+            let b = fsub(&x2, &z2, p);
+            let bb = fmul(&b, &b, p);
+            let e = fsub(&aa, &bb, p);
+            let c = fadd(&x3, &z3, p);
+            let d = fsub(&x3, &z3, p);
+            let da = fmul(&d, &a, p);
+            let cb = fmul(&c, &b, p);
+
+            let sum = fadd(&da, &cb, p);
+            let diff = fsub(&da, &cb, p);
+            x3 = fmul(&sum, &sum, p);
+            z3 = fmul(&x1, &fmul(&diff, &diff, p), p);
+            x2 = fmul(&aa, &bb, p);
+            z2 = fmul(&e, &fadd(&aa, &fmul(&a24, &e, p), p), p);
+        }
+        if swap {
+            core::mem::swap(&mut x2, &mut x3);
+            core::mem::swap(&mut z2, &mut z3);
+        }
+
+        // z2^(p-2) = 0 when z2 = 0
+        fmul(&x2, &z2.modpow(&(p - BigUint::from(2u32)), p), p)
+    }
+}
+
+
+///
+/// The X25519 function of RFC 7748: clamps the 32 bytes little endian
+/// `scalar`, masks the most significant bit of the little endian
+/// `u_coordinate` and returns the `u` coordinate of `scalar * u` over
+/// curve25519, encoded in 32 bytes little endian.
+///
+/// Using the base point `u = 9` gives the public key of `scalar`, using the
+/// public key of a peer gives the shared secret.
+///
+pub fn x25519(scalar: &[u8; 32], u_coordinate: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let mut u = *u_coordinate;
+    u[31] &= 127;
+
+    let curve = crate::curves::curve25519();
+    let res = curve.ladder(&BigUint::from_bytes_le(&k), &BigUint::from_bytes_le(&u));
+
+    let mut out = [0u8; 32];
+    let bytes = res.to_bytes_le();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+
+fn fadd(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a + b) % p
+}
+
+fn fsub(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a + p - b) % p
+}
+
+fn fmul(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a * b) % p
+}
+
+
+
+
+
+
+
+
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_hex(value: &str) -> [u8; 32] {
+        let bytes = BigUint::parse_bytes(value.as_bytes(), 16).unwrap().to_bytes_be();
+        let mut out = [0u8; 32];
+        out[32 - bytes.len()..].copy_from_slice(&bytes);
+        out
+    }
+
+    #[test]
+    fn test_x25519() {
+        // RFC 7748, section 5.2
+        let scalar = from_hex("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4");
+        let u = from_hex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+        assert_eq!(
+            x25519(&scalar, &u),
+            from_hex("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+
+        // one iteration of the k = u = 9 loop
+        let mut nine = [0u8; 32];
+        nine[0] = 9;
+        assert_eq!(
+            x25519(&nine, &nine),
+            from_hex("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+        );
+    }
+
+    #[test]
+    fn test_x25519_diffie_hellman() {
+        // RFC 7748, section 6.1
+        let mut base = [0u8; 32];
+        base[0] = 9;
+
+        let alice = from_hex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = from_hex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+
+        let alice_pub = x25519(&alice, &base);
+        let bob_pub = x25519(&bob, &base);
+        assert_eq!(alice_pub, from_hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        assert_eq!(bob_pub, from_hex("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+
+        let shared = from_hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519(&alice, &bob_pub), shared);
+        assert_eq!(x25519(&bob, &alice_pub), shared);
+    }
+}
@@ -0,0 +1,261 @@
+/*!
+A point bound to its curve, so the group law can be written with operators:
+
+```text
+ let p = curve.point(p)?;
+ let q = curve.point(q)?;
+ let r = &p + &q;
+ let s = -(&r * &d) + p;
+```
+
+The coordinates are checked to be on the curve once, when the point is built,
+which makes every operation infallible. Mixing points of different curves is a
+programming error and panics.
+*/
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::elliptic_curve::{CurvePoint, EllipticCurve, EllipticCurveError};
+
+///
+/// A point of `curve`, identity included.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct Point<'c> {
+    curve: &'c EllipticCurve,
+    inner: CurvePoint,
+}
+
+impl<'c> Point<'c> {
+    ///
+    /// Binds `point` to `curve`, failing if it does not satisfy the curve
+    /// equation.
+    ///
+    pub fn new(curve: &'c EllipticCurve, point: CurvePoint) -> Result<Self, EllipticCurveError> {
+        if !curve.is_on_curve(&point) {
+            return Err(EllipticCurveError::InvalidPoint(point));
+        }
+        Ok(Point { curve, inner: point })
+    }
+
+    pub fn identity(curve: &'c EllipticCurve) -> Self {
+        Point { curve, inner: CurvePoint::Identity }
+    }
+
+    pub fn curve(&self) -> &'c EllipticCurve {
+        self.curve
+    }
+
+    pub fn as_curve_point(&self) -> &CurvePoint {
+        &self.inner
+    }
+
+    pub fn into_curve_point(self) -> CurvePoint {
+        self.inner
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.inner == CurvePoint::Identity
+    }
+
+    pub fn double(&self) -> Self {
+        let inner = self.curve.double(&self.inner).expect("the point is on the curve");
+        Point { curve: self.curve, inner }
+    }
+
+    fn same_curve(&self, other: &Point) {
+        assert!(
+            core::ptr::eq(self.curve, other.curve) || self.curve == other.curve,
+            "points of different curves"
+        );
+    }
+}
+
+impl EllipticCurve {
+    ///
+    /// Binds a point to this curve, see `Point::new`.
+    ///
+    pub fn point(&self, point: CurvePoint) -> Result<Point<'_>, EllipticCurveError> {
+        Point::new(self, point)
+    }
+}
+
+impl<'c> Add<&Point<'c>> for &Point<'c> {
+    type Output = Point<'c>;
+
+    fn add(self, other: &Point<'c>) -> Point<'c> {
+        self.same_curve(other);
+        let inner = self.curve.add(&self.inner, &other.inner).expect("both points are on the curve");
+        Point { curve: self.curve, inner }
+    }
+}
+
+impl<'c> Add for Point<'c> {
+    type Output = Point<'c>;
+
+    fn add(self, other: Point<'c>) -> Point<'c> {
+        &self + &other
+    }
+}
+
+impl<'c> Neg for &Point<'c> {
+    type Output = Point<'c>;
+
+    ///
+    /// `-(x, y) = (x, -y mod p)`
+    ///
+    fn neg(self) -> Point<'c> {
+        let inner = self.curve.neg(&self.inner).expect("the point is on the curve");
+        Point { curve: self.curve, inner }
+    }
+}
+
+impl<'c> Neg for Point<'c> {
+    type Output = Point<'c>;
+
+    fn neg(self) -> Point<'c> {
+        -&self
+    }
+}
+
+impl<'c> Sub<&Point<'c>> for &Point<'c> {
+    type Output = Point<'c>;
+
+    fn sub(self, other: &Point<'c>) -> Point<'c> {
+        self + &(-other)
+    }
+}
+
+impl<'c> Sub for Point<'c> {
+    type Output = Point<'c>;
+
+    fn sub(self, other: Point<'c>) -> Point<'c> {
+        &self - &other
+    }
+}
+
+impl<'c> Mul<&BigUint> for &Point<'c> {
+    type Output = Point<'c>;
+
+    ///
+    /// `d * A`, with `0 * A = I`. Variable time in `d`: secret scalars should
+    /// go through `EllipticCurve::scalar_mul_ct`.
+    ///
+    fn mul(self, d: &BigUint) -> Point<'c> {
+        let inner = self.curve.scalar_mul(&self.inner, d).expect("the point is on the curve");
+        Point { curve: self.curve, inner }
+    }
+}
+
+impl<'c> Mul<&BigUint> for Point<'c> {
+    type Output = Point<'c>;
+
+    fn mul(self, d: &BigUint) -> Point<'c> {
+        &self * d
+    }
+}
+
+impl<'c> Mul<&BigInt> for &Point<'c> {
+    type Output = Point<'c>;
+
+    ///
+    /// `d * A` for a signed `d`: `(-d) * A = -(d * A)`.
+    ///
+    fn mul(self, d: &BigInt) -> Point<'c> {
+        let product = self * d.magnitude();
+        match d.sign() {
+            Sign::Minus => -product,
+            _ => product,
+        }
+    }
+}
+
+impl<'c> Mul<&BigInt> for Point<'c> {
+    type Output = Point<'c>;
+
+    fn mul(self, d: &BigInt) -> Point<'c> {
+        &self * d
+    }
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn curve() -> EllipticCurve {
+        // y^2 = x^3 + 2x + 2 mod 17, of order 19
+        EllipticCurve::new(BigUint::from(2u32), BigUint::from(2u32), BigUint::from(17u32)).unwrap()
+    }
+
+    fn coordinate(x: u32, y: u32) -> CurvePoint {
+        CurvePoint::Coordinate(BigUint::from(x), BigUint::from(y))
+    }
+
+    #[test]
+    fn test_new() {
+        let ec = curve();
+        assert!(ec.point(coordinate(5, 1)).is_ok());
+        assert!(ec.point(CurvePoint::Identity).unwrap().is_identity());
+        assert_eq!(
+            ec.point(coordinate(1, 1)),
+            Err(EllipticCurveError::InvalidPoint(coordinate(1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let ec = curve();
+        let p = ec.point(coordinate(6, 3)).unwrap();
+        let q = ec.point(coordinate(5, 1)).unwrap();
+
+        // (6,3) + (5,1) = (10,6)
+        assert_eq!((&p + &q).into_curve_point(), coordinate(10, 6));
+        assert_eq!(p.clone() + q.clone(), &q + &p);
+        assert_eq!(&(&p + &q) - &q, p);
+        assert!((&p - &p).is_identity());
+        assert!((&p + &(-&p)).is_identity());
+        assert_eq!(-Point::identity(&ec), Point::identity(&ec));
+        assert_eq!(&p + &Point::identity(&ec), p);
+    }
+
+    #[test]
+    fn test_mul() {
+        let ec = curve();
+        let g = ec.point(coordinate(5, 1)).unwrap();
+
+        assert_eq!(&g * &BigUint::from(2u32), g.double());
+        assert_eq!(&g * &BigUint::from(2u32), &g + &g);
+        assert_eq!((&g * &BigUint::from(16u32)).into_curve_point(), coordinate(10, 11));
+        assert!((&g * &BigUint::from(19u32)).is_identity());
+        assert!((&g * &BigUint::from(0u32)).is_identity());
+        assert_eq!(
+            &g * &BigUint::from(7u32) - &g * &BigUint::from(3u32),
+            g * &BigUint::from(4u32)
+        );
+    }
+
+    #[test]
+    fn test_mul_signed() {
+        let ec = curve();
+        let g = ec.point(coordinate(5, 1)).unwrap();
+
+        assert_eq!(&g * &BigInt::from(-1), -&g);
+        assert_eq!(&g * &BigInt::from(-3), &g * &BigUint::from(16u32));
+        assert_eq!(&g * &BigInt::from(5) + &g * &BigInt::from(-5), Point::identity(&ec));
+        assert!((g * &BigInt::from(0)).is_identity());
+    }
+
+    #[test]
+    #[should_panic(expected = "points of different curves")]
+    fn test_different_curves() {
+        let ec = curve();
+        let other = EllipticCurve::new(BigUint::from(0u32), BigUint::from(7u32), BigUint::from(17u32)).unwrap();
+        let _ = ec.point(CurvePoint::Identity).unwrap() + other.point(CurvePoint::Identity).unwrap();
+    }
+}
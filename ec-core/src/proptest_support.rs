@@ -0,0 +1,128 @@
+/*!
+proptest strategies for the `proptest` feature, to property-test protocol
+code built on the crate without writing generators:
+
+```text
+ proptest! {
+     #[test]
+     fn round_trip(key in any_nonzero_scalar(&p256.n), point in any_point_on(&p256)) {
+         ...
+     }
+ }
+```
+
+Points are generated as `k G` and shrink with `k`, towards the identity.
+*/
+
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use proptest::sample;
+
+use crate::curves::{self, CurveParams};
+use crate::elliptic_curve::CurvePoint;
+use crate::field_element::FieldElement;
+
+///
+/// An integer in `[0, n - 1]`, from `bits(n)` random bits reduced modulo
+/// `n`, which shrinks towards `0`. `n` has to be greater than `0`.
+///
+pub fn any_scalar(n: &BigUint) -> impl Strategy<Value = BigUint> {
+    let n = n.clone();
+    let size = n.bits().div_ceil(8) as usize;
+    proptest::collection::vec(any::<u8>(), size).prop_map(move |bytes| BigUint::from_bytes_be(&bytes) % &n)
+}
+
+///
+/// An integer in `[1, n - 1]`, for private keys and nonces. `n` has to be
+/// greater than `1`.
+///
+pub fn any_nonzero_scalar(n: &BigUint) -> impl Strategy<Value = BigUint> {
+    let one = BigUint::from(1u32);
+    any_scalar(&(n - &one)).prop_map(move |k| k + &one)
+}
+
+///
+/// An element of `F_p`. `p` has to be greater than `1`.
+///
+pub fn any_field_element(p: &BigUint) -> impl Strategy<Value = FieldElement> {
+    let p = p.clone();
+    any_scalar(&p).prop_map(move |value| FieldElement::new(value, p.clone()).expect("p is greater than 1"))
+}
+
+///
+/// A point of the subgroup generated by `G`, the identity included.
+///
+pub fn any_point_on(params: &CurveParams) -> impl Strategy<Value = CurvePoint> {
+    let params = params.clone();
+    any_scalar(&params.n).prop_map(move |k| {
+        params.curve.scalar_mul(&params.g, &k).expect("G is a point of the curve")
+    })
+}
+
+///
+/// A point of the subgroup generated by `G` other than the identity, e.g. a
+/// public key.
+///
+pub fn any_nonidentity_point_on(params: &CurveParams) -> impl Strategy<Value = CurvePoint> {
+    let params = params.clone();
+    any_nonzero_scalar(&params.n).prop_map(move |k| {
+        params.curve.scalar_mul(&params.g, &k).expect("G is a point of the curve")
+    })
+}
+
+///
+/// One of the curves of `curves::by_name`.
+///
+pub fn any_named_curve() -> impl Strategy<Value = &'static CurveParams> {
+    sample::select(curves::names().collect::<alloc::vec::Vec<_>>())
+        .prop_map(|name| curves::by_name(name).expect("a name of the registry"))
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_scalars_are_in_range(k in any_scalar(&curves::p256().n), d in any_nonzero_scalar(&BigUint::from(2u32))) {
+            prop_assert!(k < curves::p256().n);
+            prop_assert_eq!(d, BigUint::from(1u32));
+        }
+
+        #[test]
+        fn test_add_is_associative(
+            a in any_point_on(&curves::secp256k1()),
+            b in any_point_on(&curves::secp256k1()),
+            c in any_point_on(&curves::secp256k1()),
+        ) {
+            let curve = &curves::secp256k1().curve;
+            let left = curve.add(&curve.add(&a, &b).unwrap(), &c).unwrap();
+            let right = curve.add(&a, &curve.add(&b, &c).unwrap()).unwrap();
+            prop_assert_eq!(left, right);
+        }
+
+        #[test]
+        fn test_sec1_round_trip(params in any_named_curve(), seed in any::<u64>()) {
+            let k = BigUint::from(seed) + 1u32;
+            let point = params.curve.scalar_mul(&params.g, &k).unwrap();
+            let encoded = point.to_sec1_compressed(&params.curve).unwrap();
+            prop_assert_eq!(CurvePoint::from_sec1_compressed(&encoded, &params.curve).unwrap(), point);
+        }
+
+        #[test]
+        fn test_field_elements_are_reduced(x in any_field_element(&curves::p384().curve.p)) {
+            prop_assert!(x.value() < &curves::p384().curve.p);
+        }
+
+        #[test]
+        fn test_nonidentity_point(point in any_nonidentity_point_on(&curves::p256())) {
+            prop_assert_ne!(point, CurvePoint::Identity);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+/*!
+Implementations of the RustCrypto `elliptic_curve::Curve` and `PrimeCurve`
+traits for the named curves, so types generic over those traits, such as
+`ecdsa::Signature<C>`, can be used with the markers of `curves`:
+
+```text
+ let signature = ecdsa::Signature::<ec_core::curves::P256>::from_bytes(&bytes)?;
+```
+
+Only the sizes and the order are provided: the arithmetic of these curves is
+the one of this crate, not `elliptic_curve::CurveArithmetic`.
+*/
+
+use elliptic_curve::bigint::{U256, U384, U512, U576};
+use elliptic_curve::consts::{U32, U48, U64, U66};
+use elliptic_curve::FieldBytesEncoding;
+
+use crate::curves::{BrainpoolP256r1, BrainpoolP384r1, BrainpoolP512r1, P256, P384, P521, Secp256k1};
+
+macro_rules! rustcrypto_curve {
+    ($curve:ident, $uint:ident, $size:ident, $order:literal) => {
+        impl elliptic_curve::Curve for $curve {
+            type FieldBytesSize = $size;
+            type Uint = $uint;
+
+            const ORDER: $uint = $uint::from_be_hex($order);
+        }
+
+        impl elliptic_curve::PrimeCurve for $curve {}
+
+        // big endian, left padded to the size of the field
+        impl FieldBytesEncoding<$curve> for $uint {}
+    };
+}
+
+rustcrypto_curve!(Secp256k1, U256, U32, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+rustcrypto_curve!(P256, U256, U32, "FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551");
+rustcrypto_curve!(P384, U384, U48, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC7634D81F4372DDF581A0DB248B0A77AECEC196ACCC52973");
+rustcrypto_curve!(
+    P521,
+    U576,
+    U66,
+    "00000000000001FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFA51868783BF2F966B7FCC0148F709A5D03BB5C9B8899C47AEBB6FB71E91386409"
+);
+rustcrypto_curve!(BrainpoolP256r1, U256, U32, "A9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7");
+rustcrypto_curve!(
+    BrainpoolP384r1,
+    U384,
+    U48,
+    "8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B31F166E6CAC0425A7CF3AB6AF6B7FC3103B883202E9046565"
+);
+rustcrypto_curve!(
+    BrainpoolP512r1,
+    U512,
+    U64,
+    "AADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA70330870553E5C414CA92619418661197FAC10471DB1D381085DDADDB58796829CA90069"
+);
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves::Curve;
+    use elliptic_curve::bigint::Encoding;
+    use num_bigint::BigUint;
+
+    fn order_of<C: elliptic_curve::Curve>() -> BigUint
+    where
+        C::Uint: Encoding,
+    {
+        BigUint::from_bytes_be(C::ORDER.to_be_bytes().as_ref())
+    }
+
+    #[test]
+    fn test_orders() {
+        assert_eq!(order_of::<Secp256k1>(), *Secp256k1::order());
+        assert_eq!(order_of::<P256>(), *P256::order());
+        assert_eq!(order_of::<P384>(), *P384::order());
+        assert_eq!(order_of::<P521>(), *P521::order());
+        assert_eq!(order_of::<BrainpoolP256r1>(), *BrainpoolP256r1::order());
+        assert_eq!(order_of::<BrainpoolP384r1>(), *BrainpoolP384r1::order());
+        assert_eq!(order_of::<BrainpoolP512r1>(), *BrainpoolP512r1::order());
+    }
+
+    #[test]
+    fn test_field_bytes() {
+        let bytes = <U576 as FieldBytesEncoding<P521>>::encode_field_bytes(&<P521 as elliptic_curve::Curve>::ORDER);
+        assert_eq!(bytes.len(), 66);
+        assert_eq!(BigUint::from_bytes_be(&bytes), *P521::order());
+        assert_eq!(<U576 as FieldBytesEncoding<P521>>::decode_field_bytes(&bytes), <P521 as elliptic_curve::Curve>::ORDER);
+    }
+}
@@ -0,0 +1,267 @@
+/*!
+Scalars of an elliptic curve group: integers modulo the order `n` of the
+generator rather than modulo the field prime `p`.
+
+ECDSA computes `r`, `s` and the `u1`, `u2` of the verification in this ring:
+
+```text
+ let d = params.scalar(priv_key);
+ let s = (params.scalar(hash) + params.scalar(r) * d) * params.scalar(k).invert()?;
+```
+
+A `Scalar` is a `FieldElement` whose modulus is the group order, so the same
+rules apply: the value is reduced when built, and mixing scalars of different
+groups panics.
+*/
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::curves::CurveParams;
+use crate::field_element::FieldElement;
+use crate::finite_fields::{self, FiniteFieldError};
+
+///
+/// `value mod n`, with `value` always in `[0, n - 1]`.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Scalar(FieldElement);
+
+impl Scalar {
+    ///
+    /// Builds `value mod order`. The order has to be greater than `1` and is
+    /// expected to be prime for `invert` to be correct.
+    ///
+    pub fn new(value: BigUint, order: &BigUint) -> Result<Self, FiniteFieldError> {
+        Ok(Scalar(FieldElement::new(value, order.clone())?))
+    }
+
+    ///
+    /// A uniformly random scalar in `[0, order - 1]`, see
+    /// `FieldElement::random`.
+    ///
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R, order: &BigUint) -> Result<Self, FiniteFieldError> {
+        Ok(Scalar(FieldElement::random(rng, order.clone())?))
+    }
+
+    ///
+    /// A uniformly random scalar in `[1, order - 1]`, for private keys and
+    /// nonces.
+    ///
+    pub fn random_nonzero<R: CryptoRng + RngCore>(rng: &mut R, order: &BigUint) -> Result<Self, FiniteFieldError> {
+        Ok(Scalar(FieldElement::random_nonzero(rng, order.clone())?))
+    }
+
+    ///
+    /// Reads a big endian integer and reduces it modulo `order`.
+    ///
+    pub fn from_bytes_reduced(bytes: &[u8], order: &BigUint) -> Result<Self, FiniteFieldError> {
+        Scalar::new(BigUint::from_bytes_be(bytes), order)
+    }
+
+    pub fn value(&self) -> &BigUint {
+        self.0.value()
+    }
+
+    pub fn order(&self) -> &BigUint {
+        self.0.modulus()
+    }
+
+    pub fn into_value(self) -> BigUint {
+        self.0.into_value()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    ///
+    /// Big endian encoding on the byte size of the order.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let size = self.order().bits().div_ceil(8) as usize;
+        finite_fields::to_fixed_bytes(self.value(), size).expect("the value is smaller than the order")
+    }
+
+    ///
+    /// `self^(-1) mod n`, failing for zero.
+    ///
+    pub fn invert(&self) -> Result<Self, FiniteFieldError> {
+        Ok(Scalar(self.0.inverse()?))
+    }
+}
+
+impl CurveParams {
+    ///
+    /// `value mod n` as a scalar of this group.
+    ///
+    pub fn scalar(&self, value: BigUint) -> Scalar {
+        Scalar::new(value, &self.n).expect("the order of a group is greater than 1")
+    }
+
+    ///
+    /// A uniformly random non zero scalar of this group.
+    ///
+    pub fn random_scalar<R: CryptoRng + RngCore>(&self, rng: &mut R) -> Scalar {
+        Scalar::random_nonzero(rng, &self.n).expect("the order of a group is greater than 1")
+    }
+}
+
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Add<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, other: &Scalar) -> Scalar {
+        Scalar(&self.0 + &other.0)
+    }
+}
+
+impl Sub<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn sub(self, other: &Scalar) -> Scalar {
+        Scalar(&self.0 - &other.0)
+    }
+}
+
+impl Mul<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, other: &Scalar) -> Scalar {
+        Scalar(&self.0 * &other.0)
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar(-&self.0)
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar(-self.0)
+    }
+}
+
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident) => {
+        impl $imp for Scalar {
+            type Output = Scalar;
+
+            fn $method(self, other: Scalar) -> Scalar {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&Scalar> for Scalar {
+            type Output = Scalar;
+
+            fn $method(self, other: &Scalar) -> Scalar {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<Scalar> for &Scalar {
+            type Output = Scalar;
+
+            fn $method(self, other: Scalar) -> Scalar {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curves;
+
+    #[test]
+    fn test_reduced_modulo_order() {
+        let params = curves::secp256k1();
+        let n = params.order().clone();
+
+        assert!(params.scalar(n.clone()).is_zero());
+        assert_eq!(params.scalar(&n + BigUint::from(5u32)).value(), &BigUint::from(5u32));
+        assert_eq!(params.scalar(BigUint::from(5u32)).order(), &n);
+        assert!(Scalar::new(BigUint::from(5u32), &BigUint::from(1u32)).is_err());
+
+        let bytes = (&n + BigUint::from(1u32)).to_bytes_be();
+        assert_eq!(Scalar::from_bytes_reduced(&bytes, &n).unwrap(), params.scalar(BigUint::from(1u32)));
+        assert_eq!(params.scalar(BigUint::from(1u32)).to_bytes().len(), 32);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let params = curves::p256();
+        let n = params.order().clone();
+        let minus_one = params.scalar(&n - BigUint::from(1u32));
+        let one = params.scalar(BigUint::from(1u32));
+        let two = params.scalar(BigUint::from(2u32));
+
+        assert!((&minus_one + &one).is_zero());
+        assert_eq!(-&one, minus_one);
+        assert!((-params.scalar(BigUint::from(0u32))).is_zero());
+        assert_eq!(&one - &two, minus_one);
+        assert_eq!(&minus_one * &minus_one, one);
+        assert_eq!(two.invert().unwrap() * &two, one);
+        assert_eq!(two.invert().unwrap().value(), &((&n + BigUint::from(1u32)) >> 1));
+        assert!(params.scalar(BigUint::from(0u32)).invert().is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_equation() {
+        // s = (z + r d) / k and u1 G + u2 Q = k G with u1 = z / s, u2 = r / s
+        let params = curves::secp256k1();
+        let z = params.scalar(BigUint::from(1234u32));
+        let d = params.scalar(BigUint::from(77u32));
+        let k = params.scalar(BigUint::from(99u32));
+        let r = params.scalar(BigUint::from(5555u32));
+
+        let s = (&z + &r * &d) * k.invert().unwrap();
+        let s_inv = s.invert().unwrap();
+        assert_eq!(&z * &s_inv + &r * &s_inv * &d, k);
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(79);
+        let params = curves::p521();
+        let scalar = params.random_scalar(&mut rng);
+        assert!(!scalar.is_zero());
+        assert_eq!(scalar.order(), params.order());
+        assert_ne!(params.random_scalar(&mut rng), scalar);
+
+        // 2 bits are drawn for n = 3, one draw in four is rejected
+        let order = BigUint::from(3u32);
+        for _ in 0..100 {
+            assert!(Scalar::random(&mut rng, &order).unwrap().value() < &order);
+        }
+        assert!(Scalar::random_nonzero(&mut rng, &BigUint::from(1u32)).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+/*!
+Helpers for the `serde` feature. Big integers and byte strings are written
+as lowercase hex strings in human readable formats (JSON, TOML...) and as
+raw big endian bytes in binary formats, e.g.
+
+```text
+ #[serde(with = "ec_core::serde_support::biguint")]
+ pub value: BigUint,
+```
+*/
+
+///
+/// A `BigUint` as a hex string (`"1f"`, without `0x` prefix) or big endian
+/// bytes.
+///
+pub mod biguint {
+    use alloc::string::String;
+    use num_bigint::BigUint;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&value.to_str_radix(16))
+        } else {
+            serializer.serialize_bytes(&value.to_bytes_be())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        if deserializer.is_human_readable() {
+            let text: String = serde::Deserialize::deserialize(deserializer)?;
+            if text.is_empty() || text.starts_with('+') {
+                return Err(serde::de::Error::custom("invalid hex integer"));
+            }
+            BigUint::parse_bytes(text.as_bytes(), 16).ok_or_else(|| serde::de::Error::custom("invalid hex integer"))
+        } else {
+            Ok(BigUint::from_bytes_be(&super::bytes::deserialize(deserializer)?))
+        }
+    }
+}
+
+///
+/// A byte string as a hex string or as raw bytes.
+///
+pub mod bytes {
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let text: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            serializer.serialize_str(&text)
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let text: String = serde::Deserialize::deserialize(deserializer)?;
+            if !text.len().is_multiple_of(2) || !text.is_ascii() {
+                return Err(D::Error::custom("invalid hex string"));
+            }
+            (0..text.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| D::Error::custom("invalid hex string")))
+                .collect()
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+
+    // binary formats give either a byte string or a sequence of bytes
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a byte string")
+        }
+
+        fn visit_bytes<E: Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E: Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(bytes)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut bytes = Vec::new();
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+// ===================================
+// TEST-------------------------------
+// ===================================
+
+#[cfg(test)]
+mod test {
+    use crate::curves::{self, CurveParams};
+    use crate::*;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_json_round_trip() {
+        let params = curves::secp256k1();
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(serde_json::from_str::<CurveParams>(&json).unwrap(), params);
+
+        let point = CurvePoint::Coordinate(BigUint::from(0x1fu32), BigUint::from(0u32));
+        assert_eq!(serde_json::to_string(&point).unwrap(), r#"{"Coordinate":["1f","0"]}"#);
+        assert_eq!(serde_json::from_str::<CurvePoint>(r#"{"Coordinate":["1F","0"]}"#).unwrap(), point);
+        assert_eq!(serde_json::to_string(&CurvePoint::Identity).unwrap(), r#""Identity""#);
+        assert_eq!(serde_json::from_str::<CurvePoint>(r#""Identity""#).unwrap(), CurvePoint::Identity);
+
+        let jacobian = JacobianPoint { x: BigUint::from(1u32), y: BigUint::from(2u32), z: BigUint::from(255u32) };
+        assert_eq!(serde_json::to_string(&jacobian).unwrap(), r#"{"x":"1","y":"2","z":"ff"}"#);
+    }
+
+    #[test]
+    fn test_invalid_hex() {
+        for json in [r#"{"Coordinate":["","0"]}"#, r#"{"Coordinate":["+1","0"]}"#, r#"{"Coordinate":["0x1","0"]}"#] {
+            assert!(serde_json::from_str::<CurvePoint>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * C API of the ECDSA library (ecdsa_ffi crate).
+ *
+ * Keep in sync with ecdsa-ffi/src/lib.rs: test_header_declares_exports checks
+ * that every export and status code is declared here.
+ */
+
+#ifndef ECDSA_FFI_H
+#define ECDSA_FFI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes, returned by every function below that returns an int */
+#define ECDSA_OK (0)
+#define ECDSA_ERR_VERIFY_FAILED (-1)
+#define ECDSA_ERR_NULL_POINTER (-2)
+#define ECDSA_ERR_BAD_LENGTH (-3)
+#define ECDSA_ERR_INVALID_KEY (-4)
+#define ECDSA_ERR_INVALID_SIGNATURE (-5)
+#define ECDSA_ERR_INTERNAL (-6)
+
+/* Parameters of a named curve */
+typedef struct EcdsaContext ecdsa_ctx;
+
+/* "secp256k1", "P-256", "P-384", "P-521", "brainpoolP256r1"... NULL if unknown */
+ecdsa_ctx *ecdsa_ctx_new(const char *curve_name);
+void ecdsa_ctx_free(ecdsa_ctx *ctx);
+
+/* Exact sizes of the output buffers, 0 for a NULL context */
+size_t ecdsa_private_key_size(const ecdsa_ctx *ctx);
+size_t ecdsa_public_key_size(const ecdsa_ctx *ctx);
+size_t ecdsa_signature_size(const ecdsa_ctx *ctx);
+
+/* Private keys are big endian, public keys SEC1 uncompressed */
+int ecdsa_keygen(const ecdsa_ctx *ctx,
+                 uint8_t *priv_key_out, size_t priv_key_len,
+                 uint8_t *pub_key_out, size_t pub_key_len);
+
+int ecdsa_public_key(const ecdsa_ctx *ctx,
+                     const uint8_t *priv_key, size_t priv_key_len,
+                     uint8_t *pub_key_out, size_t pub_key_len);
+
+/* r || s over the SHA-256 digest of the message, deterministic (RFC 6979) */
+int ecdsa_sign(const ecdsa_ctx *ctx,
+               const uint8_t *priv_key, size_t priv_key_len,
+               const uint8_t *message, size_t message_len,
+               uint8_t *signature_out, size_t signature_len);
+
+/* ECDSA_OK if valid, ECDSA_ERR_VERIFY_FAILED if not; public key compressed or not */
+int ecdsa_verify(const ecdsa_ctx *ctx,
+                 const uint8_t *pub_key, size_t pub_key_len,
+                 const uint8_t *message, size_t message_len,
+                 const uint8_t *signature, size_t signature_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ECDSA_FFI_H */
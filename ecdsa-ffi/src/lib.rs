@@ -0,0 +1,446 @@
+/*!
+C ABI of the ECDSA library, declared in `include/ecdsa.h`:
+
+```text
+ ecdsa_ctx *ctx = ecdsa_ctx_new("P-256");
+ uint8_t priv[32], pub[65], sig[64];
+ ecdsa_keygen(ctx, priv, sizeof priv, pub, sizeof pub);
+ ecdsa_sign(ctx, priv, sizeof priv, msg, msg_len, sig, sizeof sig);
+ if (ecdsa_verify(ctx, pub, sizeof pub, msg, msg_len, sig, sizeof sig) == ECDSA_OK) { ... }
+ ecdsa_ctx_free(ctx);
+```
+
+A context is an opaque pointer holding the parameters of a named curve. Every
+function returns a status code (`ECDSA_OK` or a negative error) and writes its
+outputs in buffers of the exact size given by `ecdsa_private_key_size`,
+`ecdsa_public_key_size` and `ecdsa_signature_size`:
+
+- private keys are big endian integers on the byte size of the order
+- public keys are SEC1 uncompressed on output, compressed or not on input
+- signatures are `r || s`, each on the byte size of the order, computed over
+  the SHA-256 digest of the message with a RFC 6979 nonce
+
+No panic crosses the ABI: it is reported as `ECDSA_ERR_INTERNAL`.
+*/
+
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+use rust_ecdsa::ecdsa::{ECDSAErrors, ECDSA};
+use rust_ecdsa::signature::Signature;
+use sha2::{Digest, Sha256};
+
+pub const ECDSA_OK: c_int = 0;
+/// The signature does not match the message and public key
+pub const ECDSA_ERR_VERIFY_FAILED: c_int = -1;
+pub const ECDSA_ERR_NULL_POINTER: c_int = -2;
+pub const ECDSA_ERR_BAD_LENGTH: c_int = -3;
+pub const ECDSA_ERR_INVALID_KEY: c_int = -4;
+pub const ECDSA_ERR_INVALID_SIGNATURE: c_int = -5;
+pub const ECDSA_ERR_INTERNAL: c_int = -6;
+
+///
+/// The opaque `ecdsa_ctx` of the C API.
+///
+pub struct EcdsaContext {
+    params: &'static CurveParams,
+    ecdsa: ECDSA,
+}
+
+impl EcdsaContext {
+    fn scalar_size(&self) -> usize {
+        self.params.n.bits().div_ceil(8) as usize
+    }
+
+    fn public_key_size(&self) -> usize {
+        1 + 2 * self.params.curve.coordinate_size()
+    }
+}
+
+///
+/// Creates a context for a named curve (`secp256k1`, `P-256`, `P-384`...),
+/// or returns `NULL` for an unknown name.
+///
+/// # Safety
+///
+/// `curve_name` must be `NULL` or a NUL terminated string.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_ctx_new(curve_name: *const c_char) -> *mut EcdsaContext {
+    if curve_name.is_null() {
+        return std::ptr::null_mut();
+    }
+    let name = match CStr::from_ptr(curve_name).to_str() {
+        Ok(name) => name,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    match curves::by_name(name) {
+        Some(params) => Box::into_raw(Box::new(EcdsaContext { params, ecdsa: ECDSA::from(params.clone()) })),
+        None => std::ptr::null_mut(),
+    }
+}
+
+///
+/// Releases a context. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `ctx` must be `NULL` or a context from `ecdsa_ctx_new` not freed yet.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_ctx_free(ctx: *mut EcdsaContext) {
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx));
+    }
+}
+
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_private_key_size(ctx: *const EcdsaContext) -> usize {
+    ctx.as_ref().map_or(0, EcdsaContext::scalar_size)
+}
+
+///
+/// Size of an uncompressed public key.
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_public_key_size(ctx: *const EcdsaContext) -> usize {
+    ctx.as_ref().map_or(0, EcdsaContext::public_key_size)
+}
+
+///
+/// # Safety
+///
+/// `ctx` must be a live context.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_signature_size(ctx: *const EcdsaContext) -> usize {
+    ctx.as_ref().map_or(0, |ctx| 2 * ctx.scalar_size())
+}
+
+///
+/// Generates a key pair with the randomness of the operating system.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and the buffers valid for writes of their
+/// lengths.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_keygen(
+    ctx: *const EcdsaContext,
+    priv_key_out: *mut u8,
+    priv_key_len: usize,
+    pub_key_out: *mut u8,
+    pub_key_len: usize,
+) -> c_int {
+    guard(|| {
+        let ctx = context(ctx)?;
+        let priv_key_out = output(priv_key_out, priv_key_len, ctx.scalar_size())?;
+        let pub_key_out = output(pub_key_out, pub_key_len, ctx.public_key_size())?;
+
+        let (priv_key, pub_key) = ctx.ecdsa.generate_key_pair().map_err(|_| ECDSA_ERR_INTERNAL)?;
+        priv_key_out.copy_from_slice(&encode_scalar(ctx, &priv_key)?);
+        pub_key_out.copy_from_slice(
+            &pub_key.to_sec1_uncompressed(&ctx.params.curve).map_err(|_| ECDSA_ERR_INTERNAL)?,
+        );
+        Ok(ECDSA_OK)
+    })
+}
+
+///
+/// Writes the uncompressed public key of a private key.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and the buffers valid for their lengths.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_public_key(
+    ctx: *const EcdsaContext,
+    priv_key: *const u8,
+    priv_key_len: usize,
+    pub_key_out: *mut u8,
+    pub_key_len: usize,
+) -> c_int {
+    guard(|| {
+        let ctx = context(ctx)?;
+        let priv_key = decode_private_key(ctx, input(priv_key, priv_key_len)?)?;
+        let pub_key_out = output(pub_key_out, pub_key_len, ctx.public_key_size())?;
+
+        let pub_key = ctx.ecdsa.generate_pub_key(&priv_key).map_err(|_| ECDSA_ERR_INTERNAL)?;
+        pub_key_out.copy_from_slice(
+            &pub_key.to_sec1_uncompressed(&ctx.params.curve).map_err(|_| ECDSA_ERR_INTERNAL)?,
+        );
+        Ok(ECDSA_OK)
+    })
+}
+
+///
+/// Signs the SHA-256 digest of a message.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and the buffers valid for their lengths.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_sign(
+    ctx: *const EcdsaContext,
+    priv_key: *const u8,
+    priv_key_len: usize,
+    message: *const u8,
+    message_len: usize,
+    signature_out: *mut u8,
+    signature_len: usize,
+) -> c_int {
+    guard(|| {
+        let ctx = context(ctx)?;
+        let priv_key = decode_private_key(ctx, input(priv_key, priv_key_len)?)?;
+        let message = input(message, message_len)?;
+        let signature_out = output(signature_out, signature_len, 2 * ctx.scalar_size())?;
+
+        let signature = ctx.ecdsa.sign_prehash(&Sha256::digest(message), &priv_key).map_err(to_status)?;
+
+        let size = ctx.scalar_size();
+        signature_out[..size].copy_from_slice(&encode_scalar(ctx, &signature.r)?);
+        signature_out[size..].copy_from_slice(&encode_scalar(ctx, &signature.s)?);
+        Ok(ECDSA_OK)
+    })
+}
+
+///
+/// Returns `ECDSA_OK` for a valid signature, `ECDSA_ERR_VERIFY_FAILED` for a
+/// wrong one.
+///
+/// # Safety
+///
+/// `ctx` must be a live context and the buffers valid for their lengths.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ecdsa_verify(
+    ctx: *const EcdsaContext,
+    pub_key: *const u8,
+    pub_key_len: usize,
+    message: *const u8,
+    message_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+) -> c_int {
+    guard(|| {
+        let ctx = context(ctx)?;
+        let pub_key = decode_public_key(ctx, input(pub_key, pub_key_len)?)?;
+        let message = input(message, message_len)?;
+        let signature = input(signature, signature_len)?;
+
+        let size = ctx.scalar_size();
+        if signature.len() != 2 * size {
+            return Err(ECDSA_ERR_INVALID_SIGNATURE);
+        }
+        let q_order = &ctx.ecdsa.q_order;
+        let r = BigUint::from_bytes_be(&signature[..size]);
+        let s = BigUint::from_bytes_be(&signature[size..]);
+        if r == BigUint::from(0u32) || s == BigUint::from(0u32) || r >= *q_order || s >= *q_order {
+            return Err(ECDSA_ERR_VERIFY_FAILED);
+        }
+
+        match ctx.ecdsa.verify_prehash(&Sha256::digest(message), &pub_key, &Signature::new(r, s)) {
+            Ok(true) => Ok(ECDSA_OK),
+            Ok(false) => Err(ECDSA_ERR_VERIFY_FAILED),
+            Err(error) => Err(to_status(error)),
+        }
+    })
+}
+
+fn guard<F: FnOnce() -> Result<c_int, c_int>>(f: F) -> c_int {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(status)) | Ok(Err(status)) => status,
+        Err(_) => ECDSA_ERR_INTERNAL,
+    }
+}
+
+fn to_status(error: ECDSAErrors) -> c_int {
+    match error {
+        ECDSAErrors::BadArgument(_) => ECDSA_ERR_INVALID_KEY,
+        ECDSAErrors::OperationFailure(_) => ECDSA_ERR_INTERNAL,
+    }
+}
+
+unsafe fn context<'a>(ctx: *const EcdsaContext) -> Result<&'a EcdsaContext, c_int> {
+    ctx.as_ref().ok_or(ECDSA_ERR_NULL_POINTER)
+}
+
+// a NULL pointer is accepted for an empty input, e.g. an empty message
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], c_int> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(ECDSA_ERR_NULL_POINTER),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+unsafe fn output<'a>(data: *mut u8, len: usize, expected: usize) -> Result<&'a mut [u8], c_int> {
+    if data.is_null() {
+        return Err(ECDSA_ERR_NULL_POINTER);
+    }
+    if len != expected {
+        return Err(ECDSA_ERR_BAD_LENGTH);
+    }
+    Ok(slice::from_raw_parts_mut(data, len))
+}
+
+fn encode_scalar(ctx: &EcdsaContext, value: &BigUint) -> Result<Vec<u8>, c_int> {
+    to_fixed_bytes(value, ctx.scalar_size()).map_err(|_| ECDSA_ERR_INTERNAL)
+}
+
+fn decode_private_key(ctx: &EcdsaContext, bytes: &[u8]) -> Result<BigUint, c_int> {
+    if bytes.len() != ctx.scalar_size() {
+        return Err(ECDSA_ERR_BAD_LENGTH);
+    }
+    let priv_key = BigUint::from_bytes_be(bytes);
+    if priv_key == BigUint::from(0u32) || priv_key >= ctx.params.n {
+        return Err(ECDSA_ERR_INVALID_KEY);
+    }
+    Ok(priv_key)
+}
+
+fn decode_public_key(ctx: &EcdsaContext, bytes: &[u8]) -> Result<CurvePoint, c_int> {
+    let point = match bytes.first() {
+        Some(0x04) => CurvePoint::from_sec1_uncompressed(bytes, &ctx.params.curve),
+        _ => CurvePoint::from_sec1_compressed(bytes, &ctx.params.curve),
+    };
+    point.map_err(|_| ECDSA_ERR_INVALID_KEY)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ptr;
+
+    const HEADER: &str = include_str!("../include/ecdsa.h");
+
+    #[test]
+    fn test_keygen_sign_verify() {
+        for curve in [c"secp256k1", c"P-256", c"P-521"] {
+            unsafe {
+                let ctx = ecdsa_ctx_new(curve.as_ptr());
+                assert!(!ctx.is_null());
+
+                let mut priv_key = vec![0u8; ecdsa_private_key_size(ctx)];
+                let mut pub_key = vec![0u8; ecdsa_public_key_size(ctx)];
+                let mut signature = vec![0u8; ecdsa_signature_size(ctx)];
+                let message = b"hello";
+
+                let status = ecdsa_keygen(ctx, priv_key.as_mut_ptr(), priv_key.len(), pub_key.as_mut_ptr(), pub_key.len());
+                assert_eq!(status, ECDSA_OK);
+                let mut derived = vec![0u8; pub_key.len()];
+                let status = ecdsa_public_key(ctx, priv_key.as_ptr(), priv_key.len(), derived.as_mut_ptr(), derived.len());
+                assert_eq!((status, &derived), (ECDSA_OK, &pub_key));
+
+                let status = ecdsa_sign(
+                    ctx,
+                    priv_key.as_ptr(),
+                    priv_key.len(),
+                    message.as_ptr(),
+                    message.len(),
+                    signature.as_mut_ptr(),
+                    signature.len(),
+                );
+                assert_eq!(status, ECDSA_OK);
+
+                let verify = |message: &[u8], signature: &[u8]| {
+                    ecdsa_verify(ctx, pub_key.as_ptr(), pub_key.len(), message.as_ptr(), message.len(), signature.as_ptr(), signature.len())
+                };
+                assert_eq!(verify(message, &signature), ECDSA_OK);
+                assert_eq!(verify(b"hellp", &signature), ECDSA_ERR_VERIFY_FAILED);
+                assert_eq!(verify(message, &signature[1..]), ECDSA_ERR_INVALID_SIGNATURE);
+                assert_eq!(verify(message, &vec![0u8; signature.len()]), ECDSA_ERR_VERIFY_FAILED);
+
+                ecdsa_ctx_free(ctx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_signature() {
+        // RFC 6979 A.2.5, P-256 with SHA-256, message "sample"
+        let priv_key = [
+            0xc9, 0xaf, 0xa9, 0xd8, 0x45, 0xba, 0x75, 0x16, 0x6b, 0x5c, 0x21, 0x57, 0x67, 0xb1, 0xd6, 0x93,
+            0x4e, 0x50, 0xc3, 0xdb, 0x36, 0xe8, 0x9b, 0x12, 0x7b, 0x8a, 0x62, 0x2b, 0x12, 0x0f, 0x67, 0x21,
+        ];
+        let mut signature = [0u8; 64];
+        unsafe {
+            let ctx = ecdsa_ctx_new(c"prime256v1".as_ptr());
+            let status = ecdsa_sign(ctx, priv_key.as_ptr(), 32, b"sample".as_ptr(), 6, signature.as_mut_ptr(), 64);
+            assert_eq!(status, ECDSA_OK);
+            ecdsa_ctx_free(ctx);
+        }
+        assert_eq!(signature[..4], [0xef, 0xd4, 0x8b, 0x2a]);
+        assert_eq!(signature[32..36], [0xf7, 0xcb, 0x1c, 0x94]);
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            assert!(ecdsa_ctx_new(ptr::null()).is_null());
+            assert!(ecdsa_ctx_new(c"P-257".as_ptr()).is_null());
+            ecdsa_ctx_free(ptr::null_mut());
+            assert_eq!(ecdsa_signature_size(ptr::null()), 0);
+
+            let ctx = ecdsa_ctx_new(c"P-256".as_ptr());
+            let mut out = [0u8; 65];
+            let zero = [0u8; 32];
+            assert_eq!(ecdsa_keygen(ptr::null(), out.as_mut_ptr(), 32, out.as_mut_ptr(), 65), ECDSA_ERR_NULL_POINTER);
+            assert_eq!(ecdsa_keygen(ctx, ptr::null_mut(), 32, out.as_mut_ptr(), 65), ECDSA_ERR_NULL_POINTER);
+            assert_eq!(ecdsa_public_key(ctx, zero.as_ptr(), 31, out.as_mut_ptr(), 65), ECDSA_ERR_BAD_LENGTH);
+            assert_eq!(ecdsa_public_key(ctx, zero.as_ptr(), 32, out.as_mut_ptr(), 65), ECDSA_ERR_INVALID_KEY);
+            assert_eq!(ecdsa_public_key(ctx, [1u8; 32].as_ptr(), 32, out.as_mut_ptr(), 64), ECDSA_ERR_BAD_LENGTH);
+            assert_eq!(ecdsa_sign(ctx, [1u8; 32].as_ptr(), 32, ptr::null(), 1, out.as_mut_ptr(), 64), ECDSA_ERR_NULL_POINTER);
+            // empty message given as NULL
+            assert_eq!(ecdsa_sign(ctx, [1u8; 32].as_ptr(), 32, ptr::null(), 0, out.as_mut_ptr(), 64), ECDSA_OK);
+            assert_eq!(ecdsa_verify(ctx, [5u8; 33].as_ptr(), 33, ptr::null(), 0, out.as_ptr(), 64), ECDSA_ERR_INVALID_KEY);
+            ecdsa_ctx_free(ctx);
+        }
+    }
+
+    #[test]
+    fn test_header_declares_exports() {
+        for symbol in [
+            "ecdsa_ctx *ecdsa_ctx_new(",
+            "void ecdsa_ctx_free(",
+            "size_t ecdsa_private_key_size(",
+            "size_t ecdsa_public_key_size(",
+            "size_t ecdsa_signature_size(",
+            "int ecdsa_keygen(",
+            "int ecdsa_public_key(",
+            "int ecdsa_sign(",
+            "int ecdsa_verify(",
+        ] {
+            assert!(HEADER.contains(symbol), "{}", symbol);
+        }
+
+        for (name, value) in [
+            ("ECDSA_OK", ECDSA_OK),
+            ("ECDSA_ERR_VERIFY_FAILED", ECDSA_ERR_VERIFY_FAILED),
+            ("ECDSA_ERR_NULL_POINTER", ECDSA_ERR_NULL_POINTER),
+            ("ECDSA_ERR_BAD_LENGTH", ECDSA_ERR_BAD_LENGTH),
+            ("ECDSA_ERR_INVALID_KEY", ECDSA_ERR_INVALID_KEY),
+            ("ECDSA_ERR_INVALID_SIGNATURE", ECDSA_ERR_INVALID_SIGNATURE),
+            ("ECDSA_ERR_INTERNAL", ECDSA_ERR_INTERNAL),
+        ] {
+            assert!(HEADER.contains(&format!("#define {} ({})", name, value)), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,467 @@
+/*!
+Adaptor signatures (one-time verifiable encrypted signatures).
+
+A pre-signature is made for an adaptor point `T = t A`: anyone can check that
+it is valid for `T`, only the holder of `t` can `adapt` it into a valid
+signature, and once that signature is published the pre-signature holder can
+`extract_secret` `t` from it. This is what makes atomic swaps and payment
+channels work: publishing a signature reveals the secret unlocking another one.
+*/
+
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::rfc6979;
+use crate::schnorr::{Schnorr, SchnorrSignature};
+use crate::signature::Signature;
+
+///
+/// A Schnorr pre-signature `(R, s')` for the adaptor point `T`:
+///
+/// - `R = k A + T`
+/// - `s' = k + e d mod q` where `e = H(R || B || message)`
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct SchnorrPreSignature {
+    pub r_point: CurvePoint,
+    pub s: BigUint,
+}
+
+///
+/// An ECDSA pre-signature for the adaptor point `T`:
+///
+/// - `R = k T`, `r = x` component of `R` (mod q)
+/// - `R_a = k A`
+/// - `s' = (hash + d r) k^(-1) mod q`
+/// - a proof that `R` and `R_a` share the same `k`
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct EcdsaPreSignature {
+    pub r_point: CurvePoint,
+    pub r_a_point: CurvePoint,
+    pub s: BigUint,
+    pub proof: DleqProof,
+}
+
+///
+/// A Chaum-Pedersen proof `(c, z)` that `log_A(P) = log_T(Q)`:
+///
+/// ```text
+///  c = H(A, T, P, Q, z A - c P, z T - c Q)
+/// ```
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct DleqProof {
+    pub c: BigUint,
+    pub z: BigUint,
+}
+
+impl Schnorr {
+    ///
+    /// R = k A + T
+    /// e = H(R || B || message)
+    /// s' = k + e d mod q
+    ///
+    pub fn presign(
+        &self,
+        message: &[u8],
+        priv_key: &BigUint,
+        adaptor_point: &CurvePoint,
+        k_random: &BigUint,
+    ) -> Result<SchnorrPreSignature, ECDSAErrors> {
+        self.check_scalar(priv_key, "Private key")?;
+        self.check_scalar(k_random, "Random number `k`")?;
+        self.check_point(adaptor_point, "adaptor point")?;
+
+        let pub_key = self.generate_pub_key(priv_key)?;
+        let r_point = self.mul_add(k_random, &self.a_gen, &BigUint::from(1u32), adaptor_point)?;
+        let e = self.challenge(&r_point, &pub_key, message)?;
+
+        let s = (k_random + e * priv_key) % &self.q_order;
+
+        Ok(SchnorrPreSignature { r_point, s })
+    }
+
+    ///
+    /// Checks a pre-signature against the adaptor point:
+    ///
+    /// `s' A == R - T + e B`
+    ///
+    pub fn preverify(
+        &self,
+        message: &[u8],
+        pub_key: &CurvePoint,
+        adaptor_point: &CurvePoint,
+        presignature: &SchnorrPreSignature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.check_point(pub_key, "public key")?;
+        self.check_point(adaptor_point, "adaptor point")?;
+        if presignature.s >= self.q_order || presignature.r_point == CurvePoint::Identity {
+            return Ok(false);
+        }
+
+        let e = self.challenge(&presignature.r_point, pub_key, message)?;
+        let minus_t = self.mul(adaptor_point, &(&self.q_order - BigUint::from(1u32)))?;
+        let expected = self.mul_add(&BigUint::from(1u32), &presignature.r_point, &e, pub_key)?;
+        let expected = self
+            .elliptic_curve
+            .add(&expected, &minus_t)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing R - T + e B".into()))?;
+
+        Ok(self.mul(&self.a_gen, &presignature.s)? == expected)
+    }
+
+    ///
+    /// Completes a pre-signature with the adaptor secret: `s = s' + t mod q`.
+    ///
+    pub fn adapt(
+        &self,
+        presignature: &SchnorrPreSignature,
+        adaptor_secret: &BigUint,
+    ) -> Result<SchnorrSignature, ECDSAErrors> {
+        self.check_scalar(adaptor_secret, "Adaptor secret")?;
+
+        Ok(SchnorrSignature {
+            r_point: presignature.r_point.clone(),
+            s: (&presignature.s + adaptor_secret) % &self.q_order,
+        })
+    }
+
+    ///
+    /// Recovers the adaptor secret from a pre-signature and the signature
+    /// adapted from it: `t = s - s' mod q`.
+    ///
+    pub fn extract_secret(
+        &self,
+        presignature: &SchnorrPreSignature,
+        signature: &SchnorrSignature,
+    ) -> Result<BigUint, ECDSAErrors> {
+        if presignature.r_point != signature.r_point {
+            return Err(ECDSAErrors::BadArgument(
+                "The signature was not adapted from this pre-signature".into(),
+            ));
+        }
+
+        Ok((&signature.s + &self.q_order - &presignature.s % &self.q_order) % &self.q_order)
+    }
+}
+
+impl ECDSA {
+    ///
+    /// R = k T, r = x component of R (mod q)
+    /// R_a = k A
+    /// s' = (hash + d r) k^(-1) mod q
+    ///
+    pub fn presign(
+        &self,
+        hash: &BigUint,
+        priv_key: &BigUint,
+        adaptor_point: &CurvePoint,
+        k_random: &BigUint,
+    ) -> Result<EcdsaPreSignature, ECDSAErrors> {
+        if *hash >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Hash is bigger than the order of the EC group".into(),
+            ));
+        }
+        self.check_scalar(priv_key, "Private key")?;
+        self.check_scalar(k_random, "Random number `k`")?;
+        self.check_point(adaptor_point, "adaptor point")?;
+
+        let r_point = self.mul(adaptor_point, k_random)?;
+        let r_a_point = self.mul(&self.a_gen, k_random)?;
+        let r = self.x_mod_q(&r_point)?;
+
+        let k_inv = inverse_multiplicate_prime(k_random, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing k_inv".into()))?;
+        let s = (hash + r * priv_key) % &self.q_order * k_inv % &self.q_order;
+        if s == BigUint::from(0u32) {
+            return Err(ECDSAErrors::OperationFailure(
+                "Result s is zero, a different `k` has to be used".into(),
+            ));
+        }
+
+        let proof = self.prove_dleq(adaptor_point, &r_a_point, &r_point, k_random)?;
+
+        Ok(EcdsaPreSignature {
+            r_point,
+            r_a_point,
+            s,
+            proof,
+        })
+    }
+
+    ///
+    /// Checks a pre-signature against the adaptor point:
+    ///
+    /// - the proof that `log_A(R_a) = log_T(R)` is valid
+    /// - `s'^(-1) (hash A + r B) == R_a`
+    ///
+    pub fn preverify(
+        &self,
+        hash: &BigUint,
+        pub_key: &CurvePoint,
+        adaptor_point: &CurvePoint,
+        presignature: &EcdsaPreSignature,
+    ) -> Result<bool, ECDSAErrors> {
+        if *hash >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Hash value >= q (EC group order)".to_string(),
+            ));
+        }
+        self.check_point(pub_key, "public key")?;
+        self.check_point(adaptor_point, "adaptor point")?;
+
+        let EcdsaPreSignature { r_point, r_a_point, s, proof } = presignature;
+        if *s == BigUint::from(0u32) || *s >= self.q_order || *r_point == CurvePoint::Identity {
+            return Ok(false);
+        }
+        if !self.verify_dleq(adaptor_point, r_a_point, r_point, proof)? {
+            return Ok(false);
+        }
+
+        let r = self.x_mod_q(r_point)?;
+        let s_inv = inverse_multiplicate_prime(s, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing s_inv".into()))?;
+        let u1 = hash * &s_inv % &self.q_order;
+        let u2 = r * &s_inv % &self.q_order;
+
+        Ok(self.mul_add(&u1, &self.a_gen, &u2, pub_key)? == *r_a_point)
+    }
+
+    ///
+    /// Completes a pre-signature with the adaptor secret: `s = s' t^(-1) mod q`,
+    /// since `s^(-1) (hash A + r B) = t R_a = k T = R`.
+    ///
+    pub fn adapt(
+        &self,
+        presignature: &EcdsaPreSignature,
+        adaptor_secret: &BigUint,
+    ) -> Result<Signature, ECDSAErrors> {
+        self.check_scalar(adaptor_secret, "Adaptor secret")?;
+
+        let t_inv = inverse_multiplicate_prime(adaptor_secret, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing t_inv".into()))?;
+        let r = self.x_mod_q(&presignature.r_point)?;
+
+        Ok(Signature::new(r, &presignature.s * t_inv % &self.q_order))
+    }
+
+    ///
+    /// Recovers the adaptor secret from a pre-signature and the signature
+    /// adapted from it: `t = s' s^(-1) mod q`, negated if the signature was
+    /// normalized to its low-S form.
+    ///
+    pub fn extract_secret(
+        &self,
+        presignature: &EcdsaPreSignature,
+        signature: &Signature,
+        adaptor_point: &CurvePoint,
+    ) -> Result<BigUint, ECDSAErrors> {
+        if self.x_mod_q(&presignature.r_point)? != signature.r {
+            return Err(ECDSAErrors::BadArgument(
+                "The signature was not adapted from this pre-signature".into(),
+            ));
+        }
+        self.check_scalar(&signature.s, "s")?;
+
+        let s_inv = inverse_multiplicate_prime(&signature.s, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing s_inv".into()))?;
+        let t = &presignature.s * s_inv % &self.q_order;
+
+        if self.mul(&self.a_gen, &t)? == *adaptor_point {
+            return Ok(t);
+        }
+        let minus_t = &self.q_order - t;
+        if self.mul(&self.a_gen, &minus_t)? == *adaptor_point {
+            return Ok(minus_t);
+        }
+
+        Err(ECDSAErrors::BadArgument(
+            "The signature was not adapted from this pre-signature".into(),
+        ))
+    }
+
+    // the nonce w of the proof is derived from k like RFC 6979 does from d
+    fn prove_dleq(
+        &self,
+        t_point: &CurvePoint,
+        p_point: &CurvePoint,
+        q_point: &CurvePoint,
+        k: &BigUint,
+    ) -> Result<DleqProof, ECDSAErrors> {
+        let seed = Sha256::new()
+            .chain_update(self.encode(p_point)?)
+            .chain_update(self.encode(q_point)?)
+            .finalize();
+        let w = rfc6979::generate_k(&self.q_order, k, &seed);
+
+        let c = self.dleq_challenge(
+            &[t_point, p_point, q_point, &self.mul(&self.a_gen, &w)?, &self.mul(t_point, &w)?],
+        )?;
+        let z = (w + &c * k) % &self.q_order;
+
+        Ok(DleqProof { c, z })
+    }
+
+    fn verify_dleq(
+        &self,
+        t_point: &CurvePoint,
+        p_point: &CurvePoint,
+        q_point: &CurvePoint,
+        proof: &DleqProof,
+    ) -> Result<bool, ECDSAErrors> {
+        if proof.c >= self.q_order || proof.z >= self.q_order || *p_point == CurvePoint::Identity {
+            return Ok(false);
+        }
+
+        let minus_c = inverse_add(&proof.c, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing -c".into()))?;
+        let a1 = self.mul_add(&proof.z, &self.a_gen, &minus_c, p_point)?;
+        let a2 = self.mul_add(&proof.z, t_point, &minus_c, q_point)?;
+        if a1 == CurvePoint::Identity || a2 == CurvePoint::Identity {
+            return Ok(false);
+        }
+
+        Ok(self.dleq_challenge(&[t_point, p_point, q_point, &a1, &a2])? == proof.c)
+    }
+
+    // c = SHA256(A || points) mod q
+    fn dleq_challenge(&self, points: &[&CurvePoint]) -> Result<BigUint, ECDSAErrors> {
+        let mut hasher = Sha256::new().chain_update(self.encode(&self.a_gen)?);
+        for point in points {
+            hasher.update(self.encode(point)?);
+        }
+
+        Ok(BigUint::from_bytes_be(&hasher.finalize()) % &self.q_order)
+    }
+
+    fn x_mod_q(&self, point: &CurvePoint) -> Result<BigUint, ECDSAErrors> {
+        match point {
+            CurvePoint::Coordinate(x, _) if x % &self.q_order != BigUint::from(0u32) => {
+                Ok(x % &self.q_order)
+            }
+            _ => Err(ECDSAErrors::OperationFailure(
+                "Result r is zero, a different `k` has to be used".into(),
+            )),
+        }
+    }
+
+    fn mul(&self, point: &CurvePoint, scalar: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.mul_add(scalar, point, &BigUint::from(0u32), point)
+    }
+
+    fn mul_add(
+        &self,
+        u1: &BigUint,
+        p: &CurvePoint,
+        u2: &BigUint,
+        q: &CurvePoint,
+    ) -> Result<CurvePoint, ECDSAErrors> {
+        self.elliptic_curve
+            .double_scalar_mul(u1, p, u2, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error in scalar multiplication".into()))
+    }
+
+    fn encode(&self, point: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+        point
+            .to_sec1_compressed(&self.elliptic_curve)
+            .map_err(|_| ECDSAErrors::BadArgument("The identity can not be encoded".into()))
+    }
+
+    fn check_scalar(&self, scalar: &BigUint, name: &str) -> Result<(), ECDSAErrors> {
+        if *scalar == BigUint::from(0u32) || *scalar >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "{} must be in [1, q - 1]",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_point(&self, point: &CurvePoint, name: &str) -> Result<(), ECDSAErrors> {
+        if *point == CurvePoint::Identity || !self.elliptic_curve.is_on_curve(point) {
+            return Err(ECDSAErrors::BadArgument(format!("Invalid {}", name)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_schnorr_adaptor() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let priv_key = BigUint::from(123456789u32);
+        let pub_key = schnorr.generate_pub_key(&priv_key).unwrap();
+        let adaptor_secret = BigUint::from(987654321u32);
+        let adaptor_point = schnorr.generate_pub_key(&adaptor_secret).unwrap();
+        let message = b"Bob -> 1 BTC -> Alice";
+
+        let presignature = schnorr
+            .presign(message, &priv_key, &adaptor_point, &BigUint::from(42u32))
+            .unwrap();
+        assert!(schnorr.preverify(message, &pub_key, &adaptor_point, &presignature).unwrap());
+
+        // the pre-signature is not a valid signature
+        let not_adapted = SchnorrSignature {
+            r_point: presignature.r_point.clone(),
+            s: presignature.s.clone(),
+        };
+        assert!(!schnorr.verify(message, &pub_key, &not_adapted).unwrap());
+
+        let signature = schnorr.adapt(&presignature, &adaptor_secret).unwrap();
+        assert!(schnorr.verify(message, &pub_key, &signature).unwrap());
+        assert_eq!(schnorr.extract_secret(&presignature, &signature).unwrap(), adaptor_secret);
+
+        // wrong adaptor point or message
+        let other_point = schnorr.generate_pub_key(&BigUint::from(5u32)).unwrap();
+        assert!(!schnorr.preverify(message, &pub_key, &other_point, &presignature).unwrap());
+        assert!(!schnorr.preverify(b"other", &pub_key, &adaptor_point, &presignature).unwrap());
+    }
+
+    #[test]
+    fn test_ecdsa_adaptor() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let priv_key = BigUint::from(123456789u32);
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+        let adaptor_secret = BigUint::from(987654321u32);
+        let adaptor_point = ecdsa.generate_pub_key(&adaptor_secret).unwrap();
+        let hash = ecdsa.generate_hash_less_than("Bob -> 1 BTC -> Alice", &ecdsa.q_order);
+
+        let presignature = ecdsa
+            .presign(&hash, &priv_key, &adaptor_point, &BigUint::from(42u32))
+            .unwrap();
+        assert!(ecdsa.preverify(&hash, &pub_key, &adaptor_point, &presignature).unwrap());
+
+        let signature = ecdsa.adapt(&presignature, &adaptor_secret).unwrap();
+        assert!(ecdsa.verify(&hash, &pub_key, &signature).unwrap());
+        assert_eq!(
+            ecdsa.extract_secret(&presignature, &signature, &adaptor_point).unwrap(),
+            adaptor_secret
+        );
+
+        // extraction still works after low-S normalization
+        let normalized = signature.normalize_s(&ecdsa.q_order);
+        assert_eq!(
+            ecdsa.extract_secret(&presignature, &normalized, &adaptor_point).unwrap(),
+            adaptor_secret
+        );
+
+        // wrong adaptor point, hash or proof
+        let other_point = ecdsa.generate_pub_key(&BigUint::from(5u32)).unwrap();
+        assert!(!ecdsa.preverify(&hash, &pub_key, &other_point, &presignature).unwrap());
+        assert!(!ecdsa.preverify(&BigUint::from(1u32), &pub_key, &adaptor_point, &presignature).unwrap());
+
+        let mut tempered = presignature.clone();
+        tempered.proof.z += BigUint::from(1u32);
+        assert!(!ecdsa.preverify(&hash, &pub_key, &adaptor_point, &tempered).unwrap());
+
+        let other = ecdsa.sign(&hash, &priv_key, &BigUint::from(43u32)).unwrap();
+        assert!(ecdsa.extract_secret(&presignature, &other, &adaptor_point).is_err());
+    }
+}
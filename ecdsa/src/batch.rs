@@ -0,0 +1,230 @@
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::{RecoverableSignature, Signature};
+
+impl ECDSA {
+    ///
+    /// Verifies many `(hash, signature, pub_key)` entries at once and returns
+    /// the indices of the entries whose signature is not valid (empty if all
+    /// of them are).
+    ///
+    /// Each valid entry satisfies `u1_i A + u2_i B_i - R_i = I`, so with random
+    /// coefficients `a_i` the whole batch is checked with a single
+    /// multi-scalar multiplication:
+    ///
+    /// ```text
+    ///  (sum a_i u1_i) A + sum a_i u2_i B_i - sum a_i R_i = I
+    /// ```
+    ///
+    /// `R_i` can not be rebuilt from `r_i` alone (both `R` and `-R` share the
+    /// same `x`), which is why the signatures carry their recovery id. If the
+    /// batch equation does not hold, every entry is verified on its own to
+    /// identify the failing ones.
+    ///
+    pub fn verify_batch<R: CryptoRng + RngCore>(
+        &self,
+        entries: &[(BigUint, RecoverableSignature, CurvePoint)],
+        rng: &mut R,
+    ) -> Result<Vec<usize>, ECDSAErrors> {
+        if self.batch_equation_holds(entries, rng)? {
+            return Ok(Vec::new());
+        }
+
+        let is_valid = |(hash, signature, pub_key): &(BigUint, RecoverableSignature, CurvePoint)| {
+            matches!(self.verify(hash, pub_key, &signature.signature), Ok(true))
+        };
+        #[cfg(feature = "parallel")]
+        let failing = {
+            use rayon::prelude::*;
+
+            entries
+                .par_iter()
+                .enumerate()
+                .filter(|(_, entry)| !is_valid(entry))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let failing = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !is_valid(entry))
+            .map(|(i, _)| i)
+            .collect();
+
+        Ok(failing)
+    }
+
+    ///
+    /// Generates `count` key pairs, computing the public keys together with
+    /// `batch_scalar_mul` (one modular inversion for all of them).
+    ///
+    pub fn generate_key_pairs(&self, count: usize) -> Result<Vec<(BigUint, CurvePoint)>, ECDSAErrors> {
+        let terms: Vec<(BigUint, CurvePoint)> =
+            (0..count).map(|_| (self.generate_priv_key(), self.a_gen.clone())).collect();
+        let pub_keys = self
+            .elliptic_curve
+            .batch_scalar_mul(&terms)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing priv_key * a_gen".into()))?;
+
+        Ok(terms.into_iter().map(|(priv_key, _)| priv_key).zip(pub_keys).collect())
+    }
+
+    fn batch_equation_holds<R: CryptoRng + RngCore>(
+        &self,
+        entries: &[(BigUint, RecoverableSignature, CurvePoint)],
+        rng: &mut R,
+    ) -> Result<bool, ECDSAErrors> {
+        let one = BigUint::from(1u32);
+        // 128 bits coefficients are enough to make a forged batch pass with
+        // negligible probability
+        let coefficient_bound = (&one << 128u32).min(self.q_order.clone());
+        let coefficients: Vec<BigUint> = entries.iter().map(|_| rng.gen_biguint_range(&one, &coefficient_bound)).collect();
+
+        #[cfg(feature = "parallel")]
+        let entry_terms: Vec<Option<BatchTerms>> = {
+            use rayon::prelude::*;
+
+            entries
+                .par_iter()
+                .zip(coefficients)
+                .map(|(entry, a)| self.batch_terms(entry, a))
+                .collect::<Result<_, _>>()?
+        };
+        #[cfg(not(feature = "parallel"))]
+        let entry_terms: Vec<Option<BatchTerms>> = entries
+            .iter()
+            .zip(coefficients)
+            .map(|(entry, a)| self.batch_terms(entry, a))
+            .collect::<Result<_, _>>()?;
+
+        let mut u1_sum = BigUint::from(0u32);
+        let mut terms = Vec::with_capacity(2 * entries.len() + 1);
+        for entry_terms in entry_terms {
+            let Some((a_u1, pub_key_term, r_point_term)) = entry_terms else {
+                return Ok(false);
+            };
+            u1_sum = add(&u1_sum, &a_u1, &self.q_order)
+                .map_err(|_| ECDSAErrors::OperationFailure("Error adding a * u1".into()))?;
+            terms.push(pub_key_term);
+            terms.push(r_point_term);
+        }
+        terms.push((u1_sum, self.a_gen.clone()));
+
+        let sum = self
+            .elliptic_curve
+            .multi_scalar_mul(&terms)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing the batch sum".into()))?;
+
+        Ok(sum == CurvePoint::Identity)
+    }
+
+    // The terms of an entry in the batch equation, with its coefficient `a`:
+    // `a u1`, `(a u2, B)` and `(-a, R)`. None if the entry is malformed.
+    fn batch_terms(
+        &self,
+        (hash, signature, pub_key): &(BigUint, RecoverableSignature, CurvePoint),
+        a: BigUint,
+    ) -> Result<Option<BatchTerms>, ECDSAErrors> {
+        let zero = BigUint::from(0u32);
+        let q = &self.q_order;
+        let Signature { r, s } = &signature.signature;
+        if *hash >= *q || *r == zero || *r >= *q || *s == zero || *s >= *q || signature.recovery_id > 3 {
+            return Ok(None);
+        }
+        if *pub_key == CurvePoint::Identity || !self.elliptic_curve.is_on_curve(pub_key) {
+            return Ok(None);
+        }
+
+        let x = if signature.recovery_id & 2 != 0 { r + q } else { r.clone() };
+        let r_point = match self.lift_x(&x, signature.recovery_id & 1 == 1) {
+            Ok(r_point) => r_point,
+            Err(_) => return Ok(None),
+        };
+
+        let s_inv = inverse_multiplicate_prime(s, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing s_inv".into()))?;
+        let a_s_inv = multiplicate(&a, &s_inv, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error multiplying a and s_inv".into()))?;
+
+        // a * u1 = a * s^(-1) * hash, a * u2 = a * s^(-1) * r
+        let a_u1 = multiplicate(&a_s_inv, hash, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing a * u1".into()))?;
+        let a_u2 = multiplicate(&a_s_inv, r, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing a * u2".into()))?;
+        let minus_a = inverse_add(&a, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing -a".into()))?;
+
+        Ok(Some((a_u1, (a_u2, pub_key.clone()), (minus_a, r_point))))
+    }
+}
+
+// a u1, (a u2, B) and (-a, R) of an entry of the batch equation
+type BatchTerms = (BigUint, (BigUint, CurvePoint), (BigUint, CurvePoint));
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn sign_batch(ecdsa: &ECDSA, size: u32) -> Vec<(BigUint, RecoverableSignature, CurvePoint)> {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        (0..size)
+            .map(|i| {
+                let priv_key = rng.gen_biguint_range(&BigUint::from(1u32), &ecdsa.q_order);
+                let k_random = rng.gen_biguint_range(&BigUint::from(1u32), &ecdsa.q_order);
+                let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+                let hash = ecdsa.generate_hash_less_than(&format!("message {}", i), &ecdsa.q_order);
+                let signature = ecdsa.sign_recoverable(&hash, &priv_key, &k_random).unwrap();
+
+                (hash, signature, pub_key)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut entries = sign_batch(&ecdsa, 8);
+
+        assert_eq!(ecdsa.verify_batch(&entries, &mut rng).unwrap(), Vec::<usize>::new());
+        assert_eq!(ecdsa.verify_batch(&[], &mut rng).unwrap(), Vec::<usize>::new());
+
+        // tempered hash and swapped public key
+        entries[2].0 += BigUint::from(1u32);
+        entries[5].2 = entries[6].2.clone();
+        assert_eq!(ecdsa.verify_batch(&entries, &mut rng).unwrap(), vec![2, 5]);
+    }
+
+    #[test]
+    fn test_verify_batch_wrong_recovery_id() {
+        let ecdsa = ECDSA::from(ec_core::curves::p256());
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut entries = sign_batch(&ecdsa, 4);
+
+        // the batch equation fails but the signature itself is still valid
+        entries[1].1.recovery_id ^= 1;
+        assert_eq!(ecdsa.verify_batch(&entries, &mut rng).unwrap(), Vec::<usize>::new());
+
+        entries[3].1.signature.s = BigUint::from(0u32);
+        assert_eq!(ecdsa.verify_batch(&entries, &mut rng).unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_generate_key_pairs() {
+        let ecdsa = ECDSA::from(ec_core::curves::p384());
+        let key_pairs = ecdsa.generate_key_pairs(5).unwrap();
+
+        assert_eq!(key_pairs.len(), 5);
+        for (priv_key, pub_key) in &key_pairs {
+            assert_eq!(ecdsa.generate_pub_key(priv_key).unwrap(), *pub_key);
+        }
+        assert!(ecdsa.generate_key_pairs(0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use ec_core::CurvePoint;
+use hmac::{Hmac, Mac};
+use num_bigint::BigUint;
+use sha2::Sha512;
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Byte length of the chain code of an extended key.
+pub const CHAIN_CODE_SIZE: usize = 32;
+
+///
+/// A BIP-32 extended private key over secp256k1: the private key `k` and the
+/// chain code `c` used to derive its children.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExtendedPrivateKey {
+    pub priv_key: BigUint,
+    pub chain_code: [u8; CHAIN_CODE_SIZE],
+}
+
+impl ExtendedPrivateKey {
+    ///
+    /// Derives the master key of a wallet from a 16 to 64 bytes seed:
+    ///
+    /// ```text
+    ///  I = HMAC-SHA512(key = "Bitcoin seed", data = seed)
+    ///  k = I_L (the first 32 bytes), c = I_R (the last 32 bytes)
+    /// ```
+    ///
+    /// The seed is invalid if `k` is zero or not smaller than `q`.
+    ///
+    pub fn new_master(seed: &[u8]) -> Result<Self, ECDSAErrors> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(ECDSAErrors::BadArgument(
+                "Seed must be between 16 and 64 bytes long".into(),
+            ));
+        }
+
+        let mut mac = HmacSha512::new_from_slice(b"Bitcoin seed").expect("HMAC accepts keys of any size");
+        mac.update(seed);
+        let i = mac.finalize().into_bytes();
+
+        let priv_key = BigUint::from_bytes_be(&i[..32]);
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        if priv_key == BigUint::from(0u32) || priv_key >= ecdsa.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Seed gives an invalid master key".into(),
+            ));
+        }
+
+        let mut chain_code = [0u8; CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&i[32..]);
+
+        Ok(ExtendedPrivateKey {
+            priv_key,
+            chain_code,
+        })
+    }
+
+    pub fn pub_key(&self) -> Result<CurvePoint, ECDSAErrors> {
+        ECDSA::from(ec_core::curves::secp256k1()).generate_pub_key(&self.priv_key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_master() {
+        // BIP-32, test vector 1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedPrivateKey::new_master(&seed).unwrap();
+
+        assert_eq!(
+            master.priv_key,
+            BigUint::parse_bytes(
+                b"e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+                16,
+            )
+                .unwrap()
+        );
+        assert_eq!(
+            hex::encode(master.chain_code),
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508"
+        );
+        assert_eq!(
+            hex::encode(master.pub_key().unwrap().to_sec1_compressed(&ec_core::curves::secp256k1().curve).unwrap()),
+            "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"
+        );
+
+        assert!(ExtendedPrivateKey::new_master(&[0u8; 15]).is_err());
+        assert!(ExtendedPrivateKey::new_master(&[0u8; 65]).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+BIP-39 mnemonic sentences: entropy encoded as words from a list of 2048, with a
+checksum, and stretched into the seed of a BIP-32 wallet.
+
+```text
+ entropy: 128 to 256 bits, by steps of 32
+ checksum = first (entropy bits / 32) bits of SHA256(entropy)
+ each 11 bits of entropy || checksum index a word of the list
+ seed = PBKDF2-HMAC-SHA512(mnemonic, "mnemonic" || passphrase, 2048 rounds)
+```
+
+The mnemonic and the passphrase are NFKD normalized before the derivation.
+*/
+
+use num_bigint::BigUint;
+use pbkdf2::pbkdf2_hmac;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256, Sha512};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::bip32::ExtendedPrivateKey;
+use crate::ecdsa::ECDSAErrors;
+
+/// Byte length of the seed derived from a mnemonic.
+pub const SEED_SIZE: usize = 64;
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+const ENGLISH: &str = include_str!("wordlist/english.txt");
+
+///
+/// A valid mnemonic sentence of 12, 15, 18, 21 or 24 English words.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mnemonic {
+    words: Vec<&'static str>,
+}
+
+impl Mnemonic {
+    ///
+    /// Encodes 16, 20, 24, 28 or 32 bytes of entropy as a mnemonic.
+    ///
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, ECDSAErrors> {
+        if entropy.len() < 16 || entropy.len() > 32 || !entropy.len().is_multiple_of(4) {
+            return Err(ECDSAErrors::BadArgument(
+                "Entropy must be 16, 20, 24, 28 or 32 bytes long".into(),
+            ));
+        }
+
+        let checksum_bits = entropy.len() / 4;
+        let checksum = Sha256::digest(entropy)[0] >> (8 - checksum_bits);
+        let bits = (BigUint::from_bytes_be(entropy) << checksum_bits) | BigUint::from(checksum);
+
+        let word_count = (entropy.len() * 8 + checksum_bits) / 11;
+        let wordlist = wordlist();
+        let words = (0..word_count)
+            .rev()
+            .map(|i| {
+                let index = (&bits >> (11 * i)) & BigUint::from(0x7ffu32);
+                wordlist[usize::try_from(&index).expect("indices have 11 bits")]
+            })
+            .collect();
+
+        Ok(Mnemonic { words })
+    }
+
+    ///
+    /// Generates a new mnemonic of `word_count` words with the given
+    /// cryptographically secure rng.
+    ///
+    pub fn generate<R: CryptoRng + RngCore>(word_count: usize, rng: &mut R) -> Result<Self, ECDSAErrors> {
+        if ![12, 15, 18, 21, 24].contains(&word_count) {
+            return Err(ECDSAErrors::BadArgument(
+                "A mnemonic has 12, 15, 18, 21 or 24 words".into(),
+            ));
+        }
+
+        let mut entropy = vec![0u8; word_count * 4 / 3];
+        rng.fill_bytes(&mut entropy);
+        Mnemonic::from_entropy(&entropy)
+    }
+
+    ///
+    /// Parses a mnemonic sentence, checking its length, its words and its
+    /// checksum.
+    ///
+    pub fn parse(phrase: &str) -> Result<Self, ECDSAErrors> {
+        let phrase: String = phrase.nfkd().collect();
+        let wordlist = wordlist();
+
+        let indices = phrase
+            .split_whitespace()
+            .map(|word| {
+                wordlist
+                    .binary_search(&word)
+                    .map_err(|_| ECDSAErrors::BadArgument(format!("{} is not in the word list", word)))
+            })
+            .collect::<Result<Vec<usize>, ECDSAErrors>>()?;
+        if ![12, 15, 18, 21, 24].contains(&indices.len()) {
+            return Err(ECDSAErrors::BadArgument(
+                "A mnemonic has 12, 15, 18, 21 or 24 words".into(),
+            ));
+        }
+
+        let bits = indices
+            .iter()
+            .fold(BigUint::from(0u32), |acc, &index| (acc << 11) | BigUint::from(index));
+        let checksum_bits = indices.len() / 3;
+        let entropy = to_bytes(&(&bits >> checksum_bits), indices.len() * 4 / 3);
+
+        let mnemonic = Mnemonic::from_entropy(&entropy)?;
+        if mnemonic.words.len() != indices.len()
+            || mnemonic.words.iter().zip(&indices).any(|(word, &index)| *word != wordlist[index])
+        {
+            return Err(ECDSAErrors::BadArgument("Invalid mnemonic checksum".into()));
+        }
+
+        Ok(mnemonic)
+    }
+
+    ///
+    /// The entropy encoded by the mnemonic.
+    ///
+    pub fn entropy(&self) -> Vec<u8> {
+        let wordlist = wordlist();
+        let bits = self.words.iter().fold(BigUint::from(0u32), |acc, word| {
+            let index = wordlist.binary_search(word).expect("words come from the list");
+            (acc << 11) | BigUint::from(index)
+        });
+
+        to_bytes(&(bits >> (self.words.len() / 3)), self.words.len() * 4 / 3)
+    }
+
+    pub fn words(&self) -> &[&'static str] {
+        &self.words
+    }
+
+    pub fn phrase(&self) -> String {
+        self.words.join(" ")
+    }
+
+    ///
+    /// Derives the 64 bytes seed of the mnemonic protected by an optional
+    /// passphrase (`""` if none).
+    ///
+    pub fn to_seed(&self, passphrase: &str) -> [u8; SEED_SIZE] {
+        let password: String = self.phrase().nfkd().collect();
+        let salt: String = format!("mnemonic{}", passphrase).nfkd().collect();
+
+        let mut seed = [0u8; SEED_SIZE];
+        pbkdf2_hmac::<Sha512>(password.as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS, &mut seed);
+        seed
+    }
+
+    ///
+    /// Derives the BIP-32 master key of the wallet of the mnemonic.
+    ///
+    pub fn to_master_key(&self, passphrase: &str) -> Result<ExtendedPrivateKey, ECDSAErrors> {
+        ExtendedPrivateKey::new_master(&self.to_seed(passphrase))
+    }
+}
+
+fn wordlist() -> Vec<&'static str> {
+    ENGLISH.lines().collect()
+}
+
+fn to_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; len.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_mnemonic_vectors() {
+        // BIP-39 reference test vectors, passphrase "TREZOR"
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+                "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+            ),
+        ];
+
+        for (entropy, phrase, seed) in vectors {
+            let entropy = hex::decode(entropy).unwrap();
+            let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase);
+            assert_eq!(mnemonic.entropy(), entropy);
+            assert_eq!(Mnemonic::parse(phrase).unwrap(), mnemonic);
+            assert_eq!(hex::encode(mnemonic.to_seed("TREZOR")), seed);
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_to_master_key() {
+        let mnemonic = Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+            .unwrap();
+        let master = mnemonic.to_master_key("TREZOR").unwrap();
+
+        assert_eq!(master, ExtendedPrivateKey::new_master(&mnemonic.to_seed("TREZOR")).unwrap());
+        assert_ne!(master, mnemonic.to_master_key("").unwrap());
+    }
+
+    #[test]
+    fn test_generate_mnemonic() {
+        let mut rng = StdRng::seed_from_u64(8);
+        for word_count in [12, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::generate(word_count, &mut rng).unwrap();
+            assert_eq!(mnemonic.words().len(), word_count);
+            assert_eq!(Mnemonic::parse(&mnemonic.phrase()).unwrap(), mnemonic);
+        }
+
+        assert!(Mnemonic::generate(13, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_mnemonic() {
+        // bad checksum
+        assert!(Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"
+        )
+            .is_err());
+        // not in the word list
+        assert!(Mnemonic::parse(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon bitcoin"
+        )
+            .is_err());
+        // wrong length
+        assert!(Mnemonic::parse("abandon abandon abandon about").is_err());
+        assert!(Mnemonic::from_entropy(&[0u8; 17]).is_err());
+    }
+}
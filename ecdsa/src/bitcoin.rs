@@ -0,0 +1,195 @@
+use bech32::{hrp, segwit, Hrp};
+use ec_core::to_fixed_bytes;
+use num_bigint::BigUint;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::ECDSAErrors;
+use crate::keys::{PrivateKey, PublicKey};
+
+///
+/// The Bitcoin network keys and addresses are encoded for.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    fn wif_prefix(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x80,
+            Network::Testnet => 0xef,
+        }
+    }
+
+    fn p2pkh_prefix(&self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            Network::Testnet => 0x6f,
+        }
+    }
+
+    fn bech32_hrp(&self) -> Hrp {
+        match self {
+            Network::Mainnet => hrp::BC,
+            Network::Testnet => hrp::TB,
+        }
+    }
+}
+
+impl PrivateKey {
+    ///
+    /// Encodes the private key in the Wallet Import Format:
+    ///
+    /// `Base58Check(prefix || d (32 bytes) || 0x01 if compressed)`
+    ///
+    /// where `compressed` tells whether the wallet uses the compressed public
+    /// key, and the prefix is `0x80` on mainnet and `0xef` on testnet.
+    ///
+    pub fn to_wif(&self, network: Network, compressed: bool) -> String {
+        let mut payload = vec![network.wif_prefix()];
+        payload.extend_from_slice(&to_fixed_bytes(self.secret(), 32).expect("d < q fits in 32 bytes"));
+        if compressed {
+            payload.push(0x01);
+        }
+
+        bs58::encode(payload).with_check().into_string()
+    }
+
+    ///
+    /// Decodes a private key in the Wallet Import Format, returning the key,
+    /// its network and whether it is used with the compressed public key.
+    ///
+    pub fn from_wif(wif: &str) -> Result<(PrivateKey, Network, bool), ECDSAErrors> {
+        let payload = bs58::decode(wif)
+            .with_check(None)
+            .into_vec()
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid Base58Check string".into()))?;
+
+        let compressed = match payload.len() {
+            33 => false,
+            34 if payload[33] == 0x01 => true,
+            _ => return Err(ECDSAErrors::BadArgument("Invalid WIF length".into())),
+        };
+        let network = match payload[0] {
+            0x80 => Network::Mainnet,
+            0xef => Network::Testnet,
+            prefix => {
+                return Err(ECDSAErrors::BadArgument(format!(
+                    "Unknown WIF prefix {:#04x}",
+                    prefix
+                )))
+            }
+        };
+
+        let priv_key = PrivateKey::new(BigUint::from_bytes_be(&payload[1..33]))?;
+        Ok((priv_key, network, compressed))
+    }
+}
+
+impl PublicKey {
+    ///
+    /// `HASH160 = RIPEMD160(SHA256(public key))` of the SEC1 encoding of the
+    /// key, compressed or not.
+    ///
+    pub fn hash160(&self, compressed: bool) -> [u8; 20] {
+        let encoded = if compressed {
+            self.to_sec1_compressed()
+        } else {
+            self.to_sec1_uncompressed()
+        };
+
+        Ripemd160::digest(Sha256::digest(encoded)).into()
+    }
+
+    ///
+    /// The legacy pay-to-public-key-hash address:
+    ///
+    /// `Base58Check(prefix || HASH160)`
+    ///
+    /// where the prefix is `0x00` on mainnet (`1...`) and `0x6f` on testnet
+    /// (`m...` or `n...`).
+    ///
+    pub fn to_p2pkh_address(&self, network: Network, compressed: bool) -> String {
+        let mut payload = vec![network.p2pkh_prefix()];
+        payload.extend_from_slice(&self.hash160(compressed));
+
+        bs58::encode(payload).with_check().into_string()
+    }
+
+    ///
+    /// The segwit v0 pay-to-witness-public-key-hash address: the bech32
+    /// encoding of the `HASH160` of the compressed key, with the `bc` prefix
+    /// on mainnet and `tb` on testnet.
+    ///
+    pub fn to_p2wpkh_address(&self, network: Network) -> Result<String, ECDSAErrors> {
+        segwit::encode(network.bech32_hrp(), segwit::VERSION_0, &self.hash160(true))
+            .map_err(|_| ECDSAErrors::OperationFailure("Error encoding the bech32 address".into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wif() {
+        let priv_key = PrivateKey::new(
+            BigUint::parse_bytes(
+                b"0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D",
+                16,
+            )
+                .unwrap(),
+        )
+            .unwrap();
+
+        let wif = priv_key.to_wif(Network::Mainnet, false);
+        assert_eq!(wif, "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ");
+        assert_eq!(PrivateKey::from_wif(&wif).unwrap(), (priv_key.clone(), Network::Mainnet, false));
+
+        let wif = priv_key.to_wif(Network::Mainnet, true);
+        assert_eq!(wif, "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617");
+        assert_eq!(PrivateKey::from_wif(&wif).unwrap(), (priv_key.clone(), Network::Mainnet, true));
+
+        for compressed in [false, true] {
+            let wif = priv_key.to_wif(Network::Testnet, compressed);
+            assert_eq!(PrivateKey::from_wif(&wif).unwrap(), (priv_key.clone(), Network::Testnet, compressed));
+        }
+    }
+
+    #[test]
+    fn test_wif_invalid() {
+        // bad checksum
+        assert!(PrivateKey::from_wif("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTK").is_err());
+        // not base58
+        assert!(PrivateKey::from_wif("0OIl").is_err());
+        // unknown prefix
+        let payload = [vec![0x81], vec![1u8; 32]].concat();
+        assert!(PrivateKey::from_wif(&bs58::encode(payload).with_check().into_string()).is_err());
+        // zero key
+        let payload = [vec![0x80], vec![0u8; 32]].concat();
+        assert!(PrivateKey::from_wif(&bs58::encode(payload).with_check().into_string()).is_err());
+    }
+
+    #[test]
+    fn test_addresses() {
+        let pub_key = PublicKey::from_priv_key(&BigUint::from(1u32)).unwrap();
+
+        assert_eq!(hex::encode(pub_key.hash160(true)), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(pub_key.to_p2pkh_address(Network::Mainnet, true), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+        assert_eq!(pub_key.to_p2pkh_address(Network::Mainnet, false), "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        assert!(pub_key.to_p2pkh_address(Network::Testnet, true).starts_with(['m', 'n']));
+
+        // BIP-173 examples
+        assert_eq!(
+            pub_key.to_p2wpkh_address(Network::Mainnet).unwrap(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            pub_key.to_p2wpkh_address(Network::Testnet).unwrap(),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+/*!
+Blind Schnorr signatures: the user gets a regular Schnorr signature on a
+message the signer never sees, and the signer can not link the signature to
+the session that produced it.
+
+```text
+ signer                                user
+ k, R' = k A          -- R' -->
+                                       alpha, beta random
+                                       R = R' + alpha A + beta B
+                                       e = H(R || B || message)
+                      <-- e' --        e' = e + beta mod q
+ s' = k + e' d mod q  -- s' -->
+                                       s = s' + alpha mod q
+                                       signature = (R, s)
+```
+
+Each nonce `k` must answer a single challenge, which the session types below
+enforce by being consumed. Running many sessions concurrently is subject to
+the ROS attack, so signers should only run them sequentially.
+*/
+
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+
+use crate::ecdsa::ECDSAErrors;
+use crate::schnorr::{Schnorr, SchnorrSignature};
+
+///
+/// The signer side, holding the private key `d`.
+///
+pub struct BlindSigner<'a> {
+    schnorr: &'a Schnorr,
+    priv_key: BigUint,
+}
+
+///
+/// A signer session waiting for the blinded challenge `e'` answering its
+/// commitment `R' = k A`.
+///
+pub struct BlindSignerSession<'a> {
+    schnorr: &'a Schnorr,
+    priv_key: BigUint,
+    k: BigUint,
+}
+
+///
+/// The user side, holding the public key `B` of the signer.
+///
+pub struct BlindUser<'a> {
+    schnorr: &'a Schnorr,
+    pub_key: CurvePoint,
+}
+
+///
+/// A user session waiting for the blinded answer `s'`, holding the message,
+/// the blinded nonce `R` and the blinding factor `alpha`.
+///
+pub struct BlindUserSession<'a> {
+    schnorr: &'a Schnorr,
+    pub_key: CurvePoint,
+    message: Vec<u8>,
+    r_point: CurvePoint,
+    alpha: BigUint,
+}
+
+impl<'a> BlindSigner<'a> {
+    pub fn new(schnorr: &'a Schnorr, priv_key: &BigUint) -> Result<Self, ECDSAErrors> {
+        schnorr.check_scalar(priv_key, "Private key")?;
+
+        Ok(BlindSigner {
+            schnorr,
+            priv_key: priv_key.clone(),
+        })
+    }
+
+    ///
+    /// Starts a session: samples `k` and returns the commitment `R' = k A`.
+    ///
+    pub fn commit<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(BlindSignerSession<'a>, CurvePoint), ECDSAErrors> {
+        let k = rng.gen_biguint_range(&BigUint::from(1u32), &self.schnorr.q_order);
+        let commitment = self.schnorr.mul(&self.schnorr.a_gen, &k)?;
+
+        Ok((
+            BlindSignerSession {
+                schnorr: self.schnorr,
+                priv_key: self.priv_key.clone(),
+                k,
+            },
+            commitment,
+        ))
+    }
+}
+
+impl BlindSignerSession<'_> {
+    ///
+    /// Answers the blinded challenge: `s' = k + e' d mod q`.
+    ///
+    pub fn sign(self, blinded_challenge: &BigUint) -> Result<BigUint, ECDSAErrors> {
+        if *blinded_challenge >= self.schnorr.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Challenge is bigger than the order of the EC group".into(),
+            ));
+        }
+
+        Ok((self.k + blinded_challenge * self.priv_key) % &self.schnorr.q_order)
+    }
+}
+
+impl<'a> BlindUser<'a> {
+    pub fn new(schnorr: &'a Schnorr, pub_key: &CurvePoint) -> Result<Self, ECDSAErrors> {
+        schnorr.check_point(pub_key, "public key")?;
+
+        Ok(BlindUser {
+            schnorr,
+            pub_key: pub_key.clone(),
+        })
+    }
+
+    ///
+    /// Blinds the commitment of the signer for a message and returns the
+    /// blinded challenge to send back:
+    ///
+    /// ```text
+    ///  R = R' + alpha A + beta B
+    ///  e' = H(R || B || message) + beta mod q
+    /// ```
+    ///
+    pub fn blind<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        commitment: &CurvePoint,
+        rng: &mut R,
+    ) -> Result<(BlindUserSession<'a>, BigUint), ECDSAErrors> {
+        self.schnorr.check_point(commitment, "commitment")?;
+
+        let q = &self.schnorr.q_order;
+        let alpha = rng.gen_biguint_range(&BigUint::from(1u32), q);
+        let beta = rng.gen_biguint_range(&BigUint::from(1u32), q);
+
+        let blinding = self.schnorr.mul_add(&alpha, &self.schnorr.a_gen, &beta, &self.pub_key)?;
+        let r_point = self
+            .schnorr
+            .elliptic_curve
+            .add(commitment, &blinding)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing R' + alpha A + beta B".into()))?;
+        if r_point == CurvePoint::Identity {
+            return Err(ECDSAErrors::OperationFailure(
+                "Blinded nonce is the identity, blind again".into(),
+            ));
+        }
+
+        let e = self.schnorr.challenge(&r_point, &self.pub_key, message)?;
+        let blinded_challenge = (e + beta) % q;
+
+        Ok((
+            BlindUserSession {
+                schnorr: self.schnorr,
+                pub_key: self.pub_key.clone(),
+                message: message.to_vec(),
+                r_point,
+                alpha,
+            },
+            blinded_challenge,
+        ))
+    }
+}
+
+impl BlindUserSession<'_> {
+    ///
+    /// Unblinds the answer of the signer, `s = s' + alpha mod q`, and checks
+    /// that `(R, s)` is a valid signature of the message.
+    ///
+    pub fn unblind(self, blinded_s: &BigUint) -> Result<SchnorrSignature, ECDSAErrors> {
+        if *blinded_s >= self.schnorr.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "s' is bigger than the order of the EC group".into(),
+            ));
+        }
+
+        let signature = SchnorrSignature {
+            r_point: self.r_point,
+            s: (blinded_s + self.alpha) % &self.schnorr.q_order,
+        };
+        if !self.schnorr.verify(&self.message, &self.pub_key, &signature)? {
+            return Err(ECDSAErrors::OperationFailure(
+                "The signer answered with an invalid s'".into(),
+            ));
+        }
+
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_blind_sign() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(3);
+        let priv_key = BigUint::from(123456789u32);
+        let pub_key = schnorr.generate_pub_key(&priv_key).unwrap();
+        let message = b"Bob -> 1 BTC -> Alice";
+
+        let signer = BlindSigner::new(&schnorr, &priv_key).unwrap();
+        let user = BlindUser::new(&schnorr, &pub_key).unwrap();
+
+        let (signer_session, commitment) = signer.commit(&mut rng).unwrap();
+        let (user_session, blinded_challenge) = user.blind(message, &commitment, &mut rng).unwrap();
+        let blinded_s = signer_session.sign(&blinded_challenge).unwrap();
+        let signature = user_session.unblind(&blinded_s).unwrap();
+
+        assert!(schnorr.verify(message, &pub_key, &signature).unwrap());
+
+        // the signer never saw R nor s
+        assert_ne!(signature.r_point, commitment);
+        assert_ne!(signature.s, blinded_s);
+    }
+
+    #[test]
+    fn test_blind_sign_wrong_answer() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(4);
+        let priv_key = BigUint::from(123456789u32);
+        let pub_key = schnorr.generate_pub_key(&priv_key).unwrap();
+
+        let signer = BlindSigner::new(&schnorr, &BigUint::from(7u32)).unwrap();
+        let user = BlindUser::new(&schnorr, &pub_key).unwrap();
+
+        // signed with another key
+        let (signer_session, commitment) = signer.commit(&mut rng).unwrap();
+        let (user_session, blinded_challenge) = user.blind(b"msg", &commitment, &mut rng).unwrap();
+        let blinded_s = signer_session.sign(&blinded_challenge).unwrap();
+        assert!(user_session.unblind(&blinded_s).is_err());
+
+        assert!(BlindSigner::new(&schnorr, &BigUint::from(0u32)).is_err());
+        assert!(BlindUser::new(&schnorr, &CurvePoint::Identity).is_err());
+        assert!(user.blind(b"msg", &CurvePoint::Identity, &mut rng).is_err());
+    }
+}
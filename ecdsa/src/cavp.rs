@@ -0,0 +1,362 @@
+/*!
+Runner for the response files of the NIST Cryptographic Algorithm Validation
+Program (https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program),
+the official ECDSA and ECC CDH test vectors:
+
+```text
+ [P-256,SHA-256]            SigGen.txt, SigVer.rsp
+ Msg = 5905238877c7...      (Msg, d, Qx, Qy, k, R, S / Msg, Qx, Qy, R, S, Result)
+
+ [P-256]                    KAS_ECC_CDH_PrimitiveTest.txt
+ COUNT = 0                  (QCAVSx, QCAVSy, dIUT, QIUTx, QIUTy, ZIUT)
+```
+
+The results are reported as for Wycheproof, see `testvectors`. The sections
+of curves missing from `ec_core::curves` (P-224, K-xxx, B-xxx...) and of
+hash functions other than SHA-2 are skipped.
+*/
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+
+use crate::ecdh::{self, EcdhPrimitive};
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::Signature;
+use crate::testvectors::{sha2_digest, Expected, HashFn, Outcome, TestReport, TestResult};
+
+///
+/// Runs a `SigGen` response file. When the private key and the nonce are
+/// given, the signatures are recomputed from them, otherwise they are only
+/// verified.
+///
+pub fn run_sig_gen(text: &str) -> Result<TestReport, ECDSAErrors> {
+    run_records("ECDSA SigGen", text, |record| {
+        let Some((ecdsa, hash)) = signature_section(record)? else {
+            return Ok(None);
+        };
+        let hash = ecdsa.digest_to_int(&hash(&record.hex("Msg")?))?;
+        let pub_key = record.point(&ecdsa.elliptic_curve, "Qx", "Qy")?;
+        let signature = Signature::new(record.int("R")?, record.int("S")?);
+
+        let (Some(d), Some(k)) = (record.get("d"), record.get("k")) else {
+            let accepted = match pub_key.map(|pub_key| ecdsa.verify(&hash, &pub_key, &signature)) {
+                None => Err("Q is not a point of the curve".into()),
+                Some(Ok(true)) => Ok(()),
+                Some(Ok(false)) => Err("signature does not verify".into()),
+                Some(Err(e)) => Err(format!("{:?}", e)),
+            };
+            return Ok(Some((Expected::Valid, accepted)));
+        };
+        let (d, k) = (parse_int("d", d)?, parse_int("k", k)?);
+        let accepted = if pub_key != ecdsa.generate_pub_key(&d).ok() {
+            Err("Q is not the public key of d".into())
+        } else {
+            match ecdsa.sign(&hash, &d, &k) {
+                Ok(computed) if computed == signature => Ok(()),
+                Ok(_) => Err("signature mismatch".into()),
+                Err(e) => Err(format!("{:?}", e)),
+            }
+        };
+        Ok(Some((Expected::Valid, accepted)))
+    })
+}
+
+///
+/// Runs a `SigVer` response file, whose records expect the signature to be
+/// accepted (`Result = P`) or rejected (`Result = F`).
+///
+pub fn run_sig_ver(text: &str) -> Result<TestReport, ECDSAErrors> {
+    run_records("ECDSA SigVer", text, |record| {
+        let Some((ecdsa, hash)) = signature_section(record)? else {
+            return Ok(None);
+        };
+        let expected = record.result()?.unwrap_or(Expected::Valid);
+        let hash = ecdsa.digest_to_int(&hash(&record.hex("Msg")?))?;
+        let signature = Signature::new(record.int("R")?, record.int("S")?);
+
+        let accepted = match record.point(&ecdsa.elliptic_curve, "Qx", "Qy")? {
+            None => Err("Q is not a point of the curve".into()),
+            Some(pub_key) => match ecdsa.verify(&hash, &pub_key, &signature) {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("signature does not verify".into()),
+                Err(e) => Err(format!("{:?}", e)),
+            },
+        };
+        Ok(Some((expected, accepted)))
+    })
+}
+
+///
+/// Runs a `KAS_ECC_CDH_PrimitiveTest` response file: `ZIUT` has to be the
+/// shared secret of `dIUT` and the `QCAVS` point, and `QIUT` the public key
+/// of `dIUT`.
+///
+pub fn run_kas(text: &str) -> Result<TestReport, ECDSAErrors> {
+    run_records("ECC CDH", text, |record| {
+        let Some(params) = section_curve(record.section) else {
+            return Ok(None);
+        };
+        let ecdsa = ECDSA::from(params.clone());
+        let expected = record.result()?.unwrap_or(Expected::Valid);
+        let d = record.int("dIUT")?;
+        let shared = record.hex("ZIUT")?;
+
+        let accepted = match (
+            record.point(&params.curve, "QCAVSx", "QCAVSy")?,
+            record.point(&params.curve, "QIUTx", "QIUTy")?,
+        ) {
+            (None, _) => Err("QCAVS is not a point of the curve".into()),
+            (_, pub_key) if pub_key != ecdsa.generate_pub_key(&d).ok() => Err("QIUT is not the public key of dIUT".into()),
+            (Some(peer), _) => match ecdh::diffie_hellman(params, &d, &peer, EcdhPrimitive::Cofactor) {
+                Ok(secret) if secret.as_bytes() == shared.as_slice() => Ok(()),
+                Ok(_) => Err("shared secret mismatch".into()),
+                Err(e) => Err(format!("{:?}", e)),
+            },
+        };
+        Ok(Some((expected, accepted)))
+    })
+}
+
+// The `key = value` lines of a test, with the `[...]` header it is under
+struct Record<'a> {
+    section: &'a str,
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Record<'a> {
+    fn get(&self, name: &str) -> Option<&'a str> {
+        self.fields.iter().find(|(key, _)| *key == name).map(|(_, value)| *value)
+    }
+
+    fn require(&self, name: &str) -> Result<&'a str, ECDSAErrors> {
+        self.get(name)
+            .ok_or_else(|| ECDSAErrors::BadArgument(format!("Missing {} in [{}]", name, self.section)))
+    }
+
+    fn hex(&self, name: &str) -> Result<Vec<u8>, ECDSAErrors> {
+        hex::decode(self.require(name)?).map_err(|_| ECDSAErrors::BadArgument(format!("{} is not hex", name)))
+    }
+
+    fn int(&self, name: &str) -> Result<BigUint, ECDSAErrors> {
+        parse_int(name, self.require(name)?)
+    }
+
+    // None if the coordinates are not those of a point of the curve
+    fn point(&self, curve: &EllipticCurve, x: &str, y: &str) -> Result<Option<CurvePoint>, ECDSAErrors> {
+        let point = CurvePoint::Coordinate(self.int(x)?, self.int(y)?);
+        Ok(Some(point).filter(|point| curve.is_on_curve(point)))
+    }
+
+    // `Result = P` or `Result = F (3 - S changed)`
+    fn result(&self) -> Result<Option<Expected>, ECDSAErrors> {
+        match self.get("Result").map(|result| result.split_whitespace().next()) {
+            None => Ok(None),
+            Some(Some("P")) => Ok(Some(Expected::Valid)),
+            Some(Some("F")) => Ok(Some(Expected::Invalid)),
+            Some(_) => Err(ECDSAErrors::BadArgument("Result is neither P nor F".into())),
+        }
+    }
+
+    fn comment(&self) -> String {
+        match self.get("Result") {
+            Some(result) => format!("[{}] Result = {}", self.section, result),
+            None => format!("[{}]", self.section),
+        }
+    }
+}
+
+fn parse_int(name: &str, value: &str) -> Result<BigUint, ECDSAErrors> {
+    BigUint::parse_bytes(value.as_bytes(), 16).ok_or_else(|| ECDSAErrors::BadArgument(format!("{} is not hex", name)))
+}
+
+fn section_curve(section: &str) -> Option<&'static CurveParams> {
+    curves::by_name(section.split(',').next()?.trim())
+}
+
+// The curve and the hash function of a `[P-256,SHA-256]` section
+fn signature_section(record: &Record) -> Result<Option<(ECDSA, HashFn)>, ECDSAErrors> {
+    let sha = record
+        .section
+        .split_once(',')
+        .map(|(_, sha)| sha.trim())
+        .ok_or_else(|| ECDSAErrors::BadArgument(format!("[{}] is not a curve and hash section", record.section)))?;
+    Ok(section_curve(record.section).zip(sha2_digest(sha)).map(|(params, hash)| (ECDSA::from(params.clone()), hash)))
+}
+
+fn parse(text: &str) -> Result<Vec<Record<'_>>, ECDSAErrors> {
+    let mut records = Vec::new();
+    let mut section = None;
+    let mut fields = Vec::new();
+
+    // a blank line or a section header ends a record
+    for line in text.lines().map(str::trim).chain([""]) {
+        if line.is_empty() || line.starts_with('[') {
+            if !fields.is_empty() {
+                let section = section
+                    .ok_or_else(|| ECDSAErrors::BadArgument("Test outside of a [...] section".into()))?;
+                records.push(Record { section, fields: std::mem::take(&mut fields) });
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                section = Some(
+                    header
+                        .strip_suffix(']')
+                        .ok_or_else(|| ECDSAErrors::BadArgument(format!("Invalid section {}", line)))?,
+                );
+            }
+        } else if !line.starts_with('#') {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ECDSAErrors::BadArgument(format!("Invalid line {}", line)))?;
+            fields.push((key.trim(), value.trim()));
+        }
+    }
+    Ok(records)
+}
+
+// The expected result of a record and whether the implementation accepted
+// it, None if the record is skipped
+type Verdict = Option<(Expected, Result<(), String>)>;
+
+fn run_records(
+    algorithm: &str,
+    text: &str,
+    test: impl Fn(&Record) -> Result<Verdict, ECDSAErrors>,
+) -> Result<TestReport, ECDSAErrors> {
+    let results = parse(text)?
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let tc_id = match record.get("COUNT") {
+                Some(count) => count
+                    .parse()
+                    .map_err(|_| ECDSAErrors::BadArgument("COUNT is not a number".into()))?,
+                None => index as u64 + 1,
+            };
+            let (expected, outcome) = match test(record)? {
+                None => (Expected::Valid, Outcome::Skipped(format!("unsupported section [{}]", record.section))),
+                Some((expected, Ok(()))) if expected == Expected::Invalid => {
+                    (expected, Outcome::Failed("invalid test accepted".into()))
+                }
+                Some((Expected::Valid, Err(reason))) => {
+                    (Expected::Valid, Outcome::Failed(format!("valid test rejected: {}", reason)))
+                }
+                Some((expected, _)) => (expected, Outcome::Passed),
+            };
+            Ok(TestResult { tc_id, comment: record.comment(), flags: Vec::new(), expected, outcome })
+        })
+        .collect::<Result<_, ECDSAErrors>>()?;
+
+    Ok(TestReport { algorithm: algorithm.into(), results })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha2::{Digest, Sha256, Sha384};
+
+    // KAS_ECC_CDH_PrimitiveTest.txt, [P-256] COUNT = 0
+    const KAS_P256: &str = "\
+#  CAVS 14.1
+#  ECC CDH Primitive (SP800-56A Section 5.7.1.2) Test Information for \"testecccdh\"
+
+[P-256]
+
+COUNT = 0
+QCAVSx = 700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287
+QCAVSy = db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac
+dIUT = 7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534
+QIUTx = ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b230
+QIUTy = 28af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141
+ZIUT = 46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b
+
+[P-224]
+
+COUNT = 0
+QCAVSx = af33cd0629bc7e996320a3f40368f74de8704fa37b8fab69abaae280
+QCAVSy = 882092ccbba7930f419a8a4f9bb16978bbc3838729992559a6f2e2d7
+dIUT = 8346a60fc6f293ca5a0d2af68ba71d1dd389e5e40837942df3e43cbd
+QIUTx = 8de2e26adf72c582d6568ef638c4fd59b18da171bdf501f1d929e048
+QIUTy = 4a68a1c2b0fb22930d120555c1ece50ea98eea8407f2be4a5c5ee0a4
+ZIUT = 7d96f9a3bd3c05cf5cc37feb8b9d5209d5c2597464dec3e9983743e8
+";
+
+    fn sig_gen_file(params: CurveParams, sha: &str, hash: HashFn) -> String {
+        let ecdsa = ECDSA::from(params);
+        let (d, q) = ecdsa.generate_key_pair().unwrap();
+        let k = ecdsa.generate_priv_key();
+        let msg = b"CAVP message";
+        let signature = ecdsa.sign(&ecdsa.digest_to_int(&hash(msg)).unwrap(), &d, &k).unwrap();
+        let CurvePoint::Coordinate(qx, qy) = q else { unreachable!() };
+        format!(
+            "[P-{},{}]\n\nMsg = {}\nd = {:x}\nQx = {:x}\nQy = {:x}\nk = {:x}\nR = {:x}\nS = {:x}\n",
+            ecdsa.q_order.bits(),
+            sha,
+            hex::encode(msg),
+            d,
+            qx,
+            qy,
+            k,
+            signature.r,
+            signature.s
+        )
+    }
+
+    fn digest<D: Digest>(message: &[u8]) -> Vec<u8> {
+        D::digest(message).to_vec()
+    }
+
+    #[test]
+    fn test_kas() {
+        let report = run_kas(KAS_P256).unwrap();
+        assert_eq!(report.passed(), 1);
+        assert_eq!(report.skipped(), 1);
+        assert!(report.is_success());
+
+        let tampered = KAS_P256.replace("ZIUT = 46fc", "ZIUT = 47fc");
+        assert_eq!(run_kas(&tampered).unwrap().failures().len(), 1);
+    }
+
+    #[test]
+    fn test_sig_gen() {
+        for file in [
+            sig_gen_file(curves::p256(), "SHA-256", digest::<Sha256>),
+            sig_gen_file(curves::p384(), "SHA-384", digest::<Sha384>),
+        ] {
+            let report = run_sig_gen(&file).unwrap();
+            assert_eq!(report.passed(), 1, "{:?}", report);
+
+            // a nonce other than the one of the signature
+            let k_line = file.lines().find(|line| line.starts_with("k = ")).unwrap();
+            let tampered = file.replace(k_line, "k = 1");
+            assert_eq!(run_sig_gen(&tampered).unwrap().failures().len(), 1);
+
+            // without d and k the signature is verified
+            let verify_only: String =
+                file.lines().filter(|line| !line.starts_with("d =") && !line.starts_with("k =")).map(|line| format!("{}\n", line)).collect();
+            assert!(run_sig_gen(&verify_only).unwrap().is_success());
+        }
+    }
+
+    #[test]
+    fn test_sig_ver() {
+        let file = sig_gen_file(curves::p256(), "SHA-256", digest::<Sha256>)
+            .lines()
+            .filter(|line| !line.starts_with("d =") && !line.starts_with("k ="))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let passing = format!("{}\nResult = P\n", file);
+        let failing = format!("{}\nResult = F (3 - S changed)\n", file.replace("S = ", "S = 1"));
+
+        let report = run_sig_ver(&format!("{}\n{}\n[P-256,SHA-1]\n\nMsg = 00\nQx = 1\nQy = 2\nR = 3\nS = 4\nResult = P\n", passing, failing))
+            .unwrap();
+        assert_eq!(report.passed(), 2, "{:?}", report);
+        assert_eq!(report.skipped(), 1);
+        assert_eq!(report.results[1].comment, "[P-256,SHA-256] Result = F (3 - S changed)");
+        assert!(report.is_success());
+
+        // a rejected P and an accepted F
+        let swapped = format!("{}\nResult = F\n", file);
+        assert_eq!(run_sig_ver(&swapped).unwrap().failures().len(), 1);
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+A minimal CBOR (RFC 8949) encoder and decoder, covering the items used by
+COSE structures: integers, byte and text strings, arrays, maps, tags and
+`null`.
+
+Every item starts with a head `major type (3 bits) | argument`, where the
+argument is stored in the low 5 bits if it is below 24, or in the 1, 2, 4 or
+8 bytes that follow. The decoder only accepts definite lengths and the
+shortest form of every head.
+*/
+
+use crate::ecdsa::ECDSAErrors;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_NULL: u64 = 22;
+
+///
+/// A CBOR data item. Maps keep the order of their entries.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CborValue {
+    /// in `[-2^64, 2^64 - 1]`, the range of CBOR integers
+    Integer(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<CborValue>),
+    Map(Vec<(CborValue, CborValue)>),
+    Tag(u64, Box<CborValue>),
+    Null,
+}
+
+impl CborValue {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            CborValue::Integer(value) if *value >= 0 => encode_head(MAJOR_UNSIGNED, *value as u64, out),
+            CborValue::Integer(value) => encode_head(MAJOR_NEGATIVE, (-1 - *value) as u64, out),
+            CborValue::Bytes(bytes) => {
+                encode_head(MAJOR_BYTES, bytes.len() as u64, out);
+                out.extend_from_slice(bytes);
+            }
+            CborValue::Text(text) => {
+                encode_head(MAJOR_TEXT, text.len() as u64, out);
+                out.extend_from_slice(text.as_bytes());
+            }
+            CborValue::Array(items) => {
+                encode_head(MAJOR_ARRAY, items.len() as u64, out);
+                items.iter().for_each(|item| item.encode_into(out));
+            }
+            CborValue::Map(entries) => {
+                encode_head(MAJOR_MAP, entries.len() as u64, out);
+                for (key, value) in entries {
+                    key.encode_into(out);
+                    value.encode_into(out);
+                }
+            }
+            CborValue::Tag(tag, item) => {
+                encode_head(MAJOR_TAG, *tag, out);
+                item.encode_into(out);
+            }
+            CborValue::Null => encode_head(MAJOR_SIMPLE, SIMPLE_NULL, out),
+        }
+    }
+
+    ///
+    /// Decodes exactly one data item, failing on trailing bytes.
+    ///
+    pub fn decode(bytes: &[u8]) -> Result<CborValue, ECDSAErrors> {
+        let mut reader = CborReader { data: bytes, depth: 0 };
+        let value = reader.read_value()?;
+        if !reader.data.is_empty() {
+            return Err(cbor_error("Trailing data"));
+        }
+        Ok(value)
+    }
+
+    /// Looks up an entry of a map by its key.
+    pub fn get(&self, key: &CborValue) -> Option<&CborValue> {
+        match self {
+            CborValue::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+fn encode_head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+// nesting limit, so that hostile inputs can not exhaust the stack
+const MAX_DEPTH: usize = 64;
+
+struct CborReader<'a> {
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ECDSAErrors> {
+        if self.data.len() < len {
+            return Err(cbor_error("Truncated item"));
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn read_head(&mut self) -> Result<(u8, u64), ECDSAErrors> {
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+
+        let (argument, min) = match info {
+            0..=23 => return Ok((major, info as u64)),
+            24 => (self.take(1)?[0] as u64, 24),
+            25 => (u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes")) as u64, 0x100),
+            26 => (u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")) as u64, 0x10000),
+            27 => (u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")), 0x1_0000_0000),
+            _ => return Err(cbor_error("Indefinite lengths and reserved values are not supported")),
+        };
+        if argument < min {
+            return Err(cbor_error("Non minimal head"));
+        }
+        Ok((major, argument))
+    }
+
+    fn read_value(&mut self) -> Result<CborValue, ECDSAErrors> {
+        if self.depth == MAX_DEPTH {
+            return Err(cbor_error("Too deeply nested"));
+        }
+        self.depth += 1;
+
+        let (major, argument) = self.read_head()?;
+        let value = match major {
+            MAJOR_UNSIGNED => CborValue::Integer(argument as i128),
+            MAJOR_NEGATIVE => CborValue::Integer(-1 - argument as i128),
+            MAJOR_BYTES => CborValue::Bytes(self.take(self.length(argument)?)?.to_vec()),
+            MAJOR_TEXT => {
+                let bytes = self.take(self.length(argument)?)?;
+                CborValue::Text(
+                    String::from_utf8(bytes.to_vec()).map_err(|_| cbor_error("Text is not UTF-8"))?,
+                )
+            }
+            MAJOR_ARRAY => {
+                let len = self.length(argument)?;
+                let items = (0..len).map(|_| self.read_value()).collect::<Result<_, _>>()?;
+                CborValue::Array(items)
+            }
+            MAJOR_MAP => {
+                let len = self.length(argument)?;
+                let mut entries = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.read_value()?;
+                    if entries.iter().any(|(k, _)| *k == key) {
+                        return Err(cbor_error("Duplicate map key"));
+                    }
+                    entries.push((key, self.read_value()?));
+                }
+                CborValue::Map(entries)
+            }
+            MAJOR_TAG => CborValue::Tag(argument, Box::new(self.read_value()?)),
+            _ if argument == SIMPLE_NULL => CborValue::Null,
+            _ => return Err(cbor_error("Unsupported simple value or float")),
+        };
+
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    // a length can not exceed the remaining input, every item taking a byte
+    fn length(&self, argument: u64) -> Result<usize, ECDSAErrors> {
+        if argument > self.data.len() as u64 {
+            return Err(cbor_error("Truncated item"));
+        }
+        Ok(argument as usize)
+    }
+}
+
+fn cbor_error(message: &str) -> ECDSAErrors {
+    ECDSAErrors::BadArgument(format!("Invalid CBOR: {}", message))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        // RFC 8949 appendix A
+        for (value, expected) in [
+            (CborValue::Integer(0), "00"),
+            (CborValue::Integer(23), "17"),
+            (CborValue::Integer(24), "1818"),
+            (CborValue::Integer(1000000), "1a000f4240"),
+            (CborValue::Integer(1000000000000), "1b000000e8d4a51000"),
+            (CborValue::Integer(-1), "20"),
+            (CborValue::Integer(-1000), "3903e7"),
+            (CborValue::Bytes(vec![1, 2, 3, 4]), "4401020304"),
+            (CborValue::Text("IETF".into()), "6449455446"),
+            (CborValue::Null, "f6"),
+            (
+                CborValue::Array(vec![
+                    CborValue::Integer(1),
+                    CborValue::Array(vec![CborValue::Integer(2), CborValue::Integer(3)]),
+                ]),
+                "8201820203",
+            ),
+            (
+                CborValue::Map(vec![
+                    (CborValue::Text("a".into()), CborValue::Integer(1)),
+                    (CborValue::Text("b".into()), CborValue::Array(vec![CborValue::Integer(2)])),
+                ]),
+                "a261610161628102",
+            ),
+            (CborValue::Tag(1, Box::new(CborValue::Integer(1363896240))), "c11a514b67b0"),
+        ] {
+            assert_eq!(hex::encode(value.encode()), expected);
+            assert_eq!(CborValue::decode(&hex::decode(expected).unwrap()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        // non minimal head, indefinite length, truncated, trailing data,
+        // float, duplicate key, invalid UTF-8
+        for bytes in ["1817", "5f4101ff", "4401", "0000", "f93c00", "a201010102", "6180"] {
+            assert!(CborValue::decode(&hex::decode(bytes).unwrap()).is_err(), "{}", bytes);
+        }
+
+        // huge length
+        assert!(CborValue::decode(&hex::decode("9bffffffffffffffff").unwrap()).is_err());
+        // deep nesting
+        assert!(CborValue::decode(&[0x81; 100]).is_err());
+    }
+}
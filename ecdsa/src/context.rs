@@ -0,0 +1,144 @@
+/*!
+A signing and verification context, as the `secp256k1_context` of
+libsecp256k1: the precomputations of the curve (`ec_core::CurveContext`, the
+field arithmetic and the table of the multiples of the generator) are made
+once, when the context is created, and shared by every call made with it:
+
+```text
+ let context = Context::new(curves::secp256k1())?;
+ let signature = context.sign_prehash(&digest, &priv_key)?;
+ assert!(context.verify_prehash(&digest, &pub_key, &signature)?);
+```
+
+The results are the ones of the same methods of `ECDSA`. A context is
+immutable, so it can be shared between threads behind an `Arc`.
+*/
+
+use ec_core::curves::CurveParams;
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::rfc6979;
+use crate::signature::Signature;
+
+pub struct Context {
+    ecdsa: ECDSA,
+    curve: CurveContext,
+}
+
+impl Context {
+    pub fn new(params: CurveParams) -> Result<Self, ECDSAErrors> {
+        let curve = CurveContext::new(params.clone())
+            .map_err(|_| ECDSAErrors::BadArgument("The generator is not a point of the curve".into()))?;
+        Ok(Context { ecdsa: ECDSA::from(params), curve })
+    }
+
+    pub fn ecdsa(&self) -> &ECDSA {
+        &self.ecdsa
+    }
+
+    pub fn generate_key_pair(&self) -> Result<(BigUint, CurvePoint), ECDSAErrors> {
+        let priv_key = self.ecdsa.generate_priv_key();
+        let pub_key = self.generate_pub_key(&priv_key)?;
+        Ok((priv_key, pub_key))
+    }
+
+    pub fn generate_pub_key(&self, priv_key: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.mul_generator(priv_key, "Error computing priv_key * a_gen")
+    }
+
+    ///
+    /// See `ECDSA::sign`.
+    ///
+    pub fn sign(&self, hash: &BigUint, priv_key: &BigUint, k_random: &BigUint) -> Result<Signature, ECDSAErrors> {
+        let (signature, _) = self.ecdsa.sign_with_generator_mul(hash, priv_key, k_random, |k| {
+            self.mul_generator(k, "Error computing k_random * a_gen")
+        })?;
+        Ok(signature)
+    }
+
+    ///
+    /// See `ECDSA::sign_prehash`.
+    ///
+    pub fn sign_prehash(&self, digest: &[u8], priv_key: &BigUint) -> Result<Signature, ECDSAErrors> {
+        self.sign_prehash_with::<Sha256>(digest, priv_key)
+    }
+
+    ///
+    /// See `ECDSA::sign_prehash_with`.
+    ///
+    pub fn sign_prehash_with<D: Digest + BlockSizeUser>(
+        &self,
+        digest: &[u8],
+        priv_key: &BigUint,
+    ) -> Result<Signature, ECDSAErrors> {
+        let hash = self.ecdsa.digest_to_int(digest)?;
+        let k = rfc6979::generate_k_with::<D>(&self.ecdsa.q_order, priv_key, digest);
+        self.sign(&hash, priv_key, &k)
+    }
+
+    ///
+    /// See `ECDSA::verify`.
+    ///
+    pub fn verify(&self, hash: &BigUint, pub_key: &CurvePoint, signature: &Signature) -> Result<bool, ECDSAErrors> {
+        self.ecdsa.verify_with_double_mul(hash, signature, |u1, u2| {
+            self.curve
+                .double_mul_generator(u1, u2, pub_key)
+                .map_err(|_| ECDSAErrors::OperationFailure("Error in u1 * a_gen + u2 * pub_key".into()))
+        })
+    }
+
+    ///
+    /// See `ECDSA::verify_prehash`.
+    ///
+    pub fn verify_prehash(&self, digest: &[u8], pub_key: &CurvePoint, signature: &Signature) -> Result<bool, ECDSAErrors> {
+        self.verify(&self.ecdsa.digest_to_int(digest)?, pub_key, signature)
+    }
+
+    // `d * a_gen` for a secret `d`, which can not be zero
+    fn mul_generator(&self, d: &BigUint, error: &str) -> Result<CurvePoint, ECDSAErrors> {
+        if *d == BigUint::from(0u32) {
+            return Err(ECDSAErrors::OperationFailure(error.into()));
+        }
+        Ok(self.curve.mul_generator(d))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves;
+
+    #[test]
+    fn test_same_results_as_ecdsa() {
+        for params in [curves::secp256k1(), curves::p256(), curves::p521()] {
+            let context = Context::new(params).unwrap();
+            let ecdsa = context.ecdsa();
+            let (priv_key, pub_key) = context.generate_key_pair().unwrap();
+            assert_eq!(ecdsa.generate_pub_key(&priv_key).unwrap(), pub_key);
+
+            let digest = Sha256::digest(b"context");
+            let signature = context.sign_prehash(&digest, &priv_key).unwrap();
+            assert_eq!(signature, ecdsa.sign_prehash(&digest, &priv_key).unwrap());
+            assert!(context.verify_prehash(&digest, &pub_key, &signature).unwrap());
+            assert!(!context.verify_prehash(b"other digest", &pub_key, &signature).unwrap());
+
+            let other = ecdsa.generate_pub_key(&BigUint::from(3u32)).unwrap();
+            assert!(!context.verify_prehash(&digest, &other, &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rejects_zero_and_invalid_keys() {
+        let context = Context::new(curves::p256()).unwrap();
+        assert!(context.generate_pub_key(&BigUint::from(0u32)).is_err());
+        assert!(context.sign(&BigUint::from(1u32), &BigUint::from(1u32), &BigUint::from(0u32)).is_err());
+
+        let signature = context.sign_prehash(b"digest", &BigUint::from(5u32)).unwrap();
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(context.verify_prehash(b"digest", &off_curve, &signature).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+/*!
+COSE_Sign1 (RFC 9052) single signer messages with ECDSA on P-256 and
+SHA-256, the `ES256` algorithm of COSE (identifier `-7`):
+
+```text
+ COSE_Sign1 = #6.18([
+      protected:    bstr .cbor { 1 (alg): -7 },
+      unprotected:  { ? 4 (kid): bstr },
+      payload:      bstr,
+      signature:    bstr (r || s) ])
+```
+
+The signature covers the CBOR encoding of the `Sig_structure`:
+
+```text
+ [ "Signature1", protected, external_aad, payload ]
+```
+
+where `external_aad` is application data bound to the message but not sent
+with it. Detached payloads are not supported.
+*/
+
+use ec_core::curves;
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::cbor::CborValue;
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::Signature;
+
+/// COSE algorithm identifier of ECDSA with SHA-256.
+pub const ALG_ES256: i128 = -7;
+/// CBOR tag of a COSE_Sign1 message.
+pub const TAG_COSE_SIGN1: u64 = 18;
+
+const HEADER_ALG: i128 = 1;
+const HEADER_CRIT: i128 = 2;
+const HEADER_KID: i128 = 4;
+
+///
+/// Signs `payload` and returns the tagged COSE_Sign1 message, with the key
+/// identifier `kid` in the unprotected header if any. `k` is derived with
+/// RFC 6979, so signing is deterministic.
+///
+pub fn sign1(
+    payload: &[u8],
+    external_aad: &[u8],
+    kid: Option<&[u8]>,
+    priv_key: &BigUint,
+) -> Result<Vec<u8>, ECDSAErrors> {
+    let protected = CborValue::Map(vec![(CborValue::Integer(HEADER_ALG), CborValue::Integer(ALG_ES256))]).encode();
+    let unprotected = match kid {
+        Some(kid) => vec![(CborValue::Integer(HEADER_KID), CborValue::Bytes(kid.to_vec()))],
+        None => vec![],
+    };
+
+    let ecdsa = ECDSA::from(curves::p256());
+    let digest = Sha256::digest(sig_structure(&protected, external_aad, payload));
+    let signature = ecdsa.sign_prehash(&digest, priv_key)?;
+
+    let message = CborValue::Array(vec![
+        CborValue::Bytes(protected),
+        CborValue::Map(unprotected),
+        CborValue::Bytes(payload.to_vec()),
+        CborValue::Bytes(signature.to_bytes()?.to_vec()),
+    ]);
+    Ok(CborValue::Tag(TAG_COSE_SIGN1, Box::new(message)).encode())
+}
+
+///
+/// Verifies a COSE_Sign1 message, tagged or not, and returns its payload.
+/// The protected header must declare `ES256` and no critical parameters.
+///
+pub fn verify1(message: &[u8], external_aad: &[u8], pub_key: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+    let items = match CborValue::decode(message)? {
+        CborValue::Tag(TAG_COSE_SIGN1, message) => *message,
+        CborValue::Tag(tag, _) => {
+            return Err(ECDSAErrors::BadArgument(format!("Unexpected CBOR tag {}", tag)))
+        }
+        message => message,
+    };
+    let (protected, unprotected, payload, signature) = match items {
+        CborValue::Array(items) => match <[CborValue; 4]>::try_from(items) {
+            Ok([CborValue::Bytes(protected), unprotected @ CborValue::Map(_), payload, CborValue::Bytes(signature)]) => {
+                (protected, unprotected, payload, signature)
+            }
+            _ => return Err(ECDSAErrors::BadArgument("Malformed COSE_Sign1 message".into())),
+        },
+        _ => return Err(ECDSAErrors::BadArgument("Malformed COSE_Sign1 message".into())),
+    };
+    let payload = match payload {
+        CborValue::Bytes(payload) => payload,
+        CborValue::Null => return Err(ECDSAErrors::BadArgument("Detached payloads are not supported".into())),
+        _ => return Err(ECDSAErrors::BadArgument("Malformed COSE_Sign1 payload".into())),
+    };
+
+    let header = CborValue::decode(&protected)?;
+    if header.get(&CborValue::Integer(HEADER_ALG)) != Some(&CborValue::Integer(ALG_ES256)) {
+        return Err(ECDSAErrors::BadArgument("COSE algorithm is not ES256".into()));
+    }
+    if header.get(&CborValue::Integer(HEADER_CRIT)).is_some()
+        || unprotected.get(&CborValue::Integer(HEADER_ALG)).is_some()
+    {
+        return Err(ECDSAErrors::BadArgument("Unsupported COSE header parameters".into()));
+    }
+
+    let signature = Signature::from_bytes(&signature)?;
+    let ecdsa = ECDSA::from(curves::p256());
+    if signature.r >= ecdsa.q_order || signature.s >= ecdsa.q_order {
+        return Err(ECDSAErrors::BadArgument("Signature component >= q".into()));
+    }
+
+    let digest = Sha256::digest(sig_structure(&protected, external_aad, &payload));
+    if !ecdsa.verify_prehash(&digest, pub_key, &signature)? {
+        return Err(ECDSAErrors::BadArgument("Invalid COSE_Sign1 signature".into()));
+    }
+
+    Ok(payload)
+}
+
+fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    CborValue::Array(vec![
+        CborValue::Text("Signature1".into()),
+        CborValue::Bytes(protected.to_vec()),
+        CborValue::Bytes(external_aad.to_vec()),
+        CborValue::Bytes(payload.to_vec()),
+    ])
+        .encode()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let ecdsa = ECDSA::from(curves::p256());
+        let priv_key = BigUint::from(0xc0ffeeu32);
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+        let payload = b"This is the content.";
+
+        let message = sign1(payload, b"", Some(b"11"), &priv_key).unwrap();
+        // same layout as the RFC 9052 appendix C.2.1 example, up to the signature
+        assert_eq!(
+            hex::encode(&message[..message.len() - 64]),
+            "d28443a10126a10442313154546869732069732074686520636f6e74656e742e5840"
+        );
+        assert_eq!(message, sign1(payload, b"", Some(b"11"), &priv_key).unwrap());
+        assert_eq!(verify1(&message, b"", &pub_key).unwrap(), payload);
+
+        // untagged message, no kid
+        let message = sign1(payload, b"aad", None, &priv_key).unwrap();
+        assert_eq!(verify1(&message[1..], b"aad", &pub_key).unwrap(), payload);
+        assert!(verify1(&message, b"", &pub_key).is_err());
+
+        let other_key = ecdsa.generate_pub_key(&BigUint::from(7u32)).unwrap();
+        assert!(verify1(&message, b"aad", &other_key).is_err());
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        let ecdsa = ECDSA::from(curves::p256());
+        let priv_key = BigUint::from(0xc0ffeeu32);
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+        let message = sign1(b"payload", b"", None, &priv_key).unwrap();
+
+        let CborValue::Tag(_, items) = CborValue::decode(&message).unwrap() else { panic!() };
+        let CborValue::Array(items) = *items else { panic!() };
+        let rebuild = |index: usize, item: CborValue| {
+            let mut items = items.clone();
+            items[index] = item;
+            CborValue::Tag(TAG_COSE_SIGN1, Box::new(CborValue::Array(items))).encode()
+        };
+
+        // ES384 protected header, critical parameters, alg in the unprotected header
+        let es384 = CborValue::Map(vec![(CborValue::Integer(1), CborValue::Integer(-35))]).encode();
+        assert!(verify1(&rebuild(0, CborValue::Bytes(es384)), b"", &pub_key).is_err());
+        let crit = CborValue::Map(vec![
+            (CborValue::Integer(1), CborValue::Integer(-7)),
+            (CborValue::Integer(2), CborValue::Array(vec![CborValue::Integer(4)])),
+        ])
+            .encode();
+        assert!(verify1(&rebuild(0, CborValue::Bytes(crit)), b"", &pub_key).is_err());
+        let alg = CborValue::Map(vec![(CborValue::Integer(1), CborValue::Integer(-7))]);
+        assert!(verify1(&rebuild(1, alg), b"", &pub_key).is_err());
+
+        // tampered or detached payload, truncated signature, wrong tag
+        assert!(verify1(&rebuild(2, CborValue::Bytes(b"tampered".to_vec())), b"", &pub_key).is_err());
+        assert!(verify1(&rebuild(2, CborValue::Null), b"", &pub_key).is_err());
+        assert!(verify1(&rebuild(3, CborValue::Bytes(vec![1; 63])), b"", &pub_key).is_err());
+        let tagged = CborValue::Tag(98, Box::new(CborValue::Array(items.clone()))).encode();
+        assert!(verify1(&tagged, b"", &pub_key).is_err());
+    }
+}
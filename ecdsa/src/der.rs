@@ -0,0 +1,296 @@
+/*!
+A minimal ASN.1 DER writer and reader, covering the types used by key and
+signature encodings: `INTEGER`, `BIT STRING`, `OCTET STRING`, `NULL`,
+`OBJECT IDENTIFIER`, `SEQUENCE` and context specific tags.
+
+Every element is a tag-length-value triplet; the reader only accepts the
+canonical DER form (minimal lengths and minimal, non negative integers).
+*/
+
+use num_bigint::BigUint;
+
+use crate::ecdsa::ECDSAErrors;
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+
+/// `id-ecPublicKey`, the algorithm of elliptic curve keys (RFC 5480)
+pub const OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+
+/// Tag of the constructed, context specific element `[n]`.
+pub fn context_tag(n: u8) -> u8 {
+    0xa0 | n
+}
+
+pub fn encode_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let len_bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        out.push(0x80 | len_bytes.len() as u8);
+        out.extend_from_slice(&len_bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn encode_sequence(elements: &[Vec<u8>]) -> Vec<u8> {
+    encode_tlv(TAG_SEQUENCE, &elements.concat())
+}
+
+/// A non negative integer, with a leading zero byte if its top bit is set.
+pub fn encode_integer(value: &BigUint) -> Vec<u8> {
+    let mut content = value.to_bytes_be();
+    if content[0] & 0x80 != 0 {
+        content.insert(0, 0);
+    }
+    encode_tlv(TAG_INTEGER, &content)
+}
+
+pub fn encode_octet_string(bytes: &[u8]) -> Vec<u8> {
+    encode_tlv(TAG_OCTET_STRING, bytes)
+}
+
+/// A bit string made of whole bytes (no unused bits).
+pub fn encode_bit_string(bytes: &[u8]) -> Vec<u8> {
+    encode_tlv(TAG_BIT_STRING, &[&[0u8][..], bytes].concat())
+}
+
+pub fn encode_null() -> Vec<u8> {
+    encode_tlv(TAG_NULL, &[])
+}
+
+///
+/// The first two arcs are packed in one value `40 a + b`, every value is then
+/// written in base 128 with the top bit set on all bytes but the last.
+///
+pub fn encode_oid(arcs: &[u64]) -> Result<Vec<u8>, ECDSAErrors> {
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+        return Err(ECDSAErrors::BadArgument("Invalid object identifier".into()));
+    }
+
+    let mut content = Vec::new();
+    let first = arcs[0]
+        .checked_mul(40)
+        .and_then(|a| a.checked_add(arcs[1]))
+        .ok_or_else(|| ECDSAErrors::BadArgument("Invalid object identifier".into()))?;
+    for arc in std::iter::once(first).chain(arcs[2..].iter().copied()) {
+        let mut bytes = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            bytes.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        bytes.reverse();
+        content.extend_from_slice(&bytes);
+    }
+
+    Ok(encode_tlv(TAG_OID, &content))
+}
+
+/// `[n] EXPLICIT` wrapping of an already encoded element.
+pub fn encode_explicit(n: u8, element: &[u8]) -> Vec<u8> {
+    encode_tlv(context_tag(n), element)
+}
+
+///
+/// Reads the elements of a DER encoding one after the other.
+///
+#[derive(Clone)]
+pub struct DerReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        DerReader { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Checks that all the input was read.
+    pub fn finish(&self) -> Result<(), ECDSAErrors> {
+        if !self.is_empty() {
+            return Err(der_error("Trailing data"));
+        }
+        Ok(())
+    }
+
+    pub fn read_tlv(&mut self) -> Result<(u8, &'a [u8]), ECDSAErrors> {
+        if self.data.len() < 2 {
+            return Err(der_error("Truncated element"));
+        }
+        let tag = self.data[0];
+        let (len, header) = match self.data[1] {
+            len if len < 0x80 => (len as usize, 2),
+            0x80 => return Err(der_error("Indefinite lengths are not DER")),
+            len_of_len => {
+                let len_of_len = (len_of_len & 0x7f) as usize;
+                if len_of_len > std::mem::size_of::<usize>() || self.data.len() < 2 + len_of_len {
+                    return Err(der_error("Invalid length"));
+                }
+                let len_bytes = &self.data[2..2 + len_of_len];
+                let len = len_bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+                if len_bytes[0] == 0 || len < 0x80 {
+                    return Err(der_error("Non minimal length"));
+                }
+                (len, 2 + len_of_len)
+            }
+        };
+
+        if self.data.len() - header < len {
+            return Err(der_error("Truncated element"));
+        }
+        let content = &self.data[header..header + len];
+        self.data = &self.data[header + len..];
+        Ok((tag, content))
+    }
+
+    /// Reads an element and returns its whole encoding, tag and length included.
+    pub fn read_raw(&mut self) -> Result<&'a [u8], ECDSAErrors> {
+        let start = self.data;
+        self.read_tlv()?;
+        Ok(&start[..start.len() - self.data.len()])
+    }
+
+    pub fn read_expected(&mut self, expected: u8) -> Result<&'a [u8], ECDSAErrors> {
+        let (tag, content) = self.read_tlv()?;
+        if tag != expected {
+            return Err(der_error(&format!("Expected tag {:#04x}, got {:#04x}", expected, tag)));
+        }
+        Ok(content)
+    }
+
+    /// Reads a `SEQUENCE` and returns a reader over its elements.
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>, ECDSAErrors> {
+        Ok(DerReader::new(self.read_expected(TAG_SEQUENCE)?))
+    }
+
+    /// Reads an `[n] EXPLICIT` element and returns a reader over its content.
+    pub fn read_explicit(&mut self, n: u8) -> Result<DerReader<'a>, ECDSAErrors> {
+        Ok(DerReader::new(self.read_expected(context_tag(n))?))
+    }
+
+    /// Reads a minimally encoded, non negative `INTEGER`.
+    pub fn read_integer(&mut self) -> Result<BigUint, ECDSAErrors> {
+        let content = self.read_expected(TAG_INTEGER)?;
+        match content {
+            [] => Err(der_error("Empty integer")),
+            [first, ..] if first & 0x80 != 0 => Err(der_error("Negative integer")),
+            [0, second, ..] if second & 0x80 == 0 => Err(der_error("Non minimal integer")),
+            _ => Ok(BigUint::from_bytes_be(content)),
+        }
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], ECDSAErrors> {
+        self.read_expected(TAG_OCTET_STRING)
+    }
+
+    /// Reads a `BIT STRING` made of whole bytes.
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8], ECDSAErrors> {
+        match self.read_expected(TAG_BIT_STRING)? {
+            [0, bytes @ ..] => Ok(bytes),
+            _ => Err(der_error("Bit string with unused bits")),
+        }
+    }
+
+    pub fn read_null(&mut self) -> Result<(), ECDSAErrors> {
+        if !self.read_expected(TAG_NULL)?.is_empty() {
+            return Err(der_error("Non empty NULL"));
+        }
+        Ok(())
+    }
+
+    pub fn read_oid(&mut self) -> Result<Vec<u64>, ECDSAErrors> {
+        let content = self.read_expected(TAG_OID)?;
+        if content.is_empty() || content[content.len() - 1] & 0x80 != 0 {
+            return Err(der_error("Invalid object identifier"));
+        }
+
+        let mut values = Vec::new();
+        let mut value = 0u64;
+        let mut start = true;
+        for byte in content {
+            if start && *byte == 0x80 {
+                return Err(der_error("Non minimal object identifier"));
+            }
+            if value > u64::MAX >> 7 {
+                return Err(der_error("Object identifier arc too big"));
+            }
+            value = (value << 7) | (byte & 0x7f) as u64;
+            start = byte & 0x80 == 0;
+            if start {
+                values.push(value);
+                value = 0;
+            }
+        }
+
+        let (first, second) = match values[0] {
+            v if v < 40 => (0, v),
+            v if v < 80 => (1, v - 40),
+            v => (2, v - 80),
+        };
+        let mut arcs = vec![first, second];
+        arcs.extend_from_slice(&values[1..]);
+        Ok(arcs)
+    }
+}
+
+fn der_error(message: &str) -> ECDSAErrors {
+    ECDSAErrors::BadArgument(format!("Invalid DER: {}", message))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let oid = encode_oid(&[1, 2, 840, 10045, 3, 1, 7]).unwrap();
+        assert_eq!(hex::encode(&oid), "06082a8648ce3d030107");
+
+        let integer = encode_integer(&BigUint::from(0x80u32));
+        assert_eq!(hex::encode(&integer), "02020080");
+
+        let long = encode_octet_string(&[7u8; 200]);
+        assert_eq!(&long[..3], &[0x04, 0x81, 200]);
+
+        let der = encode_sequence(&[integer, oid, encode_explicit(1, &encode_bit_string(&[1, 2])), long, encode_null()]);
+        let mut reader = DerReader::new(&der);
+        let mut sequence = reader.read_sequence().unwrap();
+        reader.finish().unwrap();
+
+        assert_eq!(sequence.clone().read_raw().unwrap(), &hex::decode("02020080").unwrap()[..]);
+        assert_eq!(sequence.read_integer().unwrap(), BigUint::from(0x80u32));
+        assert_eq!(sequence.read_oid().unwrap(), vec![1, 2, 840, 10045, 3, 1, 7]);
+        assert_eq!(sequence.read_explicit(1).unwrap().read_bit_string().unwrap(), &[1, 2]);
+        assert_eq!(sequence.read_octet_string().unwrap(), &[7u8; 200][..]);
+        sequence.read_null().unwrap();
+        sequence.finish().unwrap();
+    }
+
+    #[test]
+    fn test_decode_non_canonical() {
+        // non minimal integer, negative integer, non minimal length, truncated
+        for der in ["02020001", "020180", "0281010a", "0203ff"] {
+            let bytes = hex::decode(der).unwrap();
+            assert!(DerReader::new(&bytes).read_integer().is_err(), "{}", der);
+        }
+
+        assert!(DerReader::new(&[0x30, 0x80, 0x00, 0x00]).read_sequence().is_err());
+        assert!(DerReader::new(&[0x02, 0x01, 0x01]).read_octet_string().is_err());
+        assert!(encode_oid(&[3, 1]).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+/*!
+Explicit elliptic curve domain parameters (SEC 1 section C.2, RFC 3279), as
+embedded in certificates and keys which do not use a named curve:
+
+```text
+ ECParameters ::= SEQUENCE {
+      version                 INTEGER (1),
+      fieldID                 SEQUENCE {
+           fieldType          OBJECT IDENTIFIER (prime-field),
+           prime              INTEGER (p) },
+      curve                   SEQUENCE {
+           a                  OCTET STRING (fixed width),
+           b                  OCTET STRING (fixed width),
+           seed               BIT STRING OPTIONAL },
+      base                    OCTET STRING (SEC1 encoding of G),
+      order                   INTEGER (n),
+      cofactor                INTEGER OPTIONAL (h) }
+```
+
+Only prime fields are supported. The seed, which lets one check how the
+parameters were generated, is not checked and not written. `p` and `n` are
+checked to be prime with `MILLER_RABIN_ROUNDS` rounds of Miller-Rabin.
+*/
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+
+use crate::der::{self, DerReader};
+use crate::ecdsa::ECDSAErrors;
+
+/// `prime-field`, the field type of curves over `Fp`
+pub const OID_PRIME_FIELD: &[u64] = &[1, 2, 840, 10045, 1, 1];
+
+///
+/// Encodes the domain parameters of a curve as an explicit `ECParameters`,
+/// with the uncompressed base point and the cofactor.
+///
+pub fn to_ec_parameters_der(params: &CurveParams) -> Result<Vec<u8>, ECDSAErrors> {
+    let field_size = (params.curve.p.bits() as usize).div_ceil(8);
+    let a = to_fixed_bytes(&params.curve.a, field_size)
+        .map_err(|_| ECDSAErrors::BadArgument("a does not fit in the field size".into()))?;
+    let b = to_fixed_bytes(&params.curve.b, field_size)
+        .map_err(|_| ECDSAErrors::BadArgument("b does not fit in the field size".into()))?;
+    let base = params
+        .g
+        .to_sec1_uncompressed(&params.curve)
+        .map_err(|_| ECDSAErrors::BadArgument("Generator is not a point of the curve".into()))?;
+
+    Ok(der::encode_sequence(&[
+        der::encode_integer(&BigUint::from(1u32)),
+        der::encode_sequence(&[der::encode_oid(OID_PRIME_FIELD)?, der::encode_integer(&params.curve.p)]),
+        der::encode_sequence(&[der::encode_octet_string(&a), der::encode_octet_string(&b)]),
+        der::encode_octet_string(&base),
+        der::encode_integer(&params.n),
+        der::encode_integer(&params.h),
+    ]))
+}
+
+///
+/// Decodes an explicit `ECParameters` and checks that it describes a usable
+/// group:
+///
+/// - `p > 3` is odd, `a, b < p` and `4 a^3 + 27 b^2 != 0 mod p`
+/// - `G` is a point of the curve other than the identity and `n G = I`
+///
+/// If the cofactor is missing it is derived from the Hasse bound, which
+/// requires `n > 4 sqrt(p)`.
+///
+pub fn from_ec_parameters_der(der: &[u8]) -> Result<CurveParams, ECDSAErrors> {
+    let mut reader = DerReader::new(der);
+    let mut ec_parameters = reader.read_sequence()?;
+    reader.finish()?;
+
+    if ec_parameters.read_integer()? != BigUint::from(1u32) {
+        return Err(ECDSAErrors::BadArgument("Unsupported ECParameters version".into()));
+    }
+
+    let mut field_id = ec_parameters.read_sequence()?;
+    if field_id.read_oid()? != OID_PRIME_FIELD {
+        return Err(ECDSAErrors::BadArgument("Only prime fields are supported".into()));
+    }
+    let p = field_id.read_integer()?;
+    field_id.finish()?;
+    if p <= BigUint::from(3u32) || !is_probable_prime(&p) {
+        return Err(ECDSAErrors::BadArgument("Invalid field prime".into()));
+    }
+    let field_size = (p.bits() as usize).div_ceil(8);
+
+    let mut curve = ec_parameters.read_sequence()?;
+    let a = curve.read_octet_string()?;
+    let b = curve.read_octet_string()?;
+    if a.len() != field_size || b.len() != field_size {
+        return Err(ECDSAErrors::BadArgument("Invalid field element length".into()));
+    }
+    if curve.peek_tag() == Some(der::TAG_BIT_STRING) {
+        curve.read_tlv()?;
+    }
+    curve.finish()?;
+    let curve = EllipticCurve {
+        a: BigUint::from_bytes_be(a),
+        b: BigUint::from_bytes_be(b),
+        p,
+    };
+    check_curve(&curve)?;
+
+    let base = ec_parameters.read_octet_string()?;
+    let g = match base.first() {
+        Some(0x04) => CurvePoint::from_sec1_uncompressed(base, &curve),
+        _ => CurvePoint::from_sec1_compressed(base, &curve),
+    }
+        .map_err(|_| ECDSAErrors::BadArgument("Base point is not a point of the curve".into()))?;
+
+    let n = ec_parameters.read_integer()?;
+    if !is_probable_prime(&n) {
+        return Err(ECDSAErrors::BadArgument("Invalid order".into()));
+    }
+    let h = match ec_parameters.peek_tag() {
+        Some(der::TAG_INTEGER) => ec_parameters.read_integer()?,
+        _ => hasse_cofactor(&curve.p, &n)?,
+    };
+    ec_parameters.finish()?;
+    if h == BigUint::from(0u32) {
+        return Err(ECDSAErrors::BadArgument("Invalid cofactor".into()));
+    }
+
+    let n_g = curve
+        .scalar_mul(&g, &n)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error computing n G".into()))?;
+    if n_g != CurvePoint::Identity {
+        return Err(ECDSAErrors::BadArgument("n is not the order of the base point".into()));
+    }
+
+    Ok(CurveParams::new(curve, g, n, h))
+}
+
+///
+/// Decodes the `ECDomainParameters` of a key, either a named curve OID or
+/// explicit `ECParameters`.
+///
+pub fn from_domain_parameters_der(der: &[u8]) -> Result<CurveParams, ECDSAErrors> {
+    match der.first().copied() {
+        Some(der::TAG_OID) => {
+            let mut reader = DerReader::new(der);
+            let oid = reader.read_oid()?;
+            reader.finish()?;
+            curves::by_oid(&oid)
+                .cloned()
+                .ok_or_else(|| ECDSAErrors::BadArgument("Unsupported curve".into()))
+        }
+        Some(der::TAG_SEQUENCE) => from_ec_parameters_der(der),
+        _ => Err(ECDSAErrors::BadArgument("Unsupported domain parameters".into())),
+    }
+}
+
+fn check_curve(curve: &EllipticCurve) -> Result<(), ECDSAErrors> {
+    let p = &curve.p;
+    if curve.a >= *p || curve.b >= *p {
+        return Err(ECDSAErrors::BadArgument("Curve coefficient >= p".into()));
+    }
+
+    let discriminant = (BigUint::from(4u32) * curve.a.modpow(&BigUint::from(3u32), p)
+        + BigUint::from(27u32) * curve.b.modpow(&BigUint::from(2u32), p))
+        % p;
+    if discriminant == BigUint::from(0u32) {
+        return Err(ECDSAErrors::BadArgument("Singular curve".into()));
+    }
+    Ok(())
+}
+
+// h = floor((p + 1 + 2 sqrt(p)) / n), the only candidate when n > 4 sqrt(p)
+fn hasse_cofactor(p: &BigUint, n: &BigUint) -> Result<BigUint, ECDSAErrors> {
+    let sqrt_p = p.sqrt() + BigUint::from(1u32);
+    if *n <= BigUint::from(4u32) * &sqrt_p {
+        return Err(ECDSAErrors::BadArgument(
+            "Missing cofactor can not be derived for this order".into(),
+        ));
+    }
+    Ok((p + BigUint::from(1u32) + BigUint::from(2u32) * p.sqrt()) / n)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // openssl ecparam -name prime256v1 -param_enc explicit -no_seed -outform DER
+    const P256_NO_SEED: &str = "3081e0020101302c06072a8648ce3d0101022100ffffffff00000001000000000000000000000000ffffffffffffffffffffffff30440420ffffffff00000001000000000000000000000000fffffffffffffffffffffffc04205ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b0441046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5022100ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551020101";
+    // openssl ecparam -name secp256k1 -param_enc explicit -no_seed -outform DER
+    const SECP256K1_NO_SEED: &str = "3081e0020101302c06072a8648ce3d0101022100fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f3044042000000000000000000000000000000000000000000000000000000000000000000420000000000000000000000000000000000000000000000000000000000000000704410479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141020101";
+    // openssl ecparam -name prime256v1 -param_enc explicit -conv_form compressed -outform DER, with the seed
+    const P256_COMPRESSED_SEED: &str = "MIHXAgEBMCwGByqGSM49AQECIQD/////AAAAAQAAAAAAAAAAAAAAAP///////////////zBbBCD/////AAAAAQAAAAAAAAAAAAAAAP///////////////AQgWsY12Ko6k+ez671VdpiGvGUdBrDMU7D2O848PifSYEsDFQDEnTYIhucEk2pmeOETnSa3gZ9+kAQhA2sX0fLhLEJH+Lzm5WOkQPJ3A32BLeszoPShOUXYmMKWAiEA/////wAAAAD//////////7zm+q2nF56E87nKwvxjJVECAQE=";
+
+    #[test]
+    fn test_openssl_parameters() {
+        for (expected, params) in [(P256_NO_SEED, curves::p256()), (SECP256K1_NO_SEED, curves::secp256k1())] {
+            let der = hex::decode(expected).unwrap();
+            assert_eq!(from_ec_parameters_der(&der).unwrap(), params);
+            assert_eq!(to_ec_parameters_der(&params).unwrap(), der);
+            assert_eq!(from_domain_parameters_der(&der).unwrap(), params);
+        }
+
+        use base64::Engine;
+        let der = base64::engine::general_purpose::STANDARD.decode(P256_COMPRESSED_SEED).unwrap();
+        assert_eq!(from_ec_parameters_der(&der).unwrap(), curves::p256());
+
+        let oid = der::encode_oid(curves::oid_of(&curves::p384()).unwrap()).unwrap();
+        assert_eq!(from_domain_parameters_der(&oid).unwrap(), curves::p384());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for name in ["P-384", "P-521", "brainpoolP512r1"] {
+            let params = curves::by_name(name).unwrap();
+            let der = to_ec_parameters_der(params).unwrap();
+            assert_eq!(from_ec_parameters_der(&der).unwrap(), *params);
+        }
+    }
+
+    #[test]
+    fn test_missing_cofactor() {
+        let der = hex::decode(P256_NO_SEED).unwrap();
+        // drop the cofactor and fix the sequence length
+        let mut without = der[..der.len() - 3].to_vec();
+        without[2] -= 3;
+        assert_eq!(from_ec_parameters_der(&without).unwrap(), curves::p256());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let params = curves::p256();
+
+        // base point off the curve
+        let tampered = P256_NO_SEED.replace("27d2604b04", "27d2604c04");
+        assert!(from_ec_parameters_der(&hex::decode(tampered).unwrap()).is_err());
+        let mut invalid = params.clone();
+        invalid.curve.b += BigUint::from(1u32);
+        assert!(to_ec_parameters_der(&invalid).is_err());
+
+        // wrong order
+        let mut invalid = params.clone();
+        invalid.n -= BigUint::from(2u32);
+        assert!(from_ec_parameters_der(&to_ec_parameters_der(&invalid).unwrap()).is_err());
+
+        // 2n G = I too, but 2n is not prime
+        let mut invalid = params.clone();
+        invalid.n *= BigUint::from(2u32);
+        assert!(matches!(
+            from_ec_parameters_der(&to_ec_parameters_der(&invalid).unwrap()),
+            Err(ECDSAErrors::BadArgument(message)) if message == "Invalid order"
+        ));
+
+        // singular curve y^2 = x^3
+        let mut invalid = params.clone();
+        invalid.curve.a = BigUint::from(0u32);
+        invalid.curve.b = BigUint::from(0u32);
+        invalid.g = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(from_ec_parameters_der(&to_ec_parameters_der(&invalid).unwrap()).is_err());
+
+        // implicitCA
+        assert!(from_domain_parameters_der(&der::encode_null()).is_err());
+    }
+}
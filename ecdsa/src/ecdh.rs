@@ -0,0 +1,310 @@
+/*!
+Elliptic curve Diffie-Hellman key agreement (SEC 1 section 3.3.1):
+
+```text
+ Alice (d_A, B_A = d_A G)            Bob (d_B, B_B = d_B G)
+ Z = x of d_A B_B         ==         x of d_B B_A         Standard
+ Z = x of h d_A B_B       ==         x of h d_B B_A       Cofactor
+```
+
+The cofactor primitive (ECC CDH of SP 800-56A section 5.7.1.2) multiplies by
+the cofactor `h` of the curve, which sends the small order component of a
+peer key to the identity; on curves with `h = 1` both primitives agree.
+
+The shared secret is the `x` coordinate of the product, encoded on the byte
+size of the field. The private key being secret, the product is computed
+with the constant time ladder. `Z` is not uniformly distributed, so keys are
+derived from it rather than taken from it:
+
+```text
+ key = HKDF-SHA256(salt, Z, info, len)        RFC 5869
+ key = H(Z || 1 || SharedInfo) || H(Z || 2 || SharedInfo) || ...
+                                              ANSI X9.63, counters on 4 bytes
+```
+*/
+
+use ec_core::curves::CurveParams;
+use ec_core::*;
+use hkdf::Hkdf;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::ecdsa::ECDSAErrors;
+
+///
+/// The raw shared secret `Z` of a key agreement. `==` compares secrets in
+/// constant time.
+///
+#[derive(Clone, Debug)]
+pub struct SharedSecret {
+    bytes: Vec<u8>,
+}
+
+impl SharedSecret {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    ///
+    /// Derives a `len` bytes long symmetric key from `Z` with HKDF-SHA256.
+    /// `hkdf_salt` defaults to zeros, and `info` binds the key to its use
+    /// (protocol, identities of the parties). `len` is at most `255 * 32`.
+    ///
+    pub fn derive_key(&self, hkdf_salt: Option<&[u8]>, info: &[u8], len: usize) -> Result<Vec<u8>, ECDSAErrors> {
+        let mut key = vec![0u8; len];
+        Hkdf::<Sha256>::new(hkdf_salt, &self.bytes)
+            .expand(info, &mut key)
+            .map_err(|_| ECDSAErrors::BadArgument("Key length must be at most 255 * 32 bytes".into()))?;
+        Ok(key)
+    }
+
+    ///
+    /// Derives a `len` bytes long symmetric key from `Z` with the X9.63 KDF
+    /// over `D`, as mandated by many ECIES deployments, see `x963_kdf`.
+    ///
+    pub fn derive_key_x963<D: Digest>(&self, shared_info: &[u8], len: usize) -> Result<Vec<u8>, ECDSAErrors> {
+        x963_kdf::<D>(&self.bytes, shared_info, len)
+    }
+}
+
+///
+/// The ANSI X9.63 KDF (SEC 1 section 3.6.1): the concatenation of
+/// `D(z || counter || shared_info)` for a big endian 32 bits counter starting
+/// at 1, truncated to `len` bytes. `len` must be below `(2^32 - 1)` digests.
+///
+pub fn x963_kdf<D: Digest>(z: &[u8], shared_info: &[u8], len: usize) -> Result<Vec<u8>, ECDSAErrors> {
+    let blocks = len.div_ceil(<D as Digest>::output_size());
+    if blocks as u64 > u32::MAX as u64 - 1 {
+        return Err(ECDSAErrors::BadArgument("Key length is too long for the X9.63 KDF".into()));
+    }
+
+    let mut key = Vec::with_capacity(blocks * <D as Digest>::output_size());
+    for counter in 1..=blocks as u32 {
+        key.extend_from_slice(
+            &D::new()
+                .chain_update(z)
+                .chain_update(counter.to_be_bytes())
+                .chain_update(shared_info)
+                .finalize(),
+        );
+    }
+    key.truncate(len);
+    Ok(key)
+}
+
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.ct_eq(&other.bytes)
+    }
+}
+
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SharedSecret {}
+
+///
+/// The primitive computing the shared secret, see the module documentation.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EcdhPrimitive {
+    /// `Z = x of d B`, SEC 1 section 3.3.1
+    Standard,
+    /// `Z = x of h d B`, SEC 1 section 3.3.2 and SP 800-56A ECC CDH
+    Cofactor,
+}
+
+///
+/// Computes the shared secret between `priv_key` and the public key of the
+/// peer with `primitive`. The peer key must be a point of the curve other
+/// than the identity, so that a point of small order can not leak `priv_key`
+/// modulo its order:
+///
+/// - `Standard` rejects peer keys outside of the prime order subgroup
+/// - `Cofactor` rejects peer keys of small order, `h B` being the identity
+///
+pub fn diffie_hellman(
+    params: &CurveParams,
+    priv_key: &BigUint,
+    peer_pub_key: &CurvePoint,
+    primitive: EcdhPrimitive,
+) -> Result<SharedSecret, ECDSAErrors> {
+    if *priv_key == BigUint::from(0u32) || *priv_key >= params.n {
+        return Err(ECDSAErrors::BadArgument("Private key must be in [1, q - 1]".into()));
+    }
+    if *peer_pub_key == CurvePoint::Identity || !params.curve.is_on_curve(peer_pub_key) {
+        return Err(ECDSAErrors::BadArgument("Peer public key is not a point of the curve".into()));
+    }
+
+    // h B is public, only the multiplication by priv_key has to be constant time
+    let peer_pub_key = match primitive {
+        EcdhPrimitive::Standard if !params.is_in_prime_subgroup(peer_pub_key) => {
+            return Err(ECDSAErrors::BadArgument("Peer public key is not in the prime order subgroup".into()));
+        }
+        EcdhPrimitive::Standard => peer_pub_key.clone(),
+        EcdhPrimitive::Cofactor => match params.curve.scalar_mul(peer_pub_key, &params.h) {
+            Ok(CurvePoint::Identity) => {
+                return Err(ECDSAErrors::BadArgument("Peer public key is a point of small order".into()));
+            }
+            Ok(point) => point,
+            Err(_) => return Err(ECDSAErrors::OperationFailure("Error computing h * peer_pub_key".into())),
+        },
+    };
+
+    let product = params
+        .curve
+        .scalar_mul_ct(&peer_pub_key, priv_key)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error computing priv_key * peer_pub_key".into()))?;
+
+    match product {
+        CurvePoint::Coordinate(x, _) => Ok(SharedSecret {
+            bytes: to_fixed_bytes(&x, params.curve.coordinate_size())
+                .map_err(|_| ECDSAErrors::OperationFailure("x does not fit the field size".into()))?,
+        }),
+        CurvePoint::Identity => Err(ECDSAErrors::OperationFailure("Shared secret is the identity".into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves;
+
+    fn x_bytes(params: &CurveParams, point: &CurvePoint) -> Vec<u8> {
+        match point {
+            CurvePoint::Coordinate(x, _) => to_fixed_bytes(x, params.curve.coordinate_size()).unwrap(),
+            CurvePoint::Identity => panic!("the identity has no x"),
+        }
+    }
+
+    #[test]
+    fn test_openssl_derive() {
+        // openssl pkeyutl -derive, P-256
+        let params = curves::p256();
+        let priv_key = BigUint::parse_bytes(b"8565f09239b28895cc6ed60f974f976e17db52e108afebb3cb950d5f67db6f82", 16).unwrap();
+        let peer = hex::decode(
+            "048802180b39c8c04214f1da467ecf98fd06f037114d8522d4f40cb01f8cd76a2e\
+             562f93ef1ab8077202262f60988f3fbb6772ae8b06b84ebcaeae69dc056da927",
+        )
+        .unwrap();
+        let peer = CurvePoint::from_sec1_uncompressed(&peer, &params.curve).unwrap();
+
+        let secret = diffie_hellman(&params, &priv_key, &peer, EcdhPrimitive::Standard).unwrap();
+        assert_eq!(hex::encode(secret.as_bytes()), "952d98cfa1fae00d35b4c4afb9f4ac250dce76ce23f67fbc4641d6b58f3733ab");
+        // h = 1
+        assert_eq!(diffie_hellman(&params, &priv_key, &peer, EcdhPrimitive::Cofactor).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_agreement() {
+        let params = curves::secp256k1();
+        let (alice, bob) = (BigUint::from(0xa11cu32), BigUint::from(0xb0bu32));
+        let alice_pub = params.curve.scalar_mul(&params.g, &alice).unwrap();
+        let bob_pub = params.curve.scalar_mul(&params.g, &bob).unwrap();
+
+        assert_eq!(
+            diffie_hellman(&params, &alice, &bob_pub, EcdhPrimitive::Standard).unwrap(),
+            diffie_hellman(&params, &bob, &alice_pub, EcdhPrimitive::Standard).unwrap()
+        );
+
+        assert!(diffie_hellman(&params, &BigUint::from(0u32), &bob_pub, EcdhPrimitive::Standard).is_err());
+        assert!(diffie_hellman(&params, &params.n, &bob_pub, EcdhPrimitive::Standard).is_err());
+        assert!(diffie_hellman(&params, &alice, &CurvePoint::Identity, EcdhPrimitive::Standard).is_err());
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(diffie_hellman(&params, &alice, &off_curve, EcdhPrimitive::Standard).is_err());
+    }
+
+    #[test]
+    fn test_derive_key() {
+        // RFC 5869 test cases 1 and 3
+        let secret = SharedSecret { bytes: vec![0x0b; 22] };
+        let salt: Vec<u8> = (0x00..=0x0c).collect();
+        let info: Vec<u8> = (0xf0..=0xf9).collect();
+        assert_eq!(
+            hex::encode(secret.derive_key(Some(&salt), &info, 42).unwrap()),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert_eq!(
+            hex::encode(secret.derive_key(None, &[], 42).unwrap()),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+
+        // both parties derive the same key
+        let params = curves::p256();
+        let (alice, bob) = (BigUint::from(0xa11cu32), BigUint::from(0xb0bu32));
+        let alice_pub = params.curve.scalar_mul(&params.g, &alice).unwrap();
+        let bob_pub = params.curve.scalar_mul(&params.g, &bob).unwrap();
+        let alice_key = diffie_hellman(&params, &alice, &bob_pub, EcdhPrimitive::Standard).unwrap().derive_key(None, b"test", 32).unwrap();
+        let bob_key = diffie_hellman(&params, &bob, &alice_pub, EcdhPrimitive::Standard).unwrap().derive_key(None, b"test", 32).unwrap();
+        assert_eq!(alice_key, bob_key);
+        assert_ne!(alice_key, diffie_hellman(&params, &bob, &alice_pub, EcdhPrimitive::Standard).unwrap().derive_key(None, b"other", 32).unwrap());
+
+        assert!(secret.derive_key(None, &[], 255 * 32).is_ok());
+        assert!(secret.derive_key(None, &[], 255 * 32 + 1).is_err());
+    }
+
+    #[test]
+    fn test_x963_kdf() {
+        // NIST CAVS SP 800-135 ANSI X9.63 KDF, SHA-256
+        let z = hex::decode("96c05619d56c328ab95fe84b18264b08725b85e33fd34f08").unwrap();
+        assert_eq!(hex::encode(x963_kdf::<Sha256>(&z, &[], 16).unwrap()), "443024c3dae66b95e6f5670601558f71");
+
+        let secret = SharedSecret { bytes: hex::decode("22518b10e70f2a3f243810ae3254139efbee04aa57c7af7d").unwrap() };
+        let shared_info = hex::decode("75eef81aa3041e33b80971203d2c0c52").unwrap();
+        assert_eq!(
+            hex::encode(secret.derive_key_x963::<Sha256>(&shared_info, 128).unwrap()),
+            "c498af77161cc59f2962b9a713e2b215152d139766ce34a776df11866a69bf2e\
+             52a13d9c7c6fc878c50c5ea0bc7b00e0da2447cfd874f6cf92f30d0097111485\
+             500c90c3af8b487872d04685d14c8d1dc8d7fa08beb0ce0ababc11f0bd496269\
+             142d43525a78e5bc79a17f59676a5706dc54d54d4d1f0bd7e386128ec26afc21"
+        );
+
+        // a prefix of a longer key
+        let long = secret.derive_key_x963::<sha2::Sha512>(&shared_info, 100).unwrap();
+        assert_eq!(&long[..20], &secret.derive_key_x963::<sha2::Sha512>(&shared_info, 20).unwrap()[..]);
+        assert!(x963_kdf::<Sha256>(&z, &[], 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_small_subgroup_point() {
+        // y^2 = x^3 + x + 5 mod 23 has 22 points: n = 11, h = 2
+        let params = CurveParams::new(
+            EllipticCurve::new(BigUint::from(1u32), BigUint::from(5u32), BigUint::from(23u32)).unwrap(),
+            CurvePoint::Coordinate(BigUint::from(11u32), BigUint::from(6u32)),
+            BigUint::from(11u32),
+            BigUint::from(2u32),
+        );
+        let bob_pub = params.curve.scalar_mul(&params.g, &BigUint::from(4u32)).unwrap();
+        assert!(diffie_hellman(&params, &BigUint::from(3u32), &bob_pub, EcdhPrimitive::Standard).is_ok());
+
+        // (16, 0) has order 2, (3, 9) order 22
+        for point in [(16u32, 0u32), (3, 9)] {
+            let point = CurvePoint::Coordinate(BigUint::from(point.0), BigUint::from(point.1));
+            assert!(params.curve.is_on_curve(&point));
+            assert!(diffie_hellman(&params, &BigUint::from(3u32), &point, EcdhPrimitive::Standard).is_err());
+        }
+
+        // the cofactor primitive gives Z = x of 2 * 3 * 4 G to both parties
+        let alice_pub = params.curve.scalar_mul(&params.g, &BigUint::from(3u32)).unwrap();
+        let alice = diffie_hellman(&params, &BigUint::from(3u32), &bob_pub, EcdhPrimitive::Cofactor).unwrap();
+        let bob = diffie_hellman(&params, &BigUint::from(4u32), &alice_pub, EcdhPrimitive::Cofactor).unwrap();
+        assert_eq!(alice, bob);
+        let expected = params.curve.scalar_mul(&params.g, &BigUint::from(24u32)).unwrap();
+        assert_eq!(alice.as_bytes(), x_bytes(&params, &expected));
+        assert_ne!(alice, diffie_hellman(&params, &BigUint::from(3u32), &bob_pub, EcdhPrimitive::Standard).unwrap());
+
+        // it rejects the point of order 2, and maps (3, 9) into the subgroup
+        let small = CurvePoint::Coordinate(BigUint::from(16u32), BigUint::from(0u32));
+        assert!(diffie_hellman(&params, &BigUint::from(3u32), &small, EcdhPrimitive::Cofactor).is_err());
+        let mixed = CurvePoint::Coordinate(BigUint::from(3u32), BigUint::from(9u32));
+        let shared = diffie_hellman(&params, &BigUint::from(3u32), &mixed, EcdhPrimitive::Cofactor).unwrap();
+        let expected = params.curve.scalar_mul(&mixed, &BigUint::from(6u32)).unwrap();
+        assert_eq!(shared.as_bytes(), x_bytes(&params, &expected));
+        let off_curve = CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32));
+        assert!(diffie_hellman(&params, &BigUint::from(3u32), &off_curve, EcdhPrimitive::Cofactor).is_err());
+    }
+}
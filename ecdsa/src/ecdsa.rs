@@ -1,11 +1,17 @@
 use ec_core::*;
 use num_bigint::{BigUint, RandBigInt};
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use sha256::digest;
 
+use crate::rfc6979;
+use crate::signature::{RecoverableSignature, Signature};
+
+#[allow(clippy::upper_case_acronyms)]
 pub struct ECDSA {
-    elliptic_curve: EllipticCurve,
-    a_gen: CurvePoint,
-    q_order: BigUint,
+    pub elliptic_curve: EllipticCurve,
+    pub a_gen: CurvePoint,
+    pub q_order: BigUint,
 }
 
 #[derive(Debug)]
@@ -14,6 +20,16 @@ pub enum ECDSAErrors {
     OperationFailure(String),
 }
 
+impl From<CurveParams> for ECDSA {
+    fn from(params: CurveParams) -> Self {
+        ECDSA {
+            elliptic_curve: params.curve,
+            a_gen: params.g,
+            q_order: params.n,
+        }
+    }
+}
+
 impl ECDSA {
     // Generates: d, B where B = d A
     pub fn generate_key_pair(&self) -> Result<(BigUint, CurvePoint), ECDSAErrors> {
@@ -28,7 +44,7 @@ impl ECDSA {
 
     pub fn generate_pub_key(&self, priv_key: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
         self.elliptic_curve
-            .scalar_mul(&self.a_gen, priv_key)
+            .scalar_mul_strict(&self.a_gen, priv_key)
             .map_err(|_| ECDSAErrors::OperationFailure("Error computing priv_key * a_gen".into()))
     }
 
@@ -38,6 +54,12 @@ impl ECDSA {
         rng.gen_biguint_range(&BigUint::from(1u32), max)
     }
 
+    // value mod q
+    fn scalar(&self, value: &BigUint) -> Result<Scalar, ECDSAErrors> {
+        Scalar::new(value.clone(), &self.q_order)
+            .map_err(|_| ECDSAErrors::BadArgument("The order of the EC group has to be greater than 1".into()))
+    }
+
     ///
     /// R = k A -> take `r = x` component
     /// s = (hash(message) + d * r) * k^(-1) mod q
@@ -47,7 +69,177 @@ impl ECDSA {
         hash: &BigUint,
         priv_key: &BigUint,
         k_random: &BigUint,
-    ) -> Result<(BigUint, BigUint), ECDSAErrors> {
+    ) -> Result<Signature, ECDSAErrors> {
+        let (signature, _) = self.sign_with_r_point(hash, priv_key, k_random)?;
+        Ok(signature)
+    }
+
+    ///
+    /// Same as `sign` but also returns the recovery id needed to recover the
+    /// public key from the signature:
+    ///
+    /// recovery_id = (y_R is odd) | (x_R >= q) << 1
+    ///
+    pub fn sign_recoverable(
+        &self,
+        hash: &BigUint,
+        priv_key: &BigUint,
+        k_random: &BigUint,
+    ) -> Result<RecoverableSignature, ECDSAErrors> {
+        let (signature, r_point) = self.sign_with_r_point(hash, priv_key, k_random)?;
+
+        if let CurvePoint::Coordinate(x, y) = r_point {
+            let mut recovery_id = u8::from(y.bit(0));
+            if x >= self.q_order {
+                recovery_id |= 2;
+            }
+            return Ok(RecoverableSignature::new(signature, recovery_id));
+        }
+
+        Err(ECDSAErrors::OperationFailure(
+            "Result k_random * a_gen is the identity".into(),
+        ))
+    }
+
+    ///
+    /// Signs a message digest computed by the caller, e.g. a transaction
+    /// hash, with a RFC 6979 deterministic nonce. The digest is truncated to
+    /// the bit size of the order and is not hashed again:
+    ///
+    /// `hash = bits2int(digest) mod q`
+    ///
+    /// The nonce is derived with HMAC-SHA256, see `sign_prehash_with` for
+    /// digests of other hash functions.
+    ///
+    pub fn sign_prehash(&self, digest: &[u8], priv_key: &BigUint) -> Result<Signature, ECDSAErrors> {
+        self.sign_prehash_with::<Sha256>(digest, priv_key)
+    }
+
+    ///
+    /// Same as `sign_prehash` with the RFC 6979 nonce derived with HMAC over
+    /// `D`, the hash function which produced the digest.
+    ///
+    pub fn sign_prehash_with<D: Digest + BlockSizeUser>(
+        &self,
+        digest: &[u8],
+        priv_key: &BigUint,
+    ) -> Result<Signature, ECDSAErrors> {
+        let hash = self.digest_to_int(digest)?;
+        let k = rfc6979::generate_k_with::<D>(&self.q_order, priv_key, digest);
+        self.sign(&hash, priv_key, &k)
+    }
+
+    ///
+    /// Same as `sign_prehash` with additional data mixed into the nonce
+    /// (RFC 6979 section 3.6), e.g. 32 random bytes so that a fault or a side
+    /// channel on the nonce computation does not hit the same `k` twice. An
+    /// empty `extra_entropy` gives the signature of `sign_prehash`.
+    ///
+    pub fn sign_prehash_with_extra_entropy(
+        &self,
+        digest: &[u8],
+        priv_key: &BigUint,
+        extra_entropy: &[u8],
+    ) -> Result<Signature, ECDSAErrors> {
+        let hash = self.digest_to_int(digest)?;
+        let k = rfc6979::generate_k_with_extra::<Sha256>(&self.q_order, priv_key, digest, extra_entropy);
+        self.sign(&hash, priv_key, &k)
+    }
+
+    ///
+    /// Signs as Bitcoin Core does: a low-S signature whose `r` is low too
+    /// (see `Signature::is_low_r`), the nonce being derived again with a
+    /// counter as extra entropy (32 bytes, little endian) until `r` is low.
+    /// The first attempt is the signature of `sign_prehash`, normalized, and
+    /// it takes two attempts on average.
+    ///
+    pub fn sign_prehash_low_r(&self, digest: &[u8], priv_key: &BigUint) -> Result<Signature, ECDSAErrors> {
+        let mut signature = self.sign_prehash(digest, priv_key)?;
+        let mut counter = 0u32;
+        while !signature.is_low_r(&self.q_order) {
+            counter += 1;
+            let mut extra_entropy = [0u8; 32];
+            extra_entropy[..4].copy_from_slice(&counter.to_le_bytes());
+            signature = self.sign_prehash_with_extra_entropy(digest, priv_key, &extra_entropy)?;
+        }
+        Ok(signature.normalize_s(&self.q_order))
+    }
+
+    ///
+    /// Hashes the message with `message_digest` and signs the digest, see
+    /// `sign_prehash`.
+    ///
+    pub fn sign_message(&self, message: &[u8], priv_key: &BigUint) -> Result<Signature, ECDSAErrors> {
+        match self.q_order.bits().div_ceil(8) {
+            0..=32 => self.sign_message_with::<Sha256>(message, priv_key),
+            33..=48 => self.sign_message_with::<Sha384>(message, priv_key),
+            _ => self.sign_message_with::<Sha512>(message, priv_key),
+        }
+    }
+
+    ///
+    /// Hashes the message with `D` chosen by the caller, e.g. `Sha512` or a
+    /// BLAKE2 hasher, and signs the digest.
+    ///
+    pub fn sign_message_with<D: Digest + BlockSizeUser>(
+        &self,
+        message: &[u8],
+        priv_key: &BigUint,
+    ) -> Result<Signature, ECDSAErrors> {
+        self.sign_prehash_with::<D>(&D::digest(message), priv_key)
+    }
+
+    ///
+    /// The digest of a message signed by `sign_message`, with the SHA-2
+    /// function matching the size of the order as in the RustCrypto curve
+    /// crates: SHA-256 up to 256 bits, SHA-384 up to 384 bits, SHA-512 above.
+    ///
+    pub fn message_digest(&self, message: &[u8]) -> Vec<u8> {
+        match self.q_order.bits().div_ceil(8) {
+            0..=32 => Sha256::digest(message).to_vec(),
+            33..=48 => Sha384::digest(message).to_vec(),
+            _ => Sha512::digest(message).to_vec(),
+        }
+    }
+
+    ///
+    /// The integer signed for a message digest, per FIPS 186-4 section 6.4:
+    /// the leftmost `bitlen(q)` bits of the digest, reduced modulo `q`. A
+    /// digest shorter than the order (SHA-512 on P-521) is used whole, a
+    /// longer one (SHA-512 on P-256) is truncated:
+    ///
+    /// `hash = bits2int(digest) mod q`
+    ///
+    pub fn digest_to_int(&self, digest: &[u8]) -> Result<BigUint, ECDSAErrors> {
+        if digest.is_empty() {
+            return Err(ECDSAErrors::BadArgument("Digest can not be empty".into()));
+        }
+
+        Ok(rfc6979::bits2int(digest, &self.q_order) % &self.q_order)
+    }
+
+    fn sign_with_r_point(
+        &self,
+        hash: &BigUint,
+        priv_key: &BigUint,
+        k_random: &BigUint,
+    ) -> Result<(Signature, CurvePoint), ECDSAErrors> {
+        self.sign_with_generator_mul(hash, priv_key, k_random, |k| {
+            self.elliptic_curve
+                .scalar_mul_strict(&self.a_gen, k)
+                .map_err(|_| ECDSAErrors::OperationFailure("Error computing k_random * a_gen".into()))
+        })
+    }
+
+    // `sign_with_r_point` computing `k_random * a_gen` with `mul_generator`,
+    // see `Context`
+    pub(crate) fn sign_with_generator_mul(
+        &self,
+        hash: &BigUint,
+        priv_key: &BigUint,
+        k_random: &BigUint,
+        mul_generator: impl FnOnce(&BigUint) -> Result<CurvePoint, ECDSAErrors>,
+    ) -> Result<(Signature, CurvePoint), ECDSAErrors> {
         if *hash >= self.q_order {
             return Err(ECDSAErrors::BadArgument(
                 "Hash is bigger than the order of the EC group".into(),
@@ -66,32 +258,30 @@ impl ECDSA {
             ));
         }
 
-        let r_point = self
-            .elliptic_curve
-            .scalar_mul(&self.a_gen, k_random)
-            .map_err(|_| {
-                ECDSAErrors::OperationFailure("Error computing k_random * a_gen".into())
-            })?;
-
-        if let CurvePoint::Coordinate(r, _) = r_point {
-            let s = multiplicate(&r, priv_key, &self.q_order).map_err(|_| {
-                ECDSAErrors::OperationFailure("Error multiplying r * priv_key".into())
-            })?;
+        let r_point = mul_generator(k_random)?;
 
-            let s = add(&s, hash, &self.q_order).map_err(|_| {
-                ECDSAErrors::OperationFailure("Error adding hash + r * priv_key".into())
-            })?;
+        if let CurvePoint::Coordinate(x, _) = &r_point {
+            let r = x % &self.q_order;
+            if r == BigUint::from(0u32) {
+                return Err(ECDSAErrors::OperationFailure(
+                    "Result r is zero, a different `k` has to be used".into(),
+                ));
+            }
 
-            let k_inv = inverse_multiplicate_prime(k_random, &self.q_order)
+            let k_inv = self
+                .scalar(k_random)?
+                .invert()
                 .map_err(|_| ECDSAErrors::OperationFailure("Error computing k_inv".into()))?;
+            let s = (self.scalar(hash)? + self.scalar(&r)? * self.scalar(priv_key)?) * k_inv;
+            let s = s.into_value();
 
-            let s = multiplicate(&s, &k_inv, &self.q_order).map_err(|_| {
-                ECDSAErrors::OperationFailure(
-                    "Error computing (hash + r * priv_key) * k_inv".into(),
-                )
-            })?;
+            if s == BigUint::from(0u32) {
+                return Err(ECDSAErrors::OperationFailure(
+                    "Result s is zero, a different `k` has to be used".into(),
+                ));
+            }
 
-            return Ok((r, s));
+            return Ok((Signature::new(r, s), r_point));
         }
 
         Err(ECDSAErrors::OperationFailure(
@@ -112,7 +302,22 @@ impl ECDSA {
         &self,
         hash: &BigUint,
         pub_key: &CurvePoint,
-        signature: &(BigUint, BigUint),
+        signature: &Signature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.verify_with_double_mul(hash, signature, |u1, u2| {
+            self.elliptic_curve
+                .double_scalar_mul(u1, &self.a_gen, u2, pub_key)
+                .map_err(|_| ECDSAErrors::OperationFailure("Error in u1 * a_gen + u2 * pub_key".into()))
+        })
+    }
+
+    // `verify` computing `u1 * a_gen + u2 * pub_key` with `double_mul`, see
+    // `Context`
+    pub(crate) fn verify_with_double_mul(
+        &self,
+        hash: &BigUint,
+        signature: &Signature,
+        double_mul: impl FnOnce(&BigUint, &BigUint) -> Result<CurvePoint, ECDSAErrors>,
     ) -> Result<bool, ECDSAErrors> {
         if *hash >= self.q_order {
             return Err(ECDSAErrors::BadArgument(
@@ -120,40 +325,164 @@ impl ECDSA {
             ));
         }
 
-        let (r, s) = signature;
+        let Signature { r, s } = signature;
+        if *r >= self.q_order || *s >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "r and s have to be smaller than q (EC group order)".into(),
+            ));
+        }
 
-        let s_inv = inverse_multiplicate_prime(s, &self.q_order)
+        let s_inv = self
+            .scalar(s)?
+            .invert()
             .map_err(|_| ECDSAErrors::OperationFailure("Error computing s_inv".into()))?;
+        let u1 = (self.scalar(hash)? * &s_inv).into_value();
+        let u2 = (self.scalar(r)? * &s_inv).into_value();
 
-        let u1 = multiplicate(&s_inv, hash, &self.q_order).map_err(|_| {
-            ECDSAErrors::OperationFailure("Error multiplying s_inv and hash".into())
-        })?;
+        let p = double_mul(&u1, &u2)?;
 
-        let u2 = multiplicate(&s_inv, r, &self.q_order)
-            .map_err(|_| ECDSAErrors::OperationFailure("Error multiplying s_inv and r".into()))?;
+        if let CurvePoint::Coordinate(xp, _) = p {
+            return Ok(xp % &self.q_order == *r);
+        }
 
-        let u1a = self
-            .elliptic_curve
-            .scalar_mul(&self.a_gen, &u1)
-            .map_err(|_| ECDSAErrors::OperationFailure("Error in u1 * a_gen".into()))?;
+        Err(ECDSAErrors::OperationFailure(
+            "Result is the identity".into(),
+        ))
+    }
 
-        let u2b = self
-            .elliptic_curve
-            .scalar_mul(pub_key, &u2)
-            .map_err(|_| ECDSAErrors::OperationFailure("Error in u2 * pub_key".into()))?;
+    ///
+    /// Same as `verify` but also rejects "high-S" signatures (`s > q / 2`),
+    /// which is what Bitcoin and Ethereum consensus rules expect in order to
+    /// avoid signature malleability.
+    ///
+    pub fn verify_strict(
+        &self,
+        hash: &BigUint,
+        pub_key: &CurvePoint,
+        signature: &Signature,
+    ) -> Result<bool, ECDSAErrors> {
+        if signature.s > &self.q_order >> 1 {
+            return Ok(false);
+        }
 
-        let p = self
+        self.verify(hash, pub_key, signature)
+    }
+
+    ///
+    /// Verifies a signature over a message digest computed by the caller, see
+    /// `sign_prehash`.
+    ///
+    pub fn verify_prehash(
+        &self,
+        digest: &[u8],
+        pub_key: &CurvePoint,
+        signature: &Signature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.verify(&self.digest_to_int(digest)?, pub_key, signature)
+    }
+
+    ///
+    /// Verifies a signature over the `message_digest` of a message, see
+    /// `sign_message`.
+    ///
+    pub fn verify_message(
+        &self,
+        message: &[u8],
+        pub_key: &CurvePoint,
+        signature: &Signature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.verify_prehash(&self.message_digest(message), pub_key, signature)
+    }
+
+    ///
+    /// Verifies a signature over the digest of a message with `D`, see
+    /// `sign_message_with`.
+    ///
+    pub fn verify_message_with<D: Digest>(
+        &self,
+        message: &[u8],
+        pub_key: &CurvePoint,
+        signature: &Signature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.verify_prehash(&D::digest(message), pub_key, signature)
+    }
+
+    ///
+    /// Recovers the public key that produced a recoverable signature:
+    ///
+    /// R = (r + (recovery_id >> 1) * q, y) with y parity = recovery_id & 1
+    /// B = r^(-1) * (s R - hash(message) A)
+    ///
+    pub fn recover_public_key(
+        &self,
+        hash: &BigUint,
+        signature: &RecoverableSignature,
+    ) -> Result<CurvePoint, ECDSAErrors> {
+        if *hash >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Hash value >= q (EC group order)".to_string(),
+            ));
+        }
+
+        if signature.recovery_id > 3 {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Invalid recovery id: {}",
+                signature.recovery_id
+            )));
+        }
+
+        let Signature { r, s } = &signature.signature;
+        let zero = BigUint::from(0u32);
+        if *r == zero || *r >= self.q_order || *s == zero || *s >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Signature components must be in [1, q - 1]".into(),
+            ));
+        }
+
+        let x = if signature.recovery_id & 2 != 0 {
+            r + &self.q_order
+        } else {
+            r.clone()
+        };
+        let r_point = self.lift_x(&x, signature.recovery_id & 1 == 1)?;
+
+        let r_inv = inverse_multiplicate_prime(r, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing r_inv".into()))?;
+
+        // u1 = -hash * r^(-1) mod q
+        let u1 = inverse_add(hash, &self.q_order)
+            .and_then(|minus_hash| multiplicate(&minus_hash, &r_inv, &self.q_order))
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing -hash * r_inv".into()))?;
+
+        // u2 = s * r^(-1) mod q
+        let u2 = multiplicate(s, &r_inv, &self.q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error multiplying s and r_inv".into()))?;
+
+        let pub_key = self
             .elliptic_curve
-            .add(&u1a, &u2b)
-            .map_err(|_| ECDSAErrors::OperationFailure("Error in u1a + u2b".into()))?;
+            .double_scalar_mul(&u1, &self.a_gen, &u2, &r_point)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error in u1 * a_gen + u2 * R".into()))?;
 
-        if let CurvePoint::Coordinate(xp, _) = p {
-            return Ok(xp == *r);
+        if pub_key == CurvePoint::Identity {
+            return Err(ECDSAErrors::OperationFailure(
+                "Recovered public key is the identity".into(),
+            ));
         }
 
-        Err(ECDSAErrors::OperationFailure(
-            "Result is the identity".into(),
-        ))
+        Ok(pub_key)
+    }
+
+    // (x, y) such that y^2 = x^3 + a x + b and y has the requested parity
+    pub(crate) fn lift_x(&self, x: &BigUint, odd: bool) -> Result<CurvePoint, ECDSAErrors> {
+        let ec = &self.elliptic_curve;
+        if *x >= ec.p {
+            return Err(ECDSAErrors::BadArgument(
+                "x component is bigger than the EC field prime".into(),
+            ));
+        }
+
+        CurvePoint::lift_x(x, odd, ec)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing sqrt(x^3 + a x + b)".into()))
     }
 
     /// 0 < hash < max
@@ -161,7 +490,7 @@ impl ECDSA {
         let digest = digest(message);
         let hash_bytes = hex::decode(digest).expect("Could not convert hash to Vec<u8>");
         let hash = BigUint::from_bytes_be(&hash_bytes);
-        let hash = hash.modpow(&BigUint::from(1u32), &(max - BigUint::from(1u32)));
+        let hash = hash % (max - BigUint::from(1u32));
         hash + BigUint::from(1u32)
     }
 }
@@ -286,9 +615,9 @@ mod test {
             .sign(&hash, &priv_key, &k_random)
             .expect("Could not sign");
 
-        let (r, s) = signature;
+        let Signature { r, s } = signature;
 
-        let tempered_signature = (
+        let tempered_signature = Signature::new(
             (r + BigUint::from(1u32)).modpow(&BigUint::from(1u32), &ecdsa.q_order),
             s,
         );
@@ -511,9 +840,9 @@ mod test {
         let signature = ecdsa
             .sign(&hash, &priv_key, &k_random)
             .expect("Could not sign");
-        let (r, s) = signature;
+        let Signature { r, s } = signature;
 
-        let tempered_signature = (
+        let tempered_signature = Signature::new(
             (r + BigUint::from(1u32)).modpow(&BigUint::from(1u32), &ecdsa.q_order),
             s,
         );
@@ -527,4 +856,296 @@ mod test {
             "Verification should have failed due to tempered signature"
         );
     }
+
+    fn secp256k1() -> ECDSA {
+        ECDSA::from(ec_core::curves::secp256k1())
+    }
+
+    #[test]
+    fn test_secp256_recover_public_key() {
+        let ecdsa = secp256k1();
+
+        let priv_key = BigUint::parse_bytes(
+            b"483ADB7726A3C4655DA4FBFC0E1208A8F017B448A68554199C47D08FFB10E4B9",
+            16,
+        )
+            .expect("Could not convert hex to private key");
+
+        let pub_key = ecdsa
+            .generate_pub_key(&priv_key)
+            .expect("Could not compute PubKey");
+
+        let message = "Bob -> 1 BTC -> Alice";
+        let hash = ecdsa.generate_hash_less_than(message, &ecdsa.q_order);
+
+        for k_random in [
+            b"19BE666EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B15E81798".as_slice(),
+            b"0000000000000000000000000000000000000000000000000000000000000003".as_slice(),
+        ] {
+            let k_random = BigUint::parse_bytes(k_random, 16).expect("Could not convert k");
+
+            let signature = ecdsa
+                .sign_recoverable(&hash, &priv_key, &k_random)
+                .expect("Could not sign");
+
+            let recovered = ecdsa
+                .recover_public_key(&hash, &signature)
+                .expect("Could not recover");
+            assert_eq!(recovered, pub_key);
+
+            let verify_result = ecdsa
+                .verify(&hash, &recovered, &signature.into())
+                .expect("Could not verify");
+            assert!(verify_result, "Verification should have succeed");
+        }
+    }
+
+    #[test]
+    fn test_secp256_recover_public_key_wrong_input() {
+        let ecdsa = secp256k1();
+
+        let priv_key = BigUint::from(1234567u32);
+        let pub_key = ecdsa
+            .generate_pub_key(&priv_key)
+            .expect("Could not compute PubKey");
+
+        let hash = ecdsa.generate_hash_less_than("Bob -> 1 BTC -> Alice", &ecdsa.q_order);
+        let signature = ecdsa
+            .sign_recoverable(&hash, &priv_key, &BigUint::from(987654321u32))
+            .expect("Could not sign");
+
+        // a tempered message recovers a different key
+        let hash = ecdsa.generate_hash_less_than("Bob -> 2 BTC -> Alice", &ecdsa.q_order);
+        let recovered = ecdsa
+            .recover_public_key(&hash, &signature)
+            .expect("Could not recover");
+        assert_ne!(recovered, pub_key);
+
+        let mut bad_id = signature.clone();
+        bad_id.recovery_id = 4;
+        assert!(ecdsa.recover_public_key(&hash, &bad_id).is_err());
+    }
+
+    #[test]
+    fn test_verify_strict_rejects_high_s() {
+        let elliptic_curve = EllipticCurve {
+            a: BigUint::from(2u32),
+            b: BigUint::from(2u32),
+            p: BigUint::from(17u32),
+        };
+
+        let a_gen = CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32));
+
+        let q_order = BigUint::from(19u32);
+
+        let ecdsa = ECDSA {
+            elliptic_curve,
+            a_gen,
+            q_order,
+        };
+
+        let priv_key = BigUint::from(7u32);
+        let pub_key = ecdsa
+            .generate_pub_key(&priv_key)
+            .expect("Could not compute PubKey");
+
+        let hash = ecdsa.generate_hash_less_than("Bob -> 1 BTC -> Alice", &ecdsa.q_order);
+
+        for k in 1u32..19 {
+            let signature = match ecdsa.sign(&hash, &priv_key, &BigUint::from(k)) {
+                Ok(signature) => signature,
+                Err(_) => continue,
+            };
+
+            let low = signature.normalize_s(&ecdsa.q_order);
+            let high = Signature::new(low.r.clone(), &ecdsa.q_order - &low.s);
+
+            // both forms are valid under the lax rules
+            assert!(ecdsa.verify(&hash, &pub_key, &low).unwrap());
+            assert!(ecdsa.verify(&hash, &pub_key, &high).unwrap());
+
+            assert!(ecdsa.verify_strict(&hash, &pub_key, &low).unwrap());
+            assert!(!ecdsa.verify_strict(&hash, &pub_key, &high).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_unreduced_signature() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let priv_key = BigUint::from(7u32);
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+        let hash = ecdsa.generate_hash_less_than("Bob -> 1 BTC -> Alice", &ecdsa.q_order);
+        let signature = ecdsa.sign(&hash, &priv_key, &BigUint::from(11u32)).unwrap();
+
+        // s + q is congruent to s but is not a valid encoding
+        let unreduced = Signature::new(signature.r.clone(), &signature.s + &ecdsa.q_order);
+        assert!(ecdsa.verify(&hash, &pub_key, &signature).unwrap());
+        assert!(ecdsa.verify(&hash, &pub_key, &unreduced).is_err());
+    }
+
+    // First vector of the FIPS 186-4 `SigGen.txt` known answer tests, where `m`
+    // is the prehashed message.
+    #[test]
+    fn test_sign_prehash_and_message() {
+        // RFC 6979 A.2.5, P-256 with SHA-256, message "sample"
+        let ecdsa = ECDSA::from(ec_core::curves::p256());
+        let priv_key = BigUint::parse_bytes(
+            b"c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            16,
+        )
+        .unwrap();
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+        let expected = Signature::new(
+            BigUint::parse_bytes(b"efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716", 16).unwrap(),
+            BigUint::parse_bytes(b"f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8", 16).unwrap(),
+        );
+
+        let digest = Sha256::digest(b"sample");
+        assert_eq!(ecdsa.sign_message(b"sample", &priv_key).unwrap(), expected);
+        assert_eq!(ecdsa.sign_prehash(&digest, &priv_key).unwrap(), expected);
+
+        assert!(ecdsa.verify_message(b"sample", &pub_key, &expected).unwrap());
+        assert!(ecdsa.verify_prehash(&digest, &pub_key, &expected).unwrap());
+
+        // empty extra entropy is plain RFC 6979
+        assert_eq!(ecdsa.sign_prehash_with_extra_entropy(&digest, &priv_key, &[]).unwrap(), expected);
+        let hardened = ecdsa.sign_prehash_with_extra_entropy(&digest, &priv_key, &[0x5a; 32]).unwrap();
+        assert_ne!(hardened, expected);
+        assert!(ecdsa.verify_prehash(&digest, &pub_key, &hardened).unwrap());
+
+        // the prehash is not hashed again
+        assert!(!ecdsa.verify_message(&digest, &pub_key, &expected).unwrap());
+
+        // RFC 6979 A.2.5, SHA-512
+        let expected = Signature::new(
+            BigUint::parse_bytes(b"8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00", 16).unwrap(),
+            BigUint::parse_bytes(b"2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe", 16).unwrap(),
+        );
+        assert_eq!(ecdsa.sign_message_with::<Sha512>(b"sample", &priv_key).unwrap(), expected);
+        assert!(ecdsa.verify_message_with::<Sha512>(b"sample", &pub_key, &expected).unwrap());
+        assert!(!ecdsa.verify_message_with::<Sha256>(b"sample", &pub_key, &expected).unwrap());
+
+        assert_eq!(ECDSA::from(ec_core::curves::p384()).message_digest(b"sample").len(), 48);
+        assert_eq!(ECDSA::from(ec_core::curves::p521()).message_digest(b"sample").len(), 64);
+
+        assert!(ecdsa.sign_prehash(&[], &priv_key).is_err());
+        assert!(ecdsa.verify_prehash(&[], &pub_key, &expected).is_err());
+    }
+
+    #[test]
+    fn test_sign_prehash_low_r() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let priv_key = BigUint::from(0xc0ffeeu32);
+        let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+
+        let mut ground = 0;
+        for i in 0..16u8 {
+            let digest = Sha256::digest([i]);
+            let signature = ecdsa.sign_prehash_low_r(&digest, &priv_key).unwrap();
+            assert!(signature.is_low_r(&ecdsa.q_order) && signature.is_low_s(&ecdsa.q_order));
+            assert!(signature.to_der().len() <= 70);
+            assert!(ecdsa.verify_prehash(&digest, &pub_key, &signature).unwrap());
+            assert_eq!(signature, ecdsa.sign_prehash_low_r(&digest, &priv_key).unwrap());
+
+            let plain = ecdsa.sign_prehash(&digest, &priv_key).unwrap();
+            if plain.is_low_r(&ecdsa.q_order) {
+                assert_eq!(signature, plain.normalize_s(&ecdsa.q_order));
+            } else {
+                let extra_entropy = [&[1u8, 0, 0, 0][..], &[0; 28]].concat();
+                let retry = ecdsa.sign_prehash_with_extra_entropy(&digest, &priv_key, &extra_entropy).unwrap();
+                if retry.is_low_r(&ecdsa.q_order) {
+                    assert_eq!(signature, retry.normalize_s(&ecdsa.q_order));
+                }
+                ground += 1;
+            }
+        }
+        assert!(ground > 0);
+    }
+
+    #[test]
+    fn test_digest_to_int_truncation() {
+        let digest = Sha512::digest(b"sample");
+
+        // 512 bits digest, 256 bits order: the leftmost 256 bits
+        let p256 = ECDSA::from(ec_core::curves::p256());
+        assert_eq!(
+            p256.digest_to_int(&digest).unwrap(),
+            BigUint::from_bytes_be(&digest[..32]) % &p256.q_order
+        );
+
+        // 512 bits digest, 521 bits order: the whole digest
+        let p521 = ECDSA::from(ec_core::curves::p521());
+        assert_eq!(p521.digest_to_int(&digest).unwrap(), BigUint::from_bytes_be(&digest));
+
+        // 528 bits digest, 521 bits order: shifted by 7 bits
+        let oversized = [0xffu8; 66];
+        let expected = ((BigUint::from(1u32) << 521) - BigUint::from(1u32)) % &p521.q_order;
+        assert_eq!(p521.digest_to_int(&oversized).unwrap(), expected);
+
+        let priv_key = BigUint::from(0xC0FFEEu32);
+        let pub_key = p521.generate_pub_key(&priv_key).unwrap();
+        for digest in [&digest[..], &oversized[..]] {
+            let signature = p521.sign_prehash(digest, &priv_key).unwrap();
+            assert!(p521.verify_prehash(digest, &pub_key, &signature).unwrap());
+        }
+        let signature = p521.sign_message(b"sample", &priv_key).unwrap();
+        assert!(p521.verify_message(b"sample", &pub_key, &signature).unwrap());
+        assert!(p521.verify_message_with::<Sha512>(b"sample", &pub_key, &signature).unwrap());
+    }
+
+    fn check_sign_known_answer(params: ec_core::CurveParams, vector: [&str; 5]) {
+        let [d, k, m, r, s] = vector.map(|value| {
+            BigUint::parse_bytes(value.as_bytes(), 16).expect("Could not convert hex")
+        });
+
+        let ecdsa = ECDSA::from(params);
+
+        let signature = ecdsa.sign(&m, &d, &k).expect("Could not sign");
+        assert_eq!(signature, Signature::new(r, s));
+
+        let pub_key = ecdsa.generate_pub_key(&d).expect("Could not compute PubKey");
+        assert!(ecdsa.verify(&m, &pub_key, &signature).expect("Could not verify"));
+    }
+
+    #[test]
+    fn test_p256_sign_known_answer() {
+        check_sign_known_answer(
+            ec_core::curves::p256(),
+            [
+                "519b423d715f8b581f4fa8ee59f4771a5b44c8130b4e3eacca54a56dda72b464",
+                "94a1bbb14b906a61a280f245f9e93c7f3b4a6247824f5d33b9670787642a68de",
+                "44acf6b7e36c1342c2c5897204fe09504e1e2efb1a900377dbc4e7a6a133ec56",
+                "f3ac8061b514795b8843e3d6629527ed2afd6b1f6a555a7acabb5e6f79c8c2ac",
+                "8bf77819ca05a6b2786c76262bf7371cef97b218e96f175a3ccdda2acc058903",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_p384_sign_known_answer() {
+        check_sign_known_answer(
+            ec_core::curves::p384(),
+            [
+                "201b432d8df14324182d6261db3e4b3f46a8284482d52e370da41e6cbdf45ec2952f5db7ccbce3bc29449f4fb080ac97",
+                "dcedabf85978e090f733c6e16646fa34df9ded6e5ce28c6676a00f58a25283db8885e16ce5bf97f917c81e1f25c9c771",
+                "31a452d6164d904bb5724c878280231eae705c29ce9d4bc7d58e020e1085f17eebcc1a38f0ed0bf2b344d81fbd896825",
+                "50835a9251bad008106177ef004b091a1e4235cd0da84fff54542b0ed755c1d6f251609d14ecf18f9e1ddfe69b946e32",
+                "0475f3d30c6463b646e8d3bf2455830314611cbde404be518b14464fdb195fdcc92eb222e61f426a4a592c00a6a89721",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_p521_sign_known_answer() {
+        check_sign_known_answer(
+            ec_core::curves::p521(),
+            [
+                "00f749d32704bc533ca82cef0acf103d8f4fba67f08d2678e515ed7db886267ffaf02fab0080dca2359b72f574ccc29a0f218c8655c0cccf9fee6c5e567aa14cb926",
+                "003af5ab6caa29a6de86a5bab9aa83c3b16a17ffcd52b5c60c769be3053cdddeac60812d12fecf46cfe1f3db9ac9dcf881fcec3f0aa733d4ecbb83c7593e864c6df1",
+                "000065f83408092261bda599389df03382c5be01a81fe00a36f3f4bb6541263f801627c440e50809712b0cace7c217e6e5051af81de9bfec3204dcd63c4f9a741047",
+                "004de826ea704ad10bc0f7538af8a3843f284f55c8b946af9235af5af74f2b76e099e4bc72fd79d28a380f8d4b4c919ac290d248c37983ba05aea42e2dd79fdd33e8",
+                "0087488c859a96fea266ea13bf6d114c429b163be97a57559086edb64aed4a18594b46fb9efc7fd25d8b2de8f09ca0587f54bd287299f47b2ff124aac566e8ee3b43",
+            ],
+        );
+    }
 }
@@ -0,0 +1,226 @@
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+use crate::ecdsa::ECDSAErrors;
+
+/// Byte length of Ed25519 secret keys, public keys and of each half of a signature.
+pub const KEY_SIZE: usize = 32;
+
+///
+/// EdDSA over a twisted Edwards curve as specified in RFC 8032, instantiated
+/// as Ed25519 (SHA-512 and edwards25519):
+///
+/// - `h = SHA512(secret)`, `s = clamp(h[..32])`, `A = s B`
+/// - `r = SHA512(h[32..] || M) mod L`, `R = r B`
+/// - `k = SHA512(R || A || M) mod L`, `S = r + k s mod L`
+/// - signature = `R || S`
+///
+#[allow(clippy::upper_case_acronyms)]
+pub struct EdDSA {
+    pub edwards_curve: EdwardsCurve,
+    pub b_gen: EdwardsPoint,
+    pub l_order: BigUint,
+}
+
+impl EdDSA {
+    pub fn ed25519() -> Self {
+        let params = ec_core::curves::ed25519();
+
+        EdDSA {
+            edwards_curve: params.curve,
+            b_gen: params.g,
+            l_order: params.n,
+        }
+    }
+
+    ///
+    /// Computes the encoded public key `A = s B` of a 32 bytes secret key.
+    ///
+    pub fn generate_pub_key(&self, secret: &[u8; KEY_SIZE]) -> Result<[u8; KEY_SIZE], ECDSAErrors> {
+        let (s, _) = expand_secret(secret);
+        let a = self.mul_base(&s)?;
+        self.encode(&a)
+    }
+
+    ///
+    /// Signs a message with a 32 bytes secret key, returning `R || S`.
+    ///
+    pub fn sign(&self, message: &[u8], secret: &[u8; KEY_SIZE]) -> Result<[u8; 2 * KEY_SIZE], ECDSAErrors> {
+        let (s, prefix) = expand_secret(secret);
+        let a = self.encode(&self.mul_base(&s)?)?;
+
+        let r = self.hash_to_scalar(&[&prefix, message]);
+        let r_point = self.encode(&self.mul_base(&r)?)?;
+
+        let k = self.hash_to_scalar(&[&r_point, &a, message]);
+        let big_s = (r + k * s) % &self.l_order;
+
+        let mut signature = [0u8; 2 * KEY_SIZE];
+        signature[..KEY_SIZE].copy_from_slice(&r_point);
+        signature[KEY_SIZE..].copy_from_slice(&to_le_bytes(&big_s));
+        Ok(signature)
+    }
+
+    ///
+    /// Verifies a signature `R || S` of a message for an encoded public key
+    /// with the cofactored equation:
+    ///
+    /// `8 S B = 8 R + 8 k A`
+    ///
+    pub fn verify(
+        &self,
+        message: &[u8],
+        pub_key: &[u8; KEY_SIZE],
+        signature: &[u8; 2 * KEY_SIZE],
+    ) -> Result<bool, ECDSAErrors> {
+        let ec = &self.edwards_curve;
+        let a = ec
+            .decode(pub_key)
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid public key encoding".into()))?;
+        let r_point = ec
+            .decode(&signature[..KEY_SIZE])
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid R encoding".into()))?;
+
+        let big_s = BigUint::from_bytes_le(&signature[KEY_SIZE..]);
+        if big_s >= self.l_order {
+            return Ok(false);
+        }
+
+        let k = self.hash_to_scalar(&[&signature[..KEY_SIZE], pub_key, message]);
+
+        let sb = self.mul_base(&big_s)?;
+        let ka = ec
+            .scalar_mul(&a, &k)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing k * A".into()))?;
+        let rhs = ec
+            .add(&r_point, &ka)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing R + k * A".into()))?;
+
+        let cofactor = BigUint::from(8u32);
+        let lhs = ec.scalar_mul(&sb, &cofactor);
+        let rhs = ec.scalar_mul(&rhs, &cofactor);
+        Ok(lhs.is_ok() && lhs == rhs)
+    }
+
+    fn mul_base(&self, scalar: &BigUint) -> Result<EdwardsPoint, ECDSAErrors> {
+        self.edwards_curve
+            .scalar_mul(&self.b_gen, scalar)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing scalar * b_gen".into()))
+    }
+
+    fn encode(&self, point: &EdwardsPoint) -> Result<[u8; KEY_SIZE], ECDSAErrors> {
+        let bytes = self
+            .edwards_curve
+            .encode(point)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error encoding point".into()))?;
+
+        bytes
+            .try_into()
+            .map_err(|_| ECDSAErrors::OperationFailure("Encoded point is not 32 bytes long".into()))
+    }
+
+    // SHA512(parts) as a little endian integer mod L
+    fn hash_to_scalar(&self, parts: &[&[u8]]) -> BigUint {
+        let mut hasher = Sha512::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        BigUint::from_bytes_le(&hasher.finalize()) % &self.l_order
+    }
+}
+
+// h = SHA512(secret) -> (clamped s, prefix)
+fn expand_secret(secret: &[u8; KEY_SIZE]) -> (BigUint, [u8; KEY_SIZE]) {
+    let h = Sha512::digest(secret);
+
+    let mut s = [0u8; KEY_SIZE];
+    s.copy_from_slice(&h[..KEY_SIZE]);
+    s[0] &= 248;
+    s[31] &= 127;
+    s[31] |= 64;
+
+    let mut prefix = [0u8; KEY_SIZE];
+    prefix.copy_from_slice(&h[KEY_SIZE..]);
+
+    (BigUint::from_bytes_le(&s), prefix)
+}
+
+fn to_le_bytes(value: &BigUint) -> [u8; KEY_SIZE] {
+    let mut bytes = [0u8; KEY_SIZE];
+    let le = value.to_bytes_le();
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_hex<const N: usize>(value: &str) -> [u8; N] {
+        hex::decode(value).unwrap().try_into().unwrap()
+    }
+
+    // RFC 8032, section 7.1
+    const VECTORS: [(&str, &str, &str, &str); 3] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+    ];
+
+    #[test]
+    fn test_ed25519_known_answers() {
+        let eddsa = EdDSA::ed25519();
+
+        for (secret, pub_key, message, signature) in VECTORS {
+            let secret = from_hex::<32>(secret);
+            let message = hex::decode(message).unwrap();
+
+            assert_eq!(eddsa.generate_pub_key(&secret).unwrap(), from_hex::<32>(pub_key));
+            assert_eq!(eddsa.sign(&message, &secret).unwrap(), from_hex::<64>(signature));
+            assert!(eddsa
+                .verify(&message, &from_hex(pub_key), &from_hex(signature))
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_ed25519_verify_tempered() {
+        let eddsa = EdDSA::ed25519();
+        let secret = [7u8; 32];
+        let pub_key = eddsa.generate_pub_key(&secret).unwrap();
+
+        let message = b"Bob -> 1 BTC -> Alice";
+        let signature = eddsa.sign(message, &secret).unwrap();
+        assert!(eddsa.verify(message, &pub_key, &signature).unwrap());
+
+        assert!(!eddsa.verify(b"Bob -> 2 BTC -> Alice", &pub_key, &signature).unwrap());
+
+        let mut tempered = signature;
+        tempered[40] ^= 1;
+        assert!(!eddsa.verify(message, &pub_key, &tempered).unwrap());
+
+        // S >= L
+        let mut high_s = signature;
+        high_s[63] = 0xFF;
+        assert!(!eddsa.verify(message, &pub_key, &high_s).unwrap());
+
+        let other = eddsa.generate_pub_key(&[8u8; 32]).unwrap();
+        assert!(!eddsa.verify(message, &other, &signature).unwrap());
+    }
+}
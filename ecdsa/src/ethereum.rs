@@ -0,0 +1,279 @@
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::keys::PublicKey;
+use crate::rfc6979;
+use crate::signature::{RecoverableSignature, Signature};
+
+/// Byte length of an Ethereum address.
+pub const ADDRESS_SIZE: usize = 20;
+
+impl PublicKey {
+    ///
+    /// The Ethereum address of the public key: the last 20 bytes of the
+    /// Keccak-256 hash of the uncompressed point without its `0x04` prefix.
+    ///
+    pub fn to_eth_address_bytes(&self) -> [u8; ADDRESS_SIZE] {
+        let hash = keccak256(&self.to_sec1_uncompressed()[1..]);
+
+        let mut address = [0u8; ADDRESS_SIZE];
+        address.copy_from_slice(&hash[32 - ADDRESS_SIZE..]);
+        address
+    }
+
+    ///
+    /// The Ethereum address of the public key, hex encoded with the EIP-55
+    /// mixed case checksum (e.g. `0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf`).
+    ///
+    pub fn to_eth_address(&self) -> String {
+        to_checksum_address(&self.to_eth_address_bytes())
+    }
+}
+
+/// Keccak-256 hash of `data`, as used all over Ethereum.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+///
+/// EIP-55 encoding of an address: each letter of the lowercase hex address is
+/// uppercased if the matching nibble of `Keccak256(hex address)` is `>= 8`.
+///
+pub fn to_checksum_address(address: &[u8; ADDRESS_SIZE]) -> String {
+    let lowercase = hex::encode(address);
+    let hash = keccak256(lowercase.as_bytes());
+
+    let checksummed: String = lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+///
+/// Signs the 32 bytes hash of a transaction for the chain `chain_id` and
+/// returns `(v, r, s)` with the replay protected `v` of EIP-155:
+///
+/// `v = recovery_id + 2 chain_id + 35`
+///
+/// `k` is derived with RFC 6979 and the signature is in its low-S form, as
+/// required by Ethereum.
+///
+pub fn sign_eip155(
+    tx_hash: &[u8; 32],
+    priv_key: &BigUint,
+    chain_id: u64,
+) -> Result<(u64, BigUint, BigUint), ECDSAErrors> {
+    let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+    let hash = ecdsa.digest_to_int(tx_hash)?;
+    let k = rfc6979::generate_k(&ecdsa.q_order, priv_key, tx_hash);
+
+    let signature = ecdsa
+        .sign_recoverable(&hash, priv_key, &k)?
+        .normalize_s(&ecdsa.q_order);
+    if signature.recovery_id > 1 {
+        return Err(ECDSAErrors::OperationFailure(
+            "x component of R is bigger than q, v can not encode it".into(),
+        ));
+    }
+
+    let v = u64::from(signature.recovery_id) + 2 * chain_id + 35;
+    Ok((v, signature.signature.r, signature.signature.s))
+}
+
+///
+/// Recovers the public key that signed a transaction hash from its EIP-155
+/// `(v, r, s)` signature for the chain `chain_id`.
+///
+pub fn recover_eip155(
+    tx_hash: &[u8; 32],
+    chain_id: u64,
+    v: u64,
+    r: &BigUint,
+    s: &BigUint,
+) -> Result<PublicKey, ECDSAErrors> {
+    let recovery_id = v
+        .checked_sub(2 * chain_id + 35)
+        .filter(|recovery_id| *recovery_id <= 1)
+        .ok_or_else(|| ECDSAErrors::BadArgument(format!("Invalid v {} for chain {}", v, chain_id)))?;
+
+    let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+    let hash = ecdsa.digest_to_int(tx_hash)?;
+    let signature = RecoverableSignature::new(Signature::new(r.clone(), s.clone()), recovery_id as u8);
+
+    PublicKey::from_point(ecdsa.recover_public_key(&hash, &signature)?)
+}
+
+///
+/// The EIP-191 hash of a message signed with `personal_sign`:
+///
+/// `keccak256("\x19Ethereum Signed Message:\n" || len(message) || message)`
+///
+pub fn hash_personal_message(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    keccak256(&prefixed)
+}
+
+///
+/// Signs a message as `personal_sign` does and returns `(v, r, s)` with
+/// `v = 27 + recovery_id`, the signature being in its low-S form.
+///
+pub fn sign_personal_message(
+    message: &[u8],
+    priv_key: &BigUint,
+) -> Result<(u64, BigUint, BigUint), ECDSAErrors> {
+    let hash = hash_personal_message(message);
+    let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+    let k = rfc6979::generate_k(&ecdsa.q_order, priv_key, &hash);
+
+    let signature = ecdsa
+        .sign_recoverable(&ecdsa.digest_to_int(&hash)?, priv_key, &k)?
+        .normalize_s(&ecdsa.q_order);
+    if signature.recovery_id > 1 {
+        return Err(ECDSAErrors::OperationFailure(
+            "x component of R is bigger than q, v can not encode it".into(),
+        ));
+    }
+
+    let v = u64::from(signature.recovery_id) + 27;
+    Ok((v, signature.signature.r, signature.signature.s))
+}
+
+///
+/// The `ecrecover` precompile: the address of the key which signed `hash`
+/// with the signature `(v, r, s)`, `v` being 27 or 28.
+///
+pub fn ecrecover(
+    hash: &[u8; 32],
+    v: u64,
+    r: &BigUint,
+    s: &BigUint,
+) -> Result<[u8; ADDRESS_SIZE], ECDSAErrors> {
+    let recovery_id = match v {
+        27 | 28 => (v - 27) as u8,
+        _ => return Err(ECDSAErrors::BadArgument(format!("Invalid v {}, expected 27 or 28", v))),
+    };
+
+    let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+    let signature = RecoverableSignature::new(Signature::new(r.clone(), s.clone()), recovery_id);
+    let point = ecdsa.recover_public_key(&ecdsa.digest_to_int(hash)?, &signature)?;
+
+    Ok(PublicKey::from_point(point)?.to_eth_address_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_eth_address() {
+        let pub_key = PublicKey::from_priv_key(&BigUint::from(1u32)).unwrap();
+        assert_eq!(pub_key.to_eth_address(), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+
+        let pub_key = PublicKey::from_priv_key(&BigUint::from(2u32)).unwrap();
+        assert_eq!(pub_key.to_eth_address(), "0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF");
+    }
+
+    #[test]
+    fn test_to_checksum_address() {
+        // EIP-55 test cases
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let mut address = [0u8; ADDRESS_SIZE];
+            address.copy_from_slice(&hex::decode(expected[2..].to_lowercase()).unwrap());
+            assert_eq!(to_checksum_address(&address), expected);
+        }
+    }
+
+    #[test]
+    fn test_sign_eip155() {
+        // EIP-155 example transaction
+        let priv_key = BigUint::parse_bytes(
+            b"4646464646464646464646464646464646464646464646464646464646464646",
+            16,
+        )
+            .unwrap();
+        let mut tx_hash = [0u8; 32];
+        tx_hash.copy_from_slice(
+            &hex::decode("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53").unwrap(),
+        );
+
+        let (v, r, s) = sign_eip155(&tx_hash, &priv_key, 1).unwrap();
+        assert_eq!(v, 37);
+        assert_eq!(
+            r,
+            BigUint::parse_bytes(b"18515461264373351373200002665853028612451056578545711640558177340181847433846", 10).unwrap()
+        );
+        assert_eq!(
+            s,
+            BigUint::parse_bytes(b"46948507304638947509940763649030358759909902576025900602547168820602576006531", 10).unwrap()
+        );
+
+        let pub_key = PublicKey::from_priv_key(&priv_key).unwrap();
+        assert_eq!(recover_eip155(&tx_hash, 1, v, &r, &s).unwrap(), pub_key);
+        assert_eq!(pub_key.to_eth_address(), "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F");
+
+        // v depends on the chain
+        let (v, _, _) = sign_eip155(&tx_hash, &priv_key, 5).unwrap();
+        assert!(v == 45 || v == 46);
+        assert!(recover_eip155(&tx_hash, 1, v, &r, &s).is_err());
+        assert!(recover_eip155(&tx_hash, 1, 27, &r, &s).is_err());
+    }
+
+    #[test]
+    fn test_personal_message() {
+        assert_eq!(
+            hex::encode(hash_personal_message(b"Hello World")),
+            "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+        );
+
+        let priv_key = BigUint::from(1u32);
+        let address = PublicKey::from_priv_key(&priv_key).unwrap().to_eth_address_bytes();
+        let (v, r, s) = sign_personal_message(b"Hello World", &priv_key).unwrap();
+        assert!(v == 27 || v == 28);
+
+        let hash = hash_personal_message(b"Hello World");
+        assert_eq!(ecrecover(&hash, v, &r, &s).unwrap(), address);
+        assert_ne!(ecrecover(&hash_personal_message(b"Hello World!"), v, &r, &s).ok(), Some(address));
+        assert!(ecrecover(&hash, v - 27, &r, &s).is_err());
+    }
+
+    #[test]
+    fn test_keccak_signing() {
+        use crate::keys::PrivateKey;
+        use crate::Keccak256;
+
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+
+        let priv_key = PrivateKey::<ec_core::curves::Secp256k1>::new(BigUint::from(7u32)).unwrap();
+        let signature = priv_key.sign_message_with::<Keccak256>(b"message").unwrap();
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        assert_eq!(
+            *signature.signature(),
+            ecdsa.sign_prehash_with::<Keccak256>(&keccak256(b"message"), priv_key.secret()).unwrap()
+        );
+        assert!(priv_key
+            .public_key()
+            .unwrap()
+            .verify_message_with::<Keccak256>(b"message", &signature)
+            .unwrap());
+    }
+}
@@ -0,0 +1,784 @@
+/*!
+FROST threshold Schnorr signatures
+([RFC 9591](https://www.rfc-editor.org/rfc/rfc9591)), with key generation by
+a trusted dealer. Any `MIN_PARTICIPANTS` of the `MAX_PARTICIPANTS` holders of
+a share of the key sign in two rounds:
+
+```text
+ 1. commit:     each signer i draws nonces (d_i, e_i) and sends
+                (D_i, E_i) = (d_i G, e_i G) to the coordinator
+ 2. sign:       with rho_i = H1(PK || H4(msg) || H5(commitments) || i),
+                R = sum D_i + rho_i E_i and c = H2(R || PK || msg),
+                each signer returns z_i = d_i + e_i rho_i + lambda_i s_i c
+    aggregate:  z = sum z_i, the signature is (R, z)
+```
+
+which is a Schnorr signature `z G = R + c PK` for the group public key `PK`,
+verified with `Frost::verify`. The ciphersuites are FROST(secp256k1,
+SHA-256) and FROST(P-256, SHA-256); identifiers are the indices of the
+shares, from 1.
+*/
+
+use ec_core::hash_to_curve::expand_message_xmd;
+use ec_core::*;
+use num_bigint::BigUint;
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use crate::ecdsa::ECDSAErrors;
+use crate::schnorr::{Schnorr, SchnorrSignature};
+use crate::threshold::{evaluate, lagrange_at_zero, random_polynomial};
+
+/// Byte length of the uniform bytes hashed to a scalar, `L` of RFC 9591.
+const HASH_TO_SCALAR_SIZE: usize = 48;
+
+///
+/// The share of the group signing key held by a participant, with the public
+/// keys needed to sign: its verifying share `PK_i = s_i G` and the group
+/// public key `PK`.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyShare {
+    pub identifier: u32,
+    pub min_participants: usize,
+    pub signing_share: BigUint,
+    pub verifying_share: CurvePoint,
+    pub group_public_key: CurvePoint,
+}
+
+///
+/// The public commitments `(D_i, E_i)` of a signer to its nonces, sent to the
+/// coordinator in round one.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct SigningCommitment {
+    pub identifier: u32,
+    pub hiding: CurvePoint,
+    pub binding: CurvePoint,
+}
+
+///
+/// The secret nonces `(d_i, e_i)` of a signer, used by exactly one call to
+/// `Frost::sign` which consumes them: signing twice with the same nonces
+/// reveals the signing share. Its `Debug` output shows the commitment only.
+///
+pub struct SigningNonces {
+    hiding: BigUint,
+    binding: BigUint,
+    commitment: SigningCommitment,
+}
+
+impl SigningNonces {
+    pub fn commitment(&self) -> &SigningCommitment {
+        &self.commitment
+    }
+}
+
+impl fmt::Debug for SigningNonces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces")
+            .field("hiding", &"<redacted>")
+            .field("binding", &"<redacted>")
+            .field("commitment", &self.commitment)
+            .finish()
+    }
+}
+
+///
+/// The share `z_i` of the signature returned by a signer in round two.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct SignatureShare {
+    pub identifier: u32,
+    pub z: BigUint,
+}
+
+///
+/// A FROST ciphersuite: the group, with its Schnorr helpers, and the context
+/// string prefixed to every hash.
+///
+pub struct Frost {
+    schnorr: Schnorr,
+    context_string: &'static str,
+}
+
+impl Frost {
+    /// FROST(secp256k1, SHA-256)
+    pub fn secp256k1() -> Self {
+        Frost {
+            schnorr: Schnorr::from(curves::secp256k1()),
+            context_string: "FROST-secp256k1-SHA256-v1",
+        }
+    }
+
+    /// FROST(P-256, SHA-256)
+    pub fn p256() -> Self {
+        Frost {
+            schnorr: Schnorr::from(curves::p256()),
+            context_string: "FROST-P256-SHA256-v1",
+        }
+    }
+
+    pub fn q_order(&self) -> &BigUint {
+        &self.schnorr.q_order
+    }
+
+    ///
+    /// Splits `secret_key` into `max_participants` shares, any
+    /// `min_participants` of which can sign, and returns them with the VSS
+    /// commitment `[a_0 G, ..., a_(t-1) G]` to the coefficients of the
+    /// polynomial, which lets each participant check its share with
+    /// `vss_verify`.
+    ///
+    pub fn trusted_dealer_keygen<R: CryptoRng + RngCore>(
+        &self,
+        secret_key: &BigUint,
+        max_participants: u32,
+        min_participants: usize,
+        rng: &mut R,
+    ) -> Result<(Vec<KeyShare>, Vec<CurvePoint>), ECDSAErrors> {
+        self.schnorr.check_scalar(secret_key, "Secret key")?;
+        if min_participants < 2 || max_participants < min_participants as u32 {
+            return Err(ECDSAErrors::BadArgument(
+                "The participants must be such that 2 <= min <= max".into(),
+            ));
+        }
+
+        let q = self.q_order();
+        let coefficients = random_polynomial(secret_key.clone(), min_participants - 1, q, rng);
+        let vss_commitment = coefficients
+            .iter()
+            .map(|coefficient| self.schnorr.mul(&self.schnorr.a_gen, coefficient))
+            .collect::<Result<Vec<_>, _>>()?;
+        let group_public_key = vss_commitment[0].clone();
+
+        let shares = (1..=max_participants)
+            .map(|identifier| {
+                let signing_share = evaluate(&coefficients, identifier, q);
+                Ok(KeyShare {
+                    identifier,
+                    min_participants,
+                    verifying_share: self.schnorr.mul(&self.schnorr.a_gen, &signing_share)?,
+                    signing_share,
+                    group_public_key: group_public_key.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, ECDSAErrors>>()?;
+
+        Ok((shares, vss_commitment))
+    }
+
+    ///
+    /// The public key `PK_i = sum_j C_j i^j` of participant `identifier`,
+    /// computed from the VSS commitment, for the coordinator to check the
+    /// signature shares.
+    ///
+    pub fn verifying_share(&self, vss_commitment: &[CurvePoint], identifier: u32) -> Result<CurvePoint, ECDSAErrors> {
+        let q = self.q_order();
+        let i = BigUint::from(identifier) % q;
+        let mut power = BigUint::from(1u32);
+        let mut terms = Vec::with_capacity(vss_commitment.len());
+        for commitment in vss_commitment {
+            terms.push((power.clone(), commitment.clone()));
+            power = (power * &i) % q;
+        }
+        self.schnorr
+            .elliptic_curve
+            .multi_scalar_mul(&terms)
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid VSS commitment".into()))
+    }
+
+    ///
+    /// Checks a share dealt by `trusted_dealer_keygen` against the VSS
+    /// commitment: `s_i G = sum_j C_j i^j`.
+    ///
+    pub fn vss_verify(&self, share: &KeyShare, vss_commitment: &[CurvePoint]) -> Result<bool, ECDSAErrors> {
+        let expected = self.verifying_share(vss_commitment, share.identifier)?;
+        Ok(self.schnorr.mul(&self.schnorr.a_gen, &share.signing_share)? == expected
+            && share.verifying_share == expected
+            && vss_commitment.first() == Some(&share.group_public_key))
+    }
+
+    ///
+    /// Round one: draws the nonces of a signer, each from 32 random bytes and
+    /// the signing share as `H3(random_bytes || s_i)`, so that a weak rng alone
+    /// does not reveal them.
+    ///
+    pub fn commit<R: CryptoRng + RngCore>(&self, share: &KeyShare, rng: &mut R) -> Result<SigningNonces, ECDSAErrors> {
+        let hiding = self.nonce_generate(&share.signing_share, rng)?;
+        let binding = self.nonce_generate(&share.signing_share, rng)?;
+        let commitment = SigningCommitment {
+            identifier: share.identifier,
+            hiding: self.schnorr.mul(&self.schnorr.a_gen, &hiding)?,
+            binding: self.schnorr.mul(&self.schnorr.a_gen, &binding)?,
+        };
+        Ok(SigningNonces { hiding, binding, commitment })
+    }
+
+    ///
+    /// Round two: the signature share of `share` over `message`, given the
+    /// commitments of all the signers, sorted by identifier, this one
+    /// included.
+    ///
+    pub fn sign(
+        &self,
+        share: &KeyShare,
+        nonces: SigningNonces,
+        commitments: &[SigningCommitment],
+        message: &[u8],
+    ) -> Result<SignatureShare, ECDSAErrors> {
+        if !commitments.contains(&nonces.commitment) || nonces.commitment.identifier != share.identifier {
+            return Err(ECDSAErrors::BadArgument(
+                "The commitments do not include the ones of these nonces".into(),
+            ));
+        }
+        self.check_commitments(commitments, share.min_participants)?;
+
+        let q = self.q_order();
+        let binding_factors = self.binding_factors(&share.group_public_key, commitments, message)?;
+        let binding_factor = &binding_factors[position(commitments, share.identifier)?];
+        let group_commitment = self.group_commitment(commitments, &binding_factors)?;
+        let lambda = lagrange_at_zero(share.identifier, &identifiers(commitments), q)?;
+        let challenge = self.challenge(&group_commitment, &share.group_public_key, message)?;
+
+        let z = (nonces.hiding + nonces.binding * binding_factor + lambda * &share.signing_share * challenge) % q;
+        Ok(SignatureShare { identifier: share.identifier, z })
+    }
+
+    ///
+    /// Checks the share of a signer against its verifying share, to find the
+    /// culprit when the aggregate signature is not valid:
+    ///
+    /// `z_i G = D_i + rho_i E_i + (c lambda_i) PK_i`
+    ///
+    pub fn verify_signature_share(
+        &self,
+        share: &SignatureShare,
+        verifying_share: &CurvePoint,
+        commitments: &[SigningCommitment],
+        group_public_key: &CurvePoint,
+        message: &[u8],
+    ) -> Result<bool, ECDSAErrors> {
+        self.check_commitments(commitments, 1)?;
+        self.schnorr.check_point(verifying_share, "verifying share")?;
+
+        let q = self.q_order();
+        let binding_factors = self.binding_factors(group_public_key, commitments, message)?;
+        let index = position(commitments, share.identifier)?;
+        let group_commitment = self.group_commitment(commitments, &binding_factors)?;
+        let lambda = lagrange_at_zero(share.identifier, &identifiers(commitments), q)?;
+        let challenge = self.challenge(&group_commitment, group_public_key, message)?;
+
+        let commitment = &commitments[index];
+        let commitment_share = self.schnorr.mul_add(
+            &BigUint::from(1u32),
+            &commitment.hiding,
+            &binding_factors[index],
+            &commitment.binding,
+        )?;
+        let expected = self
+            .schnorr
+            .elliptic_curve
+            .add(&commitment_share, &self.schnorr.mul(verifying_share, &((challenge * lambda) % q))?)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error adding the commitment share".into()))?;
+
+        Ok(share.z < *q && self.schnorr.mul(&self.schnorr.a_gen, &share.z)? == expected)
+    }
+
+    ///
+    /// Adds up the signature shares of all the signers of the commitments into
+    /// the signature `(R, z)`, which is checked before being returned.
+    ///
+    pub fn aggregate(
+        &self,
+        commitments: &[SigningCommitment],
+        message: &[u8],
+        group_public_key: &CurvePoint,
+        shares: &[SignatureShare],
+    ) -> Result<SchnorrSignature, ECDSAErrors> {
+        self.check_commitments(commitments, 1)?;
+        let mut senders: Vec<u32> = shares.iter().map(|share| share.identifier).collect();
+        senders.sort_unstable();
+        if senders != identifiers(commitments) {
+            return Err(ECDSAErrors::BadArgument(
+                "Expected exactly one share from every signer".into(),
+            ));
+        }
+
+        let q = self.q_order();
+        let binding_factors = self.binding_factors(group_public_key, commitments, message)?;
+        let r_point = self.group_commitment(commitments, &binding_factors)?;
+        let z = shares.iter().fold(BigUint::from(0u32), |acc, share| (acc + &share.z) % q);
+
+        let signature = SchnorrSignature { r_point, s: z };
+        if !self.verify(message, group_public_key, &signature)? {
+            return Err(ECDSAErrors::OperationFailure(
+                "The aggregate signature is not valid, check the shares with verify_signature_share".into(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    ///
+    /// Verifies a signature `(R, z)` for the group public key:
+    ///
+    /// `z G == R + c PK` where `c = H2(R || PK || message)`
+    ///
+    pub fn verify(&self, message: &[u8], group_public_key: &CurvePoint, signature: &SchnorrSignature) -> Result<bool, ECDSAErrors> {
+        self.schnorr.check_point(group_public_key, "group public key")?;
+        if signature.s >= *self.q_order() || self.schnorr.check_point(&signature.r_point, "R").is_err() {
+            return Ok(false);
+        }
+
+        let challenge = self.challenge(&signature.r_point, group_public_key, message)?;
+        let expected = self
+            .schnorr
+            .mul_add(&BigUint::from(1u32), &signature.r_point, &challenge, group_public_key)?;
+        Ok(self.schnorr.mul(&self.schnorr.a_gen, &signature.s)? == expected)
+    }
+
+    fn nonce_generate<R: CryptoRng + RngCore>(&self, secret: &BigUint, rng: &mut R) -> Result<BigUint, ECDSAErrors> {
+        let mut random_bytes = [0u8; 32];
+        rng.fill_bytes(&mut random_bytes);
+        loop {
+            let nonce = self.hash_to_scalar("nonce", &[&random_bytes, &self.encode_scalar(secret)])?;
+            if nonce != BigUint::from(0u32) {
+                return Ok(nonce);
+            }
+            rng.fill_bytes(&mut random_bytes);
+        }
+    }
+
+    // rho_i = H1(PK || H4(msg) || H5(encoded commitments) || i), in the order
+    // of the commitments
+    fn binding_factors(
+        &self,
+        group_public_key: &CurvePoint,
+        commitments: &[SigningCommitment],
+        message: &[u8],
+    ) -> Result<Vec<BigUint>, ECDSAErrors> {
+        let mut encoded_commitments = Vec::new();
+        for commitment in commitments {
+            encoded_commitments.extend(self.encode_scalar(&BigUint::from(commitment.identifier)));
+            encoded_commitments.extend(self.schnorr.encode(&commitment.hiding)?);
+            encoded_commitments.extend(self.schnorr.encode(&commitment.binding)?);
+        }
+
+        let prefix = [
+            self.schnorr.encode(group_public_key)?,
+            self.hash("msg", message).to_vec(),
+            self.hash("com", &encoded_commitments).to_vec(),
+        ]
+        .concat();
+        commitments
+            .iter()
+            .map(|commitment| self.hash_to_scalar("rho", &[&prefix, &self.encode_scalar(&BigUint::from(commitment.identifier))]))
+            .collect()
+    }
+
+    // R = sum D_i + rho_i E_i
+    fn group_commitment(&self, commitments: &[SigningCommitment], binding_factors: &[BigUint]) -> Result<CurvePoint, ECDSAErrors> {
+        let one = BigUint::from(1u32);
+        let terms: Vec<(BigUint, CurvePoint)> = commitments
+            .iter()
+            .zip(binding_factors)
+            .flat_map(|(commitment, rho)| [(one.clone(), commitment.hiding.clone()), (rho.clone(), commitment.binding.clone())])
+            .collect();
+        self.schnorr
+            .elliptic_curve
+            .multi_scalar_mul(&terms)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing the group commitment".into()))
+    }
+
+    // c = H2(R || PK || msg)
+    fn challenge(&self, group_commitment: &CurvePoint, group_public_key: &CurvePoint, message: &[u8]) -> Result<BigUint, ECDSAErrors> {
+        let r = self.schnorr.encode(group_commitment)?;
+        let pk = self.schnorr.encode(group_public_key)?;
+        self.hash_to_scalar("chal", &[&r, &pk, message])
+    }
+
+    // sorted by identifier without duplicates, at least `min` of them, with
+    // valid points
+    fn check_commitments(&self, commitments: &[SigningCommitment], min: usize) -> Result<(), ECDSAErrors> {
+        if commitments.len() < min {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "At least {} signers are needed, got {}",
+                min,
+                commitments.len()
+            )));
+        }
+        if commitments.windows(2).any(|pair| pair[0].identifier >= pair[1].identifier) {
+            return Err(ECDSAErrors::BadArgument(
+                "Commitments must be sorted by identifier, without duplicates".into(),
+            ));
+        }
+        for commitment in commitments {
+            if commitment.identifier == 0 {
+                return Err(ECDSAErrors::BadArgument("Identifiers can not be zero".into()));
+            }
+            self.schnorr.check_point(&commitment.hiding, "hiding commitment")?;
+            self.schnorr.check_point(&commitment.binding, "binding commitment")?;
+        }
+        Ok(())
+    }
+
+    // H1, H2 and H3: hash_to_field with expand_message_xmd and the DST
+    // `context_string || tag`
+    fn hash_to_scalar(&self, tag: &str, parts: &[&[u8]]) -> Result<BigUint, ECDSAErrors> {
+        let dst = [self.context_string.as_bytes(), tag.as_bytes()].concat();
+        let uniform_bytes = expand_message_xmd::<Sha256>(&parts.concat(), &dst, HASH_TO_SCALAR_SIZE)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error hashing to a scalar".into()))?;
+        Ok(BigUint::from_bytes_be(&uniform_bytes) % self.q_order())
+    }
+
+    // H4 and H5: SHA-256(context_string || tag || m)
+    fn hash(&self, tag: &str, message: &[u8]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(self.context_string)
+            .chain_update(tag)
+            .chain_update(message)
+            .finalize()
+            .into()
+    }
+
+    fn encode_scalar(&self, scalar: &BigUint) -> Vec<u8> {
+        let size = self.q_order().bits().div_ceil(8) as usize;
+        to_fixed_bytes(scalar, size).expect("scalars are reduced modulo q")
+    }
+}
+
+fn identifiers(commitments: &[SigningCommitment]) -> Vec<u32> {
+    commitments.iter().map(|commitment| commitment.identifier).collect()
+}
+
+fn position(commitments: &[SigningCommitment], identifier: u32) -> Result<usize, ECDSAErrors> {
+    commitments
+        .iter()
+        .position(|commitment| commitment.identifier == identifier)
+        .ok_or_else(|| ECDSAErrors::BadArgument(format!("{} is not a signer", identifier)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::RandBigInt;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn run_session(frost: &Frost, shares: &[KeyShare], signers: &[u32], message: &[u8]) -> Result<SchnorrSignature, ECDSAErrors> {
+        let mut rng = StdRng::seed_from_u64(21);
+        let signers: Vec<&KeyShare> = signers.iter().map(|&i| &shares[i as usize - 1]).collect();
+
+        let nonces = signers
+            .iter()
+            .map(|share| frost.commit(share, &mut rng))
+            .collect::<Result<Vec<_>, _>>()?;
+        let commitments: Vec<SigningCommitment> = nonces.iter().map(|nonces| nonces.commitment().clone()).collect();
+
+        let signature_shares = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| frost.sign(share, nonces, &commitments, message))
+            .collect::<Result<Vec<_>, _>>()?;
+        let group_public_key = &signers[0].group_public_key;
+        // the verifying shares are the ones of the dealer, even for tampered shares
+        for (share, signature_share) in signers.iter().zip(&signature_shares) {
+            if !frost.verify_signature_share(signature_share, &share.verifying_share, &commitments, group_public_key, message)? {
+                return Err(ECDSAErrors::OperationFailure(format!("Invalid share from {}", share.identifier)));
+            }
+        }
+
+        frost.aggregate(&commitments, message, group_public_key, &signature_shares)
+    }
+
+    #[test]
+    fn test_frost_sign() {
+        let mut rng = StdRng::seed_from_u64(20);
+        for frost in [Frost::secp256k1(), Frost::p256()] {
+            let secret_key = rng.gen_biguint_range(&BigUint::from(1u32), frost.q_order());
+            let (shares, vss_commitment) = frost.trusted_dealer_keygen(&secret_key, 5, 3, &mut rng).unwrap();
+            let group_public_key = frost.schnorr.mul(&frost.schnorr.a_gen, &secret_key).unwrap();
+
+            for share in &shares {
+                assert!(frost.vss_verify(share, &vss_commitment).unwrap());
+                assert_eq!(share.group_public_key, group_public_key);
+            }
+
+            for signers in [vec![1, 2, 3], vec![1, 3, 5], vec![2, 3, 4, 5], vec![1, 2, 3, 4, 5]] {
+                let signature = run_session(&frost, &shares, &signers, b"Bob -> 1 BTC -> Alice").unwrap();
+                assert!(frost.verify(b"Bob -> 1 BTC -> Alice", &group_public_key, &signature).unwrap());
+                assert!(!frost.verify(b"Bob -> 2 BTC -> Alice", &group_public_key, &signature).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_frost_wrong_input() {
+        let frost = Frost::secp256k1();
+        let mut rng = StdRng::seed_from_u64(22);
+        let (shares, vss_commitment) = frost.trusted_dealer_keygen(&BigUint::from(1234u32), 3, 2, &mut rng).unwrap();
+
+        assert!(frost.trusted_dealer_keygen(&BigUint::from(1234u32), 3, 4, &mut rng).is_err());
+        assert!(frost.trusted_dealer_keygen(&BigUint::from(1234u32), 3, 1, &mut rng).is_err());
+        assert!(frost.trusted_dealer_keygen(&BigUint::from(0u32), 3, 2, &mut rng).is_err());
+
+        // a tampered share fails the VSS check and is caught in the session
+        let mut bad_shares = shares.clone();
+        bad_shares[1].signing_share += BigUint::from(1u32);
+        assert!(!frost.vss_verify(&bad_shares[1], &vss_commitment).unwrap());
+        assert!(run_session(&frost, &bad_shares, &[1, 2], b"msg").is_err());
+
+        // not enough signers, unsorted or duplicated commitments
+        let nonces: Vec<SigningNonces> = shares.iter().map(|share| frost.commit(share, &mut rng).unwrap()).collect();
+        let commitments: Vec<SigningCommitment> = nonces.iter().map(|nonces| nonces.commitment().clone()).collect();
+        let mut nonces = nonces.into_iter();
+        assert!(frost.sign(&shares[0], nonces.next().unwrap(), &commitments[..1], b"msg").is_err());
+        let unsorted = [commitments[1].clone(), commitments[0].clone()];
+        assert!(frost.sign(&shares[1], nonces.next().unwrap(), &unsorted, b"msg").is_err());
+        let duplicated = [commitments[2].clone(), commitments[2].clone()];
+        assert!(frost.sign(&shares[2], nonces.next().unwrap(), &duplicated, b"msg").is_err());
+    }
+
+    // replays the given bytes, to feed the nonce randomness of the vectors to
+    // `commit`
+    struct ReplayRng(Vec<u8>);
+
+    impl RngCore for ReplayRng {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            let rest = self.0.split_off(dest.len());
+            dest.copy_from_slice(&self.0);
+            self.0 = rest;
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for ReplayRng {}
+
+    struct Vector {
+        frost: Frost,
+        group_secret_key: &'static str,
+        group_public_key: &'static str,
+        share_polynomial_coefficient: &'static str,
+        participant_shares: [&'static str; 3],
+        // (hiding, binding) randomness, nonces and commitments of P1 and P3
+        nonce_randomness: [(&'static str, &'static str); 2],
+        nonces: [(&'static str, &'static str); 2],
+        commitments: [(&'static str, &'static str); 2],
+        binding_factors: [&'static str; 2],
+        sig_shares: [&'static str; 2],
+        signature: &'static str,
+    }
+
+    // FROST(secp256k1, SHA-256) and FROST(P-256, SHA-256) with the keys of RFC
+    // 9591 appendix E, min 2 of max 3 participants and signers 1 and 3 on
+    // "test". The shares and the nonces and commitments of P1 are the ones of
+    // the RFC; the nonce randomness of P3 is not taken from the RFC, so the
+    // values of P3 and of round two were cross-checked against an independent
+    // implementation of the RFC instead.
+    fn vectors() -> [Vector; 2] {
+        [
+            Vector {
+                frost: Frost::secp256k1(),
+                group_secret_key: "0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114",
+                group_public_key: "02f37c34b66ced1fb51c34a90bdae006901f10625cc06c4f64663b0eae87d87b4f",
+                share_polynomial_coefficient: "fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579",
+                participant_shares: [
+                    "08f89ffe80ac94dcb920c26f3f46140bfc7f95b493f8310f5fc1ea2b01f4254c",
+                    "04f0feac2edcedc6ce1253b7fab8c86b856a797f44d83d82a385554e6e401984",
+                    "00e95d59dd0d46b0e303e500b62b7ccb0e555d49f5b849f5e748c071da8c0dbc",
+                ],
+                nonce_randomness: [
+                    (
+                        "7ea5ed09af19f6ff21040c07ec2d2adbd35b759da5a401d4c99dd26b82391cb2",
+                        "47acab018f116020c10cb9b9abdc7ac10aae1b48ca6e36dc15acb6ec9be5cdc5",
+                    ),
+                    (
+                        "e9165dad654fc20a9e31ca6f32ac032ec327b551a50e8ac5cf25f5c4c9e20757",
+                        "e9059a232598a0fba0e495a687580e624ab425337c3221246fb2c716905bc9e7",
+                    ),
+                ],
+                nonces: [
+                    (
+                        "841d3a6450d7580b4da83c8e618414d0f024391f2aeb511d7579224420aa81f0",
+                        "8d2624f532af631377f33cf44b5ac5f849067cae2eacb88680a31e77c79b5a80",
+                    ),
+                    (
+                        "178d69aebbb44938a066f9efb311fb0b5dd4d188f9e7de944f287df0051afacd",
+                        "f1a1dea4332ee55b306be3f7fb87d497deef90065246f8097e8ffd9c6e125b15",
+                    ),
+                ],
+                commitments: [
+                    (
+                        "03c699af97d26bb4d3f05232ec5e1938c12f1e6ae97643c8f8f11c9820303f1904",
+                        "02fa2aaccd51b948c9dc1a325d77226e98a5a3fe65fe9ba213761a60123040a45e",
+                    ),
+                    (
+                        "02cebd240c7c91934678046270b3af25586e9e0524fc41dda5c088b7b6daf40bd2",
+                        "0327c802791e8a1be5ee40be4b26150b135f60174c22626bd6e7543cec489a3060",
+                    ),
+                ],
+                binding_factors: [
+                    "f5ce0e9e2148aac12c939d40299290c4008c20abd48cb403f0947110b10c3343",
+                    "ab1e1c9c022c69d60e3333b8e91d946215a70349c1e4f0fe1f0b0c88206d3ee8",
+                ],
+                sig_shares: [
+                    "75d3a3f832089f46946072a3a89a867267791b9adf64e1b238e8cc13f46356bc",
+                    "2a7369a897f5f873c11865c1db3e1b69e6ec16e53edcb8aec69e6fc7a4980f70",
+                ],
+                signature: "022978685876e098d416066987db7873b87a6c5120d964864baadb83580262e01d\
+                            a0470da0c9fe97ba5578d86583d8a1dc4e6532801e419a60ff873bdb98fb662c",
+            },
+            Vector {
+                frost: Frost::p256(),
+                group_secret_key: "8ba9bba2e0fd8c4767154d35a0b7562244a4aaf6f36c8fb8735fa48b301bd8de",
+                group_public_key: "023a309ad94e9fe8a7ba45dfc58f38bf091959d3c99cfbd02b4dc00585ec45ab70",
+                share_polynomial_coefficient: "80f25e6c0709353e46bfbe882a11bdbb1f8097e46340eb8673b7e14556e6c3a4",
+                participant_shares: [
+                    "0c9c1a0fe806c184add50bbdcac913dda73e482daf95dcb9f35dbb0d8a9f7731",
+                    "8d8e787bef0ff6c2f494ca45f4dad198c6bee01212d6c84067159c52e1863ad5",
+                    "0e80d6e8f6192c003b5488ce1eec8f5429587d48cf001541e713b2d53c09d928",
+                ],
+                nonce_randomness: [
+                    (
+                        "ec4c891c85fee802a9d757a67d1252e7f4e5efb8a538991ac18fbd0e06fb6fd3",
+                        "9334e29d09061223f69a09421715a347e4e6deba77444c8f42b0c833f80f4ef9",
+                    ),
+                    (
+                        "e9165dad654fc20a9e31ca6f32ac032ec327b551a50e8ac5cf25f5c4c9e20757",
+                        "e9059a232598a0fba0e495a687580e624ab425337c3221246fb2c716905bc9e7",
+                    ),
+                ],
+                nonces: [
+                    (
+                        "9f0542a5ba879a58f255c09f06da7102ef6a2dec6279700c656d58394d8facd4",
+                        "6513dfe7429aa2fc972c69bb495b27118c45bbc6e654bb9dc9be55385b55c0d7",
+                    ),
+                    (
+                        "0a84893a213fc050fac02520b6efde28bd94d0e13a9e552aabdecab5c03b9864",
+                        "1465f19de0dbca6bbfe62d58e980600272814cabb22f4707e1028c75543d5ca5",
+                    ),
+                ],
+                commitments: [
+                    (
+                        "0213b3e6298bf8ad46fd5e9389519a8665d63d98f4ec6a1fcca434e809d2d8070e",
+                        "02188ff1390bf69374d7b272e454b1878ef10a6b6ea3ff36f114b300b4dbd5233b",
+                    ),
+                    (
+                        "03eb5dcdea44dca82a2036c74756af3f19d36b6ba6b6c8d3ea04c4488f7fe034b0",
+                        "0256f756b6626a2459eef3a6a52743d553bdc7c768576c54d4f1b1e551e10e050e",
+                    ),
+                ],
+                binding_factors: [
+                    "1a17df954389cba74fa2e250c0017111f1ffdd7c8a3ae4239624974d18067d8c",
+                    "50ee61272bf22e6ac5a27e16039a51d9bae91c280692f818690804b0c0ab4175",
+                ],
+                sig_shares: [
+                    "f63f927956ebad053a1cdec545a94381c441d5493c86f77ebb1248f701e53341",
+                    "3619dba5fb91fb98b4db1efd961914d8715619bb9753ec299cf69cdf8d8f1864",
+                ],
+                signature: "03e43da8522af74f2ab39e559d78f3fb3d0bc29012efc1ea3b69a6e8c889e783b0\
+                            2c596e20527da89ceef7fdc2dbc2585a78b0f4572cc34523644f1b1393112654",
+            },
+        ]
+    }
+
+    fn scalar(hex: &str) -> BigUint {
+        BigUint::from_bytes_be(&hex::decode(hex).unwrap())
+    }
+
+    fn encoded(frost: &Frost, point: &CurvePoint) -> String {
+        hex::encode(frost.schnorr.encode(point).unwrap())
+    }
+
+    #[test]
+    fn test_frost_vectors() {
+        for vector in vectors() {
+            let frost = &vector.frost;
+            let q = frost.q_order();
+            let message = b"test";
+
+            // the shares of the degree 1 polynomial, min 2 of max 3
+            let coefficients = [scalar(vector.group_secret_key), scalar(vector.share_polynomial_coefficient)];
+            let group_public_key = frost.schnorr.mul(&frost.schnorr.a_gen, &coefficients[0]).unwrap();
+            assert_eq!(encoded(frost, &group_public_key), vector.group_public_key);
+            let shares: Vec<KeyShare> = (1..=3u32)
+                .map(|identifier| {
+                    let signing_share = evaluate(&coefficients, identifier, q);
+                    assert_eq!(signing_share, scalar(vector.participant_shares[identifier as usize - 1]));
+                    KeyShare {
+                        identifier,
+                        min_participants: 2,
+                        verifying_share: frost.schnorr.mul(&frost.schnorr.a_gen, &signing_share).unwrap(),
+                        signing_share,
+                        group_public_key: group_public_key.clone(),
+                    }
+                })
+                .collect();
+            let signers = [&shares[0], &shares[2]];
+
+            // round one
+            let nonces: Vec<SigningNonces> = signers
+                .iter()
+                .zip(vector.nonce_randomness)
+                .map(|(share, (hiding, binding))| {
+                    let mut rng = ReplayRng([hex::decode(hiding).unwrap(), hex::decode(binding).unwrap()].concat());
+                    frost.commit(share, &mut rng).unwrap()
+                })
+                .collect();
+            for (i, nonces) in nonces.iter().enumerate() {
+                assert_eq!(nonces.hiding, scalar(vector.nonces[i].0));
+                assert_eq!(nonces.binding, scalar(vector.nonces[i].1));
+                assert_eq!(encoded(frost, &nonces.commitment.hiding), vector.commitments[i].0);
+                assert_eq!(encoded(frost, &nonces.commitment.binding), vector.commitments[i].1);
+            }
+            let commitments: Vec<SigningCommitment> = nonces.iter().map(|nonces| nonces.commitment().clone()).collect();
+
+            // round two
+            let binding_factors = frost.binding_factors(&group_public_key, &commitments, message).unwrap();
+            assert_eq!(binding_factors, vector.binding_factors.map(scalar));
+            let group_commitment = frost.group_commitment(&commitments, &binding_factors).unwrap();
+            assert_eq!(encoded(frost, &group_commitment), vector.signature[..66]);
+
+            let sig_shares: Vec<SignatureShare> = signers
+                .iter()
+                .zip(nonces)
+                .map(|(share, nonces)| frost.sign(share, nonces, &commitments, message).unwrap())
+                .collect();
+            for (sig_share, expected) in sig_shares.iter().zip(vector.sig_shares) {
+                assert_eq!(sig_share.z, scalar(expected));
+            }
+
+            let signature = frost.aggregate(&commitments, message, &group_public_key, &sig_shares).unwrap();
+            assert_eq!(
+                encoded(frost, &signature.r_point) + &hex::encode(frost.encode_scalar(&signature.s)),
+                vector.signature
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonces_debug() {
+        let frost = Frost::secp256k1();
+        let mut rng = StdRng::seed_from_u64(23);
+        let (shares, _) = frost.trusted_dealer_keygen(&BigUint::from(1234u32), 3, 2, &mut rng).unwrap();
+        let nonces = frost.commit(&shares[0], &mut rng).unwrap();
+
+        let debug = format!("{:?}", nonces);
+        assert!(debug.contains("<redacted>") && debug.contains("commitment"));
+        assert!(!debug.contains(&nonces.hiding.to_string()));
+        assert!(!debug.contains(&nonces.binding.to_string()));
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+`arbitrary::Arbitrary` implementations for fuzz targets of the parsers, see
+also `ec_core::fuzz` for points and field elements:
+
+```text
+ fuzz_target!(|signature: Signature| { ... verify, encodings ... });
+ fuzz_target!(|tree: DerTree| { let _ = from_spki_der(&tree.to_der()); });
+```
+
+A `DerTree` encodes to well formed DER whose shape (nesting, tags, integer
+and OID values) is arbitrary, which gets deeper into the structure parsers
+than random bytes. `DerTree::Raw` splices arbitrary bytes in, for their
+error paths.
+*/
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use ec_core::fuzz::{arbitrary_biguint, MAX_INT_BYTES};
+use num_bigint::BigUint;
+
+use crate::der;
+use crate::signature::Signature;
+
+/// Nesting depth of the generated `DerTree`s.
+const MAX_DEPTH: usize = 4;
+
+impl<'a> Arbitrary<'a> for Signature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Signature::new(
+            arbitrary_biguint(u, MAX_INT_BYTES)?,
+            arbitrary_biguint(u, MAX_INT_BYTES)?,
+        ))
+    }
+}
+
+///
+/// A DER element, made of the elements the parsers of the crate read.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DerTree {
+    Integer(BigUint),
+    OctetString(Vec<u8>),
+    BitString(Vec<u8>),
+    Null,
+    /// Valid arcs: the first in `[0, 2]`, the second in `[0, 39]`.
+    Oid(Vec<u64>),
+    Explicit(u8, Box<DerTree>),
+    Sequence(Vec<DerTree>),
+    /// Bytes written as is, not necessarily DER.
+    Raw(Vec<u8>),
+}
+
+impl DerTree {
+    pub fn to_der(&self) -> Vec<u8> {
+        match self {
+            DerTree::Integer(value) => der::encode_integer(value),
+            DerTree::OctetString(bytes) => der::encode_octet_string(bytes),
+            DerTree::BitString(bytes) => der::encode_bit_string(bytes),
+            DerTree::Null => der::encode_null(),
+            DerTree::Oid(arcs) => der::encode_oid(arcs).expect("generated arcs are valid"),
+            DerTree::Explicit(n, element) => der::encode_explicit(*n, &element.to_der()),
+            DerTree::Sequence(elements) => der::encode_sequence(&elements.iter().map(DerTree::to_der).collect::<Vec<_>>()),
+            DerTree::Raw(bytes) => bytes.clone(),
+        }
+    }
+
+    fn arbitrary_with_depth(u: &mut Unstructured, depth: usize) -> Result<Self> {
+        // the constructed elements are the last two choices
+        let choices = if depth < MAX_DEPTH { 8 } else { 6 };
+        Ok(match u.choose_index(choices)? {
+            0 => DerTree::Integer(arbitrary_biguint(u, MAX_INT_BYTES)?),
+            1 => DerTree::OctetString(Vec::arbitrary(u)?),
+            2 => DerTree::BitString(Vec::arbitrary(u)?),
+            3 => DerTree::Null,
+            4 => {
+                let mut arcs = vec![u.int_in_range(0..=2)?, u.int_in_range(0..=39)?];
+                for _ in 0..u.int_in_range(0..=8)? {
+                    arcs.push(u.arbitrary()?);
+                }
+                DerTree::Oid(arcs)
+            }
+            5 => DerTree::Raw(Vec::arbitrary(u)?),
+            6 => DerTree::Explicit(u.int_in_range(0..=3)?, Box::new(DerTree::arbitrary_with_depth(u, depth + 1)?)),
+            _ => {
+                let mut elements = Vec::new();
+                for _ in 0..u.int_in_range(0..=4)? {
+                    elements.push(DerTree::arbitrary_with_depth(u, depth + 1)?);
+                }
+                DerTree::Sequence(elements)
+            }
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for DerTree {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        DerTree::arbitrary_with_depth(u, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::der::DerReader;
+
+    fn contains_raw(tree: &DerTree) -> bool {
+        match tree {
+            DerTree::Raw(_) => true,
+            DerTree::Explicit(_, element) => contains_raw(element),
+            DerTree::Sequence(elements) => elements.iter().any(contains_raw),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_der_tree_is_der() {
+        let mut checked = 0;
+        for seed in 0..64u8 {
+            let data: Vec<u8> = (0..1024u32).map(|i| (i as u8).wrapping_mul(167).wrapping_add(seed)).collect();
+            let tree = DerTree::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            if contains_raw(&tree) {
+                continue;
+            }
+            let encoded = tree.to_der();
+            let mut reader = DerReader::new(&encoded);
+            reader.read_tlv().unwrap();
+            reader.finish().unwrap();
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_signature() {
+        let data = [3u8, 1, 2, 3, 1, 7];
+        let signature = Signature::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert_eq!(signature, Signature::new(BigUint::from(0x010203u32), BigUint::from(7u32)));
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+Conversions between the keys and signatures of this crate and the ones of
+the `k256` (secp256k1) and `p256` (P-256) crates, to migrate code gradually
+or to check results against those crates:
+
+```text
+ let public_key = PublicKey::<Secp256k1>::from(k256_public_key);
+ let signature = k256::ecdsa::Signature::try_from(private_key.sign_digest(&digest)?)?;
+```
+
+Both crates use RFC 6979 nonces, so for the same key and digest they produce
+the same signature as `PrivateKey::sign_digest`, up to `k256` always
+returning the low-S form.
+*/
+
+use ec_core::curves::{Curve, P256, Secp256k1};
+use ec_core::to_fixed_bytes;
+
+use crate::ecdsa::ECDSAErrors;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::signature::CurveSignature;
+
+macro_rules! interop {
+    ($krate:ident, $curve:ident) => {
+        impl From<&$krate::PublicKey> for PublicKey<$curve> {
+            fn from(key: &$krate::PublicKey) -> Self {
+                PublicKey::from_point(key.into()).expect("a k256 / p256 public key is a point of the curve")
+            }
+        }
+
+        impl From<$krate::PublicKey> for PublicKey<$curve> {
+            fn from(key: $krate::PublicKey) -> Self {
+                PublicKey::from(&key)
+            }
+        }
+
+        impl From<&PublicKey<$curve>> for $krate::PublicKey {
+            fn from(key: &PublicKey<$curve>) -> Self {
+                $krate::PublicKey::try_from(key.point()).expect("a public key is a point of the curve")
+            }
+        }
+
+        impl From<PublicKey<$curve>> for $krate::PublicKey {
+            fn from(key: PublicKey<$curve>) -> Self {
+                $krate::PublicKey::from(&key)
+            }
+        }
+
+        impl From<&$krate::SecretKey> for PrivateKey<$curve> {
+            fn from(key: &$krate::SecretKey) -> Self {
+                let secret = num_bigint::BigUint::from_bytes_be(&key.to_bytes());
+                PrivateKey::new(secret).expect("a k256 / p256 secret key is in [1, q - 1]")
+            }
+        }
+
+        impl From<$krate::SecretKey> for PrivateKey<$curve> {
+            fn from(key: $krate::SecretKey) -> Self {
+                PrivateKey::from(&key)
+            }
+        }
+
+        impl From<&PrivateKey<$curve>> for $krate::SecretKey {
+            fn from(key: &PrivateKey<$curve>) -> Self {
+                let bytes = to_fixed_bytes(key.secret(), $curve::scalar_size()).expect("the secret is below the order");
+                $krate::SecretKey::from_slice(&bytes).expect("a private key is in [1, q - 1]")
+            }
+        }
+
+        impl From<PrivateKey<$curve>> for $krate::SecretKey {
+            fn from(key: PrivateKey<$curve>) -> Self {
+                $krate::SecretKey::from(&key)
+            }
+        }
+
+        impl From<$krate::ecdsa::Signature> for CurveSignature<$curve> {
+            fn from(signature: $krate::ecdsa::Signature) -> Self {
+                CurveSignature::from_bytes(&signature.to_bytes()).expect("the components are in [1, q - 1]")
+            }
+        }
+
+        impl TryFrom<CurveSignature<$curve>> for $krate::ecdsa::Signature {
+            type Error = ECDSAErrors;
+
+            ///
+            /// Fails if `r` or `s` is not in `[1, q - 1]`.
+            ///
+            fn try_from(signature: CurveSignature<$curve>) -> Result<Self, ECDSAErrors> {
+                $krate::ecdsa::Signature::from_slice(&signature.to_bytes())
+                    .map_err(|_| ECDSAErrors::BadArgument("Signature components must be in [1, q - 1]".into()))
+            }
+        }
+    };
+}
+
+interop!(k256, Secp256k1);
+interop!(p256, P256);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use k256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+    use num_bigint::BigUint;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_keys() {
+        let private_key = PrivateKey::<Secp256k1>::new(BigUint::from(0xC0FFEEu32)).unwrap();
+        let secret_key = k256::SecretKey::from(&private_key);
+        assert_eq!(PrivateKey::from(&secret_key), private_key);
+
+        let public_key = private_key.public_key().unwrap();
+        assert_eq!(k256::PublicKey::from(&public_key), secret_key.public_key());
+        assert_eq!(PublicKey::from(secret_key.public_key()), public_key);
+
+        let secret_key = p256::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let private_key = PrivateKey::<P256>::from(&secret_key);
+        assert_eq!(p256::PublicKey::from(private_key.public_key().unwrap()), secret_key.public_key());
+    }
+
+    #[test]
+    fn test_signatures_match_k256() {
+        let digest = Sha256::digest(b"differential testing");
+        let private_key = PrivateKey::<Secp256k1>::new(BigUint::from(0xC0FFEEu32)).unwrap();
+        let signing_key = k256::ecdsa::SigningKey::from(k256::SecretKey::from(&private_key));
+
+        // both use RFC 6979, k256 normalizes s
+        let ours = private_key.sign_digest(&digest).unwrap();
+        let low_s = CurveSignature::try_from(ours.signature().normalize_s(Secp256k1::order())).unwrap();
+        let theirs: k256::ecdsa::Signature = signing_key.sign_prehash(&digest).unwrap();
+        assert_eq!(CurveSignature::from(theirs), low_s);
+
+        let verifying_key = signing_key.verifying_key();
+        let converted = k256::ecdsa::Signature::try_from(low_s).unwrap();
+        assert!(verifying_key.verify_prehash(&digest, &converted).is_ok());
+        let public_key = private_key.public_key().unwrap();
+        assert!(public_key.verify_digest(&digest, &CurveSignature::from(theirs)).unwrap());
+    }
+
+    #[test]
+    fn test_signatures_match_p256() {
+        let digest = Sha256::digest(b"differential testing");
+        let secret_key = p256::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let private_key = PrivateKey::<P256>::from(&secret_key);
+        let signing_key = p256::ecdsa::SigningKey::from(secret_key);
+
+        let theirs: p256::ecdsa::Signature = signing_key.sign_prehash(&digest).unwrap();
+        assert_eq!(CurveSignature::from(theirs), private_key.sign_digest(&digest).unwrap());
+        let converted = p256::ecdsa::Signature::try_from(private_key.sign_digest(&digest).unwrap()).unwrap();
+        assert!(signing_key.verifying_key().verify_prehash(&digest, &converted).is_ok());
+    }
+}
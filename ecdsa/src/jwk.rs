@@ -0,0 +1,340 @@
+/*!
+JSON Web Keys (RFC 7517, RFC 7518 section 6.2) of elliptic curve keys:
+
+```text
+ { "kty": "EC", "crv": "P-256", "x": "...", "y": "...", "d": "..." }
+```
+
+where `x`, `y` and `d` are the big endian, fixed width encodings of the
+coordinates of the public key and of the private key, in base64url without
+padding. `d` is only present in private keys. Members other than these
+(`kid`, `use`, `alg`...) are ignored when reading a key.
+*/
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::keys::{KeyPair, PrivateKey, PublicKey};
+
+/// The `crv` values of RFC 7518 and RFC 8812, which are also the names of the
+/// curves in `ec_core::curves`.
+const JWK_CURVES: [&str; 4] = ["P-256", "P-384", "P-521", "secp256k1"];
+
+///
+/// An elliptic curve JSON Web Key, public or private. The public key is
+/// always a point of the curve and matches the private key if any.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct Jwk {
+    crv: &'static str,
+    pub_key: CurvePoint,
+    priv_key: Option<BigUint>,
+}
+
+impl Jwk {
+    pub fn from_public_key(params: &CurveParams, pub_key: &CurvePoint) -> Result<Self, ECDSAErrors> {
+        if *pub_key == CurvePoint::Identity || !params.curve.is_on_curve(pub_key) {
+            return Err(ECDSAErrors::BadArgument(
+                "Public key is not a point of the curve".into(),
+            ));
+        }
+
+        Ok(Jwk {
+            crv: jwk_curve(params)?,
+            pub_key: pub_key.clone(),
+            priv_key: None,
+        })
+    }
+
+    pub fn from_key_pair(params: &CurveParams, key_pair: &KeyPair) -> Result<Self, ECDSAErrors> {
+        let crv = jwk_curve(params)?;
+        check_key_pair(params, &key_pair.priv_key, &key_pair.pub_key)?;
+
+        Ok(Jwk {
+            crv,
+            pub_key: key_pair.pub_key.clone(),
+            priv_key: Some(key_pair.priv_key.clone()),
+        })
+    }
+
+    /// The `crv` member, e.g. `P-256`.
+    pub fn crv(&self) -> &'static str {
+        self.crv
+    }
+
+    pub fn params(&self) -> &'static CurveParams {
+        curves::by_name(self.crv).expect("JWK curves are named curves")
+    }
+
+    pub fn pub_key(&self) -> &CurvePoint {
+        &self.pub_key
+    }
+
+    pub fn priv_key(&self) -> Option<&BigUint> {
+        self.priv_key.as_ref()
+    }
+
+    /// The key pair of a private JWK.
+    pub fn key_pair(&self) -> Option<KeyPair> {
+        self.priv_key.as_ref().map(|priv_key| KeyPair {
+            priv_key: priv_key.clone(),
+            pub_key: self.pub_key.clone(),
+        })
+    }
+
+    /// The public part of the key, as published in a JWK Set.
+    pub fn to_public(&self) -> Jwk {
+        Jwk {
+            priv_key: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        self.to_value().to_string()
+    }
+
+    ///
+    /// The key as a JSON object, with its members sorted by name.
+    ///
+    pub fn to_value(&self) -> Value {
+        let params = self.params();
+        let encoded = self
+            .pub_key
+            .to_sec1_uncompressed(&params.curve)
+            .expect("a public key is not the identity");
+        let coordinate_size = (encoded.len() - 1) / 2;
+
+        let mut value = json!({
+            "kty": "EC",
+            "crv": self.crv,
+            "x": URL_SAFE_NO_PAD.encode(&encoded[1..1 + coordinate_size]),
+            "y": URL_SAFE_NO_PAD.encode(&encoded[1 + coordinate_size..]),
+        });
+        if let Some(priv_key) = &self.priv_key {
+            let d = to_fixed_bytes(priv_key, scalar_size(params)).expect("d < q fits in the scalar size");
+            value["d"] = Value::String(URL_SAFE_NO_PAD.encode(d));
+        }
+        value
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ECDSAErrors> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid JSON".into()))?;
+        Jwk::from_value(&value)
+    }
+
+    ///
+    /// Reads an elliptic curve JWK, checking that the coordinates have the
+    /// width of the curve, that the point is on the curve and that it is the
+    /// public key of `d` if present.
+    ///
+    pub fn from_value(value: &Value) -> Result<Self, ECDSAErrors> {
+        if member(value, "kty")? != Some("EC") {
+            return Err(ECDSAErrors::BadArgument("Not an elliptic curve JWK".into()));
+        }
+        let crv = member(value, "crv")?.ok_or_else(|| ECDSAErrors::BadArgument("Missing crv".into()))?;
+        let crv = *JWK_CURVES
+            .iter()
+            .find(|name| **name == crv)
+            .ok_or_else(|| ECDSAErrors::BadArgument(format!("Unsupported curve {}", crv)))?;
+        let params = curves::by_name(crv).expect("JWK curves are named curves");
+
+        let x = decode_member(value, "x")?.ok_or_else(|| ECDSAErrors::BadArgument("Missing x".into()))?;
+        let y = decode_member(value, "y")?.ok_or_else(|| ECDSAErrors::BadArgument("Missing y".into()))?;
+        let coordinate_size = (params.curve.p.bits() as usize).div_ceil(8);
+        if x.len() != coordinate_size || y.len() != coordinate_size {
+            return Err(ECDSAErrors::BadArgument("Invalid coordinate length".into()));
+        }
+        let pub_key = CurvePoint::from_sec1_uncompressed(&[&[0x04][..], &x, &y].concat(), &params.curve)
+            .map_err(|_| ECDSAErrors::BadArgument("Public key is not a point of the curve".into()))?;
+
+        let priv_key = match decode_member(value, "d")? {
+            None => None,
+            Some(d) => {
+                if d.len() != scalar_size(params) {
+                    return Err(ECDSAErrors::BadArgument("Invalid private key length".into()));
+                }
+                let priv_key = BigUint::from_bytes_be(&d);
+                check_key_pair(params, &priv_key, &pub_key)?;
+                Some(priv_key)
+            }
+        };
+
+        Ok(Jwk { crv, pub_key, priv_key })
+    }
+}
+
+impl PublicKey {
+    /// The public JWK of the key, on `secp256k1`.
+    pub fn to_jwk(&self) -> Jwk {
+        Jwk::from_public_key(&curves::secp256k1(), self.point()).expect("a public key is a point of secp256k1")
+    }
+
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, ECDSAErrors> {
+        if jwk.crv != "secp256k1" {
+            return Err(ECDSAErrors::BadArgument("Public key is not on secp256k1".into()));
+        }
+        PublicKey::from_point(jwk.pub_key.clone())
+    }
+}
+
+impl PrivateKey {
+    /// The private JWK of the key, on `secp256k1`.
+    pub fn to_jwk(&self) -> Result<Jwk, ECDSAErrors> {
+        let key_pair = KeyPair {
+            priv_key: self.secret().clone(),
+            pub_key: self.public_key()?.point().clone(),
+        };
+        Jwk::from_key_pair(&curves::secp256k1(), &key_pair)
+    }
+
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, ECDSAErrors> {
+        match (&jwk.priv_key, jwk.crv) {
+            (Some(priv_key), "secp256k1") => PrivateKey::new(priv_key.clone()),
+            (None, _) => Err(ECDSAErrors::BadArgument("Not a private JWK".into())),
+            _ => Err(ECDSAErrors::BadArgument("Private key is not on secp256k1".into())),
+        }
+    }
+}
+
+fn jwk_curve(params: &CurveParams) -> Result<&'static str, ECDSAErrors> {
+    JWK_CURVES
+        .iter()
+        .find(|name| curves::by_name(name) == Some(params))
+        .copied()
+        .ok_or_else(|| ECDSAErrors::BadArgument("Curve has no JWK name".into()))
+}
+
+fn check_key_pair(params: &CurveParams, priv_key: &BigUint, pub_key: &CurvePoint) -> Result<(), ECDSAErrors> {
+    let ecdsa = ECDSA::from(params.clone());
+    if *priv_key == BigUint::from(0u32) || *priv_key >= ecdsa.q_order {
+        return Err(ECDSAErrors::BadArgument(
+            "Private key must be in [1, q - 1]".into(),
+        ));
+    }
+    if ecdsa.generate_pub_key(priv_key)? != *pub_key {
+        return Err(ECDSAErrors::BadArgument(
+            "Public key does not match the private key".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn scalar_size(params: &CurveParams) -> usize {
+    params.n.bits().div_ceil(8) as usize
+}
+
+// string member of the JWK, if present
+fn member<'a>(value: &'a Value, name: &str) -> Result<Option<&'a str>, ECDSAErrors> {
+    match value.get(name) {
+        None => Ok(None),
+        Some(Value::String(member)) => Ok(Some(member)),
+        Some(_) => Err(ECDSAErrors::BadArgument(format!("{} is not a string", name))),
+    }
+}
+
+// base64url member of the JWK, if present
+fn decode_member(value: &Value, name: &str) -> Result<Option<Vec<u8>>, ECDSAErrors> {
+    member(value, name)?
+        .map(|member| {
+            URL_SAFE_NO_PAD
+                .decode(member)
+                .map_err(|_| ECDSAErrors::BadArgument(format!("{} is not base64url", name)))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // RFC 7517 appendix A.2
+    const RFC7517_JWK: &str = r#"{"kty":"EC","crv":"P-256",
+        "x":"MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+        "y":"4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+        "d":"870MB6gfuTJ4HtUnUvYMyJpr5eUZNP4Bk43bVdj3eAE",
+        "use":"enc","kid":"1"}"#;
+
+    #[test]
+    fn test_rfc7517_key() {
+        let jwk = Jwk::from_json(RFC7517_JWK).unwrap();
+        assert_eq!(jwk.crv(), "P-256");
+        assert_eq!(*jwk.params(), curves::p256());
+
+        let key_pair = jwk.key_pair().unwrap();
+        assert_eq!(
+            ECDSA::from(curves::p256()).generate_pub_key(&key_pair.priv_key).unwrap(),
+            key_pair.pub_key
+        );
+        assert_eq!(Jwk::from_key_pair(jwk.params(), &key_pair).unwrap(), jwk);
+
+        assert_eq!(
+            jwk.to_json(),
+            "{\"crv\":\"P-256\",\"d\":\"870MB6gfuTJ4HtUnUvYMyJpr5eUZNP4Bk43bVdj3eAE\",\"kty\":\"EC\",\
+             \"x\":\"MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4\",\"y\":\"4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM\"}"
+        );
+
+        let public = jwk.to_public();
+        assert!(public.priv_key().is_none());
+        assert!(public.to_value().get("d").is_none());
+        assert_eq!(Jwk::from_json(&public.to_json()).unwrap(), public);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let priv_key = PrivateKey::new(BigUint::from(123456789u32)).unwrap();
+        let jwk = priv_key.to_jwk().unwrap();
+        assert_eq!(jwk.crv(), "secp256k1");
+        assert_eq!(PrivateKey::from_jwk(&Jwk::from_json(&jwk.to_json()).unwrap()).unwrap(), priv_key);
+
+        let pub_key = priv_key.public_key().unwrap();
+        assert_eq!(PublicKey::from_jwk(&Jwk::from_json(&pub_key.to_jwk().to_json()).unwrap()).unwrap(), pub_key);
+        assert!(PrivateKey::from_jwk(&pub_key.to_jwk()).is_err());
+
+        for name in ["P-384", "P-521"] {
+            let params = curves::by_name(name).unwrap();
+            let key_pair = KeyPair {
+                priv_key: BigUint::from(42u32),
+                pub_key: ECDSA::from(params.clone()).generate_pub_key(&BigUint::from(42u32)).unwrap(),
+            };
+            let jwk = Jwk::from_key_pair(params, &key_pair).unwrap();
+            assert_eq!(jwk.crv(), name);
+            assert_eq!(Jwk::from_json(&jwk.to_json()).unwrap().key_pair().unwrap(), key_pair);
+        }
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let mut value: Value = serde_json::from_str(RFC7517_JWK).unwrap();
+        assert!(Jwk::from_value(&value).is_ok());
+
+        for (name, invalid) in [
+            ("kty", json!("RSA")),
+            ("crv", json!("p-256")),
+            ("crv", json!("brainpoolP256r1")),
+            ("x", json!(3)),
+            // leading zero byte dropped
+            ("x", json!("MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D")),
+            // point off the curve
+            ("y", json!("4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyQ")),
+            // key pair mismatch
+            ("d", json!("870MB6gfuTJ4HtUnUvYMyJpr5eUZNP4Bk43bVdj3eAI")),
+            ("d", json!("870MB6gfuTJ4HtUnUvYMyJpr5eUZNP4Bk43bVdj3eA+")),
+        ] {
+            let valid = value[name].clone();
+            value[name] = invalid;
+            assert!(Jwk::from_value(&value).is_err(), "{}", name);
+            value[name] = valid;
+        }
+
+        assert!(Jwk::from_json("not json").is_err());
+        assert!(Jwk::from_public_key(&curves::brainpool_p256r1(), &curves::brainpool_p256r1().g).is_err());
+        assert!(PublicKey::from_jwk(&Jwk::from_json(RFC7517_JWK).unwrap()).is_err());
+    }
+}
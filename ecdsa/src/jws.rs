@@ -0,0 +1,228 @@
+/*!
+JSON Web Signatures (RFC 7515) with ECDSA, in compact serialization:
+
+```text
+ BASE64URL(protected header) . BASE64URL(payload) . BASE64URL(r || s)
+```
+
+The signature covers the ASCII signing input `BASE64URL(protected header) .
+BASE64URL(payload)`, hashed with SHA-256, and is encoded as the fixed width
+concatenation of `r` and `s` (not DER). Supported algorithms are `ES256`
+(P-256, RFC 7518) and `ES256K` (secp256k1, RFC 8812); a JWT is a JWS whose
+payload is a JSON object of claims.
+*/
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::Signature;
+
+///
+/// The `alg` header parameter of a JWS.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JwsAlgorithm {
+    ES256,
+    ES256K,
+}
+
+impl JwsAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JwsAlgorithm::ES256 => "ES256",
+            JwsAlgorithm::ES256K => "ES256K",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, ECDSAErrors> {
+        match name {
+            "ES256" => Ok(JwsAlgorithm::ES256),
+            "ES256K" => Ok(JwsAlgorithm::ES256K),
+            _ => Err(ECDSAErrors::BadArgument(format!("Unsupported JWS algorithm {}", name))),
+        }
+    }
+
+    /// The curve the algorithm signs with.
+    pub fn params(&self) -> &'static CurveParams {
+        let name = match self {
+            JwsAlgorithm::ES256 => "P-256",
+            JwsAlgorithm::ES256K => "secp256k1",
+        };
+        curves::by_name(name).expect("JWS curves are named curves")
+    }
+}
+
+///
+/// Signs `payload` with the protected header `{"alg": <alg>}`, returning the
+/// compact serialization.
+///
+pub fn sign(alg: JwsAlgorithm, payload: &[u8], priv_key: &BigUint) -> Result<String, ECDSAErrors> {
+    sign_with_header(alg, Map::new(), payload, priv_key)
+}
+
+///
+/// Signs `payload` with the given protected header (e.g. with `typ` or `kid`
+/// members), whose `alg` member is set to `alg`. `k` is derived with
+/// RFC 6979, so signing is deterministic.
+///
+pub fn sign_with_header(
+    alg: JwsAlgorithm,
+    mut header: Map<String, Value>,
+    payload: &[u8],
+    priv_key: &BigUint,
+) -> Result<String, ECDSAErrors> {
+    header.insert("alg".into(), Value::String(alg.name().into()));
+    let encoded_header = URL_SAFE_NO_PAD.encode(Value::Object(header).to_string());
+    let encoded_payload = URL_SAFE_NO_PAD.encode(payload);
+    let signing_input = signing_input(&encoded_header, &encoded_payload);
+
+    let ecdsa = ECDSA::from(alg.params().clone());
+    let digest = Sha256::digest(signing_input.as_bytes());
+    let signature = ecdsa.sign_prehash(&digest, priv_key)?;
+
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes()?)
+    ))
+}
+
+///
+/// Verifies a JWS in compact serialization and returns its payload. The
+/// `alg` of the protected header must be `alg`, so that the algorithm is
+/// chosen by the verifier and not by the token.
+///
+pub fn verify(jws: &str, alg: JwsAlgorithm, pub_key: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+    let (encoded_header, encoded_payload, encoded_signature) = split(jws)?;
+
+    let header = decode_header(jws)?;
+    match header.get("alg") {
+        Some(Value::String(name)) if JwsAlgorithm::from_name(name)? == alg => {}
+        _ => return Err(ECDSAErrors::BadArgument(format!("JWS alg is not {}", alg.name()))),
+    }
+    if header.contains_key("crit") {
+        return Err(ECDSAErrors::BadArgument("Unsupported critical header parameters".into()));
+    }
+
+    let signature = Signature::from_bytes(&decode(encoded_signature)?)?;
+    let ecdsa = ECDSA::from(alg.params().clone());
+    if signature.r >= ecdsa.q_order || signature.s >= ecdsa.q_order {
+        return Err(ECDSAErrors::BadArgument("Signature component >= q".into()));
+    }
+
+    let digest = Sha256::digest(signing_input(encoded_header, encoded_payload).as_bytes());
+    if !ecdsa.verify_prehash(&digest, pub_key, &signature)? {
+        return Err(ECDSAErrors::BadArgument("Invalid JWS signature".into()));
+    }
+
+    decode(encoded_payload)
+}
+
+///
+/// Decodes the protected header of a JWS in compact serialization, without
+/// verifying the signature, e.g. to read its `kid`.
+///
+pub fn decode_header(jws: &str) -> Result<Map<String, Value>, ECDSAErrors> {
+    let (encoded_header, _, _) = split(jws)?;
+    match serde_json::from_slice(&decode(encoded_header)?) {
+        Ok(Value::Object(header)) => Ok(header),
+        _ => Err(ECDSAErrors::BadArgument("JWS header is not a JSON object".into())),
+    }
+}
+
+fn signing_input(encoded_header: &str, encoded_payload: &str) -> String {
+    format!("{}.{}", encoded_header, encoded_payload)
+}
+
+fn split(jws: &str) -> Result<(&str, &str, &str), ECDSAErrors> {
+    let mut parts = jws.split('.');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(payload), Some(signature), None) => Ok((header, payload, signature)),
+        _ => Err(ECDSAErrors::BadArgument("A compact JWS has three parts".into())),
+    }
+}
+
+fn decode(part: &str) -> Result<Vec<u8>, ECDSAErrors> {
+    URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| ECDSAErrors::BadArgument("JWS part is not base64url".into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::jwk::Jwk;
+
+    // RFC 7515 appendix A.3, public part of the key
+    const RFC7515_JWK: &str = r#"{"kty":"EC","crv":"P-256",
+        "x":"f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+        "y":"x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"}"#;
+    const RFC7515_JWS: &str = "eyJhbGciOiJFUzI1NiJ9.\
+        eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.\
+        DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5djxLa8ISlSApmWQxfKTUJqPP3-Kg6NU1Q";
+
+    #[test]
+    fn test_rfc7515_example() {
+        let jwk = Jwk::from_json(RFC7515_JWK).unwrap();
+        let payload = verify(RFC7515_JWS, JwsAlgorithm::ES256, jwk.pub_key()).unwrap();
+        assert_eq!(
+            payload,
+            b"{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \"http://example.com/is_root\":true}"
+        );
+
+        // the verifier picks the algorithm
+        assert!(verify(RFC7515_JWS, JwsAlgorithm::ES256K, jwk.pub_key()).is_err());
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let claims = br#"{"sub":"1234567890","name":"John Doe","iat":1516239022}"#;
+
+        for alg in [JwsAlgorithm::ES256, JwsAlgorithm::ES256K] {
+            let ecdsa = ECDSA::from(alg.params().clone());
+            let priv_key = BigUint::from(0xc0ffeeu32);
+            let pub_key = ecdsa.generate_pub_key(&priv_key).unwrap();
+
+            let mut header = Map::new();
+            header.insert("typ".into(), Value::String("JWT".into()));
+            let jws = sign_with_header(alg, header, claims, &priv_key).unwrap();
+
+            assert_eq!(jws, sign_with_header(alg, decode_header(&jws).unwrap(), claims, &priv_key).unwrap());
+            assert_eq!(decode_header(&jws).unwrap()["alg"], alg.name());
+            assert_eq!(decode_header(&jws).unwrap()["typ"], "JWT");
+            assert_eq!(verify(&jws, alg, &pub_key).unwrap(), claims);
+            assert_eq!(verify(&sign(alg, claims, &priv_key).unwrap(), alg, &pub_key).unwrap(), claims);
+
+            let other_key = ecdsa.generate_pub_key(&BigUint::from(7u32)).unwrap();
+            assert!(verify(&jws, alg, &other_key).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_jws() {
+        let jwk = Jwk::from_json(RFC7515_JWK).unwrap();
+        let pub_key = jwk.pub_key();
+        let (header, payload, signature) = split(RFC7515_JWS).unwrap();
+
+        // tampered payload
+        let tampered = format!("{}.{}.{}", header, URL_SAFE_NO_PAD.encode(b"{}"), signature);
+        assert!(verify(&tampered, JwsAlgorithm::ES256, pub_key).is_err());
+
+        // alg none, unknown alg, crit
+        for header in [r#"{"alg":"none"}"#, r#"{"alg":"ES384"}"#, r#"{"alg":"ES256","crit":["exp"]}"#] {
+            let tampered = format!("{}.{}.{}", URL_SAFE_NO_PAD.encode(header), payload, signature);
+            assert!(verify(&tampered, JwsAlgorithm::ES256, pub_key).is_err());
+        }
+
+        // DER signature, truncated signature, missing parts
+        assert!(verify(&format!("{}.{}.{}", header, payload, &signature[1..]), JwsAlgorithm::ES256, pub_key).is_err());
+        assert!(verify(&format!("{}.{}", header, payload), JwsAlgorithm::ES256, pub_key).is_err());
+        assert!(verify(&format!("{}..", RFC7515_JWS), JwsAlgorithm::ES256, pub_key).is_err());
+    }
+}
@@ -0,0 +1,1019 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::CurveSignature;
+use ec_core::curves::{Curve, Secp256k1};
+use ec_core::CurvePoint;
+use hkdf::Hkdf;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq};
+
+/// Minimum length of the seed of `KeyPair::from_seed`, 128 bits.
+pub const MIN_SEED_SIZE: usize = 16;
+
+const SEED_SALT: &[u8] = b"rust_ecdsa KeyPair::from_seed";
+
+///
+/// A private/public key pair `(d, B)` where `B = d A` and `A` is the generator
+/// of the EC group. `==` compares the keys in constant time.
+///
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyPair {
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub priv_key: BigUint,
+    pub pub_key: CurvePoint,
+}
+
+impl KeyPair {
+    ///
+    /// Generates a new key pair using the given cryptographically secure rng:
+    ///
+    /// - `d` is sampled uniformly in `[1, q - 1]`
+    /// - `B = d A`
+    ///
+    pub fn generate<R: CryptoRng + RngCore>(
+        curve: &ECDSA,
+        rng: &mut R,
+    ) -> Result<Self, ECDSAErrors> {
+        if curve.q_order <= BigUint::from(2u32) {
+            return Err(ECDSAErrors::BadArgument(
+                "Order of the EC group is too small to sample a private key".into(),
+            ));
+        }
+
+        let priv_key = rng.gen_biguint_range(&BigUint::from(1u32), &curve.q_order);
+        let pub_key = curve.generate_pub_key(&priv_key)?;
+
+        Ok(KeyPair { priv_key, pub_key })
+    }
+
+    ///
+    /// Derives a key pair from a seed of at least `MIN_SEED_SIZE` bytes, the
+    /// same on every platform:
+    ///
+    /// - `okm = HKDF-SHA256(salt = "rust_ecdsa KeyPair::from_seed", seed)`,
+    ///   `bytes(q) + 8` bytes long
+    /// - `d = int(okm) mod (q - 1) + 1`, whose bias is below `2^-64`
+    /// - `B = d A`
+    ///
+    pub fn from_seed(curve: &ECDSA, seed: &[u8]) -> Result<Self, ECDSAErrors> {
+        if seed.len() < MIN_SEED_SIZE {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Seed must be at least {} bytes long",
+                MIN_SEED_SIZE
+            )));
+        }
+        if curve.q_order <= BigUint::from(2u32) {
+            return Err(ECDSAErrors::BadArgument(
+                "Order of the EC group is too small to sample a private key".into(),
+            ));
+        }
+
+        let mut okm = vec![0u8; curve.q_order.bits().div_ceil(8) as usize + 8];
+        Hkdf::<Sha256>::new(Some(SEED_SALT), seed)
+            .expand(&[], &mut okm)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error expanding the seed".into()))?;
+
+        let priv_key = BigUint::from_bytes_be(&okm) % (&curve.q_order - 1u32) + 1u32;
+        let pub_key = curve.generate_pub_key(&priv_key)?;
+
+        Ok(KeyPair { priv_key, pub_key })
+    }
+}
+
+///
+/// A public key `B` over the curve `C`, secp256k1 by default. It is always a
+/// point of the curve other than the identity.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct PublicKey<C: Curve = Secp256k1> {
+    point: CurvePoint,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> PublicKey<C> {
+    pub fn from_point(point: CurvePoint) -> Result<Self, ECDSAErrors> {
+        if point == CurvePoint::Identity || !C::params().curve.is_on_curve(&point) {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Public key is not a point of {}",
+                C::NAME
+            )));
+        }
+
+        Ok(PublicKey { point, curve: PhantomData })
+    }
+
+    pub fn from_priv_key(priv_key: &BigUint) -> Result<Self, ECDSAErrors> {
+        let curve = ecdsa::<C>();
+        if *priv_key == BigUint::from(0u32) || *priv_key >= curve.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Private key must be in [1, q - 1]".into(),
+            ));
+        }
+
+        Ok(PublicKey {
+            point: curve.generate_pub_key(priv_key)?,
+            curve: PhantomData,
+        })
+    }
+
+    pub fn point(&self) -> &CurvePoint {
+        &self.point
+    }
+
+    /// `0x02 | 0x03 || x`, 33 bytes on secp256k1
+    pub fn to_sec1_compressed(&self) -> Vec<u8> {
+        self.point
+            .to_sec1_compressed(&C::params().curve)
+            .expect("a public key is not the identity")
+    }
+
+    /// `0x04 || x || y`, 65 bytes on secp256k1
+    pub fn to_sec1_uncompressed(&self) -> Vec<u8> {
+        self.point
+            .to_sec1_uncompressed(&C::params().curve)
+            .expect("a public key is not the identity")
+    }
+
+    ///
+    /// Decodes a SEC1 public key, compressed or not.
+    ///
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, ECDSAErrors> {
+        let curve = &C::params().curve;
+        let point = match bytes.first() {
+            Some(0x04) => CurvePoint::from_sec1_uncompressed(bytes, curve),
+            _ => CurvePoint::from_sec1_compressed(bytes, curve),
+        }
+        .map_err(|_| ECDSAErrors::BadArgument("Invalid SEC1 public key".into()))?;
+
+        PublicKey::from_point(point)
+    }
+
+    /// Hex of the SEC1 compressed or uncompressed encoding.
+    pub fn to_hex(&self, compressed: bool) -> String {
+        if compressed {
+            ec_core::encode_hex(&self.to_sec1_compressed())
+        } else {
+            ec_core::encode_hex(&self.to_sec1_uncompressed())
+        }
+    }
+
+    ///
+    /// Decodes the hex of a SEC1 public key, compressed or not.
+    ///
+    pub fn from_hex(hex: &str) -> Result<Self, ECDSAErrors> {
+        let bytes = ec_core::decode_hex(hex).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        PublicKey::from_sec1(&bytes)
+    }
+
+    ///
+    /// `B' = B + t A`, the public key of `PrivateKey::tweak_add`. The tweak
+    /// must be in `[0, q - 1]` and the result can not be the identity.
+    ///
+    pub fn tweak_add(&self, tweak: &BigUint) -> Result<Self, ECDSAErrors> {
+        check_tweak(tweak, C::order())?;
+        let params = C::params();
+        let tweak_point = params
+            .curve
+            .scalar_mul(&params.g, tweak)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing tweak * a_gen".into()))?;
+        let point = params
+            .curve
+            .add(&self.point, &tweak_point)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing pub_key + tweak * a_gen".into()))?;
+        non_identity(point, "Tweaked public key is the identity")
+    }
+
+    ///
+    /// `B' = t B`, the public key of `PrivateKey::tweak_mul`. The tweak must
+    /// be in `[1, q - 1]`.
+    ///
+    pub fn tweak_mul(&self, tweak: &BigUint) -> Result<Self, ECDSAErrors> {
+        check_tweak(tweak, C::order())?;
+        if *tweak == BigUint::from(0u32) {
+            return Err(ECDSAErrors::BadArgument("Multiplicative tweak can not be zero".into()));
+        }
+        let point = C::params()
+            .curve
+            .scalar_mul(&self.point, tweak)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing tweak * pub_key".into()))?;
+        non_identity(point, "Tweaked public key is the identity")
+    }
+
+    ///
+    /// `B_1 + ... + B_m`, the public key of the sum of the private keys, as
+    /// in naive key aggregation (which is open to rogue key attacks when the
+    /// keys are not proven, MuSig2 weights them for that). Fails on an empty
+    /// list and when the sum is the identity, e.g. for `B` and `-B`.
+    ///
+    pub fn combine(keys: &[PublicKey<C>]) -> Result<Self, ECDSAErrors> {
+        if keys.is_empty() {
+            return Err(ECDSAErrors::BadArgument("No public key to combine".into()));
+        }
+        let curve = &C::params().curve;
+        let sum = keys.iter().try_fold(CurvePoint::Identity, |sum, key| {
+            curve
+                .add(&sum, &key.point)
+                .map_err(|_| ECDSAErrors::OperationFailure("Error adding the public keys".into()))
+        })?;
+        non_identity(sum, "Combined public key is the identity")
+    }
+
+    ///
+    /// Full public key validation (NIST SP 800-56A, 5.6.2.3.3), for keys
+    /// received from a peer:
+    ///
+    /// - `B` is not the identity
+    /// - its coordinates are in `[0, p - 1]`
+    /// - `B` satisfies the equation of the curve
+    /// - `n B = I`, i.e. `B` is in the subgroup of prime order `n`
+    ///
+    /// `from_point` already rejects the identity and the points off the
+    /// curve, but not the points of small order of a curve with a cofactor.
+    ///
+    pub fn validate(&self) -> Result<(), ECDSAErrors> {
+        let params = C::params();
+        let (x, y) = match &self.point {
+            CurvePoint::Coordinate(x, y) => (x, y),
+            CurvePoint::Identity => {
+                return Err(ECDSAErrors::BadArgument("Public key is the identity".into()));
+            }
+        };
+        if *x >= params.curve.p || *y >= params.curve.p {
+            return Err(ECDSAErrors::BadArgument(
+                "Public key coordinates must be in [0, p - 1]".into(),
+            ));
+        }
+        if !params.curve.is_on_curve(&self.point) {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Public key is not a point of {}",
+                C::NAME
+            )));
+        }
+        if !params.is_in_prime_subgroup(&self.point) {
+            return Err(ECDSAErrors::BadArgument(
+                "Public key is not in the subgroup of prime order".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// The x-only form of the key along with the parity of its `y`
+    /// coordinate, `true` when odd. The key is recovered from both as
+    /// `lift_x(x)` if even, its negation otherwise.
+    ///
+    pub fn x_only_public_key(&self) -> (XOnlyPublicKey<C>, bool) {
+        match &self.point {
+            CurvePoint::Coordinate(x, y) => (
+                XOnlyPublicKey { x: x.clone(), curve: PhantomData },
+                y.bit(0),
+            ),
+            CurvePoint::Identity => unreachable!("a public key is not the identity"),
+        }
+    }
+
+    ///
+    /// Verifies a signature of the curve `C` over a message, see
+    /// `PrivateKey::sign_message`.
+    ///
+    pub fn verify_message(&self, message: &[u8], signature: &CurveSignature<C>) -> Result<bool, ECDSAErrors> {
+        ecdsa::<C>().verify_message(message, &self.point, signature.signature())
+    }
+
+    ///
+    /// Verifies a signature of the curve `C` over a message digest, see
+    /// `PrivateKey::sign_prehash`.
+    ///
+    pub fn verify_prehash(&self, digest: &[u8], signature: &CurveSignature<C>) -> Result<bool, ECDSAErrors> {
+        ecdsa::<C>().verify_prehash(digest, &self.point, signature.signature())
+    }
+
+    /// Verifies a signature over the digest of a message with `D`.
+    pub fn verify_message_with<D: Digest>(&self, message: &[u8], signature: &CurveSignature<C>) -> Result<bool, ECDSAErrors> {
+        ecdsa::<C>().verify_message_with::<D>(message, &self.point, signature.signature())
+    }
+
+    /// Same as `verify_prehash`.
+    pub fn verify_digest(&self, digest: &[u8], signature: &CurveSignature<C>) -> Result<bool, ECDSAErrors> {
+        self.verify_prehash(digest, signature)
+    }
+}
+
+///
+/// Writes the hex of the SEC1 compressed encoding.
+///
+impl<C: Curve> fmt::Display for PublicKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex(true))
+    }
+}
+
+impl<C: Curve> FromStr for PublicKey<C> {
+    type Err = ECDSAErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PublicKey::from_hex(s)
+    }
+}
+
+///
+/// An x-only public key over the curve `C`, secp256k1 by default: the `x`
+/// coordinate of a point whose `y` coordinate is implicitly even, as used by
+/// BIP 340 / Taproot and Nostr. It is serialized as `x` alone on the byte
+/// size of `p`, 32 bytes on secp256k1.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct XOnlyPublicKey<C: Curve = Secp256k1> {
+    x: BigUint,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> XOnlyPublicKey<C> {
+    ///
+    /// Builds the x-only key of a point, dropping the parity of `y`.
+    ///
+    pub fn from_point(point: &CurvePoint) -> Result<Self, ECDSAErrors> {
+        Ok(PublicKey::<C>::from_point(point.clone())?.x_only_public_key().0)
+    }
+
+    pub fn x(&self) -> &BigUint {
+        &self.x
+    }
+
+    /// The point `(x, y)` with `y` even.
+    pub fn to_point(&self) -> CurvePoint {
+        CurvePoint::lift_x(&self.x, false, &C::params().curve)
+            .expect("an x-only public key is the x coordinate of a point")
+    }
+
+    pub fn to_public_key(&self) -> PublicKey<C> {
+        PublicKey { point: self.to_point(), curve: PhantomData }
+    }
+
+    /// `x` big endian on the byte size of `p`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        ec_core::to_fixed_bytes(&self.x, field_size::<C>()).expect("x is below p")
+    }
+
+    ///
+    /// Decodes an x-only public key, rejecting encodings of the wrong size,
+    /// `x >= p` and `x` that is not the coordinate of any point.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ECDSAErrors> {
+        if bytes.len() != field_size::<C>() {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "X-only public key must be {} bytes",
+                field_size::<C>()
+            )));
+        }
+
+        let x = BigUint::from_bytes_be(bytes);
+        CurvePoint::lift_x(&x, false, &C::params().curve)
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid x-only public key".into()))?;
+
+        Ok(XOnlyPublicKey { x, curve: PhantomData })
+    }
+
+    pub fn to_hex(&self) -> String {
+        ec_core::encode_hex(&self.to_bytes())
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, ECDSAErrors> {
+        let bytes = ec_core::decode_hex(hex).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        XOnlyPublicKey::from_bytes(&bytes)
+    }
+}
+
+impl<C: Curve> fmt::Display for XOnlyPublicKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl<C: Curve> FromStr for XOnlyPublicKey<C> {
+    type Err = ECDSAErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        XOnlyPublicKey::from_hex(s)
+    }
+}
+
+impl<C: Curve> From<XOnlyPublicKey<C>> for PublicKey<C> {
+    fn from(key: XOnlyPublicKey<C>) -> Self {
+        key.to_public_key()
+    }
+}
+
+///
+/// A private key `d` over the curve `C`, secp256k1 by default, always in
+/// `[1, q - 1]`.
+///
+#[derive(Clone, Debug)]
+pub struct PrivateKey<C: Curve = Secp256k1> {
+    secret: BigUint,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> PrivateKey<C> {
+    pub fn new(secret: BigUint) -> Result<Self, ECDSAErrors> {
+        if secret == BigUint::from(0u32) || secret >= *C::order() {
+            return Err(ECDSAErrors::BadArgument(
+                "Private key must be in [1, q - 1]".into(),
+            ));
+        }
+
+        Ok(PrivateKey { secret, curve: PhantomData })
+    }
+
+    pub fn secret(&self) -> &BigUint {
+        &self.secret
+    }
+
+    ///
+    /// `d' = d + t mod q`, the key of `B + t A`. Used by BIP-32 child
+    /// derivation, Taproot and pay-to-contract commitments. The tweak must
+    /// be in `[0, q - 1]` and the result can not be zero.
+    ///
+    pub fn tweak_add(&self, tweak: &BigUint) -> Result<Self, ECDSAErrors> {
+        let q_order = C::order();
+        check_tweak(tweak, q_order)?;
+        PrivateKey::new((&self.secret + tweak) % q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Tweaked private key is zero".into()))
+    }
+
+    ///
+    /// `d' = d t mod q`, the key of `t B`. The tweak must be in
+    /// `[1, q - 1]`, which keeps the result in `[1, q - 1]` since `q` is a
+    /// prime.
+    ///
+    pub fn tweak_mul(&self, tweak: &BigUint) -> Result<Self, ECDSAErrors> {
+        let q_order = C::order();
+        check_tweak(tweak, q_order)?;
+        if *tweak == BigUint::from(0u32) {
+            return Err(ECDSAErrors::BadArgument("Multiplicative tweak can not be zero".into()));
+        }
+        PrivateKey::new((&self.secret * tweak) % q_order)
+            .map_err(|_| ECDSAErrors::OperationFailure("Tweaked private key is zero".into()))
+    }
+
+    pub fn public_key(&self) -> Result<PublicKey<C>, ECDSAErrors> {
+        PublicKey::from_priv_key(&self.secret)
+    }
+
+    /// Hex of `d` big endian on the byte size of the order.
+    pub fn to_hex(&self) -> String {
+        ec_core::encode_hex(&ec_core::to_fixed_bytes(&self.secret, C::scalar_size()).expect("a private key is below q"))
+    }
+
+    ///
+    /// Decodes a private key from the hex of `d`, which must be exactly the
+    /// byte size of the order.
+    ///
+    pub fn from_hex(hex: &str) -> Result<Self, ECDSAErrors> {
+        let bytes = ec_core::decode_hex(hex).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        if bytes.len() != C::scalar_size() {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Private key must be {} bytes",
+                C::scalar_size()
+            )));
+        }
+
+        PrivateKey::new(BigUint::from_bytes_be(&bytes))
+    }
+
+    ///
+    /// Hashes the message with the SHA-2 function matching the size of the
+    /// order, see `ECDSA::message_digest`, and signs the digest.
+    ///
+    pub fn sign_message(&self, message: &[u8]) -> Result<CurveSignature<C>, ECDSAErrors> {
+        CurveSignature::try_from(ecdsa::<C>().sign_message(message, &self.secret)?)
+    }
+
+    ///
+    /// Signs a message digest with a RFC 6979 deterministic nonce, the
+    /// digest being truncated to the bit size of the order and not hashed
+    /// again:
+    ///
+    /// `hash = bits2int(digest) mod q`
+    ///
+    pub fn sign_prehash(&self, digest: &[u8]) -> Result<CurveSignature<C>, ECDSAErrors> {
+        CurveSignature::try_from(ecdsa::<C>().sign_prehash(digest, &self.secret)?)
+    }
+
+    ///
+    /// `sign_prehash` with additional data mixed into the nonce, see
+    /// `ECDSA::sign_prehash_with_extra_entropy`.
+    ///
+    pub fn sign_prehash_with_extra_entropy(
+        &self,
+        digest: &[u8],
+        extra_entropy: &[u8],
+    ) -> Result<CurveSignature<C>, ECDSAErrors> {
+        CurveSignature::try_from(ecdsa::<C>().sign_prehash_with_extra_entropy(digest, &self.secret, extra_entropy)?)
+    }
+
+    ///
+    /// A low-R, low-S signature of a digest, see `ECDSA::sign_prehash_low_r`.
+    ///
+    pub fn sign_prehash_low_r(&self, digest: &[u8]) -> Result<CurveSignature<C>, ECDSAErrors> {
+        CurveSignature::try_from(ecdsa::<C>().sign_prehash_low_r(digest, &self.secret)?)
+    }
+
+    ///
+    /// Hashes the message with `D` chosen by the caller and signs the
+    /// digest, see `ECDSA::sign_message_with`.
+    ///
+    pub fn sign_message_with<D: Digest + BlockSizeUser>(&self, message: &[u8]) -> Result<CurveSignature<C>, ECDSAErrors> {
+        CurveSignature::try_from(ecdsa::<C>().sign_message_with::<D>(message, &self.secret)?)
+    }
+
+    /// Same as `sign_prehash`.
+    pub fn sign_digest(&self, digest: &[u8]) -> Result<CurveSignature<C>, ECDSAErrors> {
+        self.sign_prehash(digest)
+    }
+}
+
+///
+/// Parses the hex of a private key, see `from_hex`. There is deliberately no
+/// `Display` counterpart so that secrets do not end up in logs: use `to_hex`.
+///
+impl<C: Curve> FromStr for PrivateKey<C> {
+    type Err = ECDSAErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PrivateKey::from_hex(s)
+    }
+}
+
+impl ConstantTimeEq for KeyPair {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ec_core::biguint_ct_eq(&self.priv_key, &other.priv_key) & self.pub_key.ct_eq(&other.pub_key)
+    }
+}
+
+impl PartialEq for KeyPair {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+///
+/// Constant time comparison of the secrets, which `==` uses as well.
+///
+impl<C: Curve> ConstantTimeEq for PrivateKey<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ec_core::biguint_ct_eq(&self.secret, &other.secret)
+    }
+}
+
+impl<C: Curve> PartialEq for PrivateKey<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<C: Curve> Eq for PrivateKey<C> {}
+
+///
+/// A public key is serialized as its SEC1 compressed encoding, hex in human
+/// readable formats. Both SEC1 encodings are accepted back.
+///
+#[cfg(feature = "serde")]
+impl<C: Curve> serde::Serialize for PublicKey<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ec_core::serde_support::bytes::serialize(&self.to_sec1_compressed(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: Curve> serde::Deserialize<'de> for PublicKey<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = ec_core::serde_support::bytes::deserialize(deserializer)?;
+        PublicKey::from_sec1(&encoded).map_err(|_| serde::de::Error::custom("invalid public key"))
+    }
+}
+
+///
+/// An x-only public key is serialized as its 32 bytes (on secp256k1) `x`
+/// coordinate, hex in human readable formats.
+///
+#[cfg(feature = "serde")]
+impl<C: Curve> serde::Serialize for XOnlyPublicKey<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ec_core::serde_support::bytes::serialize(&self.to_bytes(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: Curve> serde::Deserialize<'de> for XOnlyPublicKey<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = ec_core::serde_support::bytes::deserialize(deserializer)?;
+        XOnlyPublicKey::from_bytes(&encoded).map_err(|_| serde::de::Error::custom("invalid x-only public key"))
+    }
+}
+
+///
+/// A private key is serialized as a big endian integer on the byte size of
+/// the order (32 bytes on secp256k1), hex in human readable formats.
+///
+#[cfg(feature = "serde")]
+impl<C: Curve> serde::Serialize for PrivateKey<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = ec_core::to_fixed_bytes(&self.secret, C::scalar_size()).expect("a private key is below q");
+        ec_core::serde_support::bytes::serialize(&bytes, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: Curve> serde::Deserialize<'de> for PrivateKey<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = ec_core::serde_support::bytes::deserialize(deserializer)?;
+        if bytes.len() != C::scalar_size() {
+            return Err(serde::de::Error::custom(format!(
+                "private key must be {} bytes",
+                C::scalar_size()
+            )));
+        }
+
+        PrivateKey::new(BigUint::from_bytes_be(&bytes))
+            .map_err(|_| serde::de::Error::custom("private key must be in [1, q - 1]"))
+    }
+}
+
+fn ecdsa<C: Curve>() -> ECDSA {
+    ECDSA::from(C::params().clone())
+}
+
+fn field_size<C: Curve>() -> usize {
+    C::p().bits().div_ceil(8) as usize
+}
+
+fn non_identity<C: Curve>(point: CurvePoint, error: &str) -> Result<PublicKey<C>, ECDSAErrors> {
+    if point == CurvePoint::Identity {
+        return Err(ECDSAErrors::OperationFailure(error.into()));
+    }
+    Ok(PublicKey { point, curve: PhantomData })
+}
+
+// a tweak is a scalar, it is rejected rather than reduced when not below `q`
+fn check_tweak(tweak: &BigUint, q_order: &BigUint) -> Result<(), ECDSAErrors> {
+    if tweak >= q_order {
+        return Err(ECDSAErrors::BadArgument("Tweak must be in [0, q - 1]".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves::Secp256k1;
+    use ec_core::EllipticCurve;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn test_curve() -> ECDSA {
+        ECDSA {
+            elliptic_curve: EllipticCurve {
+                a: BigUint::from(2u32),
+                b: BigUint::from(2u32),
+                p: BigUint::from(17u32),
+            },
+            a_gen: CurvePoint::Coordinate(BigUint::from(5u32), BigUint::from(1u32)),
+            q_order: BigUint::from(19u32),
+        }
+    }
+
+    #[test]
+    fn test_public_key_validate() {
+        use ec_core::curves::P256;
+
+        let key = PublicKey::<P256>::from_priv_key(&BigUint::from(12345u32)).unwrap();
+        assert!(key.validate().is_ok());
+
+        let p = &P256::params().curve.p;
+        let (x, y) = match key.point() {
+            CurvePoint::Coordinate(x, y) => (x.clone(), y.clone()),
+            CurvePoint::Identity => unreachable!(),
+        };
+        let invalid = [
+            CurvePoint::Identity,
+            // same point modulo p, but not reduced
+            CurvePoint::Coordinate(&x + p, y.clone()),
+            CurvePoint::Coordinate(x, y + 1u32),
+        ];
+        for point in invalid {
+            assert!(PublicKey::<P256> { point, curve: PhantomData }.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_private_key_tweaks() {
+        let q_order = Secp256k1::order();
+        let key = PrivateKey::<Secp256k1>::new(BigUint::from(5u32)).unwrap();
+
+        assert_eq!(key.tweak_add(&BigUint::from(0u32)).unwrap(), key);
+        assert_eq!(key.tweak_add(&(q_order - 2u32)).unwrap().secret(), &BigUint::from(3u32));
+        assert_eq!(key.tweak_mul(&BigUint::from(3u32)).unwrap().secret(), &BigUint::from(15u32));
+        assert_eq!(key.tweak_mul(&(q_order - 1u32)).unwrap().secret(), &(q_order - 5u32));
+
+        // the tweaked keys match the tweaked public keys
+        let tweak = BigUint::from(0xdeadbeefu32);
+        let ecdsa = ecdsa::<Secp256k1>();
+        let curve = &ecdsa.elliptic_curve;
+        let pub_key = key.public_key().unwrap();
+        let expected = curve.add(pub_key.point(), &ecdsa.generate_pub_key(&tweak).unwrap()).unwrap();
+        assert_eq!(key.tweak_add(&tweak).unwrap().public_key().unwrap().point(), &expected);
+        let expected = curve.scalar_mul(pub_key.point(), &tweak).unwrap();
+        assert_eq!(key.tweak_mul(&tweak).unwrap().public_key().unwrap().point(), &expected);
+
+        // zero results and out of range tweaks
+        assert!(key.tweak_add(&(q_order - 5u32)).is_err());
+        assert!(key.tweak_add(q_order).is_err());
+        assert!(key.tweak_mul(&BigUint::from(0u32)).is_err());
+        assert!(key.tweak_mul(q_order).is_err());
+    }
+
+    #[test]
+    fn test_public_key_tweaks() {
+        let q_order = Secp256k1::order();
+        let key = PrivateKey::<Secp256k1>::new(BigUint::from(0x1234u32)).unwrap();
+        let pub_key = key.public_key().unwrap();
+        let tweak = BigUint::from(0xdeadbeefu32);
+
+        assert_eq!(pub_key.tweak_add(&tweak).unwrap(), key.tweak_add(&tweak).unwrap().public_key().unwrap());
+        assert_eq!(pub_key.tweak_mul(&tweak).unwrap(), key.tweak_mul(&tweak).unwrap().public_key().unwrap());
+        assert_eq!(pub_key.tweak_add(&BigUint::from(0u32)).unwrap(), pub_key);
+
+        // B + (q - d) A = I
+        assert!(pub_key.tweak_add(&(q_order - 0x1234u32)).is_err());
+        assert!(pub_key.tweak_add(q_order).is_err());
+        assert!(pub_key.tweak_mul(&BigUint::from(0u32)).is_err());
+    }
+
+    #[test]
+    fn test_public_key_combine() {
+        let keys: Vec<PublicKey<Secp256k1>> = [3u32, 5, 7]
+            .iter()
+            .map(|d| PublicKey::from_priv_key(&BigUint::from(*d)).unwrap())
+            .collect();
+        assert_eq!(PublicKey::combine(&keys).unwrap(), PublicKey::from_priv_key(&BigUint::from(15u32)).unwrap());
+        assert_eq!(PublicKey::combine(&keys[..1]).unwrap(), keys[0]);
+
+        let negated = PublicKey::<Secp256k1>::from_priv_key(&(Secp256k1::order() - 3u32)).unwrap();
+        assert!(PublicKey::combine(&[keys[0].clone(), negated]).is_err());
+        assert!(PublicKey::<Secp256k1>::combine(&[]).is_err());
+    }
+
+    #[test]
+    fn test_private_key_ct_eq() {
+        let a = PrivateKey::<Secp256k1>::new(BigUint::from(5u32)).unwrap();
+        let b = PrivateKey::<Secp256k1>::new(BigUint::from(6u32)).unwrap();
+        assert!(bool::from(a.ct_eq(&a.clone())));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert_eq!(a, a.clone());
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_generate_key_pair() {
+        let ecdsa = test_curve();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..50 {
+            let key_pair = KeyPair::generate(&ecdsa, &mut rng).expect("Could not generate keys");
+
+            assert!(key_pair.priv_key >= BigUint::from(1u32));
+            assert!(key_pair.priv_key < ecdsa.q_order);
+            assert_eq!(
+                key_pair.pub_key,
+                ecdsa
+                    .elliptic_curve
+                    .scalar_mul(&ecdsa.a_gen, &key_pair.priv_key)
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_key_pair_is_reproducible_with_seeded_rng() {
+        let ecdsa = test_curve();
+
+        let a = KeyPair::generate(&ecdsa, &mut StdRng::seed_from_u64(7)).unwrap();
+        let b = KeyPair::generate(&ecdsa, &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_key_pair_from_seed() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let seed = [0x42u8; 32];
+
+        let a = KeyPair::from_seed(&ecdsa, &seed).unwrap();
+        assert_eq!(a, KeyPair::from_seed(&ecdsa, &seed).unwrap());
+        assert_eq!(a.pub_key, ecdsa.generate_pub_key(&a.priv_key).unwrap());
+        assert!(a.priv_key >= BigUint::from(1u32) && a.priv_key < ecdsa.q_order);
+        // computed independently from RFC 5869
+        assert_eq!(a.priv_key, BigUint::parse_bytes(b"8610275acc0ebeb869875f4496c43c9c5cc80a712db35cedbb91676ba6a7a408", 16).unwrap());
+
+        assert_ne!(a, KeyPair::from_seed(&ecdsa, &[0x43u8; 32]).unwrap());
+        assert_ne!(a.priv_key, KeyPair::from_seed(&test_curve(), &seed).unwrap().priv_key);
+        assert!(KeyPair::from_seed(&ecdsa, &seed[..MIN_SEED_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn test_generate_key_pair_tiny_order() {
+        let mut ecdsa = test_curve();
+        ecdsa.q_order = BigUint::from(2u32);
+
+        assert!(KeyPair::generate(&ecdsa, &mut rand::thread_rng()).is_err());
+    }
+
+    #[test]
+    fn test_public_key() {
+        let pub_key = PublicKey::<Secp256k1>::from_priv_key(&BigUint::from(1u32)).unwrap();
+        let g = ec_core::curves::secp256k1().g;
+        assert_eq!(*pub_key.point(), g);
+        assert_eq!(PublicKey::<Secp256k1>::from_point(g).unwrap(), pub_key);
+
+        assert_eq!(pub_key.to_sec1_compressed().len(), 33);
+        assert_eq!(pub_key.to_sec1_uncompressed().len(), 65);
+
+        assert!(PublicKey::<Secp256k1>::from_point(CurvePoint::Identity).is_err());
+        assert!(PublicKey::<Secp256k1>::from_point(CurvePoint::Coordinate(BigUint::from(1u32), BigUint::from(1u32))).is_err());
+        assert!(PublicKey::<Secp256k1>::from_priv_key(&BigUint::from(0u32)).is_err());
+    }
+
+    #[test]
+    fn test_private_key() {
+        let priv_key = PrivateKey::<Secp256k1>::new(BigUint::from(1u32)).unwrap();
+        assert_eq!(*priv_key.public_key().unwrap().point(), ec_core::curves::secp256k1().g);
+
+        assert!(PrivateKey::<Secp256k1>::new(BigUint::from(0u32)).is_err());
+        assert!(PrivateKey::<Secp256k1>::new(ec_core::curves::secp256k1().n).is_err());
+    }
+
+    #[test]
+    fn test_keys_of_other_curves() {
+        use ec_core::curves::{P256, P384};
+        use sha2::{Digest, Sha256};
+
+        // RFC 6979 A.2.5, P-256 with SHA-256, message "sample"
+        let priv_key = PrivateKey::<P256>::new(
+            BigUint::parse_bytes(b"c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 16).unwrap(),
+        )
+        .unwrap();
+        let digest = Sha256::digest(b"sample");
+        let signature = priv_key.sign_digest(&digest).unwrap();
+        assert_eq!(
+            hex::encode(signature.to_bytes()),
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+             f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+        );
+
+        let pub_key = priv_key.public_key().unwrap();
+        assert!(pub_key.verify_digest(&digest, &signature).unwrap());
+        assert!(!pub_key.verify_digest(&Sha256::digest(b"samplf"), &signature).unwrap());
+        assert_eq!(PublicKey::<P256>::from_sec1(&pub_key.to_sec1_compressed()).unwrap(), pub_key);
+
+        // the P-256 generator is not a point of P-384, nor of secp256k1
+        let g = ec_core::curves::p256().g;
+        assert!(PublicKey::<P384>::from_point(g.clone()).is_err());
+        assert!(PublicKey::<Secp256k1>::from_point(g).is_err());
+        assert!(PrivateKey::<P384>::new(ec_core::curves::p384().n).is_err());
+    }
+
+    #[test]
+    fn test_x_only_public_key() {
+        // BIP 340 test vector 0, secret key 3
+        let pub_key = PublicKey::<Secp256k1>::from_priv_key(&BigUint::from(3u32)).unwrap();
+        let (x_only, odd) = pub_key.x_only_public_key();
+        assert_eq!(
+            hex::encode(x_only.to_bytes()),
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+        assert_eq!(XOnlyPublicKey::<Secp256k1>::from_bytes(&x_only.to_bytes()).unwrap(), x_only);
+        assert_eq!(XOnlyPublicKey::<Secp256k1>::from_point(pub_key.point()).unwrap(), x_only);
+
+        let curve = &ec_core::curves::secp256k1().curve;
+        let expected = if odd { curve.neg(pub_key.point()).unwrap() } else { pub_key.point().clone() };
+        assert_eq!(x_only.to_point(), expected);
+        assert_eq!(PublicKey::from(x_only.clone()).point(), &expected);
+
+        // the negated key has the same x-only form and the other parity
+        let negated = PublicKey::<Secp256k1>::from_point(curve.neg(pub_key.point()).unwrap()).unwrap();
+        assert_eq!(negated.x_only_public_key(), (x_only, !odd));
+
+        // BIP 340 test vectors 5 (not on the curve) and 14 (x >= p), wrong size
+        for encoded in [
+            "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036",
+        ] {
+            assert!(XOnlyPublicKey::<Secp256k1>::from_bytes(&hex::decode(encoded).unwrap()).is_err());
+        }
+        assert!(XOnlyPublicKey::<Secp256k1>::from_point(&CurvePoint::Identity).is_err());
+    }
+
+    #[test]
+    fn test_sign_message_and_prehash() {
+        use sha2::{Digest, Sha256};
+
+        let priv_key = PrivateKey::<Secp256k1>::new(BigUint::from(0xC0FFEEu32)).unwrap();
+        let pub_key = priv_key.public_key().unwrap();
+
+        let signature = priv_key.sign_message(b"hello world").unwrap();
+        let digest = Sha256::digest(b"hello world");
+        assert_eq!(priv_key.sign_prehash(&digest).unwrap(), signature);
+        assert_eq!(priv_key.sign_digest(&digest).unwrap(), signature);
+
+        assert!(pub_key.verify_message(b"hello world", &signature).unwrap());
+        assert!(pub_key.verify_prehash(&digest, &signature).unwrap());
+        assert!(!pub_key.verify_message(&digest, &signature).unwrap());
+        assert!(!pub_key.verify_message(b"hello world!", &signature).unwrap());
+
+        let signature = priv_key.sign_message_with::<sha2::Sha512>(b"hello world").unwrap();
+        assert!(pub_key.verify_message_with::<sha2::Sha512>(b"hello world", &signature).unwrap());
+        assert!(!pub_key.verify_message(b"hello world", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_hex_keys() {
+        let priv_key = PrivateKey::<Secp256k1>::from_hex(&format!("{}03", "00".repeat(31))).unwrap();
+        assert_eq!(*priv_key.secret(), BigUint::from(3u32));
+        assert_eq!(priv_key.to_hex(), format!("{}03", "00".repeat(31)));
+
+        let pub_key = priv_key.public_key().unwrap();
+        let compressed = pub_key.to_hex(true);
+        assert_eq!(compressed, "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        assert_eq!(pub_key.to_hex(false).len(), 130);
+        assert_eq!(PublicKey::<Secp256k1>::from_hex(&compressed).unwrap(), pub_key);
+        assert_eq!(PublicKey::<Secp256k1>::from_hex(&pub_key.to_hex(false)).unwrap(), pub_key);
+
+        let (x_only, _) = pub_key.x_only_public_key();
+        assert_eq!(x_only.to_hex(), compressed[2..]);
+        assert_eq!(XOnlyPublicKey::<Secp256k1>::from_hex(&compressed[2..]).unwrap(), x_only);
+
+        // not hex, short, zero
+        assert!(PrivateKey::<Secp256k1>::from_hex("zz").is_err());
+        assert!(PrivateKey::<Secp256k1>::from_hex("03").is_err());
+        assert!(PrivateKey::<Secp256k1>::from_hex(&"00".repeat(32)).is_err());
+        assert!(PublicKey::<Secp256k1>::from_hex(&compressed[..65]).is_err());
+        assert!(XOnlyPublicKey::<Secp256k1>::from_hex(&compressed).is_err());
+    }
+
+    #[test]
+    fn test_display_from_str_keys() {
+        let priv_key: PrivateKey = format!("{}03", "00".repeat(31)).parse().unwrap();
+        let pub_key = priv_key.public_key().unwrap();
+
+        let text = pub_key.to_string();
+        assert_eq!(text, "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        assert_eq!(text.parse::<PublicKey>().unwrap(), pub_key);
+        assert_eq!(pub_key.to_hex(false).parse::<PublicKey>().unwrap(), pub_key);
+
+        let (x_only, _) = pub_key.x_only_public_key();
+        assert_eq!(x_only.to_string(), text[2..]);
+        assert_eq!(text[2..].parse::<XOnlyPublicKey>().unwrap(), x_only);
+
+        assert!("Identity".parse::<PublicKey>().is_err());
+        assert!("".parse::<PrivateKey>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keys() {
+        let priv_key = PrivateKey::<Secp256k1>::new(BigUint::from(1u32)).unwrap();
+        let json = serde_json::to_string(&priv_key).unwrap();
+        assert_eq!(json, format!("\"{}01\"", "00".repeat(31)));
+        assert_eq!(serde_json::from_str::<PrivateKey>(&json).unwrap(), priv_key);
+
+        let pub_key = priv_key.public_key().unwrap();
+        let json = serde_json::to_string(&pub_key).unwrap();
+        assert_eq!(json, "\"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\"");
+        assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), pub_key);
+        let uncompressed = format!("\"{}\"", hex::encode(pub_key.to_sec1_uncompressed()));
+        assert_eq!(serde_json::from_str::<PublicKey>(&uncompressed).unwrap(), pub_key);
+
+        // zero, too short, off curve
+        for json in [format!("\"{}\"", "00".repeat(32)), "\"01\"".into()] {
+            assert!(serde_json::from_str::<PrivateKey>(&json).is_err());
+        }
+        assert!(serde_json::from_str::<PublicKey>(&format!("\"02{}\"", "00".repeat(31) + "05")).is_err());
+
+        let (x_only, _) = pub_key.x_only_public_key();
+        let json = serde_json::to_string(&x_only).unwrap();
+        assert_eq!(json, "\"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\"");
+        assert_eq!(serde_json::from_str::<XOnlyPublicKey>(&json).unwrap(), x_only);
+
+        let key_pair = KeyPair { priv_key: BigUint::from(1u32), pub_key: pub_key.point().clone() };
+        let json = serde_json::to_string(&key_pair).unwrap();
+        assert_eq!(serde_json::from_str::<KeyPair>(&json).unwrap(), key_pair);
+    }
+}
@@ -1 +1,57 @@
-mod ecdsa;
+pub mod adaptor;
+pub mod batch;
+pub mod bip32;
+pub mod bip39;
+pub mod bitcoin;
+pub mod blind;
+pub mod cbor;
+#[cfg(feature = "cavp")]
+pub mod cavp;
+pub mod context;
+pub mod cose;
+pub mod der;
+pub mod ec_parameters;
+pub mod ecdh;
+pub mod ecdsa;
+#[cfg(feature = "ethereum")]
+pub mod ethereum;
+pub mod eddsa;
+pub mod frost;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod jwk;
+pub mod jws;
+pub mod keys;
+pub mod musig;
+pub mod paillier;
+pub mod pem;
+pub mod pkcs8;
+pub mod rfc6979;
+pub mod ring;
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
+pub mod schnorr;
+pub mod shamir;
+pub mod signature;
+pub mod spki;
+pub mod sshsig;
+pub mod taproot;
+pub mod testvectors;
+pub mod threshold;
+#[cfg(feature = "two-party-semi-honest")]
+pub mod two_party;
+pub mod vrf;
+pub mod x509;
+
+pub use context::Context;
+pub use ecdsa::{ECDSAErrors, ECDSA};
+pub use eddsa::EdDSA;
+pub use keys::{KeyPair, PrivateKey, PublicKey, XOnlyPublicKey};
+#[cfg(feature = "keccak")]
+pub use sha3::Keccak256;
+pub use schnorr::{Schnorr, SchnorrSignature};
+pub use shamir::SecretShare;
+pub use signature::{CurveSignature, DerParsing, RecoverableSignature, Signature};
+pub use vrf::ECVRF;
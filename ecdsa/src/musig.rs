@@ -0,0 +1,219 @@
+/*!
+The key aggregation of MuSig2 (BIP-327 `KeyAgg`), on its own: the aggregate
+key of `u` signers, which is all a wallet needs to derive the address of a
+multi-signature output, without the nonce rounds of the signing protocol.
+
+```text
+ L   = hash_KeyAgg list(pk_1 || ... || pk_u)
+ a_i = 1 if pk_i is the second distinct key of the list,
+       int(hash_KeyAgg coefficient(L || pk_i)) mod n otherwise
+ Q   = a_1 P_1 + ... + a_u P_u
+```
+
+The coefficients prevent rogue key attacks: a signer can not choose its key
+as a function of the others' to control `Q`. Keys are 33 bytes SEC1
+compressed encodings, in the order given (`key_sort` gives the canonical
+order). Tweaks (BIP-32 derivation, Taproot) are applied to the context,
+which tracks them in `gacc` and `tacc`:
+
+```text
+ Q' = g Q + t G      g = -1 for an x-only tweak of a Q with an odd y, 1 otherwise
+```
+*/
+
+use num_bigint::BigUint;
+
+use crate::ecdsa::ECDSAErrors;
+use crate::keys::{PublicKey, XOnlyPublicKey};
+use crate::taproot::{tagged_hash, HASH_SIZE};
+use ec_core::curves::{Curve, Secp256k1};
+
+///
+/// An aggregate key `Q` with the tweaks applied so far: `Q` equals
+/// `gacc Q_0 + tacc G`, `Q_0` being the untweaked aggregate key.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyAggContext {
+    q: PublicKey<Secp256k1>,
+    gacc: BigUint,
+    tacc: BigUint,
+}
+
+impl KeyAggContext {
+    ///
+    /// Aggregates the public keys of the signers. Fails on an empty list
+    /// and when `Q` is the identity.
+    ///
+    pub fn new(pub_keys: &[PublicKey<Secp256k1>]) -> Result<Self, ECDSAErrors> {
+        if pub_keys.is_empty() {
+            return Err(ECDSAErrors::BadArgument("No public key to aggregate".into()));
+        }
+
+        let list_hash = hash_keys(pub_keys);
+        let second_key = second_key(pub_keys);
+        let terms = pub_keys
+            .iter()
+            .map(|pub_key| pub_key.tweak_mul(&coefficient(&list_hash, second_key.as_deref(), pub_key)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(KeyAggContext {
+            q: PublicKey::combine(&terms)?,
+            gacc: BigUint::from(1u32),
+            tacc: BigUint::from(0u32),
+        })
+    }
+
+    /// The aggregate key, tweaks included.
+    pub fn aggregate_key(&self) -> &PublicKey<Secp256k1> {
+        &self.q
+    }
+
+    /// The x-only aggregate key, e.g. the output key of a Taproot address.
+    pub fn x_only_key(&self) -> XOnlyPublicKey<Secp256k1> {
+        self.q.x_only_public_key().0
+    }
+
+    pub fn gacc(&self) -> &BigUint {
+        &self.gacc
+    }
+
+    pub fn tacc(&self) -> &BigUint {
+        &self.tacc
+    }
+
+    ///
+    /// Applies a plain tweak (`x_only = false`, e.g. BIP-32) or an x-only one
+    /// (e.g. the Taproot tweak of `crate::taproot::tap_tweak`). Fails when
+    /// the tweak is not below `n` or the tweaked key is the identity.
+    ///
+    pub fn apply_tweak(&self, tweak: &[u8; HASH_SIZE], x_only: bool) -> Result<Self, ECDSAErrors> {
+        let n = Secp256k1::order();
+        let odd = self.q.x_only_public_key().1;
+        let g = if x_only && odd { n - 1u32 } else { BigUint::from(1u32) };
+        let t = BigUint::from_bytes_be(tweak);
+
+        let q = self.q.tweak_mul(&g)?.tweak_add(&t)?;
+        Ok(KeyAggContext {
+            q,
+            gacc: (&g * &self.gacc) % n,
+            tacc: (t + &g * &self.tacc) % n,
+        })
+    }
+}
+
+///
+/// The keys sorted by their encoding, so that every signer gets the same
+/// aggregate key whatever the order they learnt the keys in.
+///
+pub fn key_sort(pub_keys: &[PublicKey<Secp256k1>]) -> Vec<PublicKey<Secp256k1>> {
+    let mut sorted = pub_keys.to_vec();
+    sorted.sort_by_key(|pub_key| pub_key.to_sec1_compressed());
+    sorted
+}
+
+/// `L = hash_KeyAgg list(pk_1 || ... || pk_u)`
+pub fn hash_keys(pub_keys: &[PublicKey<Secp256k1>]) -> [u8; HASH_SIZE] {
+    let data: Vec<u8> = pub_keys.iter().flat_map(|pub_key| pub_key.to_sec1_compressed()).collect();
+    tagged_hash("KeyAgg list", &data)
+}
+
+///
+/// The coefficient `a_i` of `pub_key` in the aggregate key of `pub_keys`,
+/// as needed by the signing protocol to compute its partial signatures.
+/// `pub_key` has to be one of `pub_keys`.
+///
+pub fn key_agg_coefficient(pub_keys: &[PublicKey<Secp256k1>], pub_key: &PublicKey<Secp256k1>) -> Result<BigUint, ECDSAErrors> {
+    if !pub_keys.contains(pub_key) {
+        return Err(ECDSAErrors::BadArgument("The key is not one of the aggregated keys".into()));
+    }
+    Ok(coefficient(&hash_keys(pub_keys), second_key(pub_keys).as_deref(), pub_key))
+}
+
+// the first key different from the first one, whose coefficient is 1
+fn second_key(pub_keys: &[PublicKey<Secp256k1>]) -> Option<Vec<u8>> {
+    pub_keys
+        .iter()
+        .find(|pub_key| *pub_key != &pub_keys[0])
+        .map(PublicKey::to_sec1_compressed)
+}
+
+fn coefficient(list_hash: &[u8; HASH_SIZE], second_key: Option<&[u8]>, pub_key: &PublicKey<Secp256k1>) -> BigUint {
+    let encoded = pub_key.to_sec1_compressed();
+    if second_key == Some(encoded.as_slice()) {
+        return BigUint::from(1u32);
+    }
+    let hash = tagged_hash("KeyAgg coefficient", &[&list_hash[..], &encoded].concat());
+    BigUint::from_bytes_be(&hash) % Secp256k1::order()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::taproot;
+
+    fn keys(indices: &[usize]) -> Vec<PublicKey<Secp256k1>> {
+        // BIP-327 key_agg_vectors.json
+        let keys = [
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66",
+        ];
+        indices.iter().map(|i| PublicKey::from_hex(keys[*i]).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_key_agg() {
+        for (indices, expected) in [
+            (&[0, 1, 2][..], "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c"),
+            (&[2, 1, 0][..], "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b"),
+            (&[0, 0, 0][..], "b436e3bad62b8cd409969a224731c193d051162d8c5ae8b109306127da3aa935"),
+            (&[0, 0, 1, 1][..], "69bc22bfa5d106306e48a20679de1d7389386124d07571d0d872686028c26a3e"),
+        ] {
+            let context = KeyAggContext::new(&keys(indices)).unwrap();
+            assert_eq!(context.x_only_key().to_hex(), expected);
+        }
+
+        // the order matters, unless the keys are sorted
+        let (a, b) = (keys(&[0, 1, 2]), keys(&[2, 1, 0]));
+        assert_eq!(KeyAggContext::new(&key_sort(&a)).unwrap(), KeyAggContext::new(&key_sort(&b)).unwrap());
+
+        // the second distinct key has a coefficient of 1
+        assert_eq!(key_agg_coefficient(&a, &a[1]).unwrap(), BigUint::from(1u32));
+        assert_ne!(key_agg_coefficient(&a, &a[0]).unwrap(), BigUint::from(1u32));
+        assert!(key_agg_coefficient(&a[..2], &a[2]).is_err());
+
+        assert!(KeyAggContext::new(&[]).is_err());
+    }
+
+    #[test]
+    fn test_apply_tweak() {
+        let context = KeyAggContext::new(&keys(&[0, 1, 2])).unwrap();
+        let untweaked = context.aggregate_key().clone();
+
+        let mut tweaked = context.clone();
+        for (i, x_only) in [true, false, true, true].into_iter().enumerate() {
+            tweaked = tweaked.apply_tweak(&tagged_hash("test tweak", &[i as u8]), x_only).unwrap();
+        }
+        // Q = gacc Q_0 + tacc G
+        let expected = untweaked.tweak_mul(tweaked.gacc()).unwrap().tweak_add(tweaked.tacc()).unwrap();
+        assert_eq!(tweaked.aggregate_key(), &expected);
+
+        // an x-only Taproot tweak gives the output key of BIP-341
+        let internal_key = context.x_only_key();
+        let tweak = taproot::tap_tweak(&internal_key, None).unwrap();
+        let tweak: [u8; HASH_SIZE] = ec_core::to_fixed_bytes(&tweak, HASH_SIZE).unwrap().try_into().unwrap();
+        let output = context.apply_tweak(&tweak, true).unwrap();
+        assert_eq!(output.aggregate_key().x_only_public_key(), taproot::taproot_tweak(&internal_key, None).unwrap());
+
+        // tweaks not below n, or giving the identity: Q = a_1 d G for a
+        // single key, so t = -a_1 d cancels it
+        assert!(context.apply_tweak(&[0xff; HASH_SIZE], false).is_err());
+        let n = Secp256k1::order();
+        let d = BigUint::from(0xc0ffeeu32);
+        let single = [PublicKey::from_priv_key(&d).unwrap()];
+        let a_1 = key_agg_coefficient(&single, &single[0]).unwrap();
+        let t = n - (a_1 * d) % n;
+        let t: [u8; HASH_SIZE] = ec_core::to_fixed_bytes(&t, HASH_SIZE).unwrap().try_into().unwrap();
+        assert!(KeyAggContext::new(&single).unwrap().apply_tweak(&t, false).is_err());
+    }
+}
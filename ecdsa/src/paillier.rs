@@ -0,0 +1,193 @@
+/*!
+The Paillier cryptosystem, additively homomorphic, as needed by two-party
+ECDSA. With `N = p q` and `g = N + 1`:
+
+```text
+ Enc(m)    = (1 + m N) r^N mod N^2         r random, invertible mod N
+ Dec(c)    = L(c^phi mod N^2) phi^(-1) mod N,   L(u) = (u - 1) / N
+ Enc(a) Enc(b)  = Enc(a + b mod N)
+ Enc(a)^k       = Enc(k a mod N)
+```
+
+The modulus has to be at least 2048 bits long for 112 bits of security.
+*/
+
+use ec_core::is_probable_prime;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+
+use crate::ecdsa::ECDSAErrors;
+
+/// Bit length of the moduli generated by `PaillierSecretKey::generate`.
+pub const MODULUS_BITS: u64 = 2048;
+
+///
+/// A Paillier public key, the modulus `N`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaillierPublicKey {
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub n: BigUint,
+}
+
+///
+/// A Paillier secret key: the factors of `N`, with `phi = (p - 1)(q - 1)` and
+/// its inverse modulo `N` precomputed.
+///
+#[derive(Clone, Debug)]
+pub struct PaillierSecretKey {
+    public_key: PaillierPublicKey,
+    phi: BigUint,
+    phi_inv: BigUint,
+}
+
+impl PaillierPublicKey {
+    pub fn n_squared(&self) -> BigUint {
+        &self.n * &self.n
+    }
+
+    ///
+    /// Encrypts `m`, which has to be below `N`.
+    ///
+    pub fn encrypt<R: CryptoRng + RngCore>(&self, m: &BigUint, rng: &mut R) -> Result<BigUint, ECDSAErrors> {
+        if *m >= self.n {
+            return Err(ECDSAErrors::BadArgument("Plaintext must be below N".into()));
+        }
+        let n_squared = self.n_squared();
+        // r has to be a unit of Z_N, which fails with a negligible probability
+        let r = loop {
+            let r = rng.gen_biguint_range(&BigUint::from(1u32), &self.n);
+            if r.modinv(&self.n).is_some() {
+                break r;
+            }
+        };
+        let g_m = (BigUint::from(1u32) + m * &self.n) % &n_squared;
+        Ok(g_m * r.modpow(&self.n, &n_squared) % n_squared)
+    }
+
+    /// `Enc(a + b)` from `Enc(a)` and `Enc(b)`.
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % self.n_squared()
+    }
+
+    /// `Enc(k a)` from `Enc(a)`.
+    pub fn mul(&self, a: &BigUint, k: &BigUint) -> BigUint {
+        a.modpow(k, &self.n_squared())
+    }
+}
+
+impl PaillierSecretKey {
+    ///
+    /// Generates a key whose modulus is `bits` long, from two random primes
+    /// of `bits / 2` bits with their two top bits set.
+    ///
+    pub fn generate<R: CryptoRng + RngCore>(bits: u64, rng: &mut R) -> Result<Self, ECDSAErrors> {
+        if bits < 32 || !bits.is_multiple_of(2) {
+            return Err(ECDSAErrors::BadArgument("Modulus size must be even and at least 32 bits".into()));
+        }
+        loop {
+            let p = random_prime(bits / 2, rng);
+            let q = random_prime(bits / 2, rng);
+            if p != q {
+                return PaillierSecretKey::from_primes(&p, &q);
+            }
+        }
+    }
+
+    ///
+    /// The key of `N = p q`, `p` and `q` being distinct primes of the same
+    /// size (which makes `gcd(N, phi) = 1`).
+    ///
+    pub fn from_primes(p: &BigUint, q: &BigUint) -> Result<Self, ECDSAErrors> {
+        let one = BigUint::from(1u32);
+        let n = p * q;
+        let phi = (p - &one) * (q - &one);
+        let phi_inv = phi
+            .modinv(&n)
+            .ok_or_else(|| ECDSAErrors::BadArgument("phi(N) is not invertible modulo N".into()))?;
+
+        Ok(PaillierSecretKey { public_key: PaillierPublicKey { n }, phi, phi_inv })
+    }
+
+    pub fn public_key(&self) -> &PaillierPublicKey {
+        &self.public_key
+    }
+
+    pub fn decrypt(&self, c: &BigUint) -> Result<BigUint, ECDSAErrors> {
+        let n = &self.public_key.n;
+        let n_squared = self.public_key.n_squared();
+        if *c >= n_squared {
+            return Err(ECDSAErrors::BadArgument("Ciphertext must be below N^2".into()));
+        }
+        // a ciphertext is a unit modulo N^2, otherwise c^phi is not 1 mod N
+        // and L is undefined
+        if c.modinv(n).is_none() {
+            return Err(ECDSAErrors::BadArgument("Ciphertext is not invertible modulo N".into()));
+        }
+        let u = c.modpow(&self.phi, &n_squared);
+        let l = (u - 1u32) / n;
+        Ok(l * &self.phi_inv % n)
+    }
+}
+
+// a random `bits` long prime with its two top bits set, so that the product
+// of two of them is exactly `2 bits` long
+fn random_prime<R: CryptoRng + RngCore>(bits: u64, rng: &mut R) -> BigUint {
+    loop {
+        let mut candidate = rng.gen_biguint(bits);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(bits - 2, true);
+        candidate.set_bit(0, true);
+        if is_probable_prime(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let mut rng = StdRng::seed_from_u64(30);
+        let key = PaillierSecretKey::generate(512, &mut rng).unwrap();
+        let public_key = key.public_key();
+        assert_eq!(public_key.n.bits(), 512);
+
+        let (a, b) = (BigUint::from(123456789u32), BigUint::from(987654321u32));
+        let c_a = public_key.encrypt(&a, &mut rng).unwrap();
+        let c_b = public_key.encrypt(&b, &mut rng).unwrap();
+        assert_eq!(key.decrypt(&c_a).unwrap(), a);
+        assert_ne!(c_a, public_key.encrypt(&a, &mut rng).unwrap());
+
+        // homomorphic operations
+        assert_eq!(key.decrypt(&public_key.add(&c_a, &c_b)).unwrap(), &a + &b);
+        assert_eq!(key.decrypt(&public_key.mul(&c_a, &BigUint::from(7u32))).unwrap(), &a * 7u32);
+        let n_minus_one = &public_key.n - 1u32;
+        let c = public_key.encrypt(&n_minus_one, &mut rng).unwrap();
+        assert_eq!(key.decrypt(&public_key.add(&c, &c_a)).unwrap(), &a - 1u32);
+
+        assert!(public_key.encrypt(&public_key.n, &mut rng).is_err());
+        assert!(key.decrypt(&public_key.n_squared()).is_err());
+        assert!(key.decrypt(&BigUint::from(0u32)).is_err());
+        assert!(key.decrypt(&public_key.n).is_err());
+        assert!(key.decrypt(&(&public_key.n * 3u32)).is_err());
+    }
+
+    #[test]
+    fn test_from_primes() {
+        // N = 11 * 13
+        let key = PaillierSecretKey::from_primes(&BigUint::from(11u32), &BigUint::from(13u32)).unwrap();
+        let mut rng = StdRng::seed_from_u64(31);
+        for m in 0..143u32 {
+            let c = key.public_key().encrypt(&BigUint::from(m), &mut rng).unwrap();
+            assert_eq!(key.decrypt(&c).unwrap(), BigUint::from(m));
+        }
+
+        assert!(PaillierSecretKey::generate(31, &mut rng).is_err());
+    }
+}
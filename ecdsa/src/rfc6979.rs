@@ -0,0 +1,180 @@
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use num_bigint::BigUint;
+use sha2::Sha256;
+
+///
+/// Deterministic generation of the random number `k` as specified in
+/// RFC 6979 (section 3.2) with HMAC-SHA256, from the private key `x` and the
+/// hashed message `h1`:
+///
+/// ```text
+///  V = 0x01 0x01 ... 0x01, K = 0x00 0x00 ... 0x00
+///  K = HMAC_K(V || 0x00 || int2octets(x) || bits2octets(h1)), V = HMAC_K(V)
+///  K = HMAC_K(V || 0x01 || int2octets(x) || bits2octets(h1)), V = HMAC_K(V)
+///  loop
+///       T = V = HMAC_K(V) ... until T has qlen bits
+///       k = bits2int(T), return it if 0 < k < q
+///       K = HMAC_K(V || 0x00), V = HMAC_K(V)
+/// ```
+///
+pub fn generate_k(q_order: &BigUint, priv_key: &BigUint, h1: &[u8]) -> BigUint {
+    generate_k_with::<Sha256>(q_order, priv_key, h1)
+}
+
+///
+/// Same as `generate_k` with HMAC over the hash function `D`, which RFC 6979
+/// expects to be the one that produced `h1`, e.g. `Sha512`.
+///
+pub fn generate_k_with<D: Digest + BlockSizeUser>(q_order: &BigUint, priv_key: &BigUint, h1: &[u8]) -> BigUint {
+    generate_k_with_extra::<D>(q_order, priv_key, h1, &[])
+}
+
+///
+/// The variant of RFC 6979 section 3.6, which mixes additional data `k'`
+/// (e.g. 32 fresh random bytes, as libsecp256k1's `ndata`) into the seeding
+/// of the HMAC key:
+///
+/// ```text
+///  K = HMAC_K(V || 0x00 || int2octets(x) || bits2octets(h1) || k')
+///  K = HMAC_K(V || 0x01 || int2octets(x) || bits2octets(h1) || k')
+/// ```
+///
+/// The nonce stays secure if the extra data is predictable, and is the one of
+/// `generate_k_with` when it is empty.
+///
+pub fn generate_k_with_extra<D: Digest + BlockSizeUser>(
+    q_order: &BigUint,
+    priv_key: &BigUint,
+    h1: &[u8],
+    extra: &[u8],
+) -> BigUint {
+    let rlen = q_order.bits().div_ceil(8) as usize;
+    let x = int2octets(priv_key, rlen);
+    let h1 = int2octets(&(bits2int(h1, q_order) % q_order), rlen);
+
+    let mut v = vec![0x01u8; <D as Digest>::output_size()];
+    let mut k = vec![0x00u8; <D as Digest>::output_size()];
+
+    k = hmac::<D>(&k, &[&v, &[0x00], &x, &h1, extra]);
+    v = hmac::<D>(&k, &[&v]);
+    k = hmac::<D>(&k, &[&v, &[0x01], &x, &h1, extra]);
+    v = hmac::<D>(&k, &[&v]);
+
+    loop {
+        let mut t = Vec::with_capacity(rlen);
+        while t.len() < rlen {
+            v = hmac::<D>(&k, &[&v]);
+            t.extend_from_slice(&v);
+        }
+
+        let candidate = bits2int(&t, q_order);
+        if candidate > BigUint::from(0u32) && candidate < *q_order {
+            return candidate;
+        }
+
+        k = hmac::<D>(&k, &[&v, &[0x00]]);
+        v = hmac::<D>(&k, &[&v]);
+    }
+}
+
+///
+/// The leftmost `qlen` bits of a byte string as an integer, e.g. to turn a
+/// digest into the hash signed with a curve of order `q`.
+///
+pub fn bits2int(bytes: &[u8], q_order: &BigUint) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let blen = 8 * bytes.len() as u64;
+    let qlen = q_order.bits();
+    if blen > qlen {
+        value >> (blen - qlen)
+    } else {
+        value
+    }
+}
+
+fn int2octets(value: &BigUint, rlen: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; rlen.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes[bytes.len().saturating_sub(rlen)..]);
+    out
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha2::{Sha256, Sha512};
+
+    #[test]
+    fn test_generate_k_p256() {
+        // RFC 6979, appendix A.2.5, SHA-256
+        let q_order = BigUint::parse_bytes(
+            b"FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+            16,
+        )
+            .unwrap();
+        let priv_key = BigUint::parse_bytes(
+            b"C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721",
+            16,
+        )
+            .unwrap();
+
+        let k = generate_k(&q_order, &priv_key, &Sha256::digest(b"sample"));
+        assert_eq!(
+            k,
+            BigUint::parse_bytes(
+                b"A6E3C57DD01ABE90086538398355DD4C3B17AA873382B0F24D6129493D8AAD60",
+                16,
+            )
+                .unwrap()
+        );
+
+        let k = generate_k(&q_order, &priv_key, &Sha256::digest(b"test"));
+        assert_eq!(
+            k,
+            BigUint::parse_bytes(
+                b"D16B6AE827F17175E040871A1C7EC3500192C4C92677336EC2537ACAEE0008E0",
+                16,
+            )
+                .unwrap()
+        );
+
+        // RFC 6979, appendix A.2.5, SHA-512
+        let k = generate_k_with::<Sha512>(&q_order, &priv_key, &Sha512::digest(b"sample"));
+        assert_eq!(
+            k,
+            BigUint::parse_bytes(
+                b"5FA81C63109BADB88C1F367B47DA606DA28CAD69AA22C4FE6AD7DF73A7173AA5",
+                16,
+            )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_k_with_extra() {
+        let q_order = BigUint::parse_bytes(b"FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551", 16).unwrap();
+        let priv_key = BigUint::parse_bytes(b"C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721", 16).unwrap();
+        let h1 = Sha256::digest(b"sample");
+
+        // no extra data is plain RFC 6979
+        let k = generate_k_with_extra::<Sha256>(&q_order, &priv_key, &h1, &[]);
+        assert_eq!(k, generate_k(&q_order, &priv_key, &h1));
+
+        let k1 = generate_k_with_extra::<Sha256>(&q_order, &priv_key, &h1, &[1u8; 32]);
+        let k2 = generate_k_with_extra::<Sha256>(&q_order, &priv_key, &h1, &[2u8; 32]);
+        assert_ne!(k1, k);
+        assert_ne!(k1, k2);
+        assert_eq!(k1, generate_k_with_extra::<Sha256>(&q_order, &priv_key, &h1, &[1u8; 32]));
+        assert!(k1 > BigUint::from(0u32) && k1 < q_order);
+    }
+}
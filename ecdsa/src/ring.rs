@@ -0,0 +1,347 @@
+/*!
+Ring signatures: a member of a ring of public keys `P_0, ..., P_(n-1)` signs on
+behalf of the whole ring, and verifiers can not tell which member it was.
+
+- SAG (spontaneous anonymous group signatures, Abe-Ohkubo-Suzuki) closes a
+  ring of Schnorr challenges:
+
+```text
+ c_(i+1) = H(ring || message || s_i A + c_i P_i)
+ s_pi = alpha - c_pi x mod q, so that s_pi A + c_pi P_pi = alpha A
+ signature = (c_0, s_0, ..., s_(n-1))
+```
+
+- LSAG (linkable SAG, Liu-Wei-Wong) also publishes the key image
+  `I = x H_p(P_pi)`, the same for every signature of a given key, so two
+  signatures of the same member can be linked without revealing who it is:
+
+```text
+ c_(i+1) = H(ring || message || I || s_i A + c_i P_i || s_i H_p(P_i) + c_i I)
+```
+*/
+
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::ECDSAErrors;
+use crate::schnorr::Schnorr;
+
+///
+/// A SAG ring signature: the first challenge `c_0` and a response `s_i` for
+/// every member of the ring.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct RingSignature {
+    pub c: BigUint,
+    pub s: Vec<BigUint>,
+}
+
+///
+/// An LSAG ring signature: a `RingSignature` and the key image `I` of the
+/// signer.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct LinkableRingSignature {
+    pub key_image: CurvePoint,
+    pub c: BigUint,
+    pub s: Vec<BigUint>,
+}
+
+impl LinkableRingSignature {
+    ///
+    /// Two signatures were made with the same private key if they share the
+    /// same key image.
+    ///
+    pub fn is_linked(&self, other: &LinkableRingSignature) -> bool {
+        self.key_image == other.key_image
+    }
+}
+
+impl Schnorr {
+    ///
+    /// Signs a message on behalf of the ring, which must contain the public
+    /// key of `priv_key`.
+    ///
+    pub fn ring_sign<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        priv_key: &BigUint,
+        rng: &mut R,
+    ) -> Result<RingSignature, ECDSAErrors> {
+        let (c, s) = self.close_ring(message, ring, priv_key, false, rng)?;
+        Ok(RingSignature { c, s })
+    }
+
+    ///
+    /// Verifies that a ring signature was made by one of the members of the
+    /// ring.
+    ///
+    pub fn ring_verify(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        signature: &RingSignature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.verify_ring(message, ring, None, &signature.c, &signature.s)
+    }
+
+    ///
+    /// Signs a message on behalf of the ring with a linkable signature.
+    ///
+    pub fn linkable_ring_sign<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        priv_key: &BigUint,
+        rng: &mut R,
+    ) -> Result<LinkableRingSignature, ECDSAErrors> {
+        let key_image = self.key_image(priv_key)?;
+        let (c, s) = self.close_ring(message, ring, priv_key, true, rng)?;
+        Ok(LinkableRingSignature { key_image, c, s })
+    }
+
+    ///
+    /// Verifies that a linkable ring signature was made by one of the members
+    /// of the ring.
+    ///
+    pub fn linkable_ring_verify(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        signature: &LinkableRingSignature,
+    ) -> Result<bool, ECDSAErrors> {
+        if signature.key_image == CurvePoint::Identity
+            || !self.elliptic_curve.is_on_curve(&signature.key_image)
+        {
+            return Ok(false);
+        }
+
+        self.verify_ring(message, ring, Some(&signature.key_image), &signature.c, &signature.s)
+    }
+
+    ///
+    /// The key image `I = x H_p(x A)` of a private key.
+    ///
+    pub fn key_image(&self, priv_key: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.check_scalar(priv_key, "Private key")?;
+        let pub_key = self.generate_pub_key(priv_key)?;
+
+        self.mul(&self.hash_to_point(&pub_key)?, priv_key)
+    }
+
+    fn close_ring<R: CryptoRng + RngCore>(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        priv_key: &BigUint,
+        linkable: bool,
+        rng: &mut R,
+    ) -> Result<(BigUint, Vec<BigUint>), ECDSAErrors> {
+        self.check_scalar(priv_key, "Private key")?;
+        self.check_ring(ring)?;
+
+        let pub_key = self.generate_pub_key(priv_key)?;
+        let signer = ring
+            .iter()
+            .position(|member| *member == pub_key)
+            .ok_or_else(|| ECDSAErrors::BadArgument("The signer is not in the ring".into()))?;
+        let key_image = if linkable { Some(self.key_image(priv_key)?) } else { None };
+
+        let n = ring.len();
+        let one = BigUint::from(1u32);
+        let mut c = vec![BigUint::from(0u32); n];
+        let mut s: Vec<BigUint> = (0..n)
+            .map(|_| rng.gen_biguint_range(&one, &self.q_order))
+            .collect();
+
+        // the responses of the other members are random, alpha is used for the signer
+        let alpha = s[signer].clone();
+        let l_point = self.mul(&self.a_gen, &alpha)?;
+        let r_point = match &key_image {
+            Some(_) => Some(self.mul(&self.hash_to_point(&pub_key)?, &alpha)?),
+            None => None,
+        };
+        c[(signer + 1) % n] = self.ring_challenge(ring, message, key_image.as_ref(), &l_point, r_point.as_ref())?;
+
+        for offset in 1..n {
+            let i = (signer + offset) % n;
+            let (l_point, r_point) = self.ring_points(&ring[i], key_image.as_ref(), &c[i], &s[i])?;
+            c[(i + 1) % n] = self.ring_challenge(ring, message, key_image.as_ref(), &l_point, r_point.as_ref())?;
+        }
+
+        s[signer] = (alpha + &self.q_order - &c[signer] * priv_key % &self.q_order) % &self.q_order;
+
+        Ok((c[0].clone(), s))
+    }
+
+    fn verify_ring(
+        &self,
+        message: &[u8],
+        ring: &[CurvePoint],
+        key_image: Option<&CurvePoint>,
+        c_0: &BigUint,
+        s: &[BigUint],
+    ) -> Result<bool, ECDSAErrors> {
+        self.check_ring(ring)?;
+        if s.len() != ring.len() || *c_0 >= self.q_order || s.iter().any(|s_i| *s_i >= self.q_order) {
+            return Ok(false);
+        }
+
+        let mut c = c_0.clone();
+        for (member, s_i) in ring.iter().zip(s) {
+            let (l_point, r_point) = self.ring_points(member, key_image, &c, s_i)?;
+            if l_point == CurvePoint::Identity || r_point == Some(CurvePoint::Identity) {
+                return Ok(false);
+            }
+            c = self.ring_challenge(ring, message, key_image, &l_point, r_point.as_ref())?;
+        }
+
+        Ok(c == *c_0)
+    }
+
+    // L_i = s_i A + c_i P_i, R_i = s_i H_p(P_i) + c_i I
+    fn ring_points(
+        &self,
+        member: &CurvePoint,
+        key_image: Option<&CurvePoint>,
+        c: &BigUint,
+        s: &BigUint,
+    ) -> Result<(CurvePoint, Option<CurvePoint>), ECDSAErrors> {
+        let l_point = self.mul_add(s, &self.a_gen, c, member)?;
+        let r_point = match key_image {
+            Some(key_image) => Some(self.mul_add(s, &self.hash_to_point(member)?, c, key_image)?),
+            None => None,
+        };
+
+        Ok((l_point, r_point))
+    }
+
+    // H(ring || message || I || L || R) mod q
+    fn ring_challenge(
+        &self,
+        ring: &[CurvePoint],
+        message: &[u8],
+        key_image: Option<&CurvePoint>,
+        l_point: &CurvePoint,
+        r_point: Option<&CurvePoint>,
+    ) -> Result<BigUint, ECDSAErrors> {
+        let mut hasher = Sha256::new();
+        for member in ring {
+            hasher.update(self.encode(member)?);
+        }
+        hasher.update(message);
+        for point in [key_image, Some(l_point), r_point].into_iter().flatten() {
+            hasher.update(self.encode(point)?);
+        }
+
+        Ok(BigUint::from_bytes_be(&hasher.finalize()) % &self.q_order)
+    }
+
+    // try and increment: the first x = SHA256(P || counter) mod p on the curve,
+    // with an even y
+    fn hash_to_point(&self, point: &CurvePoint) -> Result<CurvePoint, ECDSAErrors> {
+        let encoded = self.encode(point)?;
+        let size = self.elliptic_curve.coordinate_size();
+
+        for counter in 0u32..256 {
+            let digest = Sha256::new()
+                .chain_update(&encoded)
+                .chain_update(counter.to_be_bytes())
+                .finalize();
+            let x = BigUint::from_bytes_be(&digest) % &self.elliptic_curve.p;
+
+            let mut candidate = vec![0x02];
+            candidate.extend_from_slice(
+                &to_fixed_bytes(&x, size)
+                    .map_err(|_| ECDSAErrors::OperationFailure("Error encoding x".into()))?,
+            );
+            if let Ok(hashed) = CurvePoint::from_sec1_compressed(&candidate, &self.elliptic_curve) {
+                return Ok(hashed);
+            }
+        }
+
+        Err(ECDSAErrors::OperationFailure(
+            "Could not hash the public key to a point".into(),
+        ))
+    }
+
+    fn check_ring(&self, ring: &[CurvePoint]) -> Result<(), ECDSAErrors> {
+        if ring.is_empty() {
+            return Err(ECDSAErrors::BadArgument("The ring is empty".into()));
+        }
+        ring.iter().try_for_each(|member| self.check_point(member, "ring member"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn ring(schnorr: &Schnorr) -> (Vec<BigUint>, Vec<CurvePoint>) {
+        let priv_keys: Vec<BigUint> = (1..=4u32).map(|i| BigUint::from(1000 * i + 7)).collect();
+        let pub_keys = priv_keys
+            .iter()
+            .map(|priv_key| schnorr.generate_pub_key(priv_key).unwrap())
+            .collect();
+        (priv_keys, pub_keys)
+    }
+
+    #[test]
+    fn test_ring_sign_verify() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(5);
+        let (priv_keys, ring) = ring(&schnorr);
+
+        for priv_key in &priv_keys {
+            let signature = schnorr.ring_sign(b"msg", &ring, priv_key, &mut rng).unwrap();
+            assert!(schnorr.ring_verify(b"msg", &ring, &signature).unwrap());
+            assert!(!schnorr.ring_verify(b"other", &ring, &signature).unwrap());
+
+            // the ring is part of the signature
+            assert!(!schnorr.ring_verify(b"msg", &ring[..3], &signature).unwrap());
+            let mut reversed = ring.clone();
+            reversed.reverse();
+            assert!(!schnorr.ring_verify(b"msg", &reversed, &signature).unwrap());
+        }
+
+        // a single member ring is a Schnorr signature
+        let signature = schnorr.ring_sign(b"msg", &ring[..1], &priv_keys[0], &mut rng).unwrap();
+        assert!(schnorr.ring_verify(b"msg", &ring[..1], &signature).unwrap());
+
+        // not in the ring
+        assert!(schnorr.ring_sign(b"msg", &ring, &BigUint::from(5u32), &mut rng).is_err());
+        assert!(schnorr.ring_sign(b"msg", &[], &priv_keys[0], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_linkable_ring_sign_verify() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(6);
+        let (priv_keys, ring) = ring(&schnorr);
+
+        let first = schnorr.linkable_ring_sign(b"vote 1", &ring, &priv_keys[2], &mut rng).unwrap();
+        let second = schnorr.linkable_ring_sign(b"vote 2", &ring, &priv_keys[2], &mut rng).unwrap();
+        let other = schnorr.linkable_ring_sign(b"vote 1", &ring, &priv_keys[1], &mut rng).unwrap();
+
+        assert!(schnorr.linkable_ring_verify(b"vote 1", &ring, &first).unwrap());
+        assert!(schnorr.linkable_ring_verify(b"vote 2", &ring, &second).unwrap());
+        assert!(schnorr.linkable_ring_verify(b"vote 1", &ring, &other).unwrap());
+
+        assert!(first.is_linked(&second));
+        assert!(!first.is_linked(&other));
+
+        // the key image can not be swapped
+        let mut tempered = first.clone();
+        tempered.key_image = other.key_image.clone();
+        assert!(!schnorr.linkable_ring_verify(b"vote 1", &ring, &tempered).unwrap());
+
+        let mut tempered = first.clone();
+        tempered.s[0] += BigUint::from(1u32);
+        assert!(!schnorr.linkable_ring_verify(b"vote 1", &ring, &tempered).unwrap());
+    }
+}
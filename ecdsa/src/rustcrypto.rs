@@ -0,0 +1,241 @@
+/*!
+RustCrypto `signature::{Signer, Verifier, SignatureEncoding}` implementations
+for the keys and signatures of this crate, so they can replace a
+`SigningKey` / `VerifyingKey` in code written against those traits:
+
+```text
+ let signature: ecdsa::Signature<P256> = private_key.try_sign(message)?;
+ public_key.verify(message, &signature)?;
+```
+
+As with the RustCrypto curve crates, the message is hashed with the SHA-2
+function matching the size of the order: SHA-256 for the 256 bits curves,
+SHA-384 for P-384 and BrainpoolP384r1, SHA-512 above.
+*/
+
+use rustcrypto_ecdsa::elliptic_curve::generic_array::ArrayLength;
+use rustcrypto_ecdsa::elliptic_curve::PrimeCurve;
+use rustcrypto_ecdsa::signature::{Error, SignatureEncoding, Signer, Verifier};
+use rustcrypto_ecdsa::SignatureSize;
+
+use ec_core::curves::Curve;
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::keys::{PrivateKey, PublicKey};
+use crate::signature::{CurveSignature, Signature, COMPONENT_SIZE};
+
+///
+/// The digest of `message` used by the `Signer` and `Verifier`
+/// implementations for the curve `C`.
+///
+pub fn message_digest<C: Curve>(message: &[u8]) -> Vec<u8> {
+    ECDSA::from(C::params().clone()).message_digest(message)
+}
+
+impl<C: Curve> Signer<CurveSignature<C>> for PrivateKey<C> {
+    fn try_sign(&self, message: &[u8]) -> Result<CurveSignature<C>, Error> {
+        self.sign_message(message).map_err(|_| Error::new())
+    }
+}
+
+impl<C: Curve> Verifier<CurveSignature<C>> for PublicKey<C> {
+    fn verify(&self, message: &[u8], signature: &CurveSignature<C>) -> Result<(), Error> {
+        match self.verify_message(message, signature) {
+            Ok(true) => Ok(()),
+            _ => Err(Error::new()),
+        }
+    }
+}
+
+///
+/// The untagged signature of the `ECDSA` API, encoded as `r || s` on 32 bytes
+/// each: use `CurveSignature` on curves with a larger order.
+///
+impl<C: Curve> Signer<Signature> for PrivateKey<C> {
+    fn try_sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        let signature: CurveSignature<C> = self.try_sign(message)?;
+        Ok(signature.into_signature())
+    }
+}
+
+impl<C: Curve> Verifier<Signature> for PublicKey<C> {
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error> {
+        let signature = CurveSignature::<C>::try_from(signature.clone()).map_err(|_| Error::new())?;
+        self.verify(message, &signature)
+    }
+}
+
+impl<C> Signer<rustcrypto_ecdsa::Signature<C>> for PrivateKey<C>
+where
+    C: Curve + PrimeCurve,
+    SignatureSize<C>: ArrayLength<u8>,
+{
+    fn try_sign(&self, message: &[u8]) -> Result<rustcrypto_ecdsa::Signature<C>, Error> {
+        let signature: CurveSignature<C> = self.try_sign(message)?;
+        rustcrypto_ecdsa::Signature::try_from(signature).map_err(|_| Error::new())
+    }
+}
+
+impl<C> Verifier<rustcrypto_ecdsa::Signature<C>> for PublicKey<C>
+where
+    C: Curve + PrimeCurve,
+    SignatureSize<C>: ArrayLength<u8>,
+{
+    fn verify(&self, message: &[u8], signature: &rustcrypto_ecdsa::Signature<C>) -> Result<(), Error> {
+        self.verify(message, &CurveSignature::from(signature.clone()))
+    }
+}
+
+impl<C> TryFrom<CurveSignature<C>> for rustcrypto_ecdsa::Signature<C>
+where
+    C: Curve + PrimeCurve,
+    SignatureSize<C>: ArrayLength<u8>,
+{
+    type Error = ECDSAErrors;
+
+    ///
+    /// Fails if `r` or `s` is not in `[1, q - 1]`.
+    ///
+    fn try_from(signature: CurveSignature<C>) -> Result<Self, ECDSAErrors> {
+        rustcrypto_ecdsa::Signature::from_slice(&signature.to_bytes())
+            .map_err(|_| ECDSAErrors::BadArgument("Signature components must be in [1, q - 1]".into()))
+    }
+}
+
+impl<C> From<rustcrypto_ecdsa::Signature<C>> for CurveSignature<C>
+where
+    C: Curve + PrimeCurve,
+    SignatureSize<C>: ArrayLength<u8>,
+{
+    fn from(signature: rustcrypto_ecdsa::Signature<C>) -> Self {
+        CurveSignature::from_bytes(&signature.to_bytes()).expect("the components are in [1, q - 1]")
+    }
+}
+
+///
+/// `r || s`, each component on the byte size of the order of `C`.
+///
+impl<C: Curve> SignatureEncoding for CurveSignature<C> {
+    type Repr = Vec<u8>;
+}
+
+impl<C: Curve> TryFrom<&[u8]> for CurveSignature<C> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        CurveSignature::from_bytes(bytes).map_err(|_| Error::new())
+    }
+}
+
+impl<C: Curve> From<CurveSignature<C>> for Vec<u8> {
+    fn from(signature: CurveSignature<C>) -> Self {
+        signature.to_bytes()
+    }
+}
+
+///
+/// The fixed width `r || s` layout of `Signature::to_bytes`.
+///
+impl SignatureEncoding for Signature {
+    type Repr = [u8; 2 * COMPONENT_SIZE];
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(bytes).map_err(|_| Error::new())
+    }
+}
+
+impl TryFrom<Signature> for [u8; 2 * COMPONENT_SIZE] {
+    type Error = Error;
+
+    fn try_from(signature: Signature) -> Result<Self, Error> {
+        signature.to_bytes().map_err(|_| Error::new())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves::{P384, P521, Secp256k1};
+    use num_bigint::BigUint;
+
+    // written against the traits only, as code using k256 or p256 would be
+    fn sign_and_verify<S, V, T>(signer: &S, verifier: &V) -> bool
+    where
+        S: Signer<T>,
+        V: Verifier<T>,
+    {
+        let signature = signer.sign(b"drop-in replacement");
+        verifier.verify(b"drop-in replacement", &signature).is_ok()
+            && verifier.verify(b"another message", &signature).is_err()
+    }
+
+    fn keys<C: Curve>() -> (PrivateKey<C>, PublicKey<C>) {
+        let private_key = PrivateKey::<C>::new(BigUint::from(0xC0FFEEu32)).unwrap();
+        let public_key = private_key.public_key().unwrap();
+        (private_key, public_key)
+    }
+
+    #[test]
+    fn test_signer_verifier() {
+        let (private_key, public_key) = keys::<Secp256k1>();
+        assert!(sign_and_verify::<_, _, CurveSignature<Secp256k1>>(&private_key, &public_key));
+        assert!(sign_and_verify::<_, _, rustcrypto_ecdsa::Signature<Secp256k1>>(&private_key, &public_key));
+
+        assert!(sign_and_verify::<_, _, Signature>(&private_key, &public_key));
+
+        let (private_key, public_key) = keys::<P521>();
+        assert!(sign_and_verify::<_, _, rustcrypto_ecdsa::Signature<P521>>(&private_key, &public_key));
+        assert!(sign_and_verify::<_, _, CurveSignature<P521>>(&private_key, &public_key));
+    }
+
+    // encodes and decodes through the trait only
+    fn round_trip<S: SignatureEncoding + PartialEq>(signature: &S) -> bool {
+        let bytes = SignatureEncoding::to_bytes(signature);
+        S::try_from(bytes.as_ref()).is_ok_and(|decoded| decoded == *signature)
+    }
+
+    #[test]
+    fn test_signature_encoding() {
+        let (private_key, _) = keys::<P384>();
+        let signature: CurveSignature<P384> = private_key.sign(b"message");
+        assert!(round_trip(&signature));
+        assert_eq!(SignatureEncoding::to_bytes(&signature).len(), 96);
+        assert_eq!(signature.encoded_len(), 96);
+
+        let (private_key, _) = keys::<Secp256k1>();
+        let signature: Signature = private_key.sign(b"message");
+        assert!(round_trip(&signature));
+        assert_eq!(SignatureEncoding::to_bytes(&signature), signature.to_bytes().unwrap());
+
+        assert!(CurveSignature::<P384>::try_from(&[0u8; 96][..]).is_err());
+        assert!(Signature::try_from(&[1u8; 63][..]).is_err());
+    }
+
+    #[test]
+    fn test_message_digest() {
+        let (private_key, public_key) = keys::<P384>();
+        let signature: CurveSignature<P384> = private_key.sign(b"message");
+        assert_eq!(message_digest::<P384>(b"message").len(), 48);
+        assert!(public_key.verify_digest(&message_digest::<P384>(b"message"), &signature).unwrap());
+        assert_eq!(message_digest::<P521>(b"message").len(), 64);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let (private_key, _) = keys::<Secp256k1>();
+        let signature: CurveSignature<Secp256k1> = private_key.sign(b"message");
+        let converted = rustcrypto_ecdsa::Signature::<Secp256k1>::try_from(signature.clone()).unwrap();
+        assert_eq!(converted.to_bytes().to_vec(), signature.to_bytes());
+        assert_eq!(CurveSignature::from(converted), signature);
+
+        // an unreduced signature is not a CurveSignature, nor a Verifier input
+        let unreduced = Signature::new(Secp256k1::order().clone(), BigUint::from(1u32));
+        assert!(CurveSignature::<Secp256k1>::try_from(unreduced.clone()).is_err());
+        let public_key = private_key.public_key().unwrap();
+        assert!(Verifier::<Signature>::verify(&public_key, b"message", &unreduced).is_err());
+    }
+}
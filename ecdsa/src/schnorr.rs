@@ -0,0 +1,188 @@
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::ECDSAErrors;
+
+///
+/// A Schnorr signature `(R, s)`:
+///
+/// - `R = k A`
+/// - `s = k + e d mod q` where `e = H(R || B || message)`
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchnorrSignature {
+    pub r_point: CurvePoint,
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub s: BigUint,
+}
+
+///
+/// Schnorr signatures over a short Weierstrass curve, with the challenge
+/// `e = SHA256(R || B || message) mod q` where the points are SEC1 compressed.
+///
+pub struct Schnorr {
+    pub elliptic_curve: EllipticCurve,
+    pub a_gen: CurvePoint,
+    pub q_order: BigUint,
+}
+
+impl From<CurveParams> for Schnorr {
+    fn from(params: CurveParams) -> Self {
+        Schnorr {
+            elliptic_curve: params.curve,
+            a_gen: params.g,
+            q_order: params.n,
+        }
+    }
+}
+
+impl Schnorr {
+    pub fn generate_pub_key(&self, priv_key: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.mul(&self.a_gen, priv_key)
+    }
+
+    ///
+    /// R = k A
+    /// e = H(R || B || message)
+    /// s = k + e d mod q
+    ///
+    pub fn sign(
+        &self,
+        message: &[u8],
+        priv_key: &BigUint,
+        k_random: &BigUint,
+    ) -> Result<SchnorrSignature, ECDSAErrors> {
+        self.check_scalar(priv_key, "Private key")?;
+        self.check_scalar(k_random, "Random number `k`")?;
+
+        let pub_key = self.generate_pub_key(priv_key)?;
+        let r_point = self.mul(&self.a_gen, k_random)?;
+        let e = self.challenge(&r_point, &pub_key, message)?;
+
+        let s = (k_random + e * priv_key) % &self.q_order;
+
+        Ok(SchnorrSignature { r_point, s })
+    }
+
+    ///
+    /// Verifies a signature for a message and public key:
+    ///
+    /// `s A == R + e B`
+    ///
+    pub fn verify(
+        &self,
+        message: &[u8],
+        pub_key: &CurvePoint,
+        signature: &SchnorrSignature,
+    ) -> Result<bool, ECDSAErrors> {
+        self.check_point(pub_key, "public key")?;
+        if signature.s >= self.q_order || !self.is_valid_point(&signature.r_point) {
+            return Ok(false);
+        }
+
+        let e = self.challenge(&signature.r_point, pub_key, message)?;
+        let expected = self.mul_add(&BigUint::from(1u32), &signature.r_point, &e, pub_key)?;
+
+        Ok(self.mul(&self.a_gen, &signature.s)? == expected)
+    }
+
+    ///
+    /// The challenge `e = SHA256(R || B || message) mod q`.
+    ///
+    pub fn challenge(
+        &self,
+        r_point: &CurvePoint,
+        pub_key: &CurvePoint,
+        message: &[u8],
+    ) -> Result<BigUint, ECDSAErrors> {
+        let digest = Sha256::new()
+            .chain_update(self.encode(r_point)?)
+            .chain_update(self.encode(pub_key)?)
+            .chain_update(message)
+            .finalize();
+
+        Ok(BigUint::from_bytes_be(&digest) % &self.q_order)
+    }
+
+    pub(crate) fn mul(&self, point: &CurvePoint, scalar: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.mul_add(scalar, point, &BigUint::from(0u32), point)
+    }
+
+    // u1 P + u2 Q, the scalars may be zero
+    pub(crate) fn mul_add(
+        &self,
+        u1: &BigUint,
+        p: &CurvePoint,
+        u2: &BigUint,
+        q: &CurvePoint,
+    ) -> Result<CurvePoint, ECDSAErrors> {
+        self.elliptic_curve
+            .double_scalar_mul(u1, p, u2, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error in scalar multiplication".into()))
+    }
+
+    pub(crate) fn encode(&self, point: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+        point
+            .to_sec1_compressed(&self.elliptic_curve)
+            .map_err(|_| ECDSAErrors::BadArgument("The identity can not be encoded".into()))
+    }
+
+    pub(crate) fn check_scalar(&self, scalar: &BigUint, name: &str) -> Result<(), ECDSAErrors> {
+        if *scalar == BigUint::from(0u32) || *scalar >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "{} must be in [1, q - 1]",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_point(&self, point: &CurvePoint, name: &str) -> Result<(), ECDSAErrors> {
+        if !self.is_valid_point(point) {
+            return Err(ECDSAErrors::BadArgument(format!("Invalid {}", name)));
+        }
+        Ok(())
+    }
+
+    fn is_valid_point(&self, point: &CurvePoint) -> bool {
+        *point != CurvePoint::Identity && self.elliptic_curve.is_on_curve(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let priv_key = BigUint::from(123456789u32);
+        let pub_key = schnorr.generate_pub_key(&priv_key).unwrap();
+
+        let signature = schnorr.sign(b"Bob -> 1 BTC -> Alice", &priv_key, &BigUint::from(42u32)).unwrap();
+        assert!(schnorr.verify(b"Bob -> 1 BTC -> Alice", &pub_key, &signature).unwrap());
+
+        assert!(!schnorr.verify(b"Bob -> 2 BTC -> Alice", &pub_key, &signature).unwrap());
+
+        let other_key = schnorr.generate_pub_key(&BigUint::from(7u32)).unwrap();
+        assert!(!schnorr.verify(b"Bob -> 1 BTC -> Alice", &other_key, &signature).unwrap());
+
+        let mut tempered = signature.clone();
+        tempered.s += BigUint::from(1u32);
+        assert!(!schnorr.verify(b"Bob -> 1 BTC -> Alice", &pub_key, &tempered).unwrap());
+    }
+
+    #[test]
+    fn test_sign_wrong_input() {
+        let schnorr = Schnorr::from(ec_core::curves::secp256k1());
+        let one = BigUint::from(1u32);
+
+        assert!(schnorr.sign(b"msg", &BigUint::from(0u32), &one).is_err());
+        assert!(schnorr.sign(b"msg", &one, &schnorr.q_order).is_err());
+        assert!(schnorr
+            .verify(b"msg", &CurvePoint::Identity, &schnorr.sign(b"msg", &one, &one).unwrap())
+            .is_err());
+    }
+}
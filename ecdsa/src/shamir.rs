@@ -0,0 +1,136 @@
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+
+///
+/// The share `(i, f(i))` of a secret split with Shamir's scheme.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecretShare {
+    pub index: u32,
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub value: BigUint,
+}
+
+impl ConstantTimeEq for SecretShare {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.index.ct_eq(&other.index) & biguint_ct_eq(&self.value, &other.value)
+    }
+}
+
+impl ECDSA {
+    ///
+    /// Splits a scalar (e.g. a private key) into `n` shares so that any `t`
+    /// of them rebuild it while fewer reveal nothing about it:
+    ///
+    /// ```text
+    ///  f(z) = secret + c_1 z + ... + c_(t-1) z^(t-1) mod q, c_i random
+    ///  share_i = (i, f(i)) for i in [1, n]
+    /// ```
+    ///
+    pub fn split_secret<R: CryptoRng + RngCore>(
+        &self,
+        secret: &BigUint,
+        threshold: usize,
+        shares: u32,
+        rng: &mut R,
+    ) -> Result<Vec<SecretShare>, ECDSAErrors> {
+        if *secret >= self.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Secret is bigger than the order of the EC group".into(),
+            ));
+        }
+        if threshold == 0 || (shares as usize) < threshold {
+            return Err(ECDSAErrors::BadArgument(
+                "Threshold must be in [1, number of shares]".into(),
+            ));
+        }
+
+        let mut coefficients = vec![secret.clone()];
+        coefficients.extend((1..threshold).map(|_| rng.gen_biguint_below(&self.q_order)));
+
+        (1..=shares)
+            .map(|index| {
+                let value = evaluate_polynomial(&coefficients, &BigUint::from(index), &self.q_order)
+                    .map_err(|_| ECDSAErrors::OperationFailure("Error evaluating the polynomial".into()))?;
+                Ok(SecretShare { index, value })
+            })
+            .collect()
+    }
+
+    ///
+    /// Rebuilds the secret from shares by interpolating `f(0)`:
+    ///
+    /// `secret = sum f(i) l_i(0) mod q`
+    ///
+    /// At least `t` shares are needed, fewer give an unrelated value.
+    ///
+    pub fn reconstruct(&self, shares: &[SecretShare]) -> Result<BigUint, ECDSAErrors> {
+        if shares.is_empty() {
+            return Err(ECDSAErrors::BadArgument("No shares to reconstruct from".into()));
+        }
+        if shares.iter().any(|share| share.index == 0 || share.value >= self.q_order) {
+            return Err(ECDSAErrors::BadArgument("Invalid share".into()));
+        }
+
+        let points: Vec<(BigUint, BigUint)> = shares
+            .iter()
+            .map(|share| (BigUint::from(share.index), share.value.clone()))
+            .collect();
+
+        lagrange_interpolate(&points, &BigUint::from(0u32), &self.q_order)
+            .map_err(|_| ECDSAErrors::BadArgument("Shares must have distinct indices".into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_split_reconstruct() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(1);
+        let secret = rng.gen_biguint_below(&ecdsa.q_order);
+
+        let shares = ecdsa.split_secret(&secret, 3, 5, &mut rng).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let subset: Vec<SecretShare> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(ecdsa.reconstruct(&subset).unwrap(), secret);
+        }
+        assert_eq!(ecdsa.reconstruct(&shares).unwrap(), secret);
+
+        // not enough shares
+        assert_ne!(ecdsa.reconstruct(&shares[..2]).unwrap(), secret);
+
+        // a single share is the secret itself when t = 1
+        let shares = ecdsa.split_secret(&secret, 1, 3, &mut rng).unwrap();
+        assert!(shares.iter().all(|share| share.value == secret));
+    }
+
+    #[test]
+    fn test_split_reconstruct_wrong_input() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(2);
+        let secret = BigUint::from(1234u32);
+
+        assert!(ecdsa.split_secret(&secret, 0, 3, &mut rng).is_err());
+        assert!(ecdsa.split_secret(&secret, 4, 3, &mut rng).is_err());
+        assert!(ecdsa.split_secret(&ecdsa.q_order, 2, 3, &mut rng).is_err());
+
+        let shares = ecdsa.split_secret(&secret, 2, 3, &mut rng).unwrap();
+        assert!(ecdsa.reconstruct(&[]).is_err());
+        assert!(ecdsa.reconstruct(&[shares[0].clone(), shares[0].clone()]).is_err());
+        assert!(ecdsa
+            .reconstruct(&[SecretShare { index: 0, value: secret }, shares[1].clone()])
+            .is_err());
+    }
+}
@@ -0,0 +1,695 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use ec_core::curves::Curve;
+use ec_core::to_fixed_bytes;
+use num_bigint::BigUint;
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::der::{self, DerReader};
+use crate::ecdsa::ECDSAErrors;
+
+/// Byte length of each component in the compact `r || s` encoding.
+pub const COMPONENT_SIZE: usize = 32;
+
+///
+/// How `Signature::from_der` reads an encoding:
+///
+/// - `Strict`: the rules of BIP-66, i.e. DER (one `SEQUENCE` of two
+///   `INTEGER`s, minimal lengths, no negative values, no unnecessary leading
+///   zero, nothing after the sequence), plus the low-S rule `s <= q / 2`.
+/// - `Lenient`: the BER-ish blobs of legacy signers, as the lax parser of
+///   libsecp256k1: long or non minimal lengths, padded or "negative"
+///   integers (read as unsigned), trailing data, and high S values.
+///
+/// Both modes require `r` and `s` in `[1, q - 1]`.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DerParsing {
+    Strict,
+    Lenient,
+}
+
+///
+/// An ECDSA signature `(r, s)`:
+///
+/// - `r = x` component of `R = k A` (mod q)
+/// - `s = (hash(message) + d * r) * k^(-1) mod q`
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signature {
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub r: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub s: BigUint,
+}
+
+impl Signature {
+    pub fn new(r: BigUint, s: BigUint) -> Self {
+        Signature { r, s }
+    }
+
+    ///
+    /// `(r, s)` and `(r, q - s)` are both valid signatures for the same
+    /// message, so consensus rules only accept the one with `s <= q / 2`.
+    /// Returns that "low-S" form of the signature.
+    ///
+    pub fn normalize_s(&self, q_order: &BigUint) -> Signature {
+        if self.s > q_order >> 1 {
+            Signature::new(self.r.clone(), q_order - &self.s)
+        } else {
+            self.clone()
+        }
+    }
+
+    ///
+    /// `s <= q / 2`, the form `normalize_s` gives.
+    ///
+    pub fn is_low_s(&self, q_order: &BigUint) -> bool {
+        self.s <= q_order >> 1
+    }
+
+    ///
+    /// `r` has its top bit clear on the byte size of the order, e.g.
+    /// `r < 2^255` on secp256k1, so that its DER encoding does not need a
+    /// leading zero and the signature is one byte shorter. Bitcoin Core
+    /// grinds its nonces until `r` is low, see `ECDSA::sign_prehash_low_r`.
+    ///
+    pub fn is_low_r(&self, q_order: &BigUint) -> bool {
+        self.r.bits() < 8 * q_order.bits().div_ceil(8)
+    }
+
+    ///
+    /// Encodes the signature in the fixed width `r || s` layout, each
+    /// component being a 32 bytes big endian integer left-padded with zeros.
+    ///
+    pub fn to_bytes(&self) -> Result<[u8; 2 * COMPONENT_SIZE], ECDSAErrors> {
+        let r = to_fixed_bytes(&self.r, COMPONENT_SIZE)
+            .map_err(|_| ECDSAErrors::BadArgument("r does not fit in 32 bytes".into()))?;
+        let s = to_fixed_bytes(&self.s, COMPONENT_SIZE)
+            .map_err(|_| ECDSAErrors::BadArgument("s does not fit in 32 bytes".into()))?;
+
+        let mut bytes = [0u8; 2 * COMPONENT_SIZE];
+        bytes[..COMPONENT_SIZE].copy_from_slice(&r);
+        bytes[COMPONENT_SIZE..].copy_from_slice(&s);
+        Ok(bytes)
+    }
+
+    ///
+    /// Decodes a signature from the fixed width `r || s` layout.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, ECDSAErrors> {
+        if bytes.len() != 2 * COMPONENT_SIZE {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Signature has to be {} bytes long, got {}",
+                2 * COMPONENT_SIZE,
+                bytes.len()
+            )));
+        }
+
+        let r = BigUint::from_bytes_be(&bytes[..COMPONENT_SIZE]);
+        let s = BigUint::from_bytes_be(&bytes[COMPONENT_SIZE..]);
+        if r == BigUint::from(0u32) || s == BigUint::from(0u32) {
+            return Err(ECDSAErrors::BadArgument(
+                "Signature components can not be zero".into(),
+            ));
+        }
+
+        Ok(Signature::new(r, s))
+    }
+
+    /// Hex of the fixed width `r || s` encoding.
+    pub fn to_hex(&self) -> Result<String, ECDSAErrors> {
+        Ok(ec_core::encode_hex(&self.to_bytes()?))
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Signature, ECDSAErrors> {
+        let bytes = ec_core::decode_hex(hex).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        Signature::from_bytes(&bytes)
+    }
+
+    ///
+    /// The DER encoding `SEQUENCE { r INTEGER, s INTEGER }`, as used by
+    /// X.509, Bitcoin scripts and OpenSSL.
+    ///
+    pub fn to_der(&self) -> Vec<u8> {
+        der::encode_sequence(&[der::encode_integer(&self.r), der::encode_integer(&self.s)])
+    }
+
+    ///
+    /// Decodes a DER signature for a curve of order `q_order`, with the rules
+    /// of `parsing`. Only `DerParsing::Strict` guarantees that the signature
+    /// has a single accepted encoding.
+    ///
+    pub fn from_der(bytes: &[u8], q_order: &BigUint, parsing: DerParsing) -> Result<Signature, ECDSAErrors> {
+        let signature = match parsing {
+            DerParsing::Strict => {
+                let mut reader = DerReader::new(bytes);
+                let mut sequence = reader.read_sequence()?;
+                reader.finish()?;
+                let r = sequence.read_integer()?;
+                let s = sequence.read_integer()?;
+                sequence.finish()?;
+                Signature::new(r, s)
+            }
+            DerParsing::Lenient => parse_der_lax(bytes)?,
+        };
+
+        let zero = BigUint::from(0u32);
+        if signature.r == zero || signature.s == zero || signature.r >= *q_order || signature.s >= *q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Signature components must be in [1, q - 1]".into(),
+            ));
+        }
+        if parsing == DerParsing::Strict && !signature.is_low_s(q_order) {
+            return Err(ECDSAErrors::BadArgument("s is not in the low half of the order".into()));
+        }
+        Ok(signature)
+    }
+}
+
+// `SEQUENCE { INTEGER, INTEGER }` read as BER would, anything after the second
+// integer ignored
+fn parse_der_lax(bytes: &[u8]) -> Result<Signature, ECDSAErrors> {
+    let mut pos = 0;
+    let sequence = read_lax_element(bytes, &mut pos, 0x30)?;
+    let mut pos = 0;
+    let r = read_lax_element(sequence, &mut pos, 0x02)?;
+    let s = read_lax_element(sequence, &mut pos, 0x02)?;
+    Ok(Signature::new(BigUint::from_bytes_be(r), BigUint::from_bytes_be(s)))
+}
+
+fn read_lax_element<'a>(bytes: &'a [u8], pos: &mut usize, tag: u8) -> Result<&'a [u8], ECDSAErrors> {
+    let error = |reason: &str| ECDSAErrors::BadArgument(format!("Invalid signature encoding: {}", reason));
+    let mut next = || {
+        let byte = bytes.get(*pos).copied().ok_or_else(|| error("truncated"))?;
+        *pos += 1;
+        Ok::<u8, ECDSAErrors>(byte)
+    };
+
+    if next()? != tag {
+        return Err(error("unexpected tag"));
+    }
+    let len = match next()? {
+        len if len < 0x80 => len as usize,
+        0x80 => return Err(error("indefinite length")),
+        len_of_len => {
+            let mut len = 0usize;
+            for _ in 0..len_of_len & 0x7f {
+                let byte = next()? as usize;
+                len = len.checked_mul(256).ok_or_else(|| error("length overflow"))? + byte;
+            }
+            len
+        }
+    };
+
+    if *pos > bytes.len() || bytes.len() - *pos < len {
+        return Err(error("truncated"));
+    }
+    let content = &bytes[*pos..*pos + len];
+    *pos += len;
+    Ok(content)
+}
+
+///
+/// A signature carrying the extra information needed to recover the public
+/// key that produced it. The recovery id encodes:
+///
+/// - bit 0: the parity of the `y` component of `R = k A`
+/// - bit 1: whether the `x` component of `R` overflowed the group order `q`
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecoverableSignature {
+    pub signature: Signature,
+    pub recovery_id: u8,
+}
+
+impl RecoverableSignature {
+    pub fn new(signature: Signature, recovery_id: u8) -> Self {
+        RecoverableSignature {
+            signature,
+            recovery_id,
+        }
+    }
+
+    ///
+    /// Returns the low-S form of the signature. Negating `s` is the same as
+    /// negating `R`, so the parity bit of the recovery id is flipped too.
+    ///
+    pub fn normalize_s(&self, q_order: &BigUint) -> RecoverableSignature {
+        let signature = self.signature.normalize_s(q_order);
+        if signature == self.signature {
+            return self.clone();
+        }
+
+        RecoverableSignature::new(signature, self.recovery_id ^ 1)
+    }
+}
+
+///
+/// Writes the hex of `r || s`, each component on 32 bytes or on the byte
+/// length of the largest one if it does not fit, e.g. 48 bytes on P-384.
+///
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let size = (self.r.bits().max(self.s.bits()).div_ceil(8) as usize).max(COMPONENT_SIZE);
+        let r = to_fixed_bytes(&self.r, size).map_err(|_| fmt::Error)?;
+        let s = to_fixed_bytes(&self.s, size).map_err(|_| fmt::Error)?;
+        write!(f, "{}{}", ec_core::encode_hex(&r), ec_core::encode_hex(&s))
+    }
+}
+
+///
+/// Parses the `Display` form of a signature: the hex of `r || s`, both
+/// components having the same length and being non zero.
+///
+impl FromStr for Signature {
+    type Err = ECDSAErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = ec_core::decode_hex(s).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        if bytes.len() < 2 * COMPONENT_SIZE || !bytes.len().is_multiple_of(2) {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Signature has to be an even number of bytes, at least {}, got {}",
+                2 * COMPONENT_SIZE,
+                bytes.len()
+            )));
+        }
+
+        let (r, s) = bytes.split_at(bytes.len() / 2);
+        let (r, s) = (BigUint::from_bytes_be(r), BigUint::from_bytes_be(s));
+        if r == BigUint::from(0u32) || s == BigUint::from(0u32) {
+            return Err(ECDSAErrors::BadArgument(
+                "Signature components can not be zero".into(),
+            ));
+        }
+
+        Ok(Signature::new(r, s))
+    }
+}
+
+///
+/// Constant time comparison, for signatures used as authentication tags
+/// (e.g. deterministic signatures compared to an expected value).
+///
+impl ConstantTimeEq for Signature {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ec_core::biguint_ct_eq(&self.r, &other.r) & ec_core::biguint_ct_eq(&self.s, &other.s)
+    }
+}
+
+impl ConstantTimeEq for RecoverableSignature {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.signature.ct_eq(&other.signature) & self.recovery_id.ct_eq(&other.recovery_id)
+    }
+}
+
+impl From<RecoverableSignature> for Signature {
+    fn from(signature: RecoverableSignature) -> Self {
+        signature.signature
+    }
+}
+
+///
+/// A signature tagged with the curve `C` of the key that produced it, so it
+/// can only be checked against a `PublicKey<C>`. `Signature` is the untagged
+/// `(r, s)` pair used by the runtime `ECDSA` API.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CurveSignature<C: Curve> {
+    signature: Signature,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> CurveSignature<C> {
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    pub fn into_signature(self) -> Signature {
+        self.signature
+    }
+
+    ///
+    /// `r || s`, each component on the byte size of the order of `C`.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let size = C::scalar_size();
+        let mut bytes = to_fixed_bytes(&self.signature.r, size).expect("r is below the order");
+        bytes.extend(to_fixed_bytes(&self.signature.s, size).expect("s is below the order"));
+        bytes
+    }
+
+    ///
+    /// Decodes `r || s`, checking that both components are in `[1, q - 1]`.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ECDSAErrors> {
+        let size = C::scalar_size();
+        if bytes.len() != 2 * size {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "Signature has to be {} bytes long, got {}",
+                2 * size,
+                bytes.len()
+            )));
+        }
+
+        let r = BigUint::from_bytes_be(&bytes[..size]);
+        let s = BigUint::from_bytes_be(&bytes[size..]);
+        CurveSignature::try_from(Signature::new(r, s))
+    }
+
+    /// See `Signature::is_low_s`.
+    pub fn is_low_s(&self) -> bool {
+        self.signature.is_low_s(C::order())
+    }
+
+    /// See `Signature::is_low_r`.
+    pub fn is_low_r(&self) -> bool {
+        self.signature.is_low_r(C::order())
+    }
+
+    /// Hex of `r || s`, see `to_bytes`.
+    pub fn to_hex(&self) -> String {
+        ec_core::encode_hex(&self.to_bytes())
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, ECDSAErrors> {
+        let bytes = ec_core::decode_hex(hex).map_err(|_| ECDSAErrors::BadArgument("Invalid hex string".into()))?;
+        CurveSignature::from_bytes(&bytes)
+    }
+
+    /// See `Signature::to_der`.
+    pub fn to_der(&self) -> Vec<u8> {
+        self.signature.to_der()
+    }
+
+    ///
+    /// See `Signature::from_der`, with the order of `C`.
+    ///
+    pub fn from_der(bytes: &[u8], parsing: DerParsing) -> Result<Self, ECDSAErrors> {
+        CurveSignature::try_from(Signature::from_der(bytes, C::order(), parsing)?)
+    }
+}
+
+impl<C: Curve> ConstantTimeEq for CurveSignature<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.signature.ct_eq(&other.signature)
+    }
+}
+
+impl<C: Curve> TryFrom<Signature> for CurveSignature<C> {
+    type Error = ECDSAErrors;
+
+    ///
+    /// Fails if `r` or `s` is not in `[1, q - 1]`, `q` being the order of `C`.
+    ///
+    fn try_from(signature: Signature) -> Result<Self, ECDSAErrors> {
+        let zero = BigUint::from(0u32);
+        if signature.r == zero || signature.s == zero || signature.r >= *C::order() || signature.s >= *C::order() {
+            return Err(ECDSAErrors::BadArgument(
+                "Signature components must be in [1, q - 1]".into(),
+            ));
+        }
+
+        Ok(CurveSignature { signature, curve: PhantomData })
+    }
+}
+
+impl<C: Curve> fmt::Display for CurveSignature<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl<C: Curve> FromStr for CurveSignature<C> {
+    type Err = ECDSAErrors;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CurveSignature::from_hex(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        let signature = Signature::new(BigUint::from(7u32), BigUint::from(15u32));
+        assert!(bool::from(signature.ct_eq(&signature.clone())));
+        assert!(!bool::from(signature.ct_eq(&Signature::new(BigUint::from(7u32), BigUint::from(16u32)))));
+        assert!(!bool::from(signature.ct_eq(&Signature::new(BigUint::from(15u32), BigUint::from(7u32)))));
+
+        let recoverable = RecoverableSignature::new(signature.clone(), 0);
+        assert!(!bool::from(recoverable.ct_eq(&RecoverableSignature::new(signature, 1))));
+    }
+
+    #[test]
+    fn test_der_round_trip() {
+        use ec_core::curves::Secp256k1;
+
+        let q_order = ec_core::curves::secp256k1().n;
+        let signature = Signature::new(BigUint::from(0x80u32), BigUint::from(0x0102u32));
+        let der = signature.to_der();
+        assert_eq!(der, [0x30, 0x08, 0x02, 0x02, 0x00, 0x80, 0x02, 0x02, 0x01, 0x02]);
+        for parsing in [DerParsing::Strict, DerParsing::Lenient] {
+            assert_eq!(Signature::from_der(&der, &q_order, parsing).unwrap(), signature);
+        }
+
+        let curve_signature = CurveSignature::<Secp256k1>::try_from(signature.clone()).unwrap();
+        assert_eq!(CurveSignature::<Secp256k1>::from_der(&curve_signature.to_der(), DerParsing::Strict).unwrap(), curve_signature);
+    }
+
+    #[test]
+    fn test_der_strict_and_lenient() {
+        let q_order = ec_core::curves::secp256k1().n;
+        let expected = Signature::new(BigUint::from(0x7fu32), BigUint::from(1u32));
+        let only_lenient: [&[u8]; 5] = [
+            // padded r
+            &[0x30, 0x07, 0x02, 0x02, 0x00, 0x7f, 0x02, 0x01, 0x01],
+            // long form lengths
+            &[0x30, 0x81, 0x07, 0x02, 0x81, 0x01, 0x7f, 0x02, 0x01, 0x01],
+            &[0x30, 0x82, 0x00, 0x06, 0x02, 0x01, 0x7f, 0x02, 0x01, 0x01],
+            // trailing data, after the sequence and inside it
+            &[0x30, 0x06, 0x02, 0x01, 0x7f, 0x02, 0x01, 0x01, 0x01],
+            &[0x30, 0x08, 0x02, 0x01, 0x7f, 0x02, 0x01, 0x01, 0x05, 0x00],
+        ];
+        for der in only_lenient {
+            assert!(Signature::from_der(der, &q_order, DerParsing::Strict).is_err());
+            assert_eq!(Signature::from_der(der, &q_order, DerParsing::Lenient).unwrap(), expected);
+        }
+
+        // "negative" r, read as unsigned
+        let der = [0x30, 0x06, 0x02, 0x01, 0x80, 0x02, 0x01, 0x01];
+        assert!(Signature::from_der(&der, &q_order, DerParsing::Strict).is_err());
+        assert_eq!(Signature::from_der(&der, &q_order, DerParsing::Lenient).unwrap().r, BigUint::from(0x80u32));
+
+        // high S
+        let high_s = Signature::new(BigUint::from(1u32), &q_order - 1u32);
+        assert!(Signature::from_der(&high_s.to_der(), &q_order, DerParsing::Strict).is_err());
+        assert_eq!(Signature::from_der(&high_s.to_der(), &q_order, DerParsing::Lenient).unwrap(), high_s);
+        let low_s = high_s.normalize_s(&q_order);
+        assert_eq!(Signature::from_der(&low_s.to_der(), &q_order, DerParsing::Strict).unwrap(), low_s);
+    }
+
+    #[test]
+    fn test_der_invalid() {
+        let q_order = ec_core::curves::secp256k1().n;
+        let invalid: [&[u8]; 6] = [
+            &[],
+            // truncated
+            &[0x30, 0x06, 0x02, 0x01, 0x7f, 0x02, 0x01],
+            &[0x30, 0x84, 0x00],
+            // not a sequence, not integers
+            &[0x31, 0x06, 0x02, 0x01, 0x7f, 0x02, 0x01, 0x01],
+            &[0x30, 0x06, 0x04, 0x01, 0x7f, 0x02, 0x01, 0x01],
+            // zero r
+            &[0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01],
+        ];
+        for der in invalid {
+            assert!(Signature::from_der(der, &q_order, DerParsing::Strict).is_err());
+            assert!(Signature::from_der(der, &q_order, DerParsing::Lenient).is_err());
+        }
+
+        let too_big = Signature::new(q_order.clone(), BigUint::from(1u32));
+        assert!(Signature::from_der(&too_big.to_der(), &q_order, DerParsing::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_low_s_and_low_r() {
+        let q_order = ec_core::curves::secp256k1().n;
+        let half: BigUint = &q_order >> 1u32;
+        let r = BigUint::from(1u32) << 255u32;
+
+        assert!(Signature::new(r.clone(), half.clone()).is_low_s(&q_order));
+        assert!(!Signature::new(r.clone(), &half + 1u32).is_low_s(&q_order));
+
+        assert!(!Signature::new(r.clone(), half.clone()).is_low_r(&q_order));
+        let low_r = Signature::new(&r - 1u32, half);
+        assert!(low_r.is_low_r(&q_order));
+        // the DER encoding of a low r has no leading zero
+        assert_eq!(low_r.to_der().len(), 70);
+    }
+
+    #[test]
+    fn test_normalize_s() {
+        let q_order = BigUint::from(19u32);
+
+        // 15 > 19 / 2 -> 19 - 15 = 4
+        let signature = Signature::new(BigUint::from(7u32), BigUint::from(15u32));
+        let normalized = signature.normalize_s(&q_order);
+        assert_eq!(normalized, Signature::new(BigUint::from(7u32), BigUint::from(4u32)));
+
+        // already low-S
+        assert_eq!(normalized.normalize_s(&q_order), normalized);
+
+        // 9 = 19 / 2 is still low
+        let signature = Signature::new(BigUint::from(7u32), BigUint::from(9u32));
+        assert_eq!(signature.normalize_s(&q_order), signature);
+    }
+
+    #[test]
+    fn test_normalize_s_recoverable() {
+        let q_order = BigUint::from(19u32);
+
+        let signature = RecoverableSignature::new(
+            Signature::new(BigUint::from(7u32), BigUint::from(15u32)),
+            2,
+        );
+        let normalized = signature.normalize_s(&q_order);
+        assert_eq!(normalized.signature.s, BigUint::from(4u32));
+        assert_eq!(normalized.recovery_id, 3);
+
+        assert_eq!(normalized.normalize_s(&q_order), normalized);
+    }
+
+    #[test]
+    fn test_to_from_bytes() {
+        let signature = Signature::new(BigUint::from(0x0102u32), BigUint::from(0xFFu32));
+        let bytes = signature.to_bytes().unwrap();
+
+        let mut expected = [0u8; 64];
+        expected[30] = 0x01;
+        expected[31] = 0x02;
+        expected[63] = 0xFF;
+        assert_eq!(bytes, expected);
+
+        assert_eq!(Signature::from_bytes(&bytes).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_to_from_bytes_full_width() {
+        let r = BigUint::parse_bytes(
+            b"EFD48B2AACB6A8FD1140DD9CD45E81D69D2C877B56AAF991C34D0EA84EAF3716",
+            16,
+        )
+            .unwrap();
+        let s = BigUint::parse_bytes(
+            b"00CB1C942D657C41D436C7A1B6E29F65F3E900DBB9AFF4064DC4AB2F843ACDA8",
+            16,
+        )
+            .unwrap();
+        let signature = Signature::new(r, s);
+
+        let bytes = signature.to_bytes().unwrap();
+        assert_eq!(bytes[0], 0xEF);
+        assert_eq!(bytes[32], 0x00);
+        assert_eq!(bytes[33], 0xCB);
+        assert_eq!(Signature::from_bytes(&bytes).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_to_from_bytes_invalid() {
+        let too_big = Signature::new(BigUint::from(1u32) << 256, BigUint::from(1u32));
+        assert!(too_big.to_bytes().is_err());
+
+        assert!(Signature::from_bytes(&[1u8; 63]).is_err());
+        assert!(Signature::from_bytes(&[1u8; 65]).is_err());
+        assert!(Signature::from_bytes(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn test_curve_signature_bytes() {
+        use ec_core::curves::{P384, Secp256k1};
+
+        let signature = Signature::new(BigUint::from(0x0102u32), BigUint::from(0xFFu32));
+        let bytes = CurveSignature::<P384>::try_from(signature.clone()).unwrap().to_bytes();
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(CurveSignature::<P384>::from_bytes(&bytes).unwrap().into_signature(), signature);
+
+        assert!(CurveSignature::<Secp256k1>::from_bytes(&bytes).is_err());
+        let mut bytes = [0xFFu8; 64];
+        assert!(CurveSignature::<Secp256k1>::from_bytes(&bytes).is_err());
+        bytes[0] = 0;
+        bytes[32] = 0;
+        assert!(CurveSignature::<Secp256k1>::from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_curve_signature_try_from() {
+        use ec_core::curves::{Curve, Secp256k1};
+
+        let one = BigUint::from(1u32);
+        let n = Secp256k1::order();
+        assert!(CurveSignature::<Secp256k1>::try_from(Signature::new(n - 1u32, n - 1u32)).is_ok());
+        for (r, s) in [(one.clone() << 300, one.clone()), (n.clone(), one.clone()), (one.clone(), n.clone()), (BigUint::from(0u32), one.clone())] {
+            assert!(CurveSignature::<Secp256k1>::try_from(Signature::new(r.clone(), s.clone())).is_err());
+            assert!(CurveSignature::<Secp256k1>::try_from(Signature::new(s, r)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        use ec_core::curves::Secp256k1;
+
+        let signature = Signature::new(BigUint::from(0x0102u32), BigUint::from(0xFFu32));
+        let hex = format!("{}0102{}ff", "00".repeat(30), "00".repeat(31));
+        assert_eq!(signature.to_hex().unwrap(), hex);
+        assert_eq!(Signature::from_hex(&hex).unwrap(), signature);
+        assert_eq!(Signature::from_hex(&hex.to_uppercase()).unwrap(), signature);
+
+        let curve_signature = CurveSignature::<Secp256k1>::try_from(signature.clone()).unwrap();
+        assert_eq!(curve_signature.to_hex(), hex);
+        assert_eq!(CurveSignature::<Secp256k1>::from_hex(&hex).unwrap(), curve_signature);
+
+        assert!(Signature::new(BigUint::from(1u32) << 256, BigUint::from(1u32)).to_hex().is_err());
+        assert!(Signature::from_hex(&hex[1..]).is_err());
+        assert!(CurveSignature::<Secp256k1>::from_hex(&hex[2..]).is_err());
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        use ec_core::curves::P384;
+
+        let signature = Signature::new(BigUint::from(0x0102u32), BigUint::from(0xFFu32));
+        assert_eq!(signature.to_string(), signature.to_hex().unwrap());
+        assert_eq!(signature.to_string().parse::<Signature>().unwrap(), signature);
+
+        // components wider than 32 bytes
+        let wide = Signature::new(BigUint::from(1u32) << 300, BigUint::from(1u32));
+        assert_eq!(wide.to_string().len(), 2 * 2 * 38);
+        assert_eq!(wide.to_string().parse::<Signature>().unwrap(), wide);
+
+        let curve_signature = CurveSignature::<P384>::try_from(signature.clone()).unwrap();
+        assert_eq!(curve_signature.to_string().len(), 2 * 96);
+        assert_eq!(curve_signature.to_string().parse::<CurveSignature<P384>>().unwrap(), curve_signature);
+
+        for text in ["", "zz", &"01".repeat(63), &"00".repeat(64)] {
+            assert!(text.parse::<Signature>().is_err(), "{}", text);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_signature() {
+        let signature = RecoverableSignature::new(Signature::new(BigUint::from(0xabcu32), BigUint::from(1u32)), 1);
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(json, r#"{"signature":{"r":"abc","s":"1"},"recovery_id":1}"#);
+        assert_eq!(serde_json::from_str::<RecoverableSignature>(&json).unwrap(), signature);
+
+        assert!(serde_json::from_str::<Signature>(r#"{"r":"xyz","s":"1"}"#).is_err());
+    }
+}
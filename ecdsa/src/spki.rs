@@ -0,0 +1,203 @@
+/*!
+SubjectPublicKeyInfo encoding of elliptic curve public keys (RFC 5480), the
+public key format of X.509 certificates and of `openssl pkey -pubout`:
+
+```text
+ SubjectPublicKeyInfo ::= SEQUENCE {
+      algorithm               SEQUENCE {
+           algorithm          OBJECT IDENTIFIER (id-ecPublicKey),
+           parameters         OBJECT IDENTIFIER (named curve) },
+      subjectPublicKey        BIT STRING (SEC1 encoding of B) }
+```
+
+Only the named curves of `ec_core::curves` are supported.
+*/
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+
+use crate::der::{self, DerReader};
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::keys::PublicKey;
+use crate::pem;
+use crate::pkcs8::curve_oid;
+
+///
+/// Encodes a public key of a named curve as a DER `SubjectPublicKeyInfo`,
+/// with the uncompressed SEC1 encoding of the point.
+///
+pub fn to_spki_der(params: &CurveParams, pub_key: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+    let oid = curve_oid(params)?;
+    let curve = ECDSA::from(params.clone()).elliptic_curve;
+    if *pub_key == CurvePoint::Identity || !curve.is_on_curve(pub_key) {
+        return Err(ECDSAErrors::BadArgument(
+            "Public key is not a point of the curve".into(),
+        ));
+    }
+    let encoded = pub_key
+        .to_sec1_uncompressed(&curve)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error encoding the public key".into()))?;
+
+    Ok(der::encode_sequence(&[
+        der::encode_sequence(&[der::encode_oid(der::OID_EC_PUBLIC_KEY)?, der::encode_oid(oid)?]),
+        der::encode_bit_string(&encoded),
+    ]))
+}
+
+///
+/// Decodes a DER `SubjectPublicKeyInfo`, returning the curve of the key and
+/// its point. Both SEC1 encodings of the point are accepted.
+///
+pub fn from_spki_der(der: &[u8]) -> Result<(&'static CurveParams, CurvePoint), ECDSAErrors> {
+    let mut reader = DerReader::new(der);
+    let mut spki = reader.read_sequence()?;
+    reader.finish()?;
+
+    let mut algorithm = spki.read_sequence()?;
+    if algorithm.read_oid()? != der::OID_EC_PUBLIC_KEY {
+        return Err(ECDSAErrors::BadArgument("Not an elliptic curve key".into()));
+    }
+    let params = curves::by_oid(&algorithm.read_oid()?)
+        .ok_or_else(|| ECDSAErrors::BadArgument("Unsupported curve".into()))?;
+    algorithm.finish()?;
+
+    let encoded = spki.read_bit_string()?;
+    spki.finish()?;
+
+    let curve = ECDSA::from(params.clone()).elliptic_curve;
+    let pub_key = match encoded.first() {
+        Some(0x04) => CurvePoint::from_sec1_uncompressed(encoded, &curve),
+        _ => CurvePoint::from_sec1_compressed(encoded, &curve),
+    }
+        .map_err(|_| ECDSAErrors::BadArgument("Invalid public key encoding".into()))?;
+
+    Ok((params, pub_key))
+}
+
+///
+/// `to_spki_der` armored as a `PUBLIC KEY` PEM document.
+///
+pub fn to_spki_pem(params: &CurveParams, pub_key: &CurvePoint) -> Result<String, ECDSAErrors> {
+    Ok(pem::encode(pem::LABEL_PUBLIC_KEY, &to_spki_der(params, pub_key)?))
+}
+
+///
+/// Decodes a `PUBLIC KEY` PEM document, e.g. the output of
+/// `openssl pkey -pubout`.
+///
+pub fn from_spki_pem(pem: &str) -> Result<(&'static CurveParams, CurvePoint), ECDSAErrors> {
+    from_spki_der(&pem::decode(pem::LABEL_PUBLIC_KEY, pem)?)
+}
+
+impl<C: Curve> PublicKey<C> {
+    ///
+    /// The DER `SubjectPublicKeyInfo` of the key, with the OID of `C`.
+    ///
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        to_spki_der(C::params(), self.point()).expect("a public key is a point of a named curve")
+    }
+
+    ///
+    /// Decodes a DER `SubjectPublicKeyInfo`, failing if the key is not on `C`.
+    ///
+    pub fn from_spki_der(der: &[u8]) -> Result<Self, ECDSAErrors> {
+        let (params, point) = from_spki_der(der)?;
+        if params != C::params() {
+            return Err(ECDSAErrors::BadArgument(format!("Public key is not on {}", C::NAME)));
+        }
+
+        PublicKey::from_point(point)
+    }
+
+    /// `to_spki_der` armored as a `PUBLIC KEY` PEM document.
+    pub fn to_pem(&self) -> String {
+        pem::encode(pem::LABEL_PUBLIC_KEY, &self.to_spki_der())
+    }
+
+    /// Decodes a `PUBLIC KEY` PEM document of a key on `C`.
+    pub fn from_pem(pem: &str) -> Result<Self, ECDSAErrors> {
+        PublicKey::from_spki_der(&pem::decode(pem::LABEL_PUBLIC_KEY, pem)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves::Secp256k1;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_openssl_keys() {
+        // openssl pkey -pubout, P-256 key of the PKCS#8 tests
+        let der = base64_decode(
+            "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEPhdRg9piE6YwHa5792blg9b14yC8\
+             aU9IiwPnhDylPmjt2qe/0cTiy52koVFXVBVCQTgIYAerQuABl3RUipjuHA==",
+        );
+        let priv_key = BigUint::parse_bytes(b"c0c2f0d473ac3002299463d0ac3bca05eec4114b25a46f13c672025c14407998", 16).unwrap();
+        let expected = ECDSA::from(curves::p256()).generate_pub_key(&priv_key).unwrap();
+
+        let (params, pub_key) = from_spki_der(&der).unwrap();
+        assert_eq!(*params, curves::p256());
+        assert_eq!(pub_key, expected);
+        assert_eq!(to_spki_der(params, &pub_key).unwrap(), der);
+
+        // secp256k1, uncompressed and compressed
+        let der = base64_decode(
+            "MFYwEAYHKoZIzj0CAQYFK4EEAAoDQgAE6/js7S6rrZXyJ7th/6MlUAFTMx11fPHY\
+             gNUTETG419gjCu4KhVU+dVIZ//iIJOcFCYXxorlOFZFMmhjs+FzQgQ==",
+        );
+        let compressed = hex::decode(
+            "3036301006072a8648ce3d020106052b8104000a03220003ebf8eced2eabad95f227bb61ffa325500153331d757cf1d880d5131131b8d7d8",
+        )
+            .unwrap();
+        let priv_key = BigUint::parse_bytes(b"73251b2042a50254d745477a0689c4b85dc916f549d7c89029a9228a3f2de7c6", 16).unwrap();
+        let expected = PublicKey::<Secp256k1>::from_priv_key(&priv_key).unwrap();
+
+        assert_eq!(PublicKey::<Secp256k1>::from_spki_der(&der).unwrap(), expected);
+        assert_eq!(PublicKey::<Secp256k1>::from_spki_der(&compressed).unwrap(), expected);
+        assert_eq!(expected.to_spki_der(), der);
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let pub_key = PublicKey::<Secp256k1>::from_priv_key(&BigUint::from(7u32)).unwrap();
+        let der = pub_key.to_spki_der();
+
+        // point off the curve
+        let mut tempered = der.clone();
+        let last = tempered.len() - 1;
+        tempered[last] ^= 1;
+        assert!(PublicKey::<Secp256k1>::from_spki_der(&tempered).is_err());
+
+        // trailing data
+        assert!(PublicKey::<Secp256k1>::from_spki_der(&[der, vec![0]].concat()).is_err());
+
+        // key of another curve
+        let params = curves::p256();
+        let point = ECDSA::from(params.clone()).generate_pub_key(&BigUint::from(7u32)).unwrap();
+        assert!(PublicKey::<Secp256k1>::from_spki_der(&to_spki_der(&params, &point).unwrap()).is_err());
+        assert!(to_spki_der(&params, &CurvePoint::Identity).is_err());
+    }
+
+    #[test]
+    fn test_pem() {
+        let pem = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEPhdRg9piE6YwHa5792blg9b14yC8
+aU9IiwPnhDylPmjt2qe/0cTiy52koVFXVBVCQTgIYAerQuABl3RUipjuHA==
+-----END PUBLIC KEY-----
+";
+        let (params, pub_key) = from_spki_pem(pem).unwrap();
+        assert_eq!(to_spki_pem(params, &pub_key).unwrap(), pem);
+        // not a secp256k1 key
+        assert!(PublicKey::<Secp256k1>::from_pem(pem).is_err());
+
+        let pub_key = PublicKey::<Secp256k1>::from_priv_key(&BigUint::from(7u32)).unwrap();
+        assert_eq!(PublicKey::<Secp256k1>::from_pem(&pub_key.to_pem()).unwrap(), pub_key);
+        assert!(PublicKey::<Secp256k1>::from_pem(&pub_key.to_pem().replace("PUBLIC KEY", "PRIVATE KEY")).is_err());
+    }
+
+    fn base64_decode(text: &str) -> Vec<u8> {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.decode(text).unwrap()
+    }
+}
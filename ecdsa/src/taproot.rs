@@ -0,0 +1,148 @@
+/*!
+BIP-341 (Taproot) key tweaking. The output key of a Taproot output commits to
+the internal key `P` and to the Merkle root of its script tree, if any:
+
+```text
+ t = int(hash_TapTweak(bytes(P) || merkle_root))
+ Q = lift_x(P) + t G
+```
+
+`Q` is published as an x-only key, and the parity of its `y` coordinate goes
+in the control block of script path spends. The private key of `Q` is
+`d + t`, `d` being negated first when `d G` has an odd `y`.
+*/
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::ECDSAErrors;
+use crate::keys::{PrivateKey, XOnlyPublicKey};
+use ec_core::curves::{Curve, Secp256k1};
+
+/// Byte length of a tagged hash, and of the Merkle root of a script tree.
+pub const HASH_SIZE: usize = 32;
+
+///
+/// The BIP-340 tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`,
+/// which keeps the hashes of different protocols apart.
+///
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; HASH_SIZE] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(data)
+        .finalize()
+        .into()
+}
+
+///
+/// The tweak `t` of an internal key, without script tree when `merkle_root`
+/// is `None` (a key path only output). Fails, with a negligible
+/// probability, when the hash is not below the order.
+///
+pub fn tap_tweak(internal_key: &XOnlyPublicKey<Secp256k1>, merkle_root: Option<&[u8; HASH_SIZE]>) -> Result<BigUint, ECDSAErrors> {
+    let mut data = internal_key.to_bytes();
+    if let Some(merkle_root) = merkle_root {
+        data.extend_from_slice(merkle_root);
+    }
+
+    let tweak = BigUint::from_bytes_be(&tagged_hash("TapTweak", &data));
+    if tweak >= *Secp256k1::order() {
+        return Err(ECDSAErrors::OperationFailure("TapTweak hash is not below the order".into()));
+    }
+    Ok(tweak)
+}
+
+///
+/// The output key `Q` of an internal key and the parity of its `y`
+/// coordinate, `true` when odd.
+///
+pub fn taproot_tweak(
+    internal_key: &XOnlyPublicKey<Secp256k1>,
+    merkle_root: Option<&[u8; HASH_SIZE]>,
+) -> Result<(XOnlyPublicKey<Secp256k1>, bool), ECDSAErrors> {
+    let tweak = tap_tweak(internal_key, merkle_root)?;
+    let output_key = internal_key.to_public_key().tweak_add(&tweak)?;
+    Ok(output_key.x_only_public_key())
+}
+
+///
+/// The private key of the output key of `taproot_tweak`, to sign for the
+/// key path.
+///
+pub fn taproot_tweak_private_key(
+    priv_key: &PrivateKey<Secp256k1>,
+    merkle_root: Option<&[u8; HASH_SIZE]>,
+) -> Result<PrivateKey<Secp256k1>, ECDSAErrors> {
+    let (internal_key, odd) = priv_key.public_key()?.x_only_public_key();
+    let priv_key = if odd {
+        PrivateKey::new(Secp256k1::order() - priv_key.secret())?
+    } else {
+        priv_key.clone()
+    };
+    priv_key.tweak_add(&tap_tweak(&internal_key, merkle_root)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::CurvePoint;
+
+    fn is_odd(point: &CurvePoint) -> bool {
+        matches!(point, CurvePoint::Coordinate(_, y) if y.bit(0))
+    }
+
+    fn merkle_root(hex: &str) -> [u8; HASH_SIZE] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_tagged_hash() {
+        let tag_hash = Sha256::digest(b"TapTweak");
+        let expected: [u8; HASH_SIZE] = Sha256::digest([&tag_hash[..], &tag_hash[..], b"data"].concat()).into();
+        assert_eq!(tagged_hash("TapTweak", b"data"), expected);
+        assert_ne!(tagged_hash("TapLeaf", b"data"), expected);
+    }
+
+    #[test]
+    fn test_taproot_tweak() {
+        // BIP-341 wallet test vectors, scriptPubKey 0 and 1
+        let internal_key = XOnlyPublicKey::from_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+        assert_eq!(
+            tap_tweak(&internal_key, None).unwrap(),
+            BigUint::parse_bytes(b"b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70", 16).unwrap()
+        );
+        let (output_key, _) = taproot_tweak(&internal_key, None).unwrap();
+        assert_eq!(output_key.to_hex(), "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343");
+
+        let internal_key = XOnlyPublicKey::from_hex("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27").unwrap();
+        let root = merkle_root("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+        assert_eq!(
+            tap_tweak(&internal_key, Some(&root)).unwrap(),
+            BigUint::parse_bytes(b"cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001", 16).unwrap()
+        );
+        let (output_key, _) = taproot_tweak(&internal_key, Some(&root)).unwrap();
+        assert_eq!(output_key.to_hex(), "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3");
+    }
+
+    #[test]
+    fn test_taproot_tweak_private_key() {
+        let root = merkle_root("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+        let mut parities = [false, false];
+        for secret in 1..=8u32 {
+            let priv_key = PrivateKey::<Secp256k1>::new(BigUint::from(secret * 7919)).unwrap();
+            let pub_key = priv_key.public_key().unwrap();
+            parities[is_odd(pub_key.point()) as usize] = true;
+
+            for merkle_root in [None, Some(&root)] {
+                let (output_key, odd) = taproot_tweak(&pub_key.x_only_public_key().0, merkle_root).unwrap();
+                let tweaked = taproot_tweak_private_key(&priv_key, merkle_root).unwrap().public_key().unwrap();
+                assert_eq!(tweaked.x_only_public_key(), (output_key, odd));
+                assert_eq!(is_odd(tweaked.point()), odd);
+            }
+        }
+        // keys with an even and an odd y were both tweaked
+        assert_eq!(parities, [true, true]);
+    }
+}
@@ -0,0 +1,459 @@
+/*!
+Runner for the ECDSA and ECDH test vectors of Project Wycheproof
+(https://github.com/C2SP/wycheproof), so that the implementation can be
+checked against the upstream JSON files, e.g. in the CI of a dependent crate:
+
+```text
+ let report = testvectors::run(&fs::read_to_string("ecdsa_secp256k1_sha256_test.json")?)?;
+ assert!(report.is_success(), "{:#?}", report.failures());
+```
+
+The test groups which are understood are:
+
+```text
+ EcdsaVerify        DER signatures                 (ecdsa_*_test.json)
+ EcdsaP1363Verify   r || s signatures              (ecdsa_*_p1363_test.json)
+ EcdhTest           SubjectPublicKeyInfo peer keys (ecdh_*_test.json)
+ EcdhEcpointTest    SEC1 peer points               (ecdh_*_ecpoint_test.json)
+```
+
+The tests of other group types, of curves missing from `ec_core::curves` and
+of hash functions other than SHA-2 are reported as skipped instead of failed.
+A `valid` test passes if it is accepted, an `invalid` one if it is rejected
+and an `acceptable` one always passes.
+*/
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+use serde_json::Value;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512, Sha512_224, Sha512_256};
+
+use crate::der::DerReader;
+use crate::ecdh::{self, EcdhPrimitive};
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::Signature;
+use crate::spki;
+
+///
+/// The `result` of a test: whether the implementation has to accept the
+/// input, reject it, or may do either.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Expected {
+    Valid,
+    Invalid,
+    Acceptable,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestResult {
+    pub tc_id: u64,
+    pub comment: String,
+    pub flags: Vec<String>,
+    pub expected: Expected,
+    pub outcome: Outcome,
+}
+
+///
+/// The results of all the tests of a file, in the order of the file.
+///
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestReport {
+    pub algorithm: String,
+    pub results: Vec<TestResult>,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> usize {
+        self.count(|outcome| *outcome == Outcome::Passed)
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Skipped(_)))
+    }
+
+    pub fn failures(&self) -> Vec<&TestResult> {
+        self.results
+            .iter()
+            .filter(|result| matches!(result.outcome, Outcome::Failed(_)))
+            .collect()
+    }
+
+    /// No test failed. Skipped tests do not count as failures.
+    pub fn is_success(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    fn count(&self, predicate: impl Fn(&Outcome) -> bool) -> usize {
+        self.results.iter().filter(|result| predicate(&result.outcome)).count()
+    }
+}
+
+///
+/// Runs all the tests of a Wycheproof JSON file.
+///
+pub fn run(json: &str) -> Result<TestReport, ECDSAErrors> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|_| ECDSAErrors::BadArgument("Invalid JSON".into()))?;
+    run_value(&value)
+}
+
+pub fn run_value(value: &Value) -> Result<TestReport, ECDSAErrors> {
+    let algorithm = string(value, "algorithm")?.to_string();
+    let groups = value
+        .get("testGroups")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ECDSAErrors::BadArgument("testGroups is not an array".into()))?;
+
+    let mut results = Vec::new();
+    for group in groups {
+        let group_type = string(group, "type")?;
+        let run_group = match group_type {
+            "EcdsaVerify" | "EcdsaP1363Verify" => ecdsa_group,
+            "EcdhTest" | "EcdhEcpointTest" => ecdh_group,
+            _ => unsupported_group,
+        };
+        results.extend(run_group(group_type, group)?);
+    }
+
+    Ok(TestReport { algorithm, results })
+}
+
+// Decides whether a test passed from what the implementation made of its
+// input: `Ok(())` if accepted, `Err(reason)` if rejected.
+fn judge(expected: Expected, accepted: Result<(), String>) -> Outcome {
+    match (expected, accepted) {
+        (Expected::Valid, Err(reason)) => Outcome::Failed(format!("valid test rejected: {}", reason)),
+        (Expected::Invalid, Ok(())) => Outcome::Failed("invalid test accepted".into()),
+        _ => Outcome::Passed,
+    }
+}
+
+// Runs `test` on every test of the group, or skips them all with `skip`.
+fn run_tests(
+    group: &Value,
+    skip: Option<String>,
+    mut test: impl FnMut(&Value) -> Result<Result<(), String>, ECDSAErrors>,
+) -> Result<Vec<TestResult>, ECDSAErrors> {
+    let tests = group
+        .get("tests")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ECDSAErrors::BadArgument("tests is not an array".into()))?;
+
+    tests
+        .iter()
+        .map(|case| {
+            let expected = match string(case, "result")? {
+                "valid" => Expected::Valid,
+                "invalid" => Expected::Invalid,
+                "acceptable" => Expected::Acceptable,
+                other => return Err(ECDSAErrors::BadArgument(format!("Unknown result {}", other))),
+            };
+            let outcome = match &skip {
+                Some(reason) => Outcome::Skipped(reason.clone()),
+                None => judge(expected, test(case)?),
+            };
+            Ok(TestResult {
+                tc_id: case
+                    .get("tcId")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| ECDSAErrors::BadArgument("tcId is not a number".into()))?,
+                comment: case.get("comment").and_then(Value::as_str).unwrap_or_default().to_string(),
+                flags: case
+                    .get("flags")
+                    .and_then(Value::as_array)
+                    .map(|flags| flags.iter().filter_map(Value::as_str).map(String::from).collect())
+                    .unwrap_or_default(),
+                expected,
+                outcome,
+            })
+        })
+        .collect()
+}
+
+fn unsupported_group(group_type: &str, group: &Value) -> Result<Vec<TestResult>, ECDSAErrors> {
+    run_tests(group, Some(format!("unsupported test group {}", group_type)), |_| unreachable!())
+}
+
+fn ecdsa_group(group_type: &str, group: &Value) -> Result<Vec<TestResult>, ECDSAErrors> {
+    // the key is `key` in the legacy files and `publicKey` in the current ones
+    let key = group
+        .get("publicKey")
+        .or_else(|| group.get("key"))
+        .ok_or_else(|| ECDSAErrors::BadArgument("Missing public key".into()))?;
+    let curve_name = string(key, "curve")?;
+    let Some(params) = curves::by_name(curve_name) else {
+        return run_tests(group, Some(format!("unsupported curve {}", curve_name)), |_| unreachable!());
+    };
+    let sha = string(group, "sha")?;
+    let Some(hash) = sha2_digest(sha) else {
+        return run_tests(group, Some(format!("unsupported hash {}", sha)), |_| unreachable!());
+    };
+
+    let ecdsa = ECDSA::from(params.clone());
+    let pub_key = CurvePoint::from_sec1_uncompressed(&hex_member(key, "uncompressed")?, &ecdsa.elliptic_curve)
+        .map_err(|_| ECDSAErrors::BadArgument("Invalid public key".into()))?;
+    let p1363 = group_type == "EcdsaP1363Verify";
+
+    run_tests(group, None, |case| {
+        let msg = hex_member(case, "msg")?;
+        let sig = hex_member(case, "sig")?;
+        let signature = if p1363 {
+            decode_p1363(params, &sig)
+        } else {
+            decode_der(&sig)
+        };
+        Ok(signature
+            .and_then(|signature| ecdsa.verify_prehash(&hash(&msg), &pub_key, &signature))
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|verified| if verified { Ok(()) } else { Err("signature does not verify".into()) }))
+    })
+}
+
+fn ecdh_group(group_type: &str, group: &Value) -> Result<Vec<TestResult>, ECDSAErrors> {
+    let curve_name = string(group, "curve")?;
+    let Some(params) = curves::by_name(curve_name) else {
+        return run_tests(group, Some(format!("unsupported curve {}", curve_name)), |_| unreachable!());
+    };
+    let curve = &params.curve;
+    let ecpoint = group_type == "EcdhEcpointTest";
+
+    run_tests(group, None, |case| {
+        let public = hex_member(case, "public")?;
+        let priv_key = BigUint::from_bytes_be(&hex_member(case, "private")?);
+        let shared = hex_member(case, "shared")?;
+
+        let peer = if ecpoint {
+            match public.first() {
+                Some(0x04) => CurvePoint::from_sec1_uncompressed(&public, curve),
+                _ => CurvePoint::from_sec1_compressed(&public, curve),
+            }
+            .map_err(|e| ECDSAErrors::BadArgument(e.to_string()))
+        } else {
+            spki::from_spki_der(&public).and_then(|(peer_params, peer)| {
+                if peer_params.curve != *curve {
+                    return Err(ECDSAErrors::BadArgument("Public key of another curve".into()));
+                }
+                Ok(peer)
+            })
+        };
+        Ok(peer
+            .and_then(|peer| ecdh::diffie_hellman(params, &priv_key, &peer, EcdhPrimitive::Standard))
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|secret| {
+                if secret.as_bytes() == shared.as_slice() {
+                    Ok(())
+                } else {
+                    Err("shared secret mismatch".into())
+                }
+            }))
+    })
+}
+
+// Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }, strict DER
+fn decode_der(sig: &[u8]) -> Result<Signature, ECDSAErrors> {
+    let mut reader = DerReader::new(sig);
+    let mut sequence = reader.read_sequence()?;
+    reader.finish()?;
+    let r = sequence.read_integer()?;
+    let s = sequence.read_integer()?;
+    sequence.finish()?;
+    Ok(Signature::new(r, s))
+}
+
+// r || s, each on the byte size of the order
+fn decode_p1363(params: &CurveParams, sig: &[u8]) -> Result<Signature, ECDSAErrors> {
+    let size = params.n.bits().div_ceil(8) as usize;
+    if sig.len() != 2 * size {
+        return Err(ECDSAErrors::BadArgument(format!("Signature must be {} bytes", 2 * size)));
+    }
+    Ok(Signature::new(
+        BigUint::from_bytes_be(&sig[..size]),
+        BigUint::from_bytes_be(&sig[size..]),
+    ))
+}
+
+pub(crate) type HashFn = fn(&[u8]) -> Vec<u8>;
+
+// The SHA-2 function of a test vector file, e.g. `SHA-256`
+pub(crate) fn sha2_digest(name: &str) -> Option<HashFn> {
+    fn digest<D: Digest>(message: &[u8]) -> Vec<u8> {
+        D::digest(message).to_vec()
+    }
+
+    match name {
+        "SHA-224" => Some(digest::<Sha224>),
+        "SHA-256" => Some(digest::<Sha256>),
+        "SHA-384" => Some(digest::<Sha384>),
+        "SHA-512" => Some(digest::<Sha512>),
+        "SHA-512/224" => Some(digest::<Sha512_224>),
+        "SHA-512/256" => Some(digest::<Sha512_256>),
+        _ => None,
+    }
+}
+
+fn string<'a>(value: &'a Value, name: &str) -> Result<&'a str, ECDSAErrors> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ECDSAErrors::BadArgument(format!("{} is not a string", name)))
+}
+
+fn hex_member(value: &Value, name: &str) -> Result<Vec<u8>, ECDSAErrors> {
+    hex::decode(string(value, name)?).map_err(|_| ECDSAErrors::BadArgument(format!("{} is not hex", name)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::der;
+    use serde_json::json;
+
+    fn ecdsa_file(group_type: &str, sig: &[u8], tampered: &[u8]) -> String {
+        let ecdsa = ECDSA::from(curves::p256());
+        let (_, pub_key) = ecdsa.generate_key_pair().unwrap();
+        json!({
+            "algorithm": "ECDSA",
+            "testGroups": [{
+                "type": group_type,
+                "publicKey": {
+                    "curve": "secp256r1",
+                    "uncompressed": hex::encode(pub_key.to_sec1_uncompressed(&ecdsa.elliptic_curve).unwrap()),
+                },
+                "sha": "SHA-256",
+                "tests": [
+                    { "tcId": 1, "comment": "valid", "flags": [], "msg": "", "sig": hex::encode(sig), "result": "valid" },
+                    { "tcId": 2, "comment": "tampered", "flags": ["ModifiedSignature"], "msg": "", "sig": hex::encode(tampered), "result": "invalid" },
+                ],
+            }],
+        })
+        .to_string()
+    }
+
+    // A file whose key matches its signatures
+    fn signed_ecdsa_file(p1363: bool) -> String {
+        let ecdsa = ECDSA::from(curves::p256());
+        let (priv_key, pub_key) = ecdsa.generate_key_pair().unwrap();
+        let signature = ecdsa.sign_message_with::<Sha256>(b"", &priv_key).unwrap();
+        let encode = |r: &BigUint, s: &BigUint| {
+            if p1363 {
+                [to_fixed_bytes(r, 32).unwrap(), to_fixed_bytes(s, 32).unwrap()].concat()
+            } else {
+                der::encode_sequence(&[der::encode_integer(r), der::encode_integer(s)])
+            }
+        };
+        let sig = encode(&signature.r, &signature.s);
+        let tampered = encode(&signature.r, &(&signature.s + 1u32));
+
+        let mut value: Value = serde_json::from_str(&ecdsa_file(
+            if p1363 { "EcdsaP1363Verify" } else { "EcdsaVerify" },
+            &sig,
+            &tampered,
+        ))
+        .unwrap();
+        value["testGroups"][0]["publicKey"]["uncompressed"] =
+            Value::String(hex::encode(pub_key.to_sec1_uncompressed(&ecdsa.elliptic_curve).unwrap()));
+        value.to_string()
+    }
+
+    #[test]
+    fn test_ecdsa_verify() {
+        for p1363 in [false, true] {
+            let report = run(&signed_ecdsa_file(p1363)).unwrap();
+            assert_eq!(report.algorithm, "ECDSA");
+            assert_eq!(report.passed(), 2);
+            assert!(report.is_success());
+            assert_eq!(report.results[1].flags, vec!["ModifiedSignature".to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_ecdsa_failures_are_reported() {
+        // the "valid" signature is for another key
+        let report = run(&ecdsa_file("EcdsaVerify", &[0x30, 0x00], &[0x30, 0x00])).unwrap();
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].tc_id, 1);
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_non_der_signature_is_rejected() {
+        let mut value: Value = serde_json::from_str(&signed_ecdsa_file(false)).unwrap();
+        // long form length of the sequence
+        let sig = hex::decode(value["testGroups"][0]["tests"][0]["sig"].as_str().unwrap()).unwrap();
+        let ber = [&[0x30, 0x81][..], &sig[1..]].concat();
+        value["testGroups"][0]["tests"][0]["sig"] = Value::String(hex::encode(ber));
+        value["testGroups"][0]["tests"][0]["result"] = Value::String("invalid".into());
+
+        assert!(run_value(&value).unwrap().is_success());
+    }
+
+    #[test]
+    fn test_unsupported_groups_are_skipped() {
+        let file = json!({
+            "algorithm": "ECDSA",
+            "testGroups": [
+                {
+                    "type": "EcdsaVerify",
+                    "publicKey": { "curve": "secp224r1", "uncompressed": "04" },
+                    "sha": "SHA-224",
+                    "tests": [{ "tcId": 1, "comment": "", "msg": "", "sig": "", "result": "valid" }],
+                },
+                {
+                    "type": "EcdsaBitcoinVerify",
+                    "tests": [{ "tcId": 2, "comment": "", "msg": "", "sig": "", "result": "valid" }],
+                },
+            ],
+        });
+        let report = run_value(&file).unwrap();
+        assert_eq!(report.skipped(), 2);
+        assert!(report.is_success());
+    }
+
+    #[test]
+    fn test_ecdh() {
+        let params = &curves::p256();
+        let ecdsa = ECDSA::from(params.clone());
+        let (priv_key, _) = ecdsa.generate_key_pair().unwrap();
+        let (_, peer) = ecdsa.generate_key_pair().unwrap();
+        let shared = ecdh::diffie_hellman(params, &priv_key, &peer, EcdhPrimitive::Standard).unwrap();
+        let ecpoint = peer.to_sec1_uncompressed(&params.curve).unwrap();
+        let spki = spki::to_spki_der(params, &peer).unwrap();
+        let off_curve = [&ecpoint[..64], &[ecpoint[64] ^ 1]].concat();
+
+        for (group_type, public) in [("EcdhEcpointTest", ecpoint.clone()), ("EcdhTest", spki)] {
+            let file = json!({
+                "algorithm": "ECDH",
+                "testGroups": [{
+                    "type": group_type,
+                    "curve": "secp256r1",
+                    "tests": [
+                        {
+                            "tcId": 1, "comment": "", "result": "valid",
+                            "public": hex::encode(&public),
+                            "private": hex::encode(priv_key.to_bytes_be()),
+                            "shared": hex::encode(shared.as_bytes()),
+                        },
+                        {
+                            "tcId": 2, "comment": "point is not on curve", "result": "invalid",
+                            "public": hex::encode(&off_curve),
+                            "private": hex::encode(priv_key.to_bytes_be()),
+                            "shared": "",
+                        },
+                    ],
+                }],
+            });
+            let report = run_value(&file).unwrap();
+            assert_eq!(report.passed(), 2, "{:?}", report);
+        }
+    }
+}
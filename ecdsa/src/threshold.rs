@@ -0,0 +1,471 @@
+/*!
+Threshold ECDSA signing in the honest majority setting (Gennaro, Jarecki,
+Krawczyk and Rabin).
+
+The private key `x` is Shamir-shared with polynomials of degree `t - 1`, so `t`
+shares define it, but it is never rebuilt: signing multiplies shared values,
+which doubles the degree of the polynomials, so every signing session needs at
+least `2t - 1` of the `n` parties.
+
+With `k` and `a` two jointly random shared values, the signature is:
+
+```text
+ mu = k a                         (opened from the degree 2t - 2 shares k_j a_j)
+ R = mu^(-1) (a G) = k^(-1) G     (a G interpolated from the a_j G)
+ r = x component of R
+ s = k (hash + r x)               (opened from the degree 2t - 2 shares)
+```
+
+which is a regular ECDSA signature with nonce `k^(-1)`. The degree `2t - 2`
+shares are masked with random sharings of zero before being opened.
+*/
+
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::signature::Signature;
+
+///
+/// The share `x_i = f(i)` of the private key held by party `i`, together with
+/// the public key `B = x A` and the threshold `t` (degree of `f` plus one).
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyShare {
+    pub index: u32,
+    pub threshold: usize,
+    pub secret_share: BigUint,
+    pub pub_key: CurvePoint,
+}
+
+///
+/// Round 1 message, sent privately from `from` to `to`: its shares of the
+/// random sharings of `k`, `a` and of the two sharings of zero.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct DealMessage {
+    pub from: u32,
+    pub to: u32,
+    pub k: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+    pub c: BigUint,
+}
+
+///
+/// Round 2 message, broadcast: `v_j = k_j a_j + b_j` and `A_j = a_j G`.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct MaskedProductMessage {
+    pub from: u32,
+    pub v: BigUint,
+    pub a_point: CurvePoint,
+}
+
+///
+/// Round 3 message, broadcast: `R` and the share
+/// `s_j = k_j (hash + r x_j) + c_j` of the signature.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct SignatureShareMessage {
+    pub from: u32,
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+enum SessionState {
+    Created,
+    Dealt,
+    Aggregated { k: BigUint, c: BigUint },
+    SharesSent { r: BigUint },
+}
+
+///
+/// The state of one party in a threshold signing session of `hash` among
+/// `signers`. Each round consumes the messages of the previous one:
+///
+/// 1. `deal` returns a `DealMessage` for every signer
+/// 2. `aggregate` takes the `DealMessage`s addressed to this party and
+///    returns the broadcast `MaskedProductMessage`
+/// 3. `sign_share` takes every `MaskedProductMessage` and returns the
+///    broadcast `SignatureShareMessage`
+/// 4. `combine` takes every `SignatureShareMessage` and returns the signature
+///
+pub struct SigningSession<'a> {
+    ecdsa: &'a ECDSA,
+    share: KeyShare,
+    signers: Vec<u32>,
+    hash: BigUint,
+    state: SessionState,
+}
+
+///
+/// Generates `n` shares of a new private key with threshold `t` as a trusted
+/// dealer would, with `ECDSA::split_secret`. The private key only lives
+/// within this function.
+///
+pub fn generate_key_shares<R: CryptoRng + RngCore>(
+    ecdsa: &ECDSA,
+    threshold: usize,
+    parties: u32,
+    rng: &mut R,
+) -> Result<Vec<KeyShare>, ECDSAErrors> {
+    // 2t - 1, None for t = 0 or when it overflows
+    let needed = threshold
+        .checked_mul(2)
+        .and_then(|double| double.checked_sub(1))
+        .and_then(|needed| u32::try_from(needed).ok());
+    if needed.is_none_or(|needed| parties < needed) {
+        return Err(ECDSAErrors::BadArgument(
+            "Signing needs 2t - 1 parties, there has to be at least that many shares".into(),
+        ));
+    }
+
+    let priv_key = rng.gen_biguint_range(&BigUint::from(1u32), &ecdsa.q_order);
+    let pub_key = ecdsa.generate_pub_key(&priv_key)?;
+
+    Ok(ecdsa
+        .split_secret(&priv_key, threshold, parties, rng)?
+        .into_iter()
+        .map(|share| KeyShare {
+            index: share.index,
+            threshold,
+            secret_share: share.value,
+            pub_key: pub_key.clone(),
+        })
+        .collect())
+}
+
+impl<'a> SigningSession<'a> {
+    pub fn new(
+        ecdsa: &'a ECDSA,
+        share: KeyShare,
+        signers: &[u32],
+        hash: &BigUint,
+    ) -> Result<Self, ECDSAErrors> {
+        if share.threshold == 0 {
+            return Err(ECDSAErrors::BadArgument("Threshold must be at least 1".into()));
+        }
+        if *hash >= ecdsa.q_order {
+            return Err(ECDSAErrors::BadArgument(
+                "Hash is bigger than the order of the EC group".into(),
+            ));
+        }
+
+        let mut signers = signers.to_vec();
+        signers.sort_unstable();
+        signers.dedup();
+        let needed = share
+            .threshold
+            .checked_mul(2)
+            .ok_or_else(|| ECDSAErrors::BadArgument("Threshold is too big".into()))?
+            - 1;
+        if signers.len() < needed {
+            return Err(ECDSAErrors::BadArgument(format!(
+                "At least {} signers are needed, got {}",
+                needed,
+                signers.len()
+            )));
+        }
+        if signers.contains(&0) || !signers.contains(&share.index) {
+            return Err(ECDSAErrors::BadArgument(
+                "Signers must be non zero indices including this party".into(),
+            ));
+        }
+
+        Ok(SigningSession {
+            ecdsa,
+            share,
+            signers,
+            hash: hash.clone(),
+            state: SessionState::Created,
+        })
+    }
+
+    ///
+    /// Round 1: samples random polynomials of degree `t - 1` for `k` and `a`
+    /// and of degree `2t - 2` with a zero constant term for the masks `b` and
+    /// `c`, and returns their evaluation for every signer.
+    ///
+    pub fn deal<R: CryptoRng + RngCore>(&mut self, rng: &mut R) -> Result<Vec<DealMessage>, ECDSAErrors> {
+        if !matches!(self.state, SessionState::Created) {
+            return Err(ECDSAErrors::OperationFailure("Shares were already dealt".into()));
+        }
+
+        let q = &self.ecdsa.q_order;
+        let t = self.share.threshold;
+        let zero = BigUint::from(0u32);
+        let k = random_polynomial(rng.gen_biguint_below(q), t - 1, q, rng);
+        let a = random_polynomial(rng.gen_biguint_below(q), t - 1, q, rng);
+        let b = random_polynomial(zero.clone(), 2 * t - 2, q, rng);
+        let c = random_polynomial(zero, 2 * t - 2, q, rng);
+
+        self.state = SessionState::Dealt;
+        Ok(self
+            .signers
+            .iter()
+            .map(|&to| DealMessage {
+                from: self.share.index,
+                to,
+                k: evaluate(&k, to, q),
+                a: evaluate(&a, to, q),
+                b: evaluate(&b, to, q),
+                c: evaluate(&c, to, q),
+            })
+            .collect())
+    }
+
+    ///
+    /// Round 2: adds up the shares dealt to this party and returns the masked
+    /// product `k_j a_j + b_j` together with `a_j G`.
+    ///
+    pub fn aggregate(&mut self, messages: &[DealMessage]) -> Result<MaskedProductMessage, ECDSAErrors> {
+        if !matches!(self.state, SessionState::Dealt) {
+            return Err(ECDSAErrors::OperationFailure("Round 1 has not been run".into()));
+        }
+        self.check_senders(messages.iter().map(|message| message.from))?;
+        if messages.iter().any(|message| message.to != self.share.index) {
+            return Err(ECDSAErrors::BadArgument("Message addressed to another party".into()));
+        }
+
+        let q = &self.ecdsa.q_order;
+        let sum = |value: fn(&DealMessage) -> &BigUint| {
+            messages.iter().fold(BigUint::from(0u32), |acc, message| (acc + value(message)) % q)
+        };
+        let k = sum(|message| &message.k);
+        let a = sum(|message| &message.a);
+        let b = sum(|message| &message.b);
+        let c = sum(|message| &message.c);
+
+        let v = (&k * &a + b) % q;
+        let a_point = self
+            .ecdsa
+            .elliptic_curve
+            .scalar_mul(&self.ecdsa.a_gen, &a)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing a_j * a_gen".into()))?;
+
+        self.state = SessionState::Aggregated { k, c };
+        Ok(MaskedProductMessage {
+            from: self.share.index,
+            v,
+            a_point,
+        })
+    }
+
+    ///
+    /// Round 3: opens `mu = k a`, computes `R = mu^(-1) (a G)` and returns this
+    /// party's share of `s`.
+    ///
+    pub fn sign_share(&mut self, messages: &[MaskedProductMessage]) -> Result<SignatureShareMessage, ECDSAErrors> {
+        let (k, c) = match &self.state {
+            SessionState::Aggregated { k, c, .. } => (k.clone(), c.clone()),
+            _ => return Err(ECDSAErrors::OperationFailure("Round 2 has not been run".into())),
+        };
+        self.check_senders(messages.iter().map(|message| message.from))?;
+
+        let q = &self.ecdsa.q_order;
+        let ec = &self.ecdsa.elliptic_curve;
+
+        let mu = messages.iter().try_fold(BigUint::from(0u32), |acc, message| {
+            let lambda = lagrange_at_zero(message.from, &self.signers, q)?;
+            Ok::<_, ECDSAErrors>((acc + lambda * &message.v) % q)
+        })?;
+        if mu == BigUint::from(0u32) {
+            return Err(ECDSAErrors::OperationFailure("k * a is zero, restart the session".into()));
+        }
+
+        let terms = messages
+            .iter()
+            .map(|message| Ok((lagrange_at_zero(message.from, &self.signers, q)?, message.a_point.clone())))
+            .collect::<Result<Vec<_>, ECDSAErrors>>()?;
+        let a_point = ec
+            .multi_scalar_mul(&terms)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error interpolating a * a_gen".into()))?;
+
+        let mu_inv = inverse_multiplicate_prime(&mu, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing mu_inv".into()))?;
+        let r_point = ec
+            .scalar_mul(&a_point, &mu_inv)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing mu_inv * a_point".into()))?;
+
+        let r = match r_point {
+            CurvePoint::Coordinate(x, _) => x % q,
+            CurvePoint::Identity => {
+                return Err(ECDSAErrors::OperationFailure("R is the identity".into()))
+            }
+        };
+        if r == BigUint::from(0u32) {
+            return Err(ECDSAErrors::OperationFailure("Result r is zero, restart the session".into()));
+        }
+
+        // s_j = k_j (hash + r x_j) + c_j
+        let s = (k * ((&self.hash + &r * &self.share.secret_share) % q) + c) % q;
+
+        self.state = SessionState::SharesSent { r: r.clone() };
+        Ok(SignatureShareMessage {
+            from: self.share.index,
+            r,
+            s,
+        })
+    }
+
+    ///
+    /// Opens `s` from the signature shares of every signer and returns the
+    /// ECDSA signature `(r, s)`, checked against the public key.
+    ///
+    pub fn combine(&self, messages: &[SignatureShareMessage]) -> Result<Signature, ECDSAErrors> {
+        let r = match &self.state {
+            SessionState::SharesSent { r } => r.clone(),
+            _ => return Err(ECDSAErrors::OperationFailure("Round 3 has not been run".into())),
+        };
+        self.check_senders(messages.iter().map(|message| message.from))?;
+        if messages.iter().any(|message| message.r != r) {
+            return Err(ECDSAErrors::OperationFailure("Signers disagree on r".into()));
+        }
+
+        let q = &self.ecdsa.q_order;
+        let s = messages.iter().try_fold(BigUint::from(0u32), |acc, message| {
+            let lambda = lagrange_at_zero(message.from, &self.signers, q)?;
+            Ok::<_, ECDSAErrors>((acc + lambda * &message.s) % q)
+        })?;
+
+        let signature = Signature::new(r, s);
+        if !self.ecdsa.verify(&self.hash, &self.share.pub_key, &signature)? {
+            return Err(ECDSAErrors::OperationFailure(
+                "The combined signature is not valid".into(),
+            ));
+        }
+
+        Ok(signature)
+    }
+
+    // exactly one message from every signer
+    fn check_senders(&self, senders: impl Iterator<Item = u32>) -> Result<(), ECDSAErrors> {
+        let mut senders: Vec<u32> = senders.collect();
+        senders.sort_unstable();
+        if senders != self.signers {
+            return Err(ECDSAErrors::BadArgument(
+                "Expected exactly one message from every signer".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// f(z) = constant + c_1 z + ... + c_degree z^degree
+pub(crate) fn random_polynomial<R: CryptoRng + RngCore>(
+    constant: BigUint,
+    degree: usize,
+    q: &BigUint,
+    rng: &mut R,
+) -> Vec<BigUint> {
+    let mut coefficients = vec![constant];
+    coefficients.extend((0..degree).map(|_| rng.gen_biguint_below(q)));
+    coefficients
+}
+
+// the coefficients are sampled below q, evaluating can not fail
+pub(crate) fn evaluate(coefficients: &[BigUint], z: u32, q: &BigUint) -> BigUint {
+    evaluate_polynomial(coefficients, &(BigUint::from(z) % q), q)
+        .expect("coefficients are smaller than q")
+}
+
+// lambda_i = prod_(j != i) j / (j - i) mod q
+pub(crate) fn lagrange_at_zero(i: u32, indices: &[u32], q: &BigUint) -> Result<BigUint, ECDSAErrors> {
+    let xs: Vec<BigUint> = indices.iter().map(|&j| BigUint::from(j) % q).collect();
+    let position = indices
+        .iter()
+        .position(|&j| j == i)
+        .ok_or_else(|| ECDSAErrors::BadArgument(format!("{} is not a signer", i)))?;
+
+    lagrange_coefficient(position, &xs, &BigUint::from(0u32), q)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error computing the Lagrange coefficient".into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn run_session(ecdsa: &ECDSA, shares: &[KeyShare], signers: &[u32], hash: &BigUint) -> Result<Signature, ECDSAErrors> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut sessions = signers
+            .iter()
+            .map(|&i| SigningSession::new(ecdsa, shares[i as usize - 1].clone(), signers, hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut dealt = Vec::new();
+        for session in sessions.iter_mut() {
+            dealt.extend(session.deal(&mut rng)?);
+        }
+
+        let mut products = Vec::new();
+        for session in sessions.iter_mut() {
+            let index = session.share.index;
+            let inbox: Vec<DealMessage> = dealt.iter().filter(|m| m.to == index).cloned().collect();
+            products.push(session.aggregate(&inbox)?);
+        }
+
+        let mut signature_shares = Vec::new();
+        for session in sessions.iter_mut() {
+            signature_shares.push(session.sign_share(&products)?);
+        }
+
+        let signatures = sessions
+            .iter()
+            .map(|session| session.combine(&signature_shares))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(signatures.windows(2).all(|pair| pair[0] == pair[1]));
+
+        Ok(signatures[0].clone())
+    }
+
+    #[test]
+    fn test_threshold_sign() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(10);
+        let shares = generate_key_shares(&ecdsa, 2, 5, &mut rng).unwrap();
+        let pub_key = shares[0].pub_key.clone();
+
+        let hash = ecdsa.generate_hash_less_than("Bob -> 1 BTC -> Alice", &ecdsa.q_order);
+
+        for signers in [vec![1, 2, 3], vec![1, 3, 5], vec![2, 3, 4, 5]] {
+            let signature = run_session(&ecdsa, &shares, &signers, &hash).unwrap();
+            assert!(ecdsa.verify(&hash, &pub_key, &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_threshold_sign_wrong_input() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(12);
+        let shares = generate_key_shares(&ecdsa, 2, 3, &mut rng).unwrap();
+        let hash = BigUint::from(1234u32);
+
+        // 2t - 1 = 3 signers are needed
+        assert!(SigningSession::new(&ecdsa, shares[0].clone(), &[1, 2], &hash).is_err());
+        assert!(SigningSession::new(&ecdsa, shares[0].clone(), &[2, 3, 4], &hash).is_err());
+        let mut bad_threshold = shares[0].clone();
+        bad_threshold.threshold = 0;
+        assert!(SigningSession::new(&ecdsa, bad_threshold.clone(), &[1, 2, 3], &hash).is_err());
+        bad_threshold.threshold = usize::MAX;
+        assert!(SigningSession::new(&ecdsa, bad_threshold, &[1, 2, 3], &hash).is_err());
+        assert!(generate_key_shares(&ecdsa, 3, 4, &mut rng).is_err());
+        assert!(generate_key_shares(&ecdsa, usize::MAX, u32::MAX, &mut rng).is_err());
+        assert!(generate_key_shares(&ecdsa, (1 << 31) + 1, u32::MAX, &mut rng).is_err());
+
+        // rounds out of order
+        let mut session = SigningSession::new(&ecdsa, shares[0].clone(), &[1, 2, 3], &hash).unwrap();
+        assert!(session.aggregate(&[]).is_err());
+        session.deal(&mut rng).unwrap();
+        assert!(session.deal(&mut rng).is_err());
+        assert!(session.aggregate(&[]).is_err());
+
+        // a tempered key share produces an invalid signature
+        let mut bad_shares = shares.clone();
+        bad_shares[1].secret_share += BigUint::from(1u32);
+        assert!(run_session(&ecdsa, &bad_shares, &[1, 2, 3], &hash).is_err());
+    }
+}
@@ -0,0 +1,645 @@
+/*!
+Two-party ECDSA (Lindell, "Fast Secure Two-Party ECDSA Signing", 2017)
+without the zero-knowledge proofs on the Paillier key of P1, so secure
+against a semi-honest P1 only: a wallet and a server each hold a share of
+the key and sign together.
+
+**Warning**: P2 only checks the bit length of P1's Paillier modulus `N`.
+The proofs of the full protocol (Lindell 2017 section 6) that `N` is a valid
+Paillier modulus and that `c_key` encrypts the logarithm of `Q1`, in range,
+are not implemented. A malicious P1 choosing a malformed `N` or `c_key`
+learns information on `x2` from `c3`, so P1 has to be trusted to follow the
+protocol; a malicious P2 learns nothing about `x1`. The module is only built
+with the `two-party-semi-honest` feature.
+
+Key generation, `Q = x1 x2 G`:
+
+```text
+ P1 -> P2   commitment to (Q1 = x1 G, proof of x1)
+ P2 -> P1   Q2 = x2 G, proof of x2
+ P1 -> P2   Q1, proof of x1, opening, Paillier key N, c_key = Enc(x1)
+```
+
+Signing `hash`, `R = k1 k2 G`:
+
+```text
+ P1 -> P2   commitment to (R1 = k1 G, proof of k1)
+ P2 -> P1   R2 = k2 G, proof of k2
+ P1 -> P2   R1, proof of k1, opening
+ P2 -> P1   c3 = Enc(rho q + k2^(-1) hash) + c_key^(k2^(-1) r x2)   rho random in [0, q^2)
+ P1         s = k1^(-1) Dec(c3) mod q, in its low-S form
+```
+
+P1 verifies the signature before releasing it. The proofs are Schnorr proofs
+of knowledge of the discrete logarithms.
+
+Messages are plain structs, serializable with the `serde` feature.
+*/
+
+use ec_core::*;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::paillier::{PaillierPublicKey, PaillierSecretKey, MODULUS_BITS};
+use crate::signature::Signature;
+
+/// Byte length of the openings of the commitments.
+const BLINDING_SIZE: usize = 32;
+
+///
+/// A Schnorr proof of knowledge of `x` such that `Q = x G`:
+/// `T = t G`, `e = H(tag || G || Q || T)`, `z = t + e x mod q`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DlogProof {
+    pub commitment: CurvePoint,
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub response: BigUint,
+}
+
+///
+/// First message of P1, in key generation and in signing: a commitment to its
+/// point and the proof of its logarithm.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommitmentMessage {
+    pub commitment: [u8; 32],
+}
+
+///
+/// Message of P2, in key generation and in signing: its point and the proof
+/// of its logarithm.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointMessage {
+    pub point: CurvePoint,
+    pub proof: DlogProof,
+}
+
+///
+/// Second message of P1, in key generation and in signing: the opening of its
+/// commitment.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecommitmentMessage {
+    pub point: CurvePoint,
+    pub proof: DlogProof,
+    pub blinding: [u8; BLINDING_SIZE],
+}
+
+///
+/// Last message of P1 in key generation: the opening of its commitment, its
+/// Paillier public key and `c_key = Enc(x1)`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyGenFinalMessage {
+    pub decommitment: DecommitmentMessage,
+    pub paillier_key: PaillierPublicKey,
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub encrypted_share: BigUint,
+}
+
+///
+/// Last message of P2 in signing: `c3`, the encryption of P1's part of `s`.
+///
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartialSignatureMessage {
+    #[cfg_attr(feature = "serde", serde(with = "ec_core::serde_support::biguint"))]
+    pub c3: BigUint,
+}
+
+///
+/// The key share of P1: `x1`, the Paillier secret key and `Q`.
+///
+#[derive(Clone, Debug)]
+pub struct Party1KeyShare {
+    pub secret_share: BigUint,
+    pub paillier_key: PaillierSecretKey,
+    pub pub_key: CurvePoint,
+}
+
+///
+/// The key share of P2: `x2`, P1's Paillier public key, `c_key = Enc(x1)` and
+/// `Q`.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct Party2KeyShare {
+    pub secret_share: BigUint,
+    pub paillier_key: PaillierPublicKey,
+    pub encrypted_share: BigUint,
+    pub pub_key: CurvePoint,
+}
+
+// the state of P1 between its two messages, in key generation and signing
+struct Party1Secret {
+    secret: BigUint,
+    decommitment: DecommitmentMessage,
+}
+
+enum Party1State {
+    Created,
+    Committed(Party1Secret),
+    Done,
+}
+
+enum Party2State {
+    Created,
+    Responded { secret: BigUint, commitment: [u8; 32] },
+    Done,
+}
+
+///
+/// P1 in key generation: `commit` then `finish`.
+///
+pub struct Party1KeyGen<'a> {
+    ecdsa: &'a ECDSA,
+    modulus_bits: u64,
+    state: Party1State,
+}
+
+///
+/// P2 in key generation: `respond` then `finish`.
+///
+pub struct Party2KeyGen<'a> {
+    ecdsa: &'a ECDSA,
+    state: Party2State,
+}
+
+///
+/// P1 in signing: `commit`, `reveal` then `finish`, which returns the
+/// signature. A round called too early fails and leaves the session as it
+/// was, a failed `finish` aborts it: a new session is needed to sign.
+///
+pub struct Party1Signing<'a> {
+    ecdsa: &'a ECDSA,
+    share: Party1KeyShare,
+    hash: BigUint,
+    r_point: Option<CurvePoint>,
+    state: Party1State,
+}
+
+///
+/// P2 in signing: `respond` then `partial_sign`.
+///
+pub struct Party2Signing<'a> {
+    ecdsa: &'a ECDSA,
+    share: Party2KeyShare,
+    hash: BigUint,
+    state: Party2State,
+}
+
+impl<'a> Party1KeyGen<'a> {
+    ///
+    /// With a Paillier modulus of `paillier::MODULUS_BITS` bits.
+    ///
+    pub fn new(ecdsa: &'a ECDSA) -> Self {
+        Party1KeyGen::with_modulus_bits(ecdsa, MODULUS_BITS)
+    }
+
+    ///
+    /// With a Paillier modulus of `modulus_bits` bits, which has to be greater
+    /// than `3 bits(q) + 2` for `c3` not to wrap around (`finish` checks it).
+    ///
+    pub fn with_modulus_bits(ecdsa: &'a ECDSA, modulus_bits: u64) -> Self {
+        Party1KeyGen { ecdsa, modulus_bits, state: Party1State::Created }
+    }
+
+    pub fn commit<R: CryptoRng + RngCore>(&mut self, rng: &mut R) -> Result<CommitmentMessage, ECDSAErrors> {
+        if !matches!(self.state, Party1State::Created) {
+            return Err(ECDSAErrors::OperationFailure("Already committed".into()));
+        }
+        let (secret, message) = party1_commit(self.ecdsa, KEYGEN_TAG, rng)?;
+        self.state = Party1State::Committed(secret);
+        Ok(message)
+    }
+
+    ///
+    /// Checks P2's proof, generates the Paillier key and returns P1's key
+    /// share with the last message.
+    ///
+    pub fn finish<R: CryptoRng + RngCore>(
+        &mut self,
+        message: &PointMessage,
+        rng: &mut R,
+    ) -> Result<(Party1KeyShare, KeyGenFinalMessage), ECDSAErrors> {
+        // an early call leaves the session as it was
+        let state = std::mem::replace(&mut self.state, Party1State::Done);
+        let Party1State::Committed(secret) = state else {
+            self.state = state;
+            return Err(ECDSAErrors::OperationFailure("Commit has not been run".into()));
+        };
+        check_modulus_bits(self.ecdsa, self.modulus_bits)?;
+        verify_dlog(self.ecdsa, KEYGEN_TAG, &message.point, &message.proof)?;
+
+        let pub_key = mul(self.ecdsa, &message.point, &secret.secret)?;
+        let paillier_key = PaillierSecretKey::generate(self.modulus_bits, rng)?;
+        let encrypted_share = paillier_key.public_key().encrypt(&secret.secret, rng)?;
+
+        let final_message = KeyGenFinalMessage {
+            decommitment: secret.decommitment,
+            paillier_key: paillier_key.public_key().clone(),
+            encrypted_share,
+        };
+        Ok((Party1KeyShare { secret_share: secret.secret, paillier_key, pub_key }, final_message))
+    }
+}
+
+impl<'a> Party2KeyGen<'a> {
+    pub fn new(ecdsa: &'a ECDSA) -> Self {
+        Party2KeyGen { ecdsa, state: Party2State::Created }
+    }
+
+    pub fn respond<R: CryptoRng + RngCore>(&mut self, message: &CommitmentMessage, rng: &mut R) -> Result<PointMessage, ECDSAErrors> {
+        if !matches!(self.state, Party2State::Created) {
+            return Err(ECDSAErrors::OperationFailure("Already responded".into()));
+        }
+        let (secret, response) = party2_respond(self.ecdsa, KEYGEN_TAG, rng)?;
+        self.state = Party2State::Responded { secret, commitment: message.commitment };
+        Ok(response)
+    }
+
+    ///
+    /// Opens P1's commitment, checks its proof and returns P2's key share.
+    /// Only the size of the Paillier modulus is checked, not that it is well
+    /// formed nor what `c_key` encrypts, see the module documentation.
+    ///
+    pub fn finish(&mut self, message: &KeyGenFinalMessage) -> Result<Party2KeyShare, ECDSAErrors> {
+        // an early call leaves the session as it was
+        let state = std::mem::replace(&mut self.state, Party2State::Done);
+        let Party2State::Responded { secret, commitment } = state else {
+            self.state = state;
+            return Err(ECDSAErrors::OperationFailure("Respond has not been run".into()));
+        };
+        check_decommitment(self.ecdsa, KEYGEN_TAG, &commitment, &message.decommitment)?;
+        check_modulus_bits(self.ecdsa, message.paillier_key.n.bits())?;
+        if message.encrypted_share >= message.paillier_key.n_squared() {
+            return Err(ECDSAErrors::BadArgument("Invalid encrypted share".into()));
+        }
+
+        Ok(Party2KeyShare {
+            pub_key: mul(self.ecdsa, &message.decommitment.point, &secret)?,
+            secret_share: secret,
+            paillier_key: message.paillier_key.clone(),
+            encrypted_share: message.encrypted_share.clone(),
+        })
+    }
+}
+
+impl<'a> Party1Signing<'a> {
+    pub fn new(ecdsa: &'a ECDSA, share: Party1KeyShare, hash: &BigUint) -> Result<Self, ECDSAErrors> {
+        check_hash(ecdsa, hash)?;
+        Ok(Party1Signing { ecdsa, share, hash: hash.clone(), r_point: None, state: Party1State::Created })
+    }
+
+    pub fn commit<R: CryptoRng + RngCore>(&mut self, rng: &mut R) -> Result<CommitmentMessage, ECDSAErrors> {
+        if !matches!(self.state, Party1State::Created) {
+            return Err(ECDSAErrors::OperationFailure("Already committed".into()));
+        }
+        let (secret, message) = party1_commit(self.ecdsa, SIGN_TAG, rng)?;
+        self.state = Party1State::Committed(secret);
+        Ok(message)
+    }
+
+    ///
+    /// Checks P2's proof, computes `R = k1 R2` and opens the commitment.
+    ///
+    pub fn reveal(&mut self, message: &PointMessage) -> Result<DecommitmentMessage, ECDSAErrors> {
+        let Party1State::Committed(secret) = &self.state else {
+            return Err(ECDSAErrors::OperationFailure("Commit has not been run".into()));
+        };
+        if self.r_point.is_some() {
+            return Err(ECDSAErrors::OperationFailure("Already revealed".into()));
+        }
+        verify_dlog(self.ecdsa, SIGN_TAG, &message.point, &message.proof)?;
+        self.r_point = Some(mul(self.ecdsa, &message.point, &secret.secret)?);
+        Ok(secret.decommitment.clone())
+    }
+
+    ///
+    /// Decrypts `c3` into the signature, which is checked against `Q`. Once
+    /// `reveal` has run, `finish` ends the session whatever its outcome: on an
+    /// invalid `c3` the session is aborted rather than retried with the same
+    /// nonce, which would let P2 try several `c3` against `k1`.
+    ///
+    pub fn finish(&mut self, message: &PartialSignatureMessage) -> Result<Signature, ECDSAErrors> {
+        let Some(r_point) = &self.r_point else {
+            return Err(ECDSAErrors::OperationFailure("Reveal has not been run".into()));
+        };
+        let Party1State::Committed(secret) = std::mem::replace(&mut self.state, Party1State::Done) else {
+            return Err(ECDSAErrors::OperationFailure("Already finished".into()));
+        };
+
+        let q = &self.ecdsa.q_order;
+        let r = x_mod_q(r_point, q)?;
+        let s_prime = self.share.paillier_key.decrypt(&message.c3)? % q;
+        let k1_inv = inverse_multiplicate_prime(&secret.secret, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing k1_inv".into()))?;
+        let signature = Signature::new(r, s_prime * k1_inv % q).normalize_s(q);
+
+        if signature.s == BigUint::from(0u32) || !self.ecdsa.verify(&self.hash, &self.share.pub_key, &signature)? {
+            return Err(ECDSAErrors::OperationFailure("The signature is not valid".into()));
+        }
+        Ok(signature)
+    }
+}
+
+impl<'a> Party2Signing<'a> {
+    pub fn new(ecdsa: &'a ECDSA, share: Party2KeyShare, hash: &BigUint) -> Result<Self, ECDSAErrors> {
+        check_hash(ecdsa, hash)?;
+        Ok(Party2Signing { ecdsa, share, hash: hash.clone(), state: Party2State::Created })
+    }
+
+    pub fn respond<R: CryptoRng + RngCore>(&mut self, message: &CommitmentMessage, rng: &mut R) -> Result<PointMessage, ECDSAErrors> {
+        if !matches!(self.state, Party2State::Created) {
+            return Err(ECDSAErrors::OperationFailure("Already responded".into()));
+        }
+        let (secret, response) = party2_respond(self.ecdsa, SIGN_TAG, rng)?;
+        self.state = Party2State::Responded { secret, commitment: message.commitment };
+        Ok(response)
+    }
+
+    ///
+    /// Opens P1's commitment, computes `R = k2 R1` and returns `c3`.
+    ///
+    pub fn partial_sign<R: CryptoRng + RngCore>(
+        &mut self,
+        message: &DecommitmentMessage,
+        rng: &mut R,
+    ) -> Result<PartialSignatureMessage, ECDSAErrors> {
+        // an early call leaves the session as it was
+        let state = std::mem::replace(&mut self.state, Party2State::Done);
+        let Party2State::Responded { secret: k2, commitment } = state else {
+            self.state = state;
+            return Err(ECDSAErrors::OperationFailure("Respond has not been run".into()));
+        };
+        check_decommitment(self.ecdsa, SIGN_TAG, &commitment, message)?;
+
+        let q = &self.ecdsa.q_order;
+        let r = x_mod_q(&mul(self.ecdsa, &message.point, &k2)?, q)?;
+        let k2_inv = inverse_multiplicate_prime(&k2, q)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing k2_inv".into()))?;
+
+        // c1 = Enc(rho q + k2^(-1) hash), c2 = c_key^(k2^(-1) r x2)
+        let paillier_key = &self.share.paillier_key;
+        let rho = rng.gen_biguint_below(&(q * q));
+        let c1 = paillier_key.encrypt(&(rho * q + &k2_inv * &self.hash % q), rng)?;
+        let v = k2_inv * r * &self.share.secret_share % q;
+        let c2 = paillier_key.mul(&self.share.encrypted_share, &v);
+
+        Ok(PartialSignatureMessage { c3: paillier_key.add(&c1, &c2) })
+    }
+}
+
+const KEYGEN_TAG: &[u8] = b"2P-ECDSA keygen";
+const SIGN_TAG: &[u8] = b"2P-ECDSA sign";
+
+fn party1_commit<R: CryptoRng + RngCore>(
+    ecdsa: &ECDSA,
+    tag: &[u8],
+    rng: &mut R,
+) -> Result<(Party1Secret, CommitmentMessage), ECDSAErrors> {
+    let secret = rng.gen_biguint_range(&BigUint::from(1u32), &ecdsa.q_order);
+    let point = ecdsa.generate_pub_key(&secret)?;
+    let proof = prove_dlog(ecdsa, tag, &secret, &point, rng)?;
+    let mut blinding = [0u8; BLINDING_SIZE];
+    rng.fill_bytes(&mut blinding);
+
+    let decommitment = DecommitmentMessage { point, proof, blinding };
+    let commitment = commit(ecdsa, tag, &decommitment)?;
+    Ok((Party1Secret { secret, decommitment }, CommitmentMessage { commitment }))
+}
+
+fn party2_respond<R: CryptoRng + RngCore>(ecdsa: &ECDSA, tag: &[u8], rng: &mut R) -> Result<(BigUint, PointMessage), ECDSAErrors> {
+    let secret = rng.gen_biguint_range(&BigUint::from(1u32), &ecdsa.q_order);
+    let point = ecdsa.generate_pub_key(&secret)?;
+    let proof = prove_dlog(ecdsa, tag, &secret, &point, rng)?;
+    Ok((secret, PointMessage { point, proof }))
+}
+
+// H(tag || Q || T || z || blinding)
+fn commit(ecdsa: &ECDSA, tag: &[u8], decommitment: &DecommitmentMessage) -> Result<[u8; 32], ECDSAErrors> {
+    Ok(Sha256::new()
+        .chain_update(tag)
+        .chain_update(encode(ecdsa, &decommitment.point)?)
+        .chain_update(encode(ecdsa, &decommitment.proof.commitment)?)
+        .chain_update(decommitment.proof.response.to_bytes_be())
+        .chain_update(decommitment.blinding)
+        .finalize()
+        .into())
+}
+
+fn check_decommitment(ecdsa: &ECDSA, tag: &[u8], commitment: &[u8; 32], decommitment: &DecommitmentMessage) -> Result<(), ECDSAErrors> {
+    if commit(ecdsa, tag, decommitment)? != *commitment {
+        return Err(ECDSAErrors::BadArgument("The opening does not match the commitment".into()));
+    }
+    verify_dlog(ecdsa, tag, &decommitment.point, &decommitment.proof)
+}
+
+fn prove_dlog<R: CryptoRng + RngCore>(
+    ecdsa: &ECDSA,
+    tag: &[u8],
+    secret: &BigUint,
+    point: &CurvePoint,
+    rng: &mut R,
+) -> Result<DlogProof, ECDSAErrors> {
+    let q = &ecdsa.q_order;
+    let t = rng.gen_biguint_range(&BigUint::from(1u32), q);
+    let commitment = ecdsa.generate_pub_key(&t)?;
+    let e = dlog_challenge(ecdsa, tag, point, &commitment)?;
+    Ok(DlogProof { commitment, response: (t + e * secret) % q })
+}
+
+// z G == T + e Q
+fn verify_dlog(ecdsa: &ECDSA, tag: &[u8], point: &CurvePoint, proof: &DlogProof) -> Result<(), ECDSAErrors> {
+    let ec = &ecdsa.elliptic_curve;
+    if *point == CurvePoint::Identity || !ec.is_on_curve(point) || !ec.is_on_curve(&proof.commitment) {
+        return Err(ECDSAErrors::BadArgument("Invalid point".into()));
+    }
+    let e = dlog_challenge(ecdsa, tag, point, &proof.commitment)?;
+    let expected = ec
+        .add(&proof.commitment, &mul(ecdsa, point, &e)?)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error computing T + e Q".into()))?;
+    if proof.response >= ecdsa.q_order || mul(ecdsa, &ecdsa.a_gen, &proof.response)? != expected {
+        return Err(ECDSAErrors::BadArgument("Invalid proof of knowledge".into()));
+    }
+    Ok(())
+}
+
+fn dlog_challenge(ecdsa: &ECDSA, tag: &[u8], point: &CurvePoint, commitment: &CurvePoint) -> Result<BigUint, ECDSAErrors> {
+    let digest = Sha256::new()
+        .chain_update(tag)
+        .chain_update(encode(ecdsa, &ecdsa.a_gen)?)
+        .chain_update(encode(ecdsa, point)?)
+        .chain_update(encode(ecdsa, commitment)?)
+        .finalize();
+    Ok(BigUint::from_bytes_be(&digest) % &ecdsa.q_order)
+}
+
+fn encode(ecdsa: &ECDSA, point: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+    point
+        .to_sec1_compressed(&ecdsa.elliptic_curve)
+        .map_err(|_| ECDSAErrors::BadArgument("The identity can not be encoded".into()))
+}
+
+fn mul(ecdsa: &ECDSA, point: &CurvePoint, scalar: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+    ecdsa
+        .elliptic_curve
+        .scalar_mul(point, scalar)
+        .map_err(|_| ECDSAErrors::OperationFailure("Error in scalar multiplication".into()))
+}
+
+fn x_mod_q(point: &CurvePoint, q: &BigUint) -> Result<BigUint, ECDSAErrors> {
+    match point {
+        CurvePoint::Coordinate(x, _) if x % q != BigUint::from(0u32) => Ok(x % q),
+        _ => Err(ECDSAErrors::OperationFailure("r is zero, restart the session".into())),
+    }
+}
+
+fn check_hash(ecdsa: &ECDSA, hash: &BigUint) -> Result<(), ECDSAErrors> {
+    if *hash >= ecdsa.q_order {
+        return Err(ECDSAErrors::BadArgument(
+            "Hash is bigger than the order of the EC group".into(),
+        ));
+    }
+    Ok(())
+}
+
+// rho q + k2^(-1) hash + k2^(-1) r x2 x1 < q^3 + 2 q^2 has to be below N
+fn check_modulus_bits(ecdsa: &ECDSA, modulus_bits: u64) -> Result<(), ECDSAErrors> {
+    if modulus_bits < 3 * ecdsa.q_order.bits() + 2 {
+        return Err(ECDSAErrors::BadArgument(format!(
+            "The Paillier modulus must be at least {} bits long",
+            3 * ecdsa.q_order.bits() + 2
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn keygen(ecdsa: &ECDSA, rng: &mut StdRng) -> (Party1KeyShare, Party2KeyShare) {
+        let mut party1 = Party1KeyGen::with_modulus_bits(ecdsa, 1024);
+        let mut party2 = Party2KeyGen::new(ecdsa);
+
+        let message1 = party1.commit(rng).unwrap();
+        let message2 = party2.respond(&message1, rng).unwrap();
+        let (share1, message3) = party1.finish(&message2, rng).unwrap();
+        let share2 = party2.finish(&message3).unwrap();
+        (share1, share2)
+    }
+
+    fn sign(ecdsa: &ECDSA, share1: &Party1KeyShare, share2: &Party2KeyShare, hash: &BigUint, rng: &mut StdRng) -> Result<Signature, ECDSAErrors> {
+        let mut party1 = Party1Signing::new(ecdsa, share1.clone(), hash)?;
+        let mut party2 = Party2Signing::new(ecdsa, share2.clone(), hash)?;
+
+        let message1 = party1.commit(rng)?;
+        let message2 = party2.respond(&message1, rng)?;
+        let message3 = party1.reveal(&message2)?;
+        let message4 = party2.partial_sign(&message3, rng)?;
+        party1.finish(&message4)
+    }
+
+    #[test]
+    fn test_keygen_and_sign() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(40);
+        let (share1, share2) = keygen(&ecdsa, &mut rng);
+        assert_eq!(share1.pub_key, share2.pub_key);
+        let expected = ecdsa.generate_pub_key(&(&share1.secret_share * &share2.secret_share % &ecdsa.q_order)).unwrap();
+        assert_eq!(share1.pub_key, expected);
+
+        for message in ["Bob -> 1 BTC -> Alice", "Alice -> 2 BTC -> Bob"] {
+            let hash = ecdsa.generate_hash_less_than(message, &ecdsa.q_order);
+            let signature = sign(&ecdsa, &share1, &share2, &hash, &mut rng).unwrap();
+            assert!(ecdsa.verify(&hash, &share1.pub_key, &signature).unwrap());
+            assert!(signature.is_low_s(&ecdsa.q_order));
+        }
+    }
+
+    #[test]
+    fn test_wrong_input() {
+        let ecdsa = ECDSA::from(ec_core::curves::secp256k1());
+        let mut rng = StdRng::seed_from_u64(41);
+        let (share1, share2) = keygen(&ecdsa, &mut rng);
+        let hash = BigUint::from(1234u32);
+
+        // a modulus too small for c3
+        let mut party1 = Party1KeyGen::with_modulus_bits(&ecdsa, 512);
+        let mut party2 = Party2KeyGen::new(&ecdsa);
+        let message2 = party2.respond(&party1.commit(&mut rng).unwrap(), &mut rng).unwrap();
+        assert!(party1.finish(&message2, &mut rng).is_err());
+
+        // an opening which does not match the commitment
+        let mut party1 = Party1Signing::new(&ecdsa, share1.clone(), &hash).unwrap();
+        let mut party2 = Party2Signing::new(&ecdsa, share2.clone(), &hash).unwrap();
+        let message1 = party1.commit(&mut rng).unwrap();
+        let message2 = party2.respond(&message1, &mut rng).unwrap();
+        let mut message3 = party1.reveal(&message2).unwrap();
+        message3.blinding[0] ^= 1;
+        assert!(party2.partial_sign(&message3, &mut rng).is_err());
+
+        // an invalid proof from P2
+        let mut party1 = Party1Signing::new(&ecdsa, share1.clone(), &hash).unwrap();
+        let mut party2 = Party2Signing::new(&ecdsa, share2.clone(), &hash).unwrap();
+        let mut message2 = party2.respond(&party1.commit(&mut rng).unwrap(), &mut rng).unwrap();
+        message2.proof.response += 1u32;
+        assert!(party1.reveal(&message2).is_err());
+
+        // rounds out of order, finishing too early keeps the session going
+        let mut party1 = Party1Signing::new(&ecdsa, share1.clone(), &hash).unwrap();
+        let mut party2 = Party2Signing::new(&ecdsa, share2.clone(), &hash).unwrap();
+        let early = PartialSignatureMessage { c3: BigUint::from(1u32) };
+        assert!(party1.finish(&early).is_err());
+        let message2 = party2.respond(&party1.commit(&mut rng).unwrap(), &mut rng).unwrap();
+        assert!(party1.finish(&early).is_err());
+        let message4 = party2.partial_sign(&party1.reveal(&message2).unwrap(), &mut rng).unwrap();
+        let signature = party1.finish(&message4).unwrap();
+        assert!(ecdsa.verify(&hash, &share1.pub_key, &signature).unwrap());
+        assert!(party1.finish(&message4).is_err());
+
+        // ciphertexts which are not units modulo N
+        let n = share1.paillier_key.public_key().n.clone();
+        for c3 in [BigUint::from(0u32), n] {
+            let mut party1 = Party1Signing::new(&ecdsa, share1.clone(), &hash).unwrap();
+            let mut party2 = Party2Signing::new(&ecdsa, share2.clone(), &hash).unwrap();
+            let message2 = party2.respond(&party1.commit(&mut rng).unwrap(), &mut rng).unwrap();
+            let message4 = party2.partial_sign(&party1.reveal(&message2).unwrap(), &mut rng).unwrap();
+            assert!(party1.finish(&PartialSignatureMessage { c3 }).is_err());
+            // the session is aborted, even with the right c3
+            assert!(party1.finish(&message4).is_err());
+        }
+
+        let mut party2 = Party2Signing::new(&ecdsa, share2.clone(), &hash).unwrap();
+        assert!(party2.partial_sign(&message3, &mut rng).is_err());
+        let mut party1 = Party1KeyGen::with_modulus_bits(&ecdsa, 1024);
+        let mut party2 = Party2KeyGen::new(&ecdsa);
+        assert!(party1.finish(&message2, &mut rng).is_err());
+        let message1 = party1.commit(&mut rng).unwrap();
+        let mut early_party2 = Party2KeyGen::new(&ecdsa);
+        let (_, final_message) = party1.finish(&party2.respond(&message1, &mut rng).unwrap(), &mut rng).unwrap();
+        assert!(early_party2.finish(&final_message).is_err());
+        early_party2.respond(&message1, &mut rng).unwrap();
+        assert!(early_party2.finish(&final_message).is_ok());
+        assert!(party2.finish(&final_message).is_ok());
+        assert!(Party1Signing::new(&ecdsa, share1, &ecdsa.q_order).is_err());
+
+        // a tampered share of P2 gives an invalid signature, which P1 rejects
+        let mut bad_share2 = share2;
+        bad_share2.secret_share += 1u32;
+        let mut rng = StdRng::seed_from_u64(42);
+        let (share1, _) = keygen(&ecdsa, &mut rng);
+        assert!(sign(&ecdsa, &share1, &bad_share2, &hash, &mut rng).is_err());
+    }
+}
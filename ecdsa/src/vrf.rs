@@ -0,0 +1,256 @@
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::ECDSAErrors;
+use crate::rfc6979;
+
+/// Byte length of the truncated challenge `c`.
+pub const CHALLENGE_SIZE: usize = 16;
+
+///
+/// Elliptic curve verifiable random function as specified in RFC 9381, with
+/// the `ECVRF-P256-SHA256-SSWU` suite.
+///
+/// The holder of the private key `x` computes a proof `pi` for an input
+/// `alpha`, anyone holding the public key `Y = x B` can check it and both
+/// derive the same pseudorandom output `beta`:
+///
+/// ```text
+///  H = encode_to_curve(Y || alpha), Gamma = x H
+///  k = nonce(x, H), c = challenge(Y, H, Gamma, k B, k H)
+///  s = k + c x mod q
+///  pi = Gamma || c || s
+///  beta = SHA256(suite || 0x03 || Gamma || 0x00)
+/// ```
+///
+#[allow(clippy::upper_case_acronyms)]
+pub struct ECVRF {
+    pub hash_to_curve: HashToCurve,
+    pub suite_string: u8,
+    pub h2c_suite_id: &'static [u8],
+}
+
+impl ECVRF {
+    /// `ECVRF-P256-SHA256-SSWU`
+    pub fn p256() -> Self {
+        ECVRF {
+            hash_to_curve: HashToCurve::p256(),
+            suite_string: 0x02,
+            h2c_suite_id: b"P256_XMD:SHA-256_SSWU_NU_",
+        }
+    }
+
+    ///
+    /// Computes the proof `pi = Gamma || c || s` for the input `alpha`.
+    ///
+    pub fn prove(&self, priv_key: &BigUint, alpha: &[u8]) -> Result<Vec<u8>, ECDSAErrors> {
+        let params = &self.hash_to_curve.params;
+        if *priv_key == BigUint::from(0u32) || *priv_key >= params.n {
+            return Err(ECDSAErrors::BadArgument(
+                "Private key must be in [1, q - 1]".into(),
+            ));
+        }
+
+        let pub_key = self.mul(&params.g, priv_key)?;
+        let pub_key_string = self.point_to_string(&pub_key)?;
+
+        let h = self.encode_to_curve(&pub_key_string, alpha)?;
+        let h_string = self.point_to_string(&h)?;
+        let gamma = self.mul(&h, priv_key)?;
+
+        let k = rfc6979::generate_k(&params.n, priv_key, &Sha256::digest(&h_string));
+        let u = self.mul(&params.g, &k)?;
+        let v = self.mul(&h, &k)?;
+        let c = self.challenge(&[&pub_key, &h, &gamma, &u, &v])?;
+
+        let s = (k + &c * priv_key) % &params.n;
+
+        let mut proof = self.point_to_string(&gamma)?;
+        proof.extend_from_slice(&to_fixed(&c, CHALLENGE_SIZE)?);
+        proof.extend_from_slice(&to_fixed(&s, self.scalar_size())?);
+        Ok(proof)
+    }
+
+    ///
+    /// Verifies the proof of `alpha` for a public key, returning the output
+    /// `beta` if it is valid and `None` if it is not:
+    ///
+    /// ```text
+    ///  U = s B - c Y, V = s H - c Gamma
+    ///  valid if c == challenge(Y, H, Gamma, U, V)
+    /// ```
+    ///
+    pub fn verify(
+        &self,
+        pub_key: &CurvePoint,
+        alpha: &[u8],
+        proof: &[u8],
+    ) -> Result<Option<[u8; 32]>, ECDSAErrors> {
+        let params = &self.hash_to_curve.params;
+        if *pub_key == CurvePoint::Identity || !params.curve.is_on_curve(pub_key) {
+            return Err(ECDSAErrors::BadArgument("Invalid public key".into()));
+        }
+
+        let (gamma, c, s) = match self.decode_proof(proof) {
+            Ok(decoded) => decoded,
+            Err(_) => return Ok(None),
+        };
+
+        let h = self.encode_to_curve(&self.point_to_string(pub_key)?, alpha)?;
+
+        let minus_c = inverse_add(&(&c % &params.n), &params.n)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing -c".into()))?;
+        let u = params
+            .curve
+            .double_scalar_mul(&s, &params.g, &minus_c, pub_key)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing s B - c Y".into()))?;
+        let v = params
+            .curve
+            .double_scalar_mul(&s, &h, &minus_c, &gamma)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error computing s H - c Gamma".into()))?;
+
+        if self.challenge(&[pub_key, &h, &gamma, &u, &v])? != c {
+            return Ok(None);
+        }
+
+        Ok(Some(self.gamma_to_hash(&gamma)?))
+    }
+
+    ///
+    /// Computes the output `beta` of a proof without verifying it.
+    ///
+    pub fn proof_to_hash(&self, proof: &[u8]) -> Result<[u8; 32], ECDSAErrors> {
+        let (gamma, _, _) = self.decode_proof(proof)?;
+        self.gamma_to_hash(&gamma)
+    }
+
+    // beta = SHA256(suite || 0x03 || cofactor * Gamma || 0x00), the cofactor is 1
+    fn gamma_to_hash(&self, gamma: &CurvePoint) -> Result<[u8; 32], ECDSAErrors> {
+        Ok(Sha256::new()
+            .chain_update([self.suite_string, 0x03])
+            .chain_update(self.point_to_string(gamma)?)
+            .chain_update([0x00])
+            .finalize()
+            .into())
+    }
+
+    // Gamma || c || s
+    fn decode_proof(&self, proof: &[u8]) -> Result<(CurvePoint, BigUint, BigUint), ECDSAErrors> {
+        let params = &self.hash_to_curve.params;
+        let point_size = 1 + params.curve.coordinate_size();
+        if proof.len() != point_size + CHALLENGE_SIZE + self.scalar_size() {
+            return Err(ECDSAErrors::BadArgument("Invalid proof length".into()));
+        }
+
+        let gamma = CurvePoint::from_sec1_compressed(&proof[..point_size], &params.curve)
+            .map_err(|_| ECDSAErrors::BadArgument("Invalid Gamma encoding".into()))?;
+        let c = BigUint::from_bytes_be(&proof[point_size..point_size + CHALLENGE_SIZE]);
+        let s = BigUint::from_bytes_be(&proof[point_size + CHALLENGE_SIZE..]);
+        if s >= params.n {
+            return Err(ECDSAErrors::BadArgument("s is not smaller than q".into()));
+        }
+
+        Ok((gamma, c, s))
+    }
+
+    // c = SHA256(suite || 0x02 || points || 0x00) truncated to 16 bytes
+    fn challenge(&self, points: &[&CurvePoint]) -> Result<BigUint, ECDSAErrors> {
+        let mut hasher = Sha256::new().chain_update([self.suite_string, 0x02]);
+        for point in points {
+            hasher.update(self.point_to_string(point)?);
+        }
+        let c_string = hasher.chain_update([0x00]).finalize();
+
+        Ok(BigUint::from_bytes_be(&c_string[..CHALLENGE_SIZE]))
+    }
+
+    // encode_to_curve with the public key as salt
+    fn encode_to_curve(&self, salt: &[u8], alpha: &[u8]) -> Result<CurvePoint, ECDSAErrors> {
+        let mut dst = b"ECVRF_".to_vec();
+        dst.extend_from_slice(self.h2c_suite_id);
+        dst.push(self.suite_string);
+
+        let msg = [salt, alpha].concat();
+        self.hash_to_curve
+            .encode_to_curve(&msg, &dst)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error hashing alpha to the curve".into()))
+    }
+
+    fn mul(&self, point: &CurvePoint, scalar: &BigUint) -> Result<CurvePoint, ECDSAErrors> {
+        self.hash_to_curve
+            .params
+            .curve
+            .scalar_mul(point, scalar)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error in scalar multiplication".into()))
+    }
+
+    fn point_to_string(&self, point: &CurvePoint) -> Result<Vec<u8>, ECDSAErrors> {
+        point
+            .to_sec1_compressed(&self.hash_to_curve.params.curve)
+            .map_err(|_| ECDSAErrors::OperationFailure("Error encoding point".into()))
+    }
+
+    fn scalar_size(&self) -> usize {
+        self.hash_to_curve.params.n.bits().div_ceil(8) as usize
+    }
+}
+
+fn to_fixed(value: &BigUint, len: usize) -> Result<Vec<u8>, ECDSAErrors> {
+    to_fixed_bytes(value, len)
+        .map_err(|_| ECDSAErrors::OperationFailure("Value does not fit in the proof".into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn priv_key() -> BigUint {
+        BigUint::parse_bytes(
+            b"c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            16,
+        )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        let vrf = ECVRF::p256();
+        let params = &vrf.hash_to_curve.params;
+        let pub_key = params.curve.scalar_mul(&params.g, &priv_key()).unwrap();
+
+        let proof = vrf.prove(&priv_key(), b"sample").unwrap();
+        assert_eq!(proof.len(), 33 + 16 + 32);
+
+        // deterministic proof and output
+        assert_eq!(vrf.prove(&priv_key(), b"sample").unwrap(), proof);
+        let beta = vrf.verify(&pub_key, b"sample", &proof).unwrap();
+        assert_eq!(beta, Some(vrf.proof_to_hash(&proof).unwrap()));
+
+        // a different input gives a different output
+        let other = vrf.prove(&priv_key(), b"test").unwrap();
+        assert_ne!(vrf.proof_to_hash(&other).unwrap(), beta.unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_invalid_proofs() {
+        let vrf = ECVRF::p256();
+        let params = &vrf.hash_to_curve.params;
+        let pub_key = params.curve.scalar_mul(&params.g, &priv_key()).unwrap();
+        let proof = vrf.prove(&priv_key(), b"sample").unwrap();
+
+        assert_eq!(vrf.verify(&pub_key, b"sample2", &proof).unwrap(), None);
+
+        let other_key = params.curve.scalar_mul(&params.g, &BigUint::from(7u32)).unwrap();
+        assert_eq!(vrf.verify(&other_key, b"sample", &proof).unwrap(), None);
+
+        for i in [0, 10, 40, 80] {
+            let mut tempered = proof.clone();
+            tempered[i] ^= 1;
+            assert_eq!(vrf.verify(&pub_key, b"sample", &tempered).unwrap(), None);
+        }
+
+        assert_eq!(vrf.verify(&pub_key, b"sample", &proof[1..]).unwrap(), None);
+        assert!(vrf.verify(&CurvePoint::Identity, b"sample", &proof).is_err());
+    }
+}
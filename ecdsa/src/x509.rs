@@ -0,0 +1,211 @@
+/*!
+Verification of ECDSA signatures of X.509 certificates (RFC 5280, RFC 5758):
+
+```text
+ Certificate ::= SEQUENCE {
+      tbsCertificate          TBSCertificate,
+      signatureAlgorithm      AlgorithmIdentifier (ecdsa-with-SHA*),
+      signatureValue          BIT STRING (Ecdsa-Sig-Value) }
+
+ Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+```
+
+The signature covers the DER encoding of `tbsCertificate`, hashed with the
+function named by the algorithm identifier; the digest is truncated to the
+bit length of the order of the issuer curve. Only the signature is checked,
+not the validity period, extensions or chain.
+*/
+
+use ec_core::curves::CurveParams;
+use ec_core::*;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+use crate::der::{self, DerReader};
+use crate::ecdsa::{ECDSAErrors, ECDSA};
+use crate::pem;
+use crate::rfc6979::bits2int;
+use crate::signature::Signature;
+use crate::spki;
+
+pub const OID_ECDSA_WITH_SHA224: &[u64] = &[1, 2, 840, 10045, 4, 3, 1];
+pub const OID_ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+pub const OID_ECDSA_WITH_SHA384: &[u64] = &[1, 2, 840, 10045, 4, 3, 3];
+pub const OID_ECDSA_WITH_SHA512: &[u64] = &[1, 2, 840, 10045, 4, 3, 4];
+
+///
+/// The three parts of a DER certificate: the encoding of `tbsCertificate`,
+/// the encoding of `signatureAlgorithm` and the content of `signatureValue`.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CertificateParts<'a> {
+    pub tbs: &'a [u8],
+    pub signature_algorithm: &'a [u8],
+    pub signature_value: &'a [u8],
+}
+
+pub fn split_certificate(cert: &[u8]) -> Result<CertificateParts<'_>, ECDSAErrors> {
+    let mut reader = DerReader::new(cert);
+    let mut certificate = reader.read_sequence()?;
+    reader.finish()?;
+
+    let tbs = certificate.read_raw()?;
+    let signature_algorithm = certificate.read_raw()?;
+    let signature_value = certificate.read_bit_string()?;
+    certificate.finish()?;
+
+    Ok(CertificateParts { tbs, signature_algorithm, signature_value })
+}
+
+///
+/// The subject public key of a DER certificate, e.g. to check the
+/// certificates it issued.
+///
+pub fn certificate_public_key(cert: &[u8]) -> Result<(&'static CurveParams, CurvePoint), ECDSAErrors> {
+    let mut tbs = DerReader::new(split_certificate(cert)?.tbs).read_sequence()?;
+
+    // [0] version, serialNumber, signature, issuer, validity, subject
+    if tbs.peek_tag() == Some(der::context_tag(0)) {
+        tbs.read_tlv()?;
+    }
+    for _ in 0..5 {
+        tbs.read_tlv()?;
+    }
+
+    spki::from_spki_der(tbs.read_raw()?)
+}
+
+///
+/// Verifies the ECDSA signature of a certificate, given the DER encoding of
+/// its `tbsCertificate` and `signatureAlgorithm` and the content of its
+/// `signatureValue`, against the public key of the issuer.
+///
+pub fn verify_signature(
+    tbs: &[u8],
+    signature_algorithm: &[u8],
+    signature_value: &[u8],
+    issuer_params: &CurveParams,
+    issuer_pub_key: &CurvePoint,
+) -> Result<bool, ECDSAErrors> {
+    let mut reader = DerReader::new(signature_algorithm);
+    let mut algorithm = reader.read_sequence()?;
+    reader.finish()?;
+    let oid = algorithm.read_oid()?;
+    // the parameters of ecdsa-with-SHA* must be absent
+    algorithm.finish()?;
+
+    let digest = match oid.as_slice() {
+        OID_ECDSA_WITH_SHA224 => Sha224::digest(tbs).to_vec(),
+        OID_ECDSA_WITH_SHA256 => Sha256::digest(tbs).to_vec(),
+        OID_ECDSA_WITH_SHA384 => Sha384::digest(tbs).to_vec(),
+        OID_ECDSA_WITH_SHA512 => Sha512::digest(tbs).to_vec(),
+        _ => return Err(ECDSAErrors::BadArgument("Unsupported signature algorithm".into())),
+    };
+
+    let mut reader = DerReader::new(signature_value);
+    let mut ecdsa_sig_value = reader.read_sequence()?;
+    reader.finish()?;
+    let r = ecdsa_sig_value.read_integer()?;
+    let s = ecdsa_sig_value.read_integer()?;
+    ecdsa_sig_value.finish()?;
+
+    let ecdsa = ECDSA::from(issuer_params.clone());
+    if r == BigUint::from(0u32) || s == BigUint::from(0u32) || r >= ecdsa.q_order || s >= ecdsa.q_order {
+        return Ok(false);
+    }
+    let hash = bits2int(&digest, &ecdsa.q_order) % &ecdsa.q_order;
+    ecdsa.verify(&hash, issuer_pub_key, &Signature::new(r, s))
+}
+
+///
+/// Verifies the signature of a DER certificate against the public key of
+/// its issuer.
+///
+pub fn verify_certificate(
+    cert: &[u8],
+    issuer_params: &CurveParams,
+    issuer_pub_key: &CurvePoint,
+) -> Result<bool, ECDSAErrors> {
+    let parts = split_certificate(cert)?;
+    verify_signature(parts.tbs, parts.signature_algorithm, parts.signature_value, issuer_params, issuer_pub_key)
+}
+
+///
+/// Decodes a `CERTIFICATE` PEM document into its DER encoding.
+///
+pub fn certificate_from_pem(pem: &str) -> Result<Vec<u8>, ECDSAErrors> {
+    pem::decode(pem::LABEL_CERTIFICATE, pem)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ec_core::curves;
+
+    // openssl req -x509 -sha256, P-256 key
+    const CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBeTCCAR+gAwIBAgIUOe2X726K+ix+A4yoIa2MFqnkd6IwCgYIKoZIzj0EAwIw
+EjEQMA4GA1UEAwwHVGVzdCBDQTAeFw0yNjEwMTYwMDEzMjRaFw0zNjEwMTMwMDEz
+MjRaMBIxEDAOBgNVBAMMB1Rlc3QgQ0EwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNC
+AAS2G1bjrveZkhgJOeRmP8LITroRiYD65AmRPTgtQSfjFCaoFk0OdLCIcPW/HYL2
+Y6oqWOdtzfifaussvSGPlZSXo1MwUTAdBgNVHQ4EFgQUUUlM1xbyt9JHXhzIF5Cj
+gDqcE/owHwYDVR0jBBgwFoAUUUlM1xbyt9JHXhzIF5CjgDqcE/owDwYDVR0TAQH/
+BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiEA0pQxTHLPpjDAJXhzhvV3KQ5uvVXm
+fU1+4sVurywytMcCIAhpZoEMcKAdJzgDzR0yf1BVut5e0Up/vFrI4x4I2CuN
+-----END CERTIFICATE-----
+";
+
+    // openssl x509 -req -sha384 signed by the CA, P-384 key
+    const LEAF_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBgjCCASigAwIBAgIUB8oVu8AkZT7mhaZJaOMhlIh3u68wCgYIKoZIzj0EAwMw
+EjEQMA4GA1UEAwwHVGVzdCBDQTAeFw0yNjEwMTYwMDEzMjRaFw0zNjEwMTMwMDEz
+MjRaMA8xDTALBgNVBAMMBGxlYWYwdjAQBgcqhkjOPQIBBgUrgQQAIgNiAASwQoEw
+BzMrYoic8y3wDVoHEKVxqwLJLnitMuquP3LSlbCXc90TrHd7rjflIuq3EIsncKzS
+Ti+IyUzaUoPCid8D4Ocucjgw2XWQlOFjAWOE6gBzap+XuwpeNu+9vRApxgmjQjBA
+MB0GA1UdDgQWBBTyyMDw2plEOfcmN+vhI7vt8UVMaDAfBgNVHSMEGDAWgBRRSUzX
+FvK30kdeHMgXkKOAOpwT+jAKBggqhkjOPQQDAwNIADBFAiEAq18SqPEdfhFLiWIh
+Pz7ONUnTInvyBP0EhIs6r45RwJ4CIBiqhq08tGfjN0qfh8zBOuEsp1UXsZTUoupW
+9aJnuo22
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_verify_openssl_certificates() {
+        let ca = certificate_from_pem(CA_PEM).unwrap();
+        let leaf = certificate_from_pem(LEAF_PEM).unwrap();
+
+        let (ca_params, ca_key) = certificate_public_key(&ca).unwrap();
+        assert_eq!(*ca_params, curves::p256());
+        let (leaf_params, leaf_key) = certificate_public_key(&leaf).unwrap();
+        assert_eq!(*leaf_params, curves::p384());
+
+        // self signed with SHA-256, leaf signed with SHA-384 truncated to 256 bits
+        assert!(verify_certificate(&ca, ca_params, &ca_key).unwrap());
+        assert!(verify_certificate(&leaf, ca_params, &ca_key).unwrap());
+        assert!(!verify_certificate(&leaf, leaf_params, &leaf_key).unwrap());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let ca = certificate_from_pem(CA_PEM).unwrap();
+        let (ca_params, ca_key) = certificate_public_key(&ca).unwrap();
+        let CertificateParts { tbs, signature_algorithm, signature_value } = split_certificate(&ca).unwrap();
+        assert_eq!(signature_algorithm, der::encode_sequence(&[der::encode_oid(OID_ECDSA_WITH_SHA256).unwrap()]));
+
+        assert!(verify_signature(tbs, signature_algorithm, signature_value, ca_params, &ca_key).unwrap());
+
+        // tampered TBS, other hash function, unknown algorithm, parameters present
+        let mut tampered = tbs.to_vec();
+        tampered[20] ^= 1;
+        assert!(!verify_signature(&tampered, signature_algorithm, signature_value, ca_params, &ca_key).unwrap());
+        let sha384 = der::encode_sequence(&[der::encode_oid(OID_ECDSA_WITH_SHA384).unwrap()]);
+        assert!(!verify_signature(tbs, &sha384, signature_value, ca_params, &ca_key).unwrap());
+        let rsa = der::encode_sequence(&[der::encode_oid(&[1, 2, 840, 113549, 1, 1, 11]).unwrap(), der::encode_null()]);
+        assert!(verify_signature(tbs, &rsa, signature_value, ca_params, &ca_key).is_err());
+        let with_null = der::encode_sequence(&[der::encode_oid(OID_ECDSA_WITH_SHA256).unwrap(), der::encode_null()]);
+        assert!(verify_signature(tbs, &with_null, signature_value, ca_params, &ca_key).is_err());
+
+        // truncated signature value
+        assert!(verify_signature(tbs, signature_algorithm, &signature_value[1..], ca_params, &ca_key).is_err());
+    }
+}
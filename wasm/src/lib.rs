@@ -0,0 +1,227 @@
+/*!
+WebAssembly bindings of the ECDSA library, built with `wasm-bindgen`:
+
+```text
+ import { generate_private_key, public_key, sign, verify, ecdh } from "ecdsa_wasm";
+
+ const d = generate_private_key("P-256", crypto.getRandomValues(new Uint8Array(40)));
+ const pub = public_key("P-256", d, true);
+ const sig = sign("P-256", d, new TextEncoder().encode("hello"));
+ verify("P-256", pub, new TextEncoder().encode("hello"), sig); // true
+```
+
+Every byte string goes in and out as a `Uint8Array`:
+
+- curves are named as in `ec_core::curves::by_name` (`secp256k1`, `P-256`...)
+- private keys are big endian integers on the byte size of the order
+- public keys are SEC1 encoded, compressed or not
+- signatures are `r || s`, each on the byte size of the order, computed over
+  the SHA-256 digest of the message with a RFC 6979 nonce
+- ECDH shared secrets are the `x` coordinate on the byte size of the field
+
+Errors are thrown as JavaScript `Error`s.
+*/
+
+use ec_core::curves::{self, CurveParams};
+use ec_core::*;
+use num_bigint::BigUint;
+use rust_ecdsa::ecdh::{self, EcdhPrimitive};
+use rust_ecdsa::ecdsa::{ECDSAErrors, ECDSA};
+use rust_ecdsa::signature::Signature;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+///
+/// Derives a private key from at least `size of the order + 8` bytes of
+/// entropy, e.g. from `crypto.getRandomValues`.
+///
+#[wasm_bindgen]
+pub fn generate_private_key(curve: &str, entropy: &[u8]) -> Result<Vec<u8>, JsError> {
+    private_key_from_entropy(curve, entropy).map_err(to_js_error)
+}
+
+///
+/// The SEC1 encoded public key of a private key.
+///
+#[wasm_bindgen]
+pub fn public_key(curve: &str, private_key: &[u8], compressed: bool) -> Result<Vec<u8>, JsError> {
+    derive_public_key(curve, private_key, compressed).map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn sign(curve: &str, private_key: &[u8], message: &[u8]) -> Result<Vec<u8>, JsError> {
+    sign_message(curve, private_key, message).map_err(to_js_error)
+}
+
+///
+/// Returns `false` for a wrong signature and throws for malformed inputs.
+///
+#[wasm_bindgen]
+pub fn verify(curve: &str, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, JsError> {
+    verify_message(curve, public_key, message, signature).map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn ecdh(curve: &str, private_key: &[u8], peer_public_key: &[u8]) -> Result<Vec<u8>, JsError> {
+    shared_secret(curve, private_key, peer_public_key).map_err(to_js_error)
+}
+
+fn to_js_error(error: ECDSAErrors) -> JsError {
+    match error {
+        ECDSAErrors::BadArgument(message) | ECDSAErrors::OperationFailure(message) => JsError::new(&message),
+    }
+}
+
+fn curve_params(name: &str) -> Result<&'static CurveParams, ECDSAErrors> {
+    curves::by_name(name).ok_or_else(|| ECDSAErrors::BadArgument(format!("Unknown curve {}", name)))
+}
+
+fn scalar_size(params: &CurveParams) -> usize {
+    params.n.bits().div_ceil(8) as usize
+}
+
+fn private_key_from_entropy(curve: &str, entropy: &[u8]) -> Result<Vec<u8>, ECDSAErrors> {
+    let params = curve_params(curve)?;
+    if entropy.len() < scalar_size(params) + 8 {
+        return Err(ECDSAErrors::BadArgument(format!(
+            "At least {} bytes of entropy are needed",
+            scalar_size(params) + 8
+        )));
+    }
+
+    // 64 extra bits make the bias of the reduction negligible
+    let priv_key = BigUint::from_bytes_be(entropy) % (&params.n - BigUint::from(1u32)) + BigUint::from(1u32);
+    encode_scalar(params, &priv_key)
+}
+
+fn derive_public_key(curve: &str, private_key: &[u8], compressed: bool) -> Result<Vec<u8>, ECDSAErrors> {
+    let params = curve_params(curve)?;
+    let priv_key = decode_private_key(params, private_key)?;
+    let pub_key = ECDSA::from(params.clone()).generate_pub_key(&priv_key)?;
+
+    let encoded = if compressed {
+        pub_key.to_sec1_compressed(&params.curve)
+    } else {
+        pub_key.to_sec1_uncompressed(&params.curve)
+    };
+    encoded.map_err(|_| ECDSAErrors::OperationFailure("Public key is the identity".into()))
+}
+
+fn sign_message(curve: &str, private_key: &[u8], message: &[u8]) -> Result<Vec<u8>, ECDSAErrors> {
+    let params = curve_params(curve)?;
+    let priv_key = decode_private_key(params, private_key)?;
+    let ecdsa = ECDSA::from(params.clone());
+
+    let signature = ecdsa.sign_prehash(&Sha256::digest(message), &priv_key)?;
+
+    let mut encoded = encode_scalar(params, &signature.r)?;
+    encoded.extend(encode_scalar(params, &signature.s)?);
+    Ok(encoded)
+}
+
+fn verify_message(curve: &str, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, ECDSAErrors> {
+    let params = curve_params(curve)?;
+    let pub_key = decode_public_key(params, public_key)?;
+    let size = scalar_size(params);
+    if signature.len() != 2 * size {
+        return Err(ECDSAErrors::BadArgument(format!("Signature must be {} bytes", 2 * size)));
+    }
+
+    let r = BigUint::from_bytes_be(&signature[..size]);
+    let s = BigUint::from_bytes_be(&signature[size..]);
+    if r == BigUint::from(0u32) || s == BigUint::from(0u32) || r >= params.n || s >= params.n {
+        return Ok(false);
+    }
+
+    let ecdsa = ECDSA::from(params.clone());
+    ecdsa.verify_prehash(&Sha256::digest(message), &pub_key, &Signature::new(r, s))
+}
+
+fn shared_secret(curve: &str, private_key: &[u8], peer_public_key: &[u8]) -> Result<Vec<u8>, ECDSAErrors> {
+    let params = curve_params(curve)?;
+    let priv_key = decode_private_key(params, private_key)?;
+    let peer = decode_public_key(params, peer_public_key)?;
+
+    Ok(ecdh::diffie_hellman(params, &priv_key, &peer, EcdhPrimitive::Standard)?.as_bytes().to_vec())
+}
+
+fn encode_scalar(params: &CurveParams, value: &BigUint) -> Result<Vec<u8>, ECDSAErrors> {
+    to_fixed_bytes(value, scalar_size(params))
+        .map_err(|_| ECDSAErrors::BadArgument("Scalar does not fit the order size".into()))
+}
+
+fn decode_private_key(params: &CurveParams, bytes: &[u8]) -> Result<BigUint, ECDSAErrors> {
+    let priv_key = BigUint::from_bytes_be(bytes);
+    if bytes.len() != scalar_size(params) || priv_key == BigUint::from(0u32) || priv_key >= params.n {
+        return Err(ECDSAErrors::BadArgument("Invalid private key".into()));
+    }
+    Ok(priv_key)
+}
+
+fn decode_public_key(params: &CurveParams, bytes: &[u8]) -> Result<CurvePoint, ECDSAErrors> {
+    let point = match bytes.first() {
+        Some(0x04) => CurvePoint::from_sec1_uncompressed(bytes, &params.curve),
+        _ => CurvePoint::from_sec1_compressed(bytes, &params.curve),
+    };
+    point.map_err(|_| ECDSAErrors::BadArgument("Invalid SEC1 public key".into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        for curve in ["secp256k1", "P-256", "P-384", "P-521"] {
+            let d = private_key_from_entropy(curve, &[0x5a; 80]).unwrap();
+            assert_eq!(d.len(), scalar_size(curve_params(curve).unwrap()));
+
+            let signature = sign_message(curve, &d, b"hello").unwrap();
+            assert_eq!(signature, sign_message(curve, &d, b"hello").unwrap());
+            for compressed in [true, false] {
+                let pub_key = derive_public_key(curve, &d, compressed).unwrap();
+                assert!(verify_message(curve, &pub_key, b"hello", &signature).unwrap());
+                assert!(!verify_message(curve, &pub_key, b"hellp", &signature).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_signature() {
+        // RFC 6979 A.2.5, P-256 with SHA-256, message "sample"
+        let d = hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+        assert_eq!(
+            hex::encode(sign_message("P-256", &d, b"sample").unwrap()),
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+             f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+        );
+    }
+
+    #[test]
+    fn test_ecdh() {
+        let a = private_key_from_entropy("P-256", &[1; 40]).unwrap();
+        let b = private_key_from_entropy("P-256", &[2; 40]).unwrap();
+        let a_pub = derive_public_key("P-256", &a, true).unwrap();
+        let b_pub = derive_public_key("P-256", &b, false).unwrap();
+
+        let secret = shared_secret("P-256", &a, &b_pub).unwrap();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(secret, shared_secret("P-256", &b, &a_pub).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(private_key_from_entropy("P-257", &[0; 80]).is_err());
+        assert!(private_key_from_entropy("P-256", &[0; 39]).is_err());
+        assert!(derive_public_key("P-256", &[0; 32], true).is_err());
+        assert!(derive_public_key("P-256", &[1; 31], true).is_err());
+
+        let d = private_key_from_entropy("P-256", &[3; 40]).unwrap();
+        let pub_key = derive_public_key("P-256", &d, true).unwrap();
+        let signature = sign_message("P-256", &d, b"hello").unwrap();
+        assert!(verify_message("P-256", &pub_key, b"hello", &signature[1..]).is_err());
+        assert!(verify_message("P-256", &pub_key[1..], b"hello", &signature).is_err());
+        assert!(!verify_message("P-256", &pub_key, b"hello", &[0; 64]).unwrap());
+        assert!(shared_secret("P-256", &d, &[0x05; 33]).is_err());
+    }
+}